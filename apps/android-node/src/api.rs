@@ -18,8 +18,13 @@ pub struct IdentityResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdentityRotateResponse {
+    /// Root identity pubkey (unchanged by rotation).
     pub public_key_hex: String,
     pub rotated: bool,
+    /// Index of the new device subkey.
+    pub device_index: u32,
+    /// New device signing pubkey.
+    pub device_pubkey_hex: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -423,11 +428,40 @@ pub struct SubscriptionListResponse {
 pub struct IdentityExportResponse {
     pub public_key_hex: String,
     pub secret_key_hex: String,
+    /// NIP-19 encodings of the same keys.
+    pub npub: String,
+    pub nsec: String,
+    /// 24-word backup phrase; absent for identities imported from a raw secret.
+    #[serde(default)]
+    pub mnemonic: Option<String>,
 }
 
+/// Identity to import: exactly one of a hex secret, an `nsec` or a mnemonic.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdentityImportRequest {
-    pub secret_key_hex: String,
+    #[serde(default)]
+    pub secret_key_hex: Option<String>,
+    #[serde(default)]
+    pub nsec: Option<String>,
+    #[serde(default)]
+    pub mnemonic: Option<String>,
+}
+
+/// New keystore credential: a passphrase or a path to a key file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeystoreRekeyRequest {
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    #[serde(default)]
+    pub key_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeystoreRekeyResponse {
+    pub rekeyed: bool,
+    /// Environment variable that must hold the new credential before the
+    /// node restarts, or the keystore stays locked.
+    pub restart_env: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
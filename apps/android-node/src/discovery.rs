@@ -15,6 +15,7 @@ use crate::api::{
 use crate::protocol::ProtocolEngine;
 use crate::state::NodeState;
 use veil_core::Namespace;
+use veil_node::dht::DhtContact;
 
 const DISCOVERY_MAX_CONTACTS: usize = 64;
 const DISCOVERY_TAG_SEED: &[u8] = b"veil-discovery";
@@ -560,6 +561,21 @@ pub fn build_self_contact(node: &NodeState, protocol: &ProtocolEngine) -> Contac
     }
 }
 
+/// Maps a discovery contact to a DHT contact keyed like discovery lookups.
+///
+/// Relays seed their rendezvous DHT routing table from discovered contacts.
+pub fn dht_contact(contact: &ContactBundle) -> Option<DhtContact> {
+    let addr = contact
+        .quic_addr
+        .iter()
+        .chain(contact.ws_url.iter())
+        .find(|addr| !addr.trim().is_empty())?;
+    Some(DhtContact {
+        key: contact_key(contact),
+        addr: addr.clone(),
+    })
+}
+
 fn join_discovery_endpoint(base: &str, path: &str) -> String {
     let trimmed = base.trim_end_matches('/');
     format!("{trimmed}/{path}")
@@ -683,6 +699,19 @@ mod tests {
         assert!(results.iter().any(|c| c.peer_id == "alpha"));
     }
 
+    #[test]
+    fn dht_contact_uses_contact_keys_and_transport_addrs() {
+        let pubkey = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        let mut reachable = make_contact("vps", pubkey);
+        reachable.quic_addr = Some("203.0.113.5:9443".to_string());
+        reachable.ws_url = Some("wss://vps.example/ws".to_string());
+
+        let seed = dht_contact(&reachable).expect("reachable contact");
+        assert_eq!(seed.addr, "203.0.113.5:9443");
+        assert_eq!(seed.key, [0xAA; 32]);
+        assert!(dht_contact(&make_contact("phone", pubkey)).is_none());
+    }
+
     #[test]
     fn discovery_message_roundtrip() {
         let contact = make_contact(
@@ -13,8 +13,8 @@ pub use adapters::{
 };
 pub use api::*;
 pub use discovery::{
-    build_self_contact, discovery_tag, handle_discovery_payload, DiscoveryConfig, DiscoveryMessage,
-    DiscoveryWorker, LanDiscoveryConfig, LanDiscoveryWorker,
+    build_self_contact, dht_contact, discovery_tag, handle_discovery_payload, DiscoveryConfig,
+    DiscoveryMessage, DiscoveryWorker, LanDiscoveryConfig, LanDiscoveryWorker,
 };
 pub use protocol::{default_protocol_config, ProtocolConfig, ProtocolEngine};
 pub use server::{build_router, serve, AppState};
@@ -41,7 +41,15 @@ async fn main() {
         .unwrap_or(7788);
 
     let store_path = std::env::var("VEIL_NODE_STATE").map(PathBuf::from).ok();
-    let node = NodeState::new_with_store(env!("CARGO_PKG_VERSION"), store_path);
+    let node = match NodeState::new_with_store(env!("CARGO_PKG_VERSION"), store_path) {
+        Ok(node) => node,
+        Err(err) => {
+            tracing::error!(
+                "{err}; check VEIL_NODE_KEYSTORE_PASSPHRASE or VEIL_NODE_KEYSTORE_KEY_FILE"
+            );
+            std::process::exit(1);
+        }
+    };
     let node_arc = Arc::new(node.clone());
     let identity = node.identity();
 
@@ -59,6 +67,9 @@ async fn main() {
         identity.encrypt_key,
         identity.signer(),
     );
+    let (device_signer, delegation) = identity.device_credentials();
+    protocol_config.signer = device_signer;
+    protocol_config.delegation = Some(delegation);
     if ws_url.is_none() {
         protocol_config.ws_url = None;
     }
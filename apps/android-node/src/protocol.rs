@@ -12,7 +12,9 @@ use veil_crypto::aead::{build_veil_aad, AeadCipher, XChaCha20Poly1305Cipher};
 use veil_crypto::signing::NostrSigner;
 use veil_crypto::signing::NostrVerifier;
 use veil_fec::profile::ErasureCodingMode;
+use veil_node::backfill::{request_backfill, BackfillRequest, MAX_BACKFILL_TAGS};
 use veil_node::batch::FeedBatcher;
+use veil_node::clock::{clock_step, SystemClock};
 use veil_node::config::{BloomExchangeConfig, NodeRuntimeConfig, ProbabilisticForwardingConfig};
 use veil_node::policy::LocalWotPolicy;
 use veil_node::receive::ReceiveEvent;
@@ -24,12 +26,16 @@ use veil_node::service::{PublisherRuntime, PublisherTickInput};
 use crate::adapters::{FallbackAdapter, FastAdapter, LaneAdapter, LaneSnapshot, MultiLaneAdapter};
 use crate::api::{LaneDetail, LaneStats};
 use crate::discovery::discovery_tag;
-use veil_codec::object::decode_object_cbor_prefix;
+use veil_codec::delegation::DelegationCertificate;
+use veil_codec::object::{decode_object_cbor_prefix, ObjectExtension};
 use veil_codec::shard::decode_shard_cbor;
 use veil_core::ObjectRoot;
 use veil_fec::sharder::{derive_object_root, reconstruct_object_padded_with_mode};
 use veil_node::persistence::load_state_or_default;
 
+/// Epochs of history requested when a channel is first subscribed.
+const BACKFILL_HISTORY_EPOCHS: u32 = 7;
+
 #[derive(Debug, Clone)]
 pub struct ProtocolConfig {
     pub ws_url: Option<String>,
@@ -43,6 +49,8 @@ pub struct ProtocolConfig {
     pub encrypt_key: [u8; 32],
     pub identity_pubkey: [u8; 32],
     pub signer: NostrSigner,
+    /// Root certificate for `signer` when it is a delegated device key.
+    pub delegation: Option<DelegationCertificate>,
     pub fast_peers: Vec<String>,
     pub fallback_peers: Vec<String>,
     pub runtime_config: NodeRuntimeConfig,
@@ -85,6 +93,11 @@ impl ProtocolEngine {
             XChaCha20Poly1305Cipher,
         );
         let mut runtime = runtime;
+        runtime.state.adopt_clock_steps(clock_step(
+            &SystemClock,
+            config.runtime_config.step_duration,
+        ));
+        runtime.state.device_delegation = config.delegation.clone();
         let tag = discovery_tag(config.discovery_namespace);
         runtime.state.subscriptions.insert(tag);
         Ok(Self {
@@ -100,6 +113,25 @@ impl ProtocolEngine {
         })
     }
 
+    /// Current clock-derived step, for recording events against the same
+    /// scale the runtime ticks on.
+    pub fn current_step(&self) -> u64 {
+        clock_step(&SystemClock, self.config.runtime_config.step_duration)
+    }
+
+    /// Returns a strictly increasing step derived from the wall clock, so
+    /// persisted cache/ACK state stays valid across restarts.
+    fn next_step(&self) -> u64 {
+        let now = self.current_step();
+        let prev = self
+            .steps
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+                Some(now.max(last.saturating_add(1)))
+            })
+            .unwrap_or(0);
+        now.max(prev.saturating_add(1))
+    }
+
     pub async fn publish(&self, payload: Vec<u8>, namespace: Option<u16>) -> Result<(), String> {
         let namespace = Namespace(namespace.unwrap_or(self.config.namespace.0));
         let pubkey = *self.identity_pubkey.lock().await;
@@ -126,7 +158,7 @@ impl ProtocolEngine {
     ) -> Result<(), String> {
         let mut runtime = self.inner.lock().await;
         runtime.enqueue(payload);
-        let step = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        let step = self.next_step();
         let (fast_peers, fallback_peers) = self.publish_peer_lists().await?;
         runtime
             .tick(PublisherTickInput {
@@ -156,7 +188,7 @@ impl ProtocolEngine {
         for payload in payloads {
             runtime.enqueue(payload);
         }
-        let step = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        let step = self.next_step();
         let (fast_peers, fallback_peers) = self.publish_peer_lists().await?;
         runtime
             .tick(PublisherTickInput {
@@ -174,7 +206,7 @@ impl ProtocolEngine {
     }
 
     pub async fn publish_encoded_object(&self, encoded_object: Vec<u8>) -> Result<(), String> {
-        let step = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        let step = self.next_step();
         let (fast_peers, fallback_peers) = self.publish_peer_lists().await?;
         let mut runtime = self.inner.lock().await;
         let PublisherRuntime {
@@ -261,25 +293,7 @@ impl ProtocolEngine {
 
         // 2. For each channel name, derive tags for self and all contacts
         for channel in channels {
-            // If it looks like a hex tag, add it directly
-            if channel.len() == 64 {
-                if let Ok(bytes) = hex::decode(channel) {
-                    if let Ok(tag) = <[u8; 32]>::try_from(bytes.as_slice()) {
-                        tags.push(tag);
-                        continue;
-                    }
-                }
-            }
-
-            // Otherwise treat as channel name
-            tags.push(veil_core::tags::derive_channel_feed_tag(&my_pubkey, self.config.namespace, channel));
-            for contact in contacts {
-                if let Ok(bytes) = hex::decode(&contact.pubkey_hex) {
-                    if let Ok(pubkey) = <[u8; 32]>::try_from(bytes.as_slice()) {
-                        tags.push(veil_core::tags::derive_channel_feed_tag(&pubkey, self.config.namespace, channel));
-                    }
-                }
-            }
+            tags.extend(self.channel_tags(&my_pubkey, channel, contacts));
         }
 
         let mut runtime = self.inner.lock().await;
@@ -289,9 +303,93 @@ impl ProtocolEngine {
         }
     }
 
-    pub async fn update_identity(&self, pubkey: [u8; 32], signer: NostrSigner) {
+    /// Subscribes to `channel` and asks peers for its recent cached history.
+    ///
+    /// Returns the number of backfill requests sent across both lanes.
+    pub async fn backfill_channel(
+        &self,
+        channel: &str,
+        contacts: &[crate::api::ContactBundle],
+    ) -> Result<usize, String> {
+        let my_pubkey = *self.identity_pubkey.lock().await;
+        let tags = self.channel_tags(&my_pubkey, channel, contacts);
+        let (fast_peers, fallback_peers) = self.publish_peer_lists().await?;
+        let step = self.next_step();
+        let cfg = self.config.runtime_config.backfill;
+        let epoch_to = current_epoch();
+        let epoch_from = Epoch(epoch_to.0.saturating_sub(BACKFILL_HISTORY_EPOCHS));
+
+        let mut runtime = self.inner.lock().await;
+        let PublisherRuntime {
+            state,
+            fast_adapter,
+            fallback_adapter,
+            ..
+        } = &mut *runtime;
+        state.subscriptions.extend(tags.iter().copied());
+        // One request per peer covers every tag: peers rate-limit requests,
+        // not tags, so per-tag requests would exhaust their budget.
+        let mut sent = 0;
+        for chunk in tags.chunks(MAX_BACKFILL_TAGS) {
+            let Some(request) = BackfillRequest::for_tags(
+                chunk,
+                epoch_from,
+                epoch_to,
+                cfg.max_shards_per_response as u32,
+            ) else {
+                continue;
+            };
+            sent += request_backfill(state, fast_adapter, &fast_peers, &request, step, cfg);
+            sent += request_backfill(
+                state,
+                fallback_adapter,
+                &fallback_peers,
+                &request,
+                step,
+                cfg,
+            );
+        }
+        Ok(sent)
+    }
+
+    /// Tags for a subscription entry: a raw hex tag, or a channel name
+    /// derived for self and every contact.
+    fn channel_tags(
+        &self,
+        my_pubkey: &[u8; 32],
+        channel: &str,
+        contacts: &[crate::api::ContactBundle],
+    ) -> Vec<[u8; 32]> {
+        // If it looks like a hex tag, add it directly
+        if channel.len() == 64 {
+            if let Ok(bytes) = hex::decode(channel) {
+                if let Ok(tag) = <[u8; 32]>::try_from(bytes.as_slice()) {
+                    return vec![tag];
+                }
+            }
+        }
+
+        // Otherwise treat as channel name
+        let mut tags = vec![veil_core::tags::derive_channel_feed_tag(my_pubkey, self.config.namespace, channel)];
+        for contact in contacts {
+            if let Ok(bytes) = hex::decode(&contact.pubkey_hex) {
+                if let Ok(pubkey) = <[u8; 32]>::try_from(bytes.as_slice()) {
+                    tags.push(veil_core::tags::derive_channel_feed_tag(&pubkey, self.config.namespace, channel));
+                }
+            }
+        }
+        tags
+    }
+
+    pub async fn update_identity(
+        &self,
+        pubkey: [u8; 32],
+        signer: NostrSigner,
+        delegation: Option<DelegationCertificate>,
+    ) {
         let mut runtime = self.inner.lock().await;
         runtime.signer = Some(signer);
+        runtime.state.device_delegation = delegation;
         let mut guard = self.identity_pubkey.lock().await;
         *guard = pubkey;
     }
@@ -312,22 +410,42 @@ impl ProtocolEngine {
         let mut payload = Vec::new();
         ciborium::ser::into_writer(&items, &mut payload).map_err(|e| e.to_string())?;
 
-        let now_step = self.steps.load(Ordering::Relaxed);
+        let now_step = self.next_step();
         let epoch = current_epoch();
         let pubkey = *self.identity_pubkey.lock().await;
         let tag = derive_feed_tag(&pubkey, Namespace(namespace));
 
-        let encoded_object = veil_node::publish::build_encoded_object(
-            &payload,
-            Namespace(namespace),
-            epoch,
-            tag,
-            &runtime.encrypt_key,
-            now_step,
-            flags | veil_codec::object::OBJECT_FLAG_BATCHED,
-            &XChaCha20Poly1305Cipher,
-            runtime.signer.as_ref(),
-        )
+        let flags = flags | veil_codec::object::OBJECT_FLAG_BATCHED;
+        let delegation = runtime
+            .state
+            .device_delegation
+            .as_ref()
+            .filter(|_| (flags & veil_codec::object::OBJECT_FLAG_SIGNED) != 0);
+        let encoded_object = match delegation {
+            Some(cert) => veil_node::publish::build_encoded_object_v2(
+                &payload,
+                Namespace(namespace),
+                epoch,
+                tag,
+                &runtime.encrypt_key,
+                now_step,
+                flags,
+                vec![ObjectExtension::delegation(cert).map_err(|e| e.to_string())?],
+                &XChaCha20Poly1305Cipher,
+                runtime.signer.as_ref(),
+            ),
+            None => veil_node::publish::build_encoded_object(
+                &payload,
+                Namespace(namespace),
+                epoch,
+                tag,
+                &runtime.encrypt_key,
+                now_step,
+                flags,
+                &XChaCha20Poly1305Cipher,
+                runtime.signer.as_ref(),
+            ),
+        }
         .map_err(|e| e.to_string())?;
 
         let wire_root = veil_fec::sharder::derive_object_root(&encoded_object);
@@ -642,7 +760,7 @@ impl ProtocolEngine {
             ConfigMultiLanePumpParams {
                 fast_peers: &fast_peers,
                 fallback_peers: &fallback_peers,
-                now_step: self.steps.fetch_add(1, Ordering::Relaxed) + 1,
+                now_step: self.next_step(),
                 decrypt_key: encrypt_key,
                 config: &cfg,
                 stats: &mut stats,
@@ -740,6 +858,7 @@ pub fn default_protocol_config(
         encrypt_key,
         identity_pubkey,
         signer,
+        delegation: None,
         fast_peers: Vec::new(),
         fallback_peers: Vec::new(),
         runtime_config: cfg,
@@ -753,12 +872,7 @@ fn erasure_mode_from_shards(
 ) -> ErasureCodingMode {
     shards
         .first()
-        .map(|shard| match shard.header.erasure_mode {
-            veil_codec::shard::ShardErasureMode::Systematic => ErasureCodingMode::Systematic,
-            veil_codec::shard::ShardErasureMode::HardenedNonSystematic => {
-                ErasureCodingMode::HardenedNonSystematic
-            }
-        })
+        .map(|shard| veil_fec::sharder::erasure_mode_from_wire(shard.header.erasure_mode))
         .unwrap_or(fallback)
 }
 
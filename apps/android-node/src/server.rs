@@ -12,6 +12,7 @@ use base64::Engine;
 use tracing::info;
 use uuid::Uuid;
 use veil_core::ObjectRoot;
+use veil_crypto::nip19::decode_nsec;
 
 use crate::api::{
     AppPreferencesPublishRequest, AppPreferencesPublishResponse, BlockPublishRequest,
@@ -23,17 +24,18 @@ use crate::api::{
     FollowPublishRequest, FollowPublishResponse, GroupKeyShareRequest, GroupKeyShareResponse,
     GroupMessagePublishRequest, GroupMessagePublishResponse, GroupMessageTextPublishRequest,
     GroupMetadataPublishRequest, GroupMetadataPublishResponse, HealthResponse,
-    IdentityExportResponse, IdentityImportRequest, IdentityResponse, IdentityRotateResponse,
-    ListPublishRequest, ListPublishResponse, LiveStatusPublishRequest, LiveStatusPublishResponse,
-    MediaPublishRequest, MediaPublishResponse, MutePublishRequest, MutePublishResponse,
-    ObjectFetchResponse, ObjectPublishRequest, ObjectPublishResponse, PolicyConfigRequest,
-    PolicyConfigResponse, PolicyListsResponse, PolicySetRequest, PolicySetResponse,
-    PolicySummaryResponse, PollPublishRequest, PollPublishResponse, PollVotePublishRequest,
-    PollVotePublishResponse, PostPublishRequest, PostPublishResponse, ProfilePublishRequest,
-    ProfilePublishResponse, PublishRequest, PublishResponse, ReactionPublishRequest,
-    ReactionPublishResponse, RepostPublishRequest, RepostPublishResponse, ShardFetchResponse,
-    StatusResponse, SubscribeRequest, SubscribeResponse, SubscriptionListResponse,
-    UnsubscribeRequest, UnsubscribeResponse, ZapPublishRequest, ZapPublishResponse,
+    IdentityImportRequest, IdentityResponse, IdentityRotateResponse, KeystoreRekeyRequest,
+    KeystoreRekeyResponse, ListPublishRequest, ListPublishResponse, LiveStatusPublishRequest,
+    LiveStatusPublishResponse, MediaPublishRequest, MediaPublishResponse, MutePublishRequest,
+    MutePublishResponse, ObjectFetchResponse, ObjectPublishRequest, ObjectPublishResponse,
+    PolicyConfigRequest, PolicyConfigResponse, PolicyListsResponse, PolicySetRequest,
+    PolicySetResponse, PolicySummaryResponse, PollPublishRequest, PollPublishResponse,
+    PollVotePublishRequest, PollVotePublishResponse, PostPublishRequest, PostPublishResponse,
+    ProfilePublishRequest, ProfilePublishResponse, PublishRequest, PublishResponse,
+    ReactionPublishRequest, ReactionPublishResponse, RepostPublishRequest, RepostPublishResponse,
+    ShardFetchResponse, StatusResponse, SubscribeRequest, SubscribeResponse,
+    SubscriptionListResponse, UnsubscribeRequest, UnsubscribeResponse, ZapPublishRequest,
+    ZapPublishResponse,
 };
 use crate::discovery::{
     build_self_contact, handle_discovery_announce, handle_discovery_gossip,
@@ -65,6 +67,7 @@ pub fn build_router(state: AppState) -> Router {
         .route("/identity/rotate", post(rotate_identity))
         .route("/identity/export", get(export_identity))
         .route("/identity/import", post(import_identity))
+        .route("/identity/rekey", post(rekey_keystore))
         .route("/publish", post(publish))
         .route("/publish_object", post(publish_object))
         .route("/profile", post(publish_profile))
@@ -278,13 +281,17 @@ async fn rotate_identity(State(state): State<AppState>, headers: HeaderMap) -> R
         return StatusCode::UNAUTHORIZED.into_response();
     }
     let identity = state.node.rotate_identity();
+    let (device, delegation) = identity.device_credentials();
+    let device_pubkey_hex = hex::encode(delegation.device_pubkey);
     state
         .protocol
-        .update_identity(identity.public_key, identity.signer())
+        .update_identity(identity.public_key, device, Some(delegation))
         .await;
     Json(IdentityRotateResponse {
         public_key_hex: identity.public_key_hex(),
         rotated: true,
+        device_index: identity.device_index,
+        device_pubkey_hex,
     })
     .into_response()
 }
@@ -303,12 +310,7 @@ async fn export_identity(State(state): State<AppState>, headers: HeaderMap) -> R
         )
             .into_response();
     }
-    let (public_key_hex, secret_key_hex) = state.node.export_identity();
-    Json(IdentityExportResponse {
-        public_key_hex,
-        secret_key_hex,
-    })
-    .into_response()
+    Json(state.node.export_identity()).into_response()
 }
 
 async fn import_identity(
@@ -319,11 +321,25 @@ async fn import_identity(
     if !authorized(&headers, &state.auth_token) {
         return StatusCode::UNAUTHORIZED.into_response();
     }
-    match state.node.import_identity(request.secret_key_hex) {
+    let imported = match (request.secret_key_hex, request.nsec, request.mnemonic) {
+        (Some(secret_key_hex), None, None) => state.node.import_identity(secret_key_hex),
+        (None, Some(nsec), None) => decode_nsec(&nsec)
+            .map_err(|e| e.to_string())
+            .and_then(|secret| state.node.import_identity(hex::encode(secret))),
+        (None, None, Some(phrase)) => state.node.import_identity_mnemonic(&phrase),
+        _ => {
+            return bad_request(
+                "invalid_identity",
+                "provide exactly one of secret_key_hex, nsec or mnemonic",
+            )
+        }
+    };
+    match imported {
         Ok(identity) => {
+            let (device, delegation) = identity.device_credentials();
             state
                 .protocol
-                .update_identity(identity.public_key, identity.signer())
+                .update_identity(identity.public_key, device, Some(delegation))
                 .await;
             Json(IdentityResponse {
                 public_key_hex: identity.public_key_hex(),
@@ -334,6 +350,47 @@ async fn import_identity(
     }
 }
 
+async fn rekey_keystore(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<KeystoreRekeyRequest>,
+) -> Response {
+    if !authorized(&headers, &state.auth_token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let (credential, restart_env) = match (request.passphrase, request.key_file) {
+        (Some(passphrase), None) if !passphrase.is_empty() => {
+            (passphrase.into_bytes(), "VEIL_NODE_KEYSTORE_PASSPHRASE")
+        }
+        (None, Some(path)) => match std::fs::read(&path) {
+            Ok(bytes) if !bytes.is_empty() => (bytes, "VEIL_NODE_KEYSTORE_KEY_FILE"),
+            _ => return bad_request("invalid_key_file", "key file is unreadable or empty"),
+        },
+        _ => {
+            return bad_request(
+                "invalid_credential",
+                "provide exactly one of passphrase or key_file",
+            )
+        }
+    };
+    // Argon2id is deliberately slow; keep it off the async workers.
+    let node = state.node.clone();
+    match tokio::task::spawn_blocking(move || node.rekey_keystore(&credential)).await {
+        Ok(Ok(())) => {
+            tracing::warn!(
+                "keystore rekeyed; set {restart_env} to the new credential before restarting"
+            );
+            Json(KeystoreRekeyResponse {
+                rekeyed: true,
+                restart_env: restart_env.to_string(),
+            })
+            .into_response()
+        }
+        Ok(Err(err)) => bad_request("rekey_failed", &err),
+        Err(err) => bad_request("rekey_failed", &err.to_string()),
+    }
+}
+
 async fn publish_profile(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -1702,6 +1759,13 @@ async fn subscribe(
         return StatusCode::UNAUTHORIZED.into_response();
     }
     let changed = state.node.subscribe(&request.tag);
+    if changed {
+        let contacts = state.node.contacts();
+        match state.protocol.backfill_channel(&request.tag, &contacts).await {
+            Ok(sent) => tracing::debug!("backfill requested for {} ({sent} sends)", request.tag),
+            Err(err) => tracing::debug!("backfill skipped for {}: {err}", request.tag),
+        }
+    }
     Json(SubscribeResponse {
         subscribed: changed,
     })
@@ -1821,6 +1885,7 @@ mod tests {
     use super::*;
     use crate::api::ContactBundle;
     use crate::api::DiscoveryAnnounceResponse;
+    use crate::api::IdentityExportResponse;
     use axum::body::{Body, Bytes};
     use base64::Engine;
     use http::{Request, StatusCode};
@@ -2031,6 +2096,11 @@ mod tests {
         let parsed: IdentityExportResponse = serde_json::from_slice(&bytes).unwrap();
         assert_eq!(parsed.public_key_hex.len(), 64);
         assert_eq!(parsed.secret_key_hex.len(), 64);
+        assert_eq!(
+            decode_nsec(&parsed.nsec).map(hex::encode),
+            Ok(parsed.secret_key_hex)
+        );
+        assert!(parsed.mnemonic.is_some());
     }
 
     #[tokio::test]
@@ -2551,7 +2621,13 @@ mod tests {
         let rotated_parsed: IdentityRotateResponse =
             serde_json::from_slice(&rotated_bytes).unwrap();
         assert!(rotated_parsed.rotated);
-        assert_ne!(first_parsed.public_key_hex, rotated_parsed.public_key_hex);
+        // Rotation replaces the device subkey; the root (and feed tags) stay.
+        assert_eq!(first_parsed.public_key_hex, rotated_parsed.public_key_hex);
+        assert_eq!(rotated_parsed.device_index, 1);
+        assert_ne!(
+            rotated_parsed.device_pubkey_hex,
+            first_parsed.public_key_hex
+        );
     }
 
     #[tokio::test]
@@ -9,20 +9,31 @@ use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::api::{
-    CacheStatus, ContactBundle, EventEnvelope, LaneDetail, LaneHealth, LaneStatus, PublishRequest,
-    QueueStatus, StatusResponse,
+    CacheStatus, ContactBundle, EventEnvelope, IdentityExportResponse, LaneDetail, LaneHealth,
+    LaneStatus, PublishRequest, QueueStatus, StatusResponse,
 };
 use crate::discovery::{DiscoveryStateHandle, DiscoveryTable};
 use crate::secure_message::{
     decrypt_direct_message_payload, decrypt_group_key_share_payload, decrypt_group_message_payload,
 };
 use crate::state_store::{GroupKeyRecord, IdentityRecord, QueueItem, StateStore, StoreSnapshot};
-use veil_crypto::signing::{NostrSigner, Signer};
+use veil_codec::delegation::{DelegationCertificate, CAP_ALL};
+use veil_crypto::kdf::Argon2Params;
+use veil_crypto::mnemonic::{IdentitySeed, IDENTITY_SEED_LEN};
+use veil_crypto::nip19::{encode_npub, encode_nsec};
+use veil_crypto::signing::{NostrSigner, NostrVerifier, Signer};
+use veil_node::clock::{clock_step, SystemClock, DEFAULT_STEP_DURATION};
+use veil_node::identity::{delegate_device, DelegationScope, DEFAULT_DELEGATION_LIFETIME_SECS};
 use veil_node::policy::{
-    parse_endorsement_payload, EndorsementIngestResult, LocalWotPolicy, WotConfig, WotSummary,
+    parse_endorsement_payload, EndorsementIngestResult, KeyEventIngestResult, LocalWotPolicy,
+    WotConfig, WotSummary,
 };
+use veil_node::revocation::{parse_migration_payload, parse_revocation_payload};
 use veil_schema_feed::FeedBundle;
 
+/// Backdating applied to device certificates for peers with slow clocks.
+const DELEGATION_CLOCK_SKEW_SECS: u64 = 5 * 60;
+
 #[derive(Debug, Clone)]
 pub struct NodeState {
     inner: Arc<Mutex<StateInner>>,
@@ -56,18 +67,70 @@ struct StateInner {
     discovery: DiscoveryStateHandle,
 }
 
+/// Root identity plus the index of the device subkey that signs objects.
+///
+/// `public_key` is the root key that feed tags derive from; rotation only
+/// advances `device_index`. Identities created or imported from a mnemonic
+/// keep their `seed` so the phrase can be exported again.
 #[derive(Debug, Clone)]
 pub struct NodeIdentity {
     pub public_key: [u8; 32],
     pub secret_key: [u8; 32],
     pub encrypt_key: [u8; 32],
+    pub device_index: u32,
+    pub seed: Option<IdentitySeed>,
 }
 
 impl NodeIdentity {
+    /// Builds an identity from a raw Nostr secret.
+    pub fn from_secret(secret_key: [u8; 32]) -> Result<Self, String> {
+        let signer = NostrSigner::from_secret(secret_key)
+            .map_err(|_| "secret key is not a valid Nostr secp256k1 secret".to_string())?;
+        Ok(Self {
+            public_key: signer.public_key(),
+            secret_key,
+            encrypt_key: veil_crypto::keys::derive_encrypt_key(&secret_key),
+            device_index: 0,
+            seed: None,
+        })
+    }
+
+    /// Derives the identity and its feed key from a mnemonic seed.
+    pub fn from_seed(seed: IdentitySeed) -> Self {
+        let secret_key = seed.signing_secret();
+        let public_key = NostrSigner::from_secret(secret_key)
+            .expect("seed-derived secret is a valid scalar")
+            .public_key();
+        Self {
+            public_key,
+            secret_key,
+            encrypt_key: seed.encrypt_key(),
+            device_index: 0,
+            seed: Some(seed),
+        }
+    }
+
     pub fn signer(&self) -> NostrSigner {
         NostrSigner::from_secret(self.secret_key).expect("stored identity secret must be valid")
     }
 
+    /// Derives the current device signer and a fresh root-signed certificate
+    /// valid from now for [`DEFAULT_DELEGATION_LIFETIME_SECS`].
+    pub fn device_credentials(&self) -> (NostrSigner, DelegationCertificate) {
+        let now = now_millis() / 1_000;
+        delegate_device(
+            &self.secret_key,
+            self.device_index,
+            DelegationScope {
+                capabilities: CAP_ALL,
+                namespaces: Vec::new(),
+                not_before: now.saturating_sub(DELEGATION_CLOCK_SKEW_SECS),
+                not_after: now.saturating_add(DEFAULT_DELEGATION_LIFETIME_SECS),
+            },
+        )
+        .expect("stored identity secret must be valid")
+    }
+
     pub fn public_key_hex(&self) -> String {
         hex::encode(self.public_key)
     }
@@ -81,30 +144,56 @@ impl NodeIdentity {
             encrypt_key_hex: hex::encode(self.encrypt_key),
             encrypt_key_enc_nonce_b64: None,
             encrypt_key_enc_b64: None,
+            device_index: self.device_index,
+            seed_hex: self
+                .seed
+                .as_ref()
+                .map(|seed| hex::encode(seed.entropy()))
+                .unwrap_or_default(),
         }
     }
 }
 
 impl NodeState {
     pub fn new(version: impl Into<String>) -> Self {
-        Self::new_with_store(version, None)
+        Self::from_snapshot(version, None, StoreSnapshot::default())
     }
 
-    pub fn new_with_store(version: impl Into<String>, store_path: Option<PathBuf>) -> Self {
-        let (events, _) = broadcast::channel(128);
+    /// Restores state from `store_path`, failing rather than starting with a
+    /// fresh identity when the stored keystore cannot be opened.
+    pub fn new_with_store(
+        version: impl Into<String>,
+        store_path: Option<PathBuf>,
+    ) -> Result<Self, String> {
         let store = store_path.map(StateStore::new);
-        let snapshot = store.as_ref().map(StateStore::load).unwrap_or_default();
+        let snapshot = match &store {
+            Some(store) => store
+                .load()
+                .map_err(|e| format!("state keystore could not be opened: {e}"))?,
+            None => StoreSnapshot::default(),
+        };
+        Ok(Self::from_snapshot(version, store, snapshot))
+    }
+
+    fn from_snapshot(
+        version: impl Into<String>,
+        store: Option<StateStore>,
+        snapshot: StoreSnapshot,
+    ) -> Self {
+        let (events, _) = broadcast::channel(128);
         let identity = snapshot
             .identity
             .as_ref()
             .and_then(parse_identity)
             .unwrap_or_else(generate_identity);
         let queue_pending = snapshot.queue.len() as u64;
-        let wot_policy = snapshot
+        let mut wot_policy = snapshot
             .policy_json
             .as_deref()
             .and_then(|json| LocalWotPolicy::import_json(json).ok())
             .unwrap_or_default();
+        // Endorsements are ingested at the protocol engine's clock steps.
+        wot_policy.adopt_clock_steps(clock_step(&SystemClock, DEFAULT_STEP_DURATION));
         let contacts = snapshot.contacts.clone();
         let group_keys = parse_group_keys(&snapshot.group_keys);
         let subscriptions: HashSet<String> = snapshot.subscriptions.iter().cloned().collect();
@@ -128,6 +217,7 @@ impl NodeState {
                     feed_history: event_buffer.iter().cloned().collect(),
                     subscriptions: subscriptions.iter().cloned().collect(),
                     group_keys: snapshot.group_keys.clone(),
+                    keystore_b64: None,
                 });
             }
         }
@@ -171,10 +261,12 @@ impl NodeState {
         inner.identity.clone()
     }
 
+    /// Rotates the device signing subkey, keeping the root identity (and so
+    /// every follower's feed tag) unchanged.
     pub fn rotate_identity(&self) -> NodeIdentity {
         let mut inner = self.inner.lock().expect("state lock");
-        let identity = generate_identity();
-        inner.identity = identity.clone();
+        inner.identity.device_index = inner.identity.device_index.wrapping_add(1);
+        let identity = inner.identity.clone();
         if let Some(store) = &inner.store {
             store.persist(&snapshot_from_inner(&inner));
         }
@@ -382,12 +474,16 @@ impl NodeState {
         inner.subscriptions.iter().cloned().collect()
     }
 
-    pub fn export_identity(&self) -> (String, String) {
+    pub fn export_identity(&self) -> IdentityExportResponse {
         let inner = self.inner.lock().expect("state lock");
-        (
-            inner.identity.public_key_hex(),
-            hex::encode(inner.identity.secret_key),
-        )
+        let identity = &inner.identity;
+        IdentityExportResponse {
+            public_key_hex: identity.public_key_hex(),
+            secret_key_hex: hex::encode(identity.secret_key),
+            npub: encode_npub(&identity.public_key),
+            nsec: encode_nsec(&identity.secret_key),
+            mnemonic: identity.seed.as_ref().map(IdentitySeed::mnemonic),
+        }
     }
 
     pub fn import_identity(&self, secret_key_hex: String) -> Result<NodeIdentity, String> {
@@ -397,16 +493,16 @@ impl NodeState {
         }
         let mut secret_key = [0u8; 32];
         secret_key.copy_from_slice(&sec_bytes);
-        let signer = NostrSigner::from_secret(secret_key)
-            .map_err(|_| "secret key is not a valid Nostr secp256k1 secret".to_string())?;
-        let public_key = signer.public_key();
-        let encrypt_key = veil_crypto::keys::derive_encrypt_key(&secret_key);
-        let identity = NodeIdentity {
-            public_key,
-            secret_key,
-            encrypt_key,
-        };
+        self.install_identity(NodeIdentity::from_secret(secret_key)?)
+    }
+
+    /// Restores an identity and its derived keys from a 24-word mnemonic.
+    pub fn import_identity_mnemonic(&self, phrase: &str) -> Result<NodeIdentity, String> {
+        let seed = IdentitySeed::from_mnemonic(phrase).map_err(|e| e.to_string())?;
+        self.install_identity(NodeIdentity::from_seed(seed))
+    }
 
+    fn install_identity(&self, identity: NodeIdentity) -> Result<NodeIdentity, String> {
         let mut inner = self.inner.lock().expect("state lock");
         inner.identity = identity.clone();
         if let Some(store) = &inner.store {
@@ -415,6 +511,24 @@ impl NodeState {
         Ok(identity)
     }
 
+    /// Re-seals persisted secrets under a new keystore passphrase or key file.
+    ///
+    /// The node reads its credential from the environment at startup, so the
+    /// new one must be configured there before the next restart.
+    pub fn rekey_keystore(&self, credential: &[u8]) -> Result<(), String> {
+        let mut inner = self.inner.lock().expect("state lock");
+        let snapshot = snapshot_from_inner(&inner);
+        let store = inner
+            .store
+            .as_mut()
+            .ok_or_else(|| "node state is not persisted".to_string())?;
+        store
+            .rekey(credential, Argon2Params::default())
+            .map_err(|e| e.to_string())?;
+        store.persist(&snapshot);
+        Ok(())
+    }
+
     pub fn policy_summary(&self) -> WotSummary {
         let inner = self.inner.lock().expect("state lock");
         inner.wot_policy.summary()
@@ -751,6 +865,59 @@ impl NodeState {
         changed
     }
 
+    /// Applies signed revocation/migration notices from a WoT payload.
+    ///
+    /// Migrations also repoint contacts (follows) at the new key.
+    pub fn ingest_key_event_payload(&self, payload: &[u8]) -> bool {
+        let mut items = vec![payload.to_vec()];
+        if let Ok(batch) = ciborium::de::from_reader::<Vec<Vec<u8>>, _>(payload) {
+            items = batch;
+        }
+
+        let mut inner = self.inner.lock().expect("state lock");
+        let mut changed = false;
+        for item in items {
+            if let Some(revocation) = parse_revocation_payload(&item, &NostrVerifier) {
+                let migrated_to = inner.wot_policy.migrated_to(revocation.revoked);
+                changed |= matches!(
+                    inner.wot_policy.ingest_revocation(&revocation),
+                    KeyEventIngestResult::Applied
+                );
+                // The revocation rolled a migration back; point follows home.
+                if let Some(new) = migrated_to {
+                    changed = true;
+                    repoint_contacts(&mut inner, &new, &revocation.revoked);
+                }
+            } else if let Some(migration) = parse_migration_payload(&item, &NostrVerifier) {
+                if !matches!(
+                    inner.wot_policy.ingest_migration(&migration),
+                    KeyEventIngestResult::Applied
+                ) {
+                    continue;
+                }
+                changed = true;
+                repoint_contacts(&mut inner, &migration.old, &migration.new);
+            }
+        }
+        if changed {
+            let summary = inner.wot_policy.summary();
+            emit_event_locked(
+                &mut inner,
+                "policy_updated",
+                serde_json::json!({
+                    "trusted": summary.trusted,
+                    "muted": summary.muted,
+                    "blocked": summary.blocked,
+                    "endorsements": summary.endorsements,
+                    "revoked": summary.revoked,
+                    "migrated": summary.migrated,
+                }),
+            );
+            self.persist_policy_locked(&mut inner);
+        }
+        changed
+    }
+
     pub fn subscribe(&self, tag: &str) -> bool {
         let mut inner = self.inner.lock().expect("state lock");
         inner.subscriptions.insert(tag.to_string())
@@ -885,6 +1052,7 @@ fn snapshot_from_inner(inner: &StateInner) -> StoreSnapshot {
         feed_history: inner.event_buffer.iter().cloned().collect(),
         subscriptions: inner.subscriptions.iter().cloned().collect(),
         group_keys: flatten_group_keys(&inner.group_keys),
+        keystore_b64: None,
     }
 }
 
@@ -895,6 +1063,21 @@ fn update_queue_counts(inner: &mut StateInner) {
 const EVENT_VERSION: u16 = 1;
 const EVENT_BUFFER_MAX: usize = 256;
 
+/// Points contacts (follows) held under `from` at `to`.
+fn repoint_contacts(inner: &mut StateInner, from: &[u8; 32], to: &[u8; 32]) {
+    let (from_hex, to_hex) = (hex::encode(from), hex::encode(to));
+    let mut moved = Vec::new();
+    for contact in inner.contacts.iter_mut() {
+        if contact.pubkey_hex.eq_ignore_ascii_case(&from_hex) {
+            contact.pubkey_hex = to_hex.clone();
+            moved.push(contact.clone());
+        }
+    }
+    for contact in moved {
+        inner.discovery.upsert(contact);
+    }
+}
+
 fn emit_event_locked(
     inner: &mut StateInner,
     event: &str,
@@ -949,6 +1132,11 @@ fn parse_identity(record: &IdentityRecord) -> Option<NodeIdentity> {
     if derived != public_key {
         return None;
     }
+    let seed = hex::decode(&record.seed_hex)
+        .ok()
+        .and_then(|bytes| <[u8; IDENTITY_SEED_LEN]>::try_from(bytes.as_slice()).ok())
+        .map(IdentitySeed::from_entropy)
+        .filter(|seed| seed.signing_secret() == secret_key);
 
     let encrypt_key = if record.encrypt_key_hex.len() == 64 {
         let bytes = hex::decode(&record.encrypt_key_hex).ok()?;
@@ -971,6 +1159,8 @@ fn parse_identity(record: &IdentityRecord) -> Option<NodeIdentity> {
         public_key,
         secret_key,
         encrypt_key,
+        device_index: record.device_index,
+        seed,
     })
 }
 
@@ -1021,20 +1211,9 @@ fn random_key_id() -> String {
 }
 
 fn generate_identity() -> NodeIdentity {
-    let (secret_key, signer) = loop {
-        let mut secret_key = [0u8; 32];
-        rand::thread_rng().fill_bytes(&mut secret_key);
-        if let Ok(signer) = NostrSigner::from_secret(secret_key) {
-            break (secret_key, signer);
-        }
-    };
-    let public_key = signer.public_key();
-    let encrypt_key = veil_crypto::keys::derive_encrypt_key(&secret_key);
-    NodeIdentity {
-        public_key,
-        secret_key,
-        encrypt_key,
-    }
+    let mut entropy = [0u8; IDENTITY_SEED_LEN];
+    rand::thread_rng().fill_bytes(&mut entropy);
+    NodeIdentity::from_seed(IdentitySeed::from_entropy(entropy))
 }
 
 #[cfg(test)]
@@ -1052,13 +1231,13 @@ mod tests {
     fn persists_queue_to_disk() {
         let dir = tempdir().expect("tempdir");
         let path = dir.path().join("node_state.json");
-        let state = NodeState::new_with_store("0.1-test", Some(path.clone()));
+        let state = NodeState::new_with_store("0.1-test", Some(path.clone())).expect("state");
         let _ = state.enqueue_publish(PublishRequest {
             namespace: 32,
             payload: "hello".to_string(),
         });
 
-        let restored = NodeState::new_with_store("0.1-test", Some(path));
+        let restored = NodeState::new_with_store("0.1-test", Some(path)).expect("state");
         let status = restored.status();
         assert_eq!(status.queue.pending, 1);
     }
@@ -1095,26 +1274,91 @@ mod tests {
         );
     }
 
+    #[test]
+    fn locked_keystore_fails_startup_without_overwriting() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("node_state.json");
+        let params = Argon2Params {
+            memory_kib: 8,
+            iterations: 1,
+            parallelism: 1,
+        };
+        StateStore::new_with_keystore(&path, b"other credential", params).persist(&StoreSnapshot {
+            identity: Some(generate_identity().to_record()),
+            ..StoreSnapshot::default()
+        });
+        let sealed = std::fs::read(&path).expect("state file");
+
+        assert!(NodeState::new_with_store("0.1-test", Some(path.clone())).is_err());
+        assert_eq!(std::fs::read(&path).expect("state file"), sealed);
+    }
+
     #[test]
     fn identity_persists_across_restart() {
         let dir = tempdir().expect("tempdir");
         let path = dir.path().join("node_state.json");
-        let state = NodeState::new_with_store("0.1-test", Some(path.clone()));
+        let state = NodeState::new_with_store("0.1-test", Some(path.clone())).expect("state");
         let first = state.identity();
-        let restored = NodeState::new_with_store("0.1-test", Some(path));
+        let restored = NodeState::new_with_store("0.1-test", Some(path)).expect("state");
         let second = restored.identity();
         assert_eq!(first.public_key, second.public_key);
         assert_eq!(first.secret_key, second.secret_key);
     }
 
+    #[test]
+    fn mnemonic_import_restores_identity_and_derived_keys() {
+        let dir = tempdir().expect("tempdir");
+        let original = NodeState::new("0.1-test");
+        let export = original.export_identity();
+        let phrase = export.mnemonic.expect("new identities have a mnemonic");
+        assert_eq!(phrase.split(' ').count(), 24);
+        assert!(export.nsec.starts_with("nsec1") && export.npub.starts_with("npub1"));
+
+        let path = dir.path().join("node_state.json");
+        let restored = NodeState::new_with_store("0.1-test", Some(path.clone())).expect("state");
+        let imported = restored
+            .import_identity_mnemonic(&phrase)
+            .expect("import mnemonic");
+        let before = original.identity();
+        assert_eq!(imported.secret_key, before.secret_key);
+        assert_eq!(imported.public_key, before.public_key);
+        assert_eq!(imported.encrypt_key, before.encrypt_key);
+
+        let reopened = NodeState::new_with_store("0.1-test", Some(path)).expect("state");
+        assert_eq!(reopened.export_identity().mnemonic, Some(phrase.clone()));
+        assert!(restored
+            .import_identity_mnemonic(&phrase.replacen("a", "e", 1))
+            .is_err());
+    }
+
+    #[test]
+    fn rotation_changes_device_key_but_keeps_root() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("node_state.json");
+        let state = NodeState::new_with_store("0.1-test", Some(path.clone())).expect("state");
+        let before = state.identity();
+        let rotated = state.rotate_identity();
+        assert_eq!(rotated.public_key, before.public_key);
+        assert_eq!(rotated.device_index, before.device_index + 1);
+
+        let (old_device, _) = before.device_credentials();
+        let (new_device, cert) = rotated.device_credentials();
+        assert_ne!(old_device.public_key(), new_device.public_key());
+        assert_eq!(cert.root_pubkey, rotated.public_key);
+        assert_eq!(cert.device_pubkey, new_device.public_key());
+
+        let restored = NodeState::new_with_store("0.1-test", Some(path)).expect("state");
+        assert_eq!(restored.identity().device_index, rotated.device_index);
+    }
+
     #[test]
     fn policy_persists_across_restart() {
         let dir = tempdir().expect("tempdir");
         let path = dir.path().join("node_state.json");
-        let state = NodeState::new_with_store("0.1-test", Some(path.clone()));
+        let state = NodeState::new_with_store("0.1-test", Some(path.clone())).expect("state");
         state.trust_pubkey([0x11; 32]);
 
-        let restored = NodeState::new_with_store("0.1-test", Some(path));
+        let restored = NodeState::new_with_store("0.1-test", Some(path)).expect("state");
         let summary = restored.policy_summary();
         assert_eq!(summary.trusted, 1);
     }
@@ -1139,6 +1383,53 @@ mod tests {
         assert_eq!(summary.endorsements, 1);
     }
 
+    #[test]
+    fn key_migration_moves_trust_and_follows() {
+        let state = NodeState::new("0.1-test");
+        let old = NostrSigner::from_secret([0x41; 32]).expect("old");
+        let new = NostrSigner::from_secret([0x42; 32]).expect("new");
+        state.trust_pubkey(old.public_key());
+        state.add_contact(ContactBundle {
+            peer_id: "peer-old".to_string(),
+            ws_url: None,
+            quic_addr: None,
+            pubkey_hex: hex::encode(old.public_key()),
+            rpc_url: None,
+            lan_addrs: Vec::new(),
+        });
+
+        let payload =
+            veil_node::revocation::build_migration_payload(&old, &new, 5).expect("migration");
+        assert!(state.ingest_key_event_payload(&payload));
+        assert!(!state.ingest_key_event_payload(&payload));
+        assert_eq!(state.policy_summary().migrated, 1);
+        assert!(
+            state
+                .wot_policy()
+                .explain_publisher(new.public_key(), 10)
+                .trusted_override
+        );
+        assert_eq!(
+            state.contacts()[0].pubkey_hex,
+            hex::encode(new.public_key())
+        );
+
+        let revocation =
+            veil_node::revocation::build_revocation_payload(&new, 9).expect("revocation");
+        assert!(state.ingest_key_event_payload(&revocation));
+        assert_eq!(state.policy_summary().revoked, 1);
+
+        // Revoking the old key undoes the migration and moves follows back.
+        let revocation =
+            veil_node::revocation::build_revocation_payload(&old, 9).expect("revocation");
+        assert!(state.ingest_key_event_payload(&revocation));
+        assert_eq!(state.policy_summary().migrated, 0);
+        assert_eq!(
+            state.contacts()[0].pubkey_hex,
+            hex::encode(old.public_key())
+        );
+    }
+
     #[test]
     fn queue_retries_with_backoff() {
         let state = NodeState::new("0.1-test");
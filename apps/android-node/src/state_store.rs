@@ -6,6 +6,12 @@ use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use veil_crypto::aead::{AeadCipher, XChaCha20Poly1305Cipher};
+use veil_crypto::kdf::Argon2Params;
+use veil_crypto::keystore::{Keystore, KeystoreError, KeystoreKey, KEYSTORE_SALT_LEN};
+
+const KEYSTORE_IDENTITY_SECRET: &str = "identity/secret_key";
+const KEYSTORE_IDENTITY_ENCRYPT: &str = "identity/encrypt_key";
+const KEYSTORE_IDENTITY_SEED: &str = "identity/seed";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueItem {
@@ -28,6 +34,9 @@ pub struct StoreSnapshot {
     pub subscriptions: Vec<String>,
     #[serde(default)]
     pub group_keys: Vec<GroupKeyRecord>,
+    /// Sealed keystore holding identity and group-key secrets.
+    #[serde(default)]
+    pub keystore_b64: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +54,12 @@ pub struct IdentityRecord {
     pub encrypt_key_enc_nonce_b64: Option<String>,
     #[serde(default)]
     pub encrypt_key_enc_b64: Option<String>,
+    /// Index of the current device subkey under the root identity.
+    #[serde(default)]
+    pub device_index: u32,
+    /// Mnemonic seed entropy the identity derives from, if any.
+    #[serde(default)]
+    pub seed_hex: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,27 +78,105 @@ pub struct GroupKeyRecord {
 pub struct StateStore {
     path: PathBuf,
     state_key: Option<[u8; 32]>,
+    keystore_key: Option<KeystoreKey>,
+    /// The file holds a keystore this store could not open; writing would
+    /// replace the secrets in it.
+    keystore_locked: bool,
 }
 
 impl StateStore {
+    /// Opens the store using credentials from the environment.
+    ///
+    /// Secrets go into an Argon2id keystore unlocked by
+    /// `VEIL_NODE_KEYSTORE_PASSPHRASE`, else the contents of
+    /// `VEIL_NODE_KEYSTORE_KEY_FILE`, else `VEIL_NODE_STATE_KEY_HEX`. The
+    /// state key also still decrypts secrets written by older versions.
     pub fn new(path: impl AsRef<Path>) -> Self {
-        let state_key = std::env::var("VEIL_NODE_STATE_KEY_HEX")
+        let state_key_hex = std::env::var("VEIL_NODE_STATE_KEY_HEX").ok();
+        let state_key = state_key_hex.as_deref().and_then(decode_state_key_hex);
+        let credential = std::env::var("VEIL_NODE_KEYSTORE_PASSPHRASE")
             .ok()
-            .and_then(|hex| decode_state_key_hex(&hex));
-        Self::new_with_state_key(path, state_key)
+            .map(String::into_bytes)
+            .or_else(|| {
+                let key_file = std::env::var("VEIL_NODE_KEYSTORE_KEY_FILE").ok()?;
+                fs::read(key_file)
+                    .map_err(|e| tracing::error!("keystore key file unreadable: {e}"))
+                    .ok()
+            })
+            .or_else(|| {
+                state_key
+                    .and(state_key_hex)
+                    .map(|hex| hex.trim().as_bytes().to_vec())
+            });
+        let mut store = Self::new_with_state_key(path, state_key);
+        if let Some(credential) = credential {
+            store.unlock_keystore(&credential, Argon2Params::default());
+        }
+        store
     }
 
     pub fn new_with_state_key(path: impl AsRef<Path>, state_key: Option<[u8; 32]>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let keystore_locked = read_sealed_keystore(&path).is_some();
         Self {
-            path: path.as_ref().to_path_buf(),
+            path,
             state_key,
+            keystore_key: None,
+            keystore_locked,
+        }
+    }
+
+    /// Opens the store with a keystore passphrase or key-file contents.
+    pub fn new_with_keystore(
+        path: impl AsRef<Path>,
+        credential: &[u8],
+        params: Argon2Params,
+    ) -> Self {
+        let mut store = Self::new_with_state_key(path, None);
+        store.unlock_keystore(credential, params);
+        store
+    }
+
+    /// Derives the keystore key, reusing the salt and costs of an existing
+    /// keystore so it can be opened.
+    fn unlock_keystore(&mut self, credential: &[u8], params: Argon2Params) {
+        let sealed = read_sealed_keystore(&self.path);
+        let derived = match &sealed {
+            Some(sealed) => KeystoreKey::derive_for(sealed, credential)
+                .and_then(|key| Keystore::open(sealed, &key).map(|_| key)),
+            None => KeystoreKey::derive(credential, random_salt(), params),
+        };
+        match derived {
+            Ok(key) => {
+                self.keystore_key = Some(key);
+                self.keystore_locked = false;
+            }
+            Err(e) => tracing::error!("keystore unlock failed: {e}"),
+        }
+    }
+
+    /// Whether the file holds a keystore this store could not open.
+    pub fn is_keystore_locked(&self) -> bool {
+        self.keystore_locked
+    }
+
+    /// Switches to a new passphrase or key file; secrets are re-sealed under
+    /// it on the next [`StateStore::persist`]. Refused while the existing
+    /// keystore is locked, since its secrets were never loaded.
+    pub fn rekey(&mut self, credential: &[u8], params: Argon2Params) -> Result<(), KeystoreError> {
+        if self.keystore_locked {
+            return Err(KeystoreError::WrongCredential);
         }
+        self.keystore_key = Some(KeystoreKey::derive(credential, random_salt(), params)?);
+        Ok(())
     }
 
-    pub fn load(&self) -> StoreSnapshot {
+    /// Reads the snapshot, failing when its keystore cannot be opened so the
+    /// caller never mistakes locked secrets for missing ones.
+    pub fn load(&self) -> Result<StoreSnapshot, KeystoreError> {
         let data = match fs::read(&self.path) {
             Ok(bytes) => bytes,
-            Err(_) => return StoreSnapshot::default(),
+            Err(_) => return Ok(StoreSnapshot::default()),
         };
         let mut snapshot = serde_json::from_slice::<StoreSnapshot>(&data).unwrap_or_default();
         if let (Some(key), Some(identity)) = (self.state_key, snapshot.identity.as_mut()) {
@@ -119,12 +212,45 @@ impl StateStore {
                 }
             }
         }
-        snapshot
+        if let Some(sealed) = snapshot.keystore_b64.take() {
+            let keystore = self.open_keystore(&sealed)?;
+            restore_from_keystore(&mut snapshot, &keystore);
+        }
+        Ok(snapshot)
+    }
+
+    fn open_keystore(&self, sealed_b64: &str) -> Result<Keystore, KeystoreError> {
+        let key = self
+            .keystore_key
+            .as_ref()
+            .ok_or(KeystoreError::WrongCredential)?;
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(sealed_b64)
+            .map_err(|_| KeystoreError::Malformed("keystore is not base64"))?;
+        Keystore::open(&sealed, key)
     }
 
     pub fn persist(&self, snapshot: &StoreSnapshot) {
+        if self.keystore_locked {
+            tracing::error!("state keystore is locked; refusing to overwrite it");
+            return;
+        }
         let mut to_store = snapshot.clone();
-        if let (Some(key), Some(identity)) = (self.state_key, to_store.identity.as_mut()) {
+        if let Some(key) = &self.keystore_key {
+            let mut nonce = [0u8; 24];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            let keystore = move_into_keystore(&mut to_store);
+            match keystore.seal(key, nonce) {
+                Ok(sealed) => {
+                    to_store.keystore_b64 =
+                        Some(base64::engine::general_purpose::STANDARD.encode(sealed));
+                }
+                Err(e) => {
+                    tracing::error!("state keystore seal failed: {e}");
+                    return;
+                }
+            }
+        } else if let (Some(key), Some(identity)) = (self.state_key, to_store.identity.as_mut()) {
             if identity.secret_key_hex.len() == 64 {
                 if let Some((nonce_b64, ciphertext_b64)) =
                     encrypt_secret_hex(key, identity.secret_key_hex.as_bytes())
@@ -163,6 +289,80 @@ impl StateStore {
     }
 }
 
+fn group_key_entry(record: &GroupKeyRecord) -> String {
+    format!("group_key/{}/{}", record.group_id, record.key_id)
+}
+
+/// Moves plaintext secrets out of `snapshot` into a keystore, dropping any
+/// legacy state-key ciphertexts.
+fn move_into_keystore(snapshot: &mut StoreSnapshot) -> Keystore {
+    let mut keystore = Keystore::new();
+    if let Some(identity) = snapshot.identity.as_mut() {
+        for (name, value) in [
+            (KEYSTORE_IDENTITY_SECRET, &mut identity.secret_key_hex),
+            (KEYSTORE_IDENTITY_ENCRYPT, &mut identity.encrypt_key_hex),
+            (KEYSTORE_IDENTITY_SEED, &mut identity.seed_hex),
+        ] {
+            if !value.is_empty() {
+                keystore.insert(name, std::mem::take(value).into_bytes());
+            }
+        }
+        identity.secret_key_enc_nonce_b64 = None;
+        identity.secret_key_enc_b64 = None;
+        identity.encrypt_key_enc_nonce_b64 = None;
+        identity.encrypt_key_enc_b64 = None;
+    }
+    for record in &mut snapshot.group_keys {
+        if !record.key_hex.is_empty() {
+            keystore.insert(
+                group_key_entry(record),
+                std::mem::take(&mut record.key_hex).into_bytes(),
+            );
+        }
+        record.key_enc_nonce_b64 = None;
+        record.key_enc_b64 = None;
+    }
+    keystore
+}
+
+fn restore_from_keystore(snapshot: &mut StoreSnapshot, keystore: &Keystore) {
+    let secret = |name: &str| {
+        keystore
+            .get(name)
+            .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok())
+    };
+    if let Some(identity) = snapshot.identity.as_mut() {
+        if let Some(value) = secret(KEYSTORE_IDENTITY_SECRET) {
+            identity.secret_key_hex = value;
+        }
+        if let Some(value) = secret(KEYSTORE_IDENTITY_ENCRYPT) {
+            identity.encrypt_key_hex = value;
+        }
+        if let Some(value) = secret(KEYSTORE_IDENTITY_SEED) {
+            identity.seed_hex = value;
+        }
+    }
+    for record in &mut snapshot.group_keys {
+        if let Some(value) = secret(&group_key_entry(record)) {
+            record.key_hex = value;
+        }
+    }
+}
+
+fn read_sealed_keystore(path: &Path) -> Option<Vec<u8>> {
+    let data = fs::read(path).ok()?;
+    let snapshot = serde_json::from_slice::<StoreSnapshot>(&data).ok()?;
+    base64::engine::general_purpose::STANDARD
+        .decode(snapshot.keystore_b64?)
+        .ok()
+}
+
+fn random_salt() -> [u8; KEYSTORE_SALT_LEN] {
+    let mut salt = [0u8; KEYSTORE_SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    salt
+}
+
 fn decode_state_key_hex(value: &str) -> Option<[u8; 32]> {
     let bytes = hex::decode(value.trim()).ok()?;
     if bytes.len() != 32 {
@@ -233,13 +433,15 @@ mod tests {
                 encrypt_key_hex: "cc".repeat(32),
                 encrypt_key_enc_nonce_b64: None,
                 encrypt_key_enc_b64: None,
+                device_index: 0,
+                seed_hex: String::new(),
             }),
             ..Default::default()
         };
         store.persist(&snapshot);
         let raw = fs::read_to_string(&path).expect("state file");
         assert!(!raw.contains(&"bb".repeat(32)));
-        let loaded = store.load();
+        let loaded = store.load().expect("load");
         assert_eq!(
             loaded
                 .identity
@@ -266,8 +468,90 @@ mod tests {
         store.persist(&snapshot);
         let raw = fs::read_to_string(&path).expect("state file");
         assert!(!raw.contains(&"cc".repeat(32)));
-        let loaded = store.load();
+        let loaded = store.load().expect("load");
         assert_eq!(loaded.group_keys.len(), 1);
         assert_eq!(loaded.group_keys[0].key_hex, "cc".repeat(32));
     }
+
+    const FAST_KDF: Argon2Params = Argon2Params {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn snapshot_with_secrets() -> StoreSnapshot {
+        StoreSnapshot {
+            identity: Some(IdentityRecord {
+                public_key_hex: "aa".repeat(32),
+                secret_key_hex: "bb".repeat(32),
+                secret_key_enc_nonce_b64: None,
+                secret_key_enc_b64: None,
+                encrypt_key_hex: "cc".repeat(32),
+                encrypt_key_enc_nonce_b64: None,
+                encrypt_key_enc_b64: None,
+                device_index: 0,
+                seed_hex: "ee".repeat(32),
+            }),
+            group_keys: vec![GroupKeyRecord {
+                group_id: "g".to_string(),
+                key_id: "k1".to_string(),
+                key_hex: "dd".repeat(32),
+                key_enc_nonce_b64: None,
+                key_enc_b64: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keystore_seals_identity_and_group_keys_under_passphrase() {
+        let tmp = tempdir().expect("tempdir");
+        let path = tmp.path().join("state.json");
+        let store = StateStore::new_with_keystore(&path, b"hunter2", FAST_KDF);
+        store.persist(&snapshot_with_secrets());
+        let raw = fs::read_to_string(&path).expect("state file");
+        for secret in ["bb", "cc", "dd", "ee"] {
+            assert!(!raw.contains(&secret.repeat(32)));
+        }
+
+        let reopened = StateStore::new_with_keystore(&path, b"hunter2", FAST_KDF);
+        let loaded = reopened.load().expect("load");
+        let identity = loaded.identity.expect("identity");
+        assert_eq!(identity.secret_key_hex, "bb".repeat(32));
+        assert_eq!(identity.encrypt_key_hex, "cc".repeat(32));
+        assert_eq!(identity.seed_hex, "ee".repeat(32));
+        assert_eq!(loaded.group_keys[0].key_hex, "dd".repeat(32));
+
+        let wrong = StateStore::new_with_keystore(&path, b"hunter3", FAST_KDF);
+        assert!(wrong.is_keystore_locked());
+        assert!(matches!(wrong.load(), Err(KeystoreError::WrongCredential)));
+        let sealed = fs::read(&path).expect("state file");
+        wrong.persist(&StoreSnapshot::default());
+        assert_eq!(fs::read(&path).expect("state file"), sealed);
+
+        let mut wrong = wrong;
+        assert!(wrong.rekey(b"hunter4", FAST_KDF).is_err());
+    }
+
+    #[test]
+    fn keystore_rekeys_from_legacy_state_key_to_key_file() {
+        let tmp = tempdir().expect("tempdir");
+        let path = tmp.path().join("state.json");
+        StateStore::new_with_state_key(&path, Some([1u8; 32])).persist(&snapshot_with_secrets());
+
+        let mut store = StateStore::new_with_state_key(&path, Some([1u8; 32]));
+        let snapshot = store.load().expect("load");
+        let key_file = [0x42u8; 32];
+        store.rekey(&key_file, FAST_KDF).expect("rekey");
+        store.persist(&snapshot);
+
+        let loaded = StateStore::new_with_keystore(&path, &key_file, FAST_KDF)
+            .load()
+            .expect("load");
+        assert_eq!(
+            loaded.identity.expect("identity").secret_key_hex,
+            "bb".repeat(32)
+        );
+        assert_eq!(loaded.group_keys[0].key_hex, "dd".repeat(32));
+    }
 }
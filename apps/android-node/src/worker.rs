@@ -8,6 +8,7 @@ use crate::api::QueueWorkerConfig;
 use crate::discovery::handle_discovery_payload;
 use crate::protocol::ProtocolEngine;
 use crate::state::NodeState;
+use veil_core::types::NAMESPACE_WOT;
 use veil_node::receive::ReceiveEvent;
 
 const APP_TARGET_BATCH_SIZE_BYTES: usize = 96 * 1024;
@@ -72,9 +73,12 @@ impl QueueWorker {
                         &tag,
                         flags,
                     );
+                    let key_events_changed = namespace == NAMESPACE_WOT
+                        && worker.state.ingest_key_event_payload(&payload);
                     if worker
                         .state
-                        .ingest_endorsement_payload(&payload, worker.step)
+                        .ingest_endorsement_payload(&payload, worker.protocol.current_step())
+                        || key_events_changed
                     {
                         worker
                             .protocol
@@ -11,6 +11,8 @@ pub struct VpsConfig {
     pub state_path: PathBuf,
     pub node_key_path: PathBuf,
     pub node_key: Option<String>,
+    pub node_keystore_passphrase: Option<String>,
+    pub node_keystore_key_file: Option<PathBuf>,
     pub quic_cert_path: PathBuf,
     pub quic_key_path: PathBuf,
     #[serde(with = "humantime_serde")]
@@ -63,8 +65,23 @@ pub struct VpsConfig {
     pub nostr_bridge_persist_every_updates: usize,
     #[serde(deserialize_with = "deserialize_list")]
     pub required_signed_namespaces: Vec<String>,
+    pub signature_batch: bool,
+    pub signature_batch_max: usize,
+    pub backfill_enabled: bool,
+    #[serde(deserialize_with = "deserialize_list")]
+    pub pow_stamp_namespaces: Vec<String>,
     #[serde(deserialize_with = "deserialize_list")]
     pub quic_trusted_certs: Vec<String>,
+    pub mailbox_enabled: bool,
+    pub mailbox_db_path: PathBuf,
+    #[serde(with = "humantime_serde")]
+    pub mailbox_retention: Duration,
+    pub mailbox_max_shards_per_tag: usize,
+    pub mailbox_max_bytes_per_tag: usize,
+    pub mailbox_max_tags_per_owner: usize,
+    pub mailbox_max_fetch_shards: usize,
+    pub dht_enabled: bool,
+    pub dht_advertise_addr: Option<String>,
 }
 
 fn deserialize_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
@@ -113,6 +130,8 @@ impl VpsConfig {
             .set_default("state_path", "data/veil-vps-node-state.cbor")?
             .set_default("node_key_path", "data/node_identity.key")?
             .set_default("node_key", None::<String>)?
+            .set_default("node_keystore_passphrase", None::<String>)?
+            .set_default("node_keystore_key_file", None::<String>)?
             .set_default("quic_cert_path", "data/quic_cert.der")?
             .set_default("quic_key_path", "data/quic_key.der")?
             .set_default("snapshot_interval", "60s")?
@@ -151,7 +170,19 @@ impl VpsConfig {
             .set_default("blocked_peers", Vec::<String>::new())?
             .set_default("nostr_bridge_relays", Vec::<String>::new())?
             .set_default("required_signed_namespaces", Vec::<String>::new())?
-            .set_default("quic_trusted_certs", Vec::<String>::new())?;
+            .set_default("signature_batch", false)?
+            .set_default("backfill_enabled", false)?
+            .set_default("signature_batch_max", 64)?
+            .set_default("pow_stamp_namespaces", Vec::<String>::new())?
+            .set_default("quic_trusted_certs", Vec::<String>::new())?
+            .set_default("mailbox_enabled", false)?
+            .set_default("mailbox_db_path", "data/mailbox.db")?
+            .set_default("mailbox_retention", "7d")?
+            .set_default("mailbox_max_shards_per_tag", 4096)?
+            .set_default("mailbox_max_bytes_per_tag", 16 * 1024 * 1024)?
+            .set_default("mailbox_max_tags_per_owner", 64)?
+            .set_default("mailbox_max_fetch_shards", 512)?
+            .set_default("dht_enabled", false)?;
 
         if let Some(path) = config_path {
             if path.extension().and_then(|ext| ext.to_str()) == Some("env") {
@@ -245,6 +276,16 @@ mod tests {
         assert_eq!(cfg.nostr_bridge_persist_every_updates, 32);
         assert!(!cfg.ble_enabled);
         assert_eq!(cfg.ble_mtu, 180);
+        assert!(!cfg.mailbox_enabled);
+        assert_eq!(cfg.mailbox_db_path, PathBuf::from("data/mailbox.db"));
+        assert_eq!(cfg.mailbox_retention, Duration::from_secs(7 * 24 * 3600));
+        assert_eq!(cfg.mailbox_max_shards_per_tag, 4096);
+        assert_eq!(cfg.mailbox_max_bytes_per_tag, 16 * 1024 * 1024);
+        assert_eq!(cfg.mailbox_max_tags_per_owner, 64);
+        assert_eq!(cfg.mailbox_max_fetch_shards, 512);
+        assert!(!cfg.dht_enabled);
+        assert!(!cfg.backfill_enabled);
+        assert!(cfg.dht_advertise_addr.is_none());
     }
 
     #[test]
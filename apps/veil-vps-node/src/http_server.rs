@@ -12,12 +12,17 @@ use serde::Deserialize;
 use serde_json::json;
 use tower_http::cors::CorsLayer;
 
+use crate::mailbox_db::MailboxStore;
 use crate::settings_db::SettingsStore;
 use crate::{
     decode_nostr_secret_input, logger::LogBuffer, now_unix_secs, AdminAuthState, AdminLoginRequest,
     AdminSettingUpsertRequest, MetricsState,
 };
-use veil_crypto::signing::{NostrSigner, Signer};
+use veil_crypto::signing::{NostrSigner, NostrVerifier, Signer};
+use veil_node::mailbox::{MailboxFetchRequest, MailboxRegistration};
+
+/// Clock skew tolerated on signed mailbox requests.
+const MAILBOX_MAX_CLOCK_SKEW_SECS: u64 = 300;
 
 #[derive(Clone)]
 pub struct VpsAppState {
@@ -29,6 +34,8 @@ pub struct VpsAppState {
     pub shutdown: Arc<AtomicBool>,
     pub log_buffer: Arc<LogBuffer>,
     pub runtime_config: Arc<Mutex<veil_node::config::NodeRuntimeConfig>>,
+    pub mailbox: Option<Arc<Mutex<MailboxStore>>>,
+    pub mailbox_max_fetch_shards: usize,
 }
 
 pub fn build_router(state: VpsAppState) -> Router {
@@ -62,6 +69,8 @@ pub fn build_router(state: VpsAppState) -> Router {
         .route("/discovery/announce", post(discovery_announce))
         .route("/discovery/lookup", post(discovery_lookup))
         .route("/discovery/gossip", post(discovery_gossip))
+        .route("/mailbox/register", post(mailbox_register))
+        .route("/mailbox/fetch", post(mailbox_fetch))
         .route("/latest-posts", get(latest_posts))
         .route("/latest-posts/", get(latest_posts))
         .route("/ws", get(ws_error_handler))
@@ -521,6 +530,103 @@ async fn discovery_gossip(
     Json(veil_android_node::DiscoveryGossipResponse { contacts })
 }
 
+// --- Mailbox Handlers ---
+
+async fn mailbox_register(
+    State(state): State<VpsAppState>,
+    Json(registration): Json<MailboxRegistration>,
+) -> impl IntoResponse {
+    let Some(mailbox) = state.mailbox.as_ref() else {
+        return (StatusCode::NOT_FOUND, "mailbox role disabled").into_response();
+    };
+    let now = now_unix_secs();
+    if let Err(err) = registration.verify(&NostrVerifier, now, MAILBOX_MAX_CLOCK_SKEW_SECS) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"ok": false, "error": err.to_string()})),
+        )
+            .into_response();
+    }
+    let mut store = mailbox.lock().unwrap_or_else(|e| e.into_inner());
+    match store.register(&registration, now) {
+        Ok(outcome) => Json(json!({
+            "ok": true,
+            "accepted_tags": outcome.accepted.iter().map(hex::encode).collect::<Vec<_>>(),
+            "rejected_tags": outcome.rejected.iter().map(hex::encode).collect::<Vec<_>>(),
+            "expires_at": outcome.expires_at,
+        }))
+        .into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"ok": false, "error": err})),
+        )
+            .into_response(),
+    }
+}
+
+async fn mailbox_fetch(
+    State(state): State<VpsAppState>,
+    Json(request): Json<MailboxFetchRequest>,
+) -> impl IntoResponse {
+    let Some(mailbox) = state.mailbox.as_ref() else {
+        return (StatusCode::NOT_FOUND, "mailbox role disabled").into_response();
+    };
+    let now = now_unix_secs();
+    if let Err(err) = request.verify(&NostrVerifier, now, MAILBOX_MAX_CLOCK_SKEW_SECS) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"ok": false, "error": err.to_string()})),
+        )
+            .into_response();
+    }
+    let digest = match request.signing_digest() {
+        Ok(digest) => digest,
+        Err(err) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"ok": false, "error": err.to_string()})),
+            )
+                .into_response()
+        }
+    };
+    let limit = (request.max_shards as usize).min(state.mailbox_max_fetch_shards);
+    let mut store = mailbox.lock().unwrap_or_else(|e| e.into_inner());
+    // A fetch drains the mailbox, so each signed request is served once.
+    let expires_at = request
+        .issued_at
+        .saturating_add(MAILBOX_MAX_CLOCK_SKEW_SECS);
+    match store.claim_fetch(&digest, expires_at, now) {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({"ok": false, "error": "mailbox fetch request already used"})),
+            )
+                .into_response()
+        }
+        Err(err) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"ok": false, "error": err})),
+            )
+                .into_response()
+        }
+    }
+    match store.drain(&request.owner_pubkey, &request.tags, limit, now) {
+        Ok((shards, remaining)) => Json(json!({
+            "ok": true,
+            "shards": shards.iter().map(hex::encode).collect::<Vec<_>>(),
+            "remaining": remaining,
+        }))
+        .into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"ok": false, "error": err})),
+        )
+            .into_response(),
+    }
+}
+
 // --- Admin Policy Handlers ---
 
 async fn admin_policy_summary(State(state): State<VpsAppState>, headers: HeaderMap) -> impl IntoResponse {
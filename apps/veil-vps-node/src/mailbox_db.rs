@@ -0,0 +1,628 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tracing::{debug, warn};
+use veil_codec::shard::decode_shard_cbor;
+use veil_core::hash::blake3_32;
+use veil_core::Tag;
+use veil_node::mailbox::MailboxRegistration;
+
+/// Inbound shards queued for the background mailbox writer.
+const CAPTURE_QUEUE_DEPTH: usize = 1024;
+
+/// Retention and quota limits applied to every registered mailbox.
+#[derive(Debug, Clone, Copy)]
+pub struct MailboxLimits {
+    /// Longest time a shard or registration is retained, in seconds.
+    pub retention_secs: u64,
+    /// Maximum retained shards per registered tag.
+    pub max_shards_per_tag: usize,
+    /// Maximum retained shard bytes per registered tag.
+    pub max_bytes_per_tag: usize,
+    /// Maximum tags a single owner may hold registered at once.
+    pub max_tags_per_owner: usize,
+}
+
+/// Result of applying a verified registration.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MailboxRegisterOutcome {
+    pub accepted: Vec<Tag>,
+    pub rejected: Vec<Tag>,
+    pub expires_at: u64,
+}
+
+/// Durable shard retention for registered rendezvous tags.
+///
+/// Registered tags are mirrored in memory so the inbound hot path can skip
+/// shards for unregistered tags without touching the database.
+pub struct MailboxStore {
+    conn: Connection,
+    limits: MailboxLimits,
+    registered: Arc<RwLock<HashSet<Tag>>>,
+}
+
+/// Hot-path handle that hands registered-tag shards to a writer thread.
+///
+/// [`offer`](Self::offer) only consults the in-memory tag set; the SQLite
+/// insert and quota enforcement run on the writer so a slow disk never
+/// stalls the receive loop. Shards are dropped when the queue is full.
+#[derive(Clone)]
+pub struct MailboxCapture {
+    registered: Arc<RwLock<HashSet<Tag>>>,
+    max_bytes: usize,
+    tx: SyncSender<(Vec<u8>, u64)>,
+}
+
+impl MailboxCapture {
+    /// Spawns the writer thread for `store`; it exits once every handle drops.
+    pub fn spawn(store: Arc<Mutex<MailboxStore>>) -> Self {
+        let (registered, max_bytes) = {
+            let guard = store.lock().unwrap_or_else(|e| e.into_inner());
+            (
+                Arc::clone(&guard.registered),
+                guard.limits.max_bytes_per_tag,
+            )
+        };
+        let (tx, rx) = mpsc::sync_channel::<(Vec<u8>, u64)>(CAPTURE_QUEUE_DEPTH);
+        thread::spawn(move || {
+            for (bytes, now_secs) in rx {
+                let mut guard = store.lock().unwrap_or_else(|e| e.into_inner());
+                if let Err(err) = guard.capture(&bytes, now_secs) {
+                    warn!("mailbox: failed to retain shard: {err}");
+                }
+            }
+        });
+        Self {
+            registered,
+            max_bytes,
+            tx,
+        }
+    }
+
+    /// Queues `shard_bytes` for retention if its tag is registered.
+    pub fn offer(&self, shard_bytes: &[u8], now_secs: u64) {
+        if shard_bytes.len() > self.max_bytes {
+            return;
+        }
+        {
+            let registered = self.registered.read().unwrap_or_else(|e| e.into_inner());
+            if registered.is_empty() {
+                return;
+            }
+            let Ok(shard) = decode_shard_cbor(shard_bytes) else {
+                return;
+            };
+            if !registered.contains(&shard.header.tag) {
+                return;
+            }
+        }
+        if let Err(TrySendError::Full(_)) = self.tx.try_send((shard_bytes.to_vec(), now_secs)) {
+            debug!("mailbox: capture queue full, dropping shard");
+        }
+    }
+}
+
+impl MailboxStore {
+    pub fn open(path: &Path, limits: MailboxLimits) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    format!("failed to create directory {}: {}", parent.display(), e)
+                })?;
+            }
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| format!("open mailbox db at {}: {}", path.display(), e))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             PRAGMA synchronous=NORMAL;
+             PRAGMA busy_timeout=5000;
+             CREATE TABLE IF NOT EXISTS mailboxes (
+               tag BLOB PRIMARY KEY,
+               owner BLOB NOT NULL,
+               registered_at INTEGER NOT NULL,
+               expires_at INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS mailboxes_owner ON mailboxes(owner);
+             CREATE TABLE IF NOT EXISTS mailbox_shards (
+               tag BLOB NOT NULL,
+               shard_hash BLOB NOT NULL,
+               bytes BLOB NOT NULL,
+               stored_at INTEGER NOT NULL,
+               PRIMARY KEY (tag, shard_hash)
+             );
+             CREATE INDEX IF NOT EXISTS mailbox_shards_age ON mailbox_shards(tag, stored_at);
+             CREATE TABLE IF NOT EXISTS mailbox_fetches (
+               digest BLOB PRIMARY KEY,
+               expires_at INTEGER NOT NULL
+             );",
+        )
+        .map_err(|e| format!("init mailbox db: {e}"))?;
+        let mut store = Self {
+            conn,
+            limits,
+            registered: Arc::new(RwLock::new(HashSet::new())),
+        };
+        store.reload_registered()?;
+        Ok(store)
+    }
+
+    pub fn has_registrations(&self) -> bool {
+        !self
+            .registered
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    pub fn is_registered(&self, tag: &Tag) -> bool {
+        self.registered
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(tag)
+    }
+
+    /// Applies a registration whose signature has already been verified.
+    ///
+    /// Tags held by a different unexpired owner, and tags beyond the owner's
+    /// quota, are rejected. Accepted tags have their expiry refreshed.
+    pub fn register(
+        &mut self,
+        registration: &MailboxRegistration,
+        now_secs: u64,
+    ) -> Result<MailboxRegisterOutcome, String> {
+        let expires_at = registration
+            .expires_at
+            .min(now_secs.saturating_add(self.limits.retention_secs));
+        let owner = registration.owner_pubkey.to_vec();
+        let mut owned: usize =
+            self.conn
+                .query_row(
+                    "SELECT COUNT(*) FROM mailboxes WHERE owner=?1 AND expires_at>?2",
+                    params![owner, now_secs as i64],
+                    |r| r.get::<_, i64>(0),
+                )
+                .map_err(|e| format!("count owner mailboxes: {e}"))? as usize;
+        let mut outcome = MailboxRegisterOutcome {
+            expires_at,
+            ..MailboxRegisterOutcome::default()
+        };
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("register begin: {e}"))?;
+        for tag in &registration.tags() {
+            let existing: Option<(Vec<u8>, i64)> = tx
+                .query_row(
+                    "SELECT owner, expires_at FROM mailboxes WHERE tag=?1",
+                    [tag.as_slice()],
+                    |r| Ok((r.get(0)?, r.get(1)?)),
+                )
+                .optional()
+                .map_err(|e| format!("lookup mailbox: {e}"))?;
+            let live_owner = existing
+                .as_ref()
+                .filter(|(_, exp)| *exp as u64 > now_secs)
+                .map(|(o, _)| o.as_slice());
+            match live_owner {
+                Some(o) if o != owner.as_slice() => {
+                    outcome.rejected.push(*tag);
+                    continue;
+                }
+                None if owned >= self.limits.max_tags_per_owner => {
+                    outcome.rejected.push(*tag);
+                    continue;
+                }
+                None => owned += 1,
+                Some(_) => {}
+            }
+            if existing.is_some() && live_owner.is_none() {
+                // Lapsed mailbox changing hands: never leak the old owner's shards.
+                tx.execute("DELETE FROM mailbox_shards WHERE tag=?1", [tag.as_slice()])
+                    .map_err(|e| format!("clear lapsed mailbox: {e}"))?;
+            }
+            tx.execute(
+                "INSERT INTO mailboxes (tag, owner, registered_at, expires_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(tag) DO UPDATE
+                 SET owner=excluded.owner, expires_at=excluded.expires_at",
+                params![tag.as_slice(), owner, now_secs as i64, expires_at as i64],
+            )
+            .map_err(|e| format!("upsert mailbox: {e}"))?;
+            outcome.accepted.push(*tag);
+        }
+        tx.commit().map_err(|e| format!("register commit: {e}"))?;
+        self.registered
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(outcome.accepted.iter().copied());
+        Ok(outcome)
+    }
+
+    /// Retains an inbound shard if its tag is registered.
+    ///
+    /// Returns `Ok(true)` when the shard was newly stored. Oldest shards for
+    /// the tag are evicted to stay within the per-mailbox quota.
+    pub fn capture(&mut self, shard_bytes: &[u8], now_secs: u64) -> Result<bool, String> {
+        if !self.has_registrations() || shard_bytes.len() > self.limits.max_bytes_per_tag {
+            return Ok(false);
+        }
+        let Ok(shard) = decode_shard_cbor(shard_bytes) else {
+            return Ok(false);
+        };
+        let tag = shard.header.tag;
+        if !self.is_registered(&tag) {
+            return Ok(false);
+        }
+        let inserted = self
+            .conn
+            .execute(
+                "INSERT OR IGNORE INTO mailbox_shards (tag, shard_hash, bytes, stored_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    tag.as_slice(),
+                    blake3_32(shard_bytes).as_slice(),
+                    shard_bytes,
+                    now_secs as i64
+                ],
+            )
+            .map_err(|e| format!("store mailbox shard: {e}"))?;
+        if inserted > 0 {
+            self.enforce_quota(&tag)?;
+        }
+        Ok(inserted > 0)
+    }
+
+    fn enforce_quota(&self, tag: &Tag) -> Result<(), String> {
+        let (count, bytes): (i64, i64) = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(LENGTH(bytes)), 0) FROM mailbox_shards WHERE tag=?1",
+                [tag.as_slice()],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .map_err(|e| format!("mailbox usage: {e}"))?;
+        let mut count = count as usize;
+        let mut bytes = bytes as usize;
+        if count <= self.limits.max_shards_per_tag && bytes <= self.limits.max_bytes_per_tag {
+            return Ok(());
+        }
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT shard_hash, LENGTH(bytes) FROM mailbox_shards
+                 WHERE tag=?1 ORDER BY stored_at ASC, rowid ASC",
+            )
+            .map_err(|e| format!("quota prepare: {e}"))?;
+        let rows = stmt
+            .query_map([tag.as_slice()], |r| {
+                Ok((r.get::<_, Vec<u8>>(0)?, r.get::<_, i64>(1)?))
+            })
+            .map_err(|e| format!("quota query: {e}"))?;
+        let mut evict = Vec::new();
+        for row in rows {
+            if count <= self.limits.max_shards_per_tag && bytes <= self.limits.max_bytes_per_tag {
+                break;
+            }
+            let (hash, len) = row.map_err(|e| format!("quota row: {e}"))?;
+            count -= 1;
+            bytes = bytes.saturating_sub(len as usize);
+            evict.push(hash);
+        }
+        for hash in evict {
+            self.conn
+                .execute(
+                    "DELETE FROM mailbox_shards WHERE tag=?1 AND shard_hash=?2",
+                    params![tag.as_slice(), hash],
+                )
+                .map_err(|e| format!("quota evict: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Records a verified fetch request so it cannot be replayed.
+    ///
+    /// Returns `Ok(false)` if `digest` was already served. The record is kept
+    /// until `expires_at`, after which the request fails its freshness check.
+    pub fn claim_fetch(
+        &mut self,
+        digest: &[u8; 32],
+        expires_at: u64,
+        now_secs: u64,
+    ) -> Result<bool, String> {
+        self.conn
+            .execute(
+                "DELETE FROM mailbox_fetches WHERE expires_at<?1",
+                [now_secs as i64],
+            )
+            .map_err(|e| format!("expire fetches: {e}"))?;
+        let inserted = self
+            .conn
+            .execute(
+                "INSERT OR IGNORE INTO mailbox_fetches (digest, expires_at) VALUES (?1, ?2)",
+                params![digest.as_slice(), expires_at as i64],
+            )
+            .map_err(|e| format!("record fetch: {e}"))?;
+        Ok(inserted > 0)
+    }
+
+    /// Returns and deletes up to `limit` oldest shards from `owner`'s mailboxes.
+    ///
+    /// Tags not registered to `owner` are ignored. The second element is the
+    /// number of shards still retained for the requested tags afterwards.
+    pub fn drain(
+        &mut self,
+        owner: &[u8; 32],
+        tags: &[Tag],
+        limit: usize,
+        now_secs: u64,
+    ) -> Result<(Vec<Vec<u8>>, usize), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("drain begin: {e}"))?;
+        let mut shards = Vec::new();
+        let mut remaining = 0usize;
+        for tag in tags {
+            let owned: bool = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM mailboxes WHERE tag=?1 AND owner=?2 AND expires_at>?3",
+                    params![tag.as_slice(), owner.as_slice(), now_secs as i64],
+                    |r| r.get::<_, i64>(0),
+                )
+                .map_err(|e| format!("drain owner check: {e}"))?
+                > 0;
+            if !owned {
+                continue;
+            }
+            let rows = {
+                let mut stmt = tx
+                    .prepare(
+                        "SELECT rowid, bytes FROM mailbox_shards
+                         WHERE tag=?1 ORDER BY stored_at ASC, rowid ASC LIMIT ?2",
+                    )
+                    .map_err(|e| format!("drain prepare: {e}"))?;
+                let take = limit.saturating_sub(shards.len()) as i64;
+                let mapped = stmt
+                    .query_map(params![tag.as_slice(), take], |r| {
+                        Ok((r.get::<_, i64>(0)?, r.get::<_, Vec<u8>>(1)?))
+                    })
+                    .map_err(|e| format!("drain query: {e}"))?;
+                mapped
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("drain row: {e}"))?
+            };
+            for (rowid, bytes) in rows {
+                tx.execute("DELETE FROM mailbox_shards WHERE rowid=?1", [rowid])
+                    .map_err(|e| format!("drain delete: {e}"))?;
+                shards.push(bytes);
+            }
+            remaining += tx
+                .query_row(
+                    "SELECT COUNT(*) FROM mailbox_shards WHERE tag=?1",
+                    [tag.as_slice()],
+                    |r| r.get::<_, i64>(0),
+                )
+                .map_err(|e| format!("drain remaining: {e}"))? as usize;
+        }
+        tx.commit().map_err(|e| format!("drain commit: {e}"))?;
+        Ok((shards, remaining))
+    }
+
+    /// Drops shards older than the retention window and lapsed registrations.
+    pub fn prune(&mut self, now_secs: u64) -> Result<usize, String> {
+        let cutoff = now_secs.saturating_sub(self.limits.retention_secs) as i64;
+        let mut removed = self
+            .conn
+            .execute("DELETE FROM mailbox_shards WHERE stored_at<?1", [cutoff])
+            .map_err(|e| format!("prune shards: {e}"))?;
+        removed += self
+            .conn
+            .execute(
+                "DELETE FROM mailbox_shards WHERE tag IN
+                 (SELECT tag FROM mailboxes WHERE expires_at<=?1)",
+                [now_secs as i64],
+            )
+            .map_err(|e| format!("prune lapsed shards: {e}"))?;
+        self.conn
+            .execute(
+                "DELETE FROM mailboxes WHERE expires_at<=?1",
+                [now_secs as i64],
+            )
+            .map_err(|e| format!("prune mailboxes: {e}"))?;
+        self.reload_registered()?;
+        Ok(removed)
+    }
+
+    /// Number of live mailboxes and retained shards.
+    pub fn counts(&self) -> Result<(usize, usize), String> {
+        self.conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM mailboxes), (SELECT COUNT(*) FROM mailbox_shards)",
+                [],
+                |r| Ok((r.get::<_, i64>(0)? as usize, r.get::<_, i64>(1)? as usize)),
+            )
+            .map_err(|e| format!("mailbox counts: {e}"))
+    }
+
+    fn reload_registered(&mut self) -> Result<(), String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM mailboxes")
+            .map_err(|e| format!("load mailboxes prepare: {e}"))?;
+        let rows = stmt
+            .query_map([], |r| r.get::<_, Vec<u8>>(0))
+            .map_err(|e| format!("load mailboxes query: {e}"))?;
+        let mut registered = HashSet::new();
+        for row in rows {
+            let bytes = row.map_err(|e| format!("load mailboxes row: {e}"))?;
+            if let Ok(tag) = Tag::try_from(bytes.as_slice()) {
+                registered.insert(tag);
+            }
+        }
+        drop(stmt);
+        *self.registered.write().unwrap_or_else(|e| e.into_inner()) = registered;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use veil_codec::shard::encode_shard_cbor;
+    use veil_core::hash::blake3_32;
+    use veil_core::{Epoch, Namespace, Tag};
+    use veil_crypto::signing::{Ed25519Signer, Signer};
+    use veil_fec::sharder::object_to_shards;
+    use veil_node::mailbox::{MailboxRegistration, MailboxSlot};
+
+    use super::{MailboxCapture, MailboxLimits, MailboxStore};
+
+    fn limits() -> MailboxLimits {
+        MailboxLimits {
+            retention_secs: 86_400,
+            max_shards_per_tag: 4,
+            max_bytes_per_tag: 1 << 20,
+            max_tags_per_owner: 2,
+        }
+    }
+
+    fn shard_bytes(tag: Tag, payload: &[u8]) -> Vec<Vec<u8>> {
+        object_to_shards(payload, Namespace(1), Epoch(1), tag, blake3_32(payload))
+            .expect("shard")
+            .iter()
+            .map(|s| encode_shard_cbor(s).expect("encode"))
+            .collect()
+    }
+
+    fn slots(epochs: &[u32]) -> Vec<MailboxSlot> {
+        epochs
+            .iter()
+            .map(|&epoch| MailboxSlot {
+                namespace: Namespace(1),
+                epoch: Epoch(epoch),
+            })
+            .collect()
+    }
+
+    fn open() -> (tempfile::TempDir, MailboxStore) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = MailboxStore::open(&dir.path().join("mailbox.db"), limits()).expect("open");
+        (dir, store)
+    }
+
+    #[test]
+    fn registered_tag_shards_are_retained_and_drained_by_owner_only() {
+        let (_dir, mut store) = open();
+        let owner = Ed25519Signer::from_secret([1u8; 32]);
+        let other = Ed25519Signer::from_secret([2u8; 32]);
+        let reg = MailboxRegistration::sign(&owner, slots(&[7]), 100, 1_000_000).expect("sign");
+        let tag = reg.tags()[0];
+        let outcome = store.register(&reg, 100).expect("register");
+        assert_eq!(outcome.accepted, vec![tag]);
+        assert_eq!(outcome.expires_at, 100 + 86_400);
+
+        // The same slot under another key derives a different tag.
+        let other_reg =
+            MailboxRegistration::sign(&other, slots(&[7]), 100, 1_000_000).expect("sign");
+        assert_ne!(other_reg.tags(), vec![tag]);
+
+        let shards = shard_bytes(tag, b"queued while offline");
+        for bytes in &shards {
+            assert!(store.capture(bytes, 200).expect("capture"));
+        }
+        assert!(!store.capture(&shards[0], 201).expect("dedup"));
+        assert!(!store
+            .capture(&shard_bytes([9u8; 32], b"unregistered")[0], 200)
+            .expect("capture"));
+
+        let (none, _) = store
+            .drain(&other.public_key(), &[tag], 64, 300)
+            .expect("drain");
+        assert!(none.is_empty());
+        let (first, remaining) = store
+            .drain(&owner.public_key(), &[tag], 1, 300)
+            .expect("drain");
+        assert_eq!(first, vec![shards[0].clone()]);
+        assert_eq!(remaining, shards.len() - 1);
+        let (rest, remaining) = store
+            .drain(&owner.public_key(), &[tag], 64, 300)
+            .expect("drain");
+        assert_eq!(rest.len(), shards.len() - 1);
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn quotas_and_retention_are_enforced() {
+        let (dir, mut store) = open();
+        let owner = Ed25519Signer::from_secret([3u8; 32]);
+        let reg = MailboxRegistration::sign(&owner, slots(&[1, 2, 3]), 0, 1_000_000).expect("sign");
+        let tags = reg.tags();
+        let outcome = store.register(&reg, 0).expect("register");
+        assert_eq!(outcome.accepted.len(), 2);
+        assert_eq!(outcome.rejected, vec![tags[2]]);
+
+        for i in 0..6u8 {
+            let bytes = shard_bytes(tags[0], &[i; 64]);
+            store.capture(&bytes[0], u64::from(i)).expect("capture");
+        }
+        assert_eq!(store.counts().expect("counts"), (2, 4));
+        drop(store);
+
+        let mut store =
+            MailboxStore::open(&dir.path().join("mailbox.db"), limits()).expect("reopen");
+        assert!(store.is_registered(&tags[0]));
+        let renewed =
+            MailboxRegistration::sign(&owner, slots(&[1]), 80_000, 1_000_000).expect("sign");
+        assert_eq!(
+            store
+                .register(&renewed, 80_000)
+                .expect("renew")
+                .accepted
+                .len(),
+            1
+        );
+        store.prune(86_404).expect("prune");
+        assert_eq!(store.counts().expect("counts"), (1, 2));
+        store.prune(80_000 + 86_400).expect("prune");
+        assert_eq!(store.counts().expect("counts"), (0, 0));
+        assert!(!store.has_registrations());
+    }
+
+    #[test]
+    fn fetches_are_single_use_and_capture_runs_off_thread() {
+        let (_dir, mut store) = open();
+        let digest = [5u8; 32];
+        assert!(store.claim_fetch(&digest, 400, 100).expect("claim"));
+        assert!(!store.claim_fetch(&digest, 400, 200).expect("replay"));
+        assert!(store
+            .claim_fetch(&digest, 900, 401)
+            .expect("expired record"));
+
+        let owner = Ed25519Signer::from_secret([4u8; 32]);
+        let reg = MailboxRegistration::sign(&owner, slots(&[9]), 0, 1_000_000).expect("sign");
+        store.register(&reg, 0).expect("register");
+        let store = Arc::new(Mutex::new(store));
+        let capture = MailboxCapture::spawn(Arc::clone(&store));
+        let shards = shard_bytes(reg.tags()[0], b"captured on the writer thread");
+        capture.offer(&shards[0], 10);
+        capture.offer(&shard_bytes([9u8; 32], b"unregistered")[0], 10);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let (_, retained) = store.lock().unwrap().counts().expect("counts");
+            if retained == 1 {
+                break;
+            }
+            assert!(Instant::now() < deadline, "writer never stored the shard");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
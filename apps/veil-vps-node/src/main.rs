@@ -13,11 +13,12 @@ use tracing::{debug, error, info, warn};
 mod config;
 mod http_server;
 mod logger;
+mod mailbox_db;
 mod nostr_bridge;
 mod settings_db;
 
-use bech32::{Bech32, Hrp};
 use logger::{AdminLoggerLayer, LogBuffer};
+use mailbox_db::{MailboxCapture, MailboxLimits, MailboxStore};
 use nostr_bridge::{start_nostr_bridge, NostrBridgeConfig};
 use rand::RngCore;
 use rusqlite::{params, Connection};
@@ -31,12 +32,17 @@ use veil_core::hash::blake3_32;
 use veil_core::tags::derive_channel_feed_tag;
 use veil_core::{Epoch, Namespace};
 use veil_crypto::aead::XChaCha20Poly1305Cipher;
+use veil_crypto::kdf::Argon2Params;
+use veil_crypto::keystore::{is_sealed_keystore, Keystore, KeystoreKey, KEYSTORE_SALT_LEN};
+use veil_crypto::mnemonic::IdentitySeed;
+use veil_crypto::nip19::{decode_nsec, encode_npub, encode_nsec};
 use veil_crypto::signing::{NostrSigner, NostrVerifier, Signer};
 use veil_node::batch::FeedBatcher;
 use veil_node::config::{
     AdaptiveLaneScoringConfig, BloomExchangeConfig, NodeRuntimeConfig,
-    ProbabilisticForwardingConfig,
+    ProbabilisticForwardingConfig, SignatureBatchConfig,
 };
+use veil_node::dht::{decode_dht_packet, DhtConfig, DhtContact, DhtNode};
 use veil_node::persistence::{load_state_or_default, save_state_to_path};
 use veil_node::publish::{publish_queue_tick_multi_lane, PublishQueueTickParams};
 use veil_node::service::{NodeRuntime, NodeRuntimeCallbacks};
@@ -306,14 +312,105 @@ impl FallbackPeer {
     }
 }
 
+/// Serves the rendezvous DHT on a string-addressed lane.
+///
+/// DHT packets are answered here and never reach the runtime; everything
+/// else passes through untouched.
+struct DhtAdapter<A: TransportAdapter<Peer = String>> {
+    inner: A,
+    dht: Option<DhtNode>,
+    now_step: u64,
+}
+
+impl<A: TransportAdapter<Peer = String>> DhtAdapter<A> {
+    fn new(inner: A, dht: Option<DhtNode>) -> Self {
+        Self {
+            inner,
+            dht,
+            now_step: 0,
+        }
+    }
+
+    /// Seeds the routing table, expires queries and prunes stored shards.
+    fn poll_dht(&mut self, contacts: Vec<DhtContact>, now_step: u64) {
+        self.now_step = now_step;
+        if let Some(dht) = &mut self.dht {
+            dht.seed(contacts, now_step);
+            dht.poll(now_step);
+        }
+        self.flush_dht();
+    }
+
+    fn flush_dht(&mut self) {
+        let Some(dht) = &mut self.dht else {
+            return;
+        };
+        for (addr, packet) in dht.take_outbound() {
+            if self.inner.send(&addr, &packet).is_err() {
+                debug!("dht: send to {addr} failed");
+            }
+        }
+    }
+}
+
+impl<A: TransportAdapter<Peer = String>> TransportAdapter for DhtAdapter<A> {
+    type Peer = String;
+    type Error = A::Error;
+
+    fn send(&mut self, peer: &Self::Peer, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.inner.send(peer, bytes)
+    }
+
+    fn recv(&mut self) -> Option<(Self::Peer, Vec<u8>)> {
+        loop {
+            let (peer, bytes) = self.inner.recv()?;
+            let Some(dht) = &mut self.dht else {
+                return Some((peer, bytes));
+            };
+            if decode_dht_packet(&bytes).is_none() {
+                return Some((peer, bytes));
+            }
+            dht.handle_packet(&peer, &bytes, self.now_step);
+            self.flush_dht();
+        }
+    }
+
+    fn max_payload_hint(&self) -> Option<usize> {
+        self.inner.max_payload_hint()
+    }
+
+    fn can_send(&self) -> bool {
+        self.inner.can_send()
+    }
+
+    fn can_recv(&self) -> bool {
+        self.inner.can_recv()
+    }
+
+    fn health_snapshot(&self) -> TransportHealthSnapshot {
+        self.inner.health_snapshot()
+    }
+}
+
 struct RecordingAdapter<A: TransportAdapter> {
     inner: A,
     seen: Arc<Mutex<HashSet<A::Peer>>>,
+    mailbox: Option<MailboxCapture>,
 }
 
 impl<A: TransportAdapter> RecordingAdapter<A> {
     fn new(inner: A, seen: Arc<Mutex<HashSet<A::Peer>>>) -> Self {
-        Self { inner, seen }
+        Self {
+            inner,
+            seen,
+            mailbox: None,
+        }
+    }
+
+    /// Copies inbound shards for registered mailbox tags into durable storage.
+    fn with_mailbox(mut self, mailbox: Option<MailboxCapture>) -> Self {
+        self.mailbox = mailbox;
+        self
     }
 
     fn snapshot_seen(&self) -> Vec<A::Peer>
@@ -338,9 +435,13 @@ where
 
     fn recv(&mut self) -> Option<(Self::Peer, Vec<u8>)> {
         let item = self.inner.recv();
-        if let Some((ref peer, _)) = item {
+        if let Some((ref peer, ref bytes)) = item {
             let mut guard = self.seen.lock().unwrap_or_else(|e| e.into_inner());
             guard.insert(peer.clone());
+            drop(guard);
+            if let Some(mailbox) = &self.mailbox {
+                mailbox.offer(bytes, now_unix_secs());
+            }
         }
         item
     }
@@ -493,6 +594,17 @@ fn parse_required_signed_namespaces(values: &[String]) -> HashSet<u16> {
     out
 }
 
+/// Parses `namespace:difficulty` entries; malformed entries are skipped.
+fn parse_pow_stamp_namespaces(values: &[String]) -> HashMap<u16, u8> {
+    values
+        .iter()
+        .filter_map(|value| {
+            let (ns, bits) = value.split_once(':')?;
+            Some((ns.trim().parse().ok()?, bits.trim().parse().ok()?))
+        })
+        .collect()
+}
+
 fn parse_core_tags(values: &[String]) -> Vec<[u8; 32]> {
     values
         .iter()
@@ -618,21 +730,79 @@ fn merge_peers<T: Clone + Eq + Hash>(
     out
 }
 
-fn load_or_create_node_key(path: &Path) -> Result<[u8; 32], String> {
+/// Keystore entry holding the node's Nostr secret.
+const NODE_KEYSTORE_ENTRY: &str = "node/secret_key";
+
+/// Resolves the keystore credential: a passphrase, else key-file contents.
+fn node_keystore_credential(
+    passphrase: Option<&str>,
+    key_file: Option<&Path>,
+) -> Result<Option<Vec<u8>>, String> {
+    if let Some(passphrase) = passphrase.filter(|p| !p.is_empty()) {
+        return Ok(Some(passphrase.as_bytes().to_vec()));
+    }
+    match key_file {
+        Some(path) => fs::read(path)
+            .map(Some)
+            .map_err(|e| format!("read keystore key file: {e}")),
+        None => Ok(None),
+    }
+}
+
+/// Environment variable holding the new passphrase for `rekey`.
+const NEW_KEYSTORE_PASSPHRASE_ENV: &str = "VEIL_VPS_NEW_NODE_KEYSTORE_PASSPHRASE";
+
+/// New passphrase for `rekey`, kept out of argv: the first line of stdin
+/// when asked for, else the environment.
+fn rekey_passphrase(from_stdin: bool) -> Result<Option<String>, String> {
+    if from_stdin {
+        let mut line = String::new();
+        std::io::stdin()
+            .read_line(&mut line)
+            .map_err(|e| format!("read new passphrase: {e}"))?;
+        return Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()));
+    }
+    Ok(std::env::var(NEW_KEYSTORE_PASSPHRASE_ENV).ok())
+}
+
+/// Loads the node key, generating one if missing.
+///
+/// With a credential the file is an Argon2id keystore; a legacy raw, hex or
+/// nsec key file is sealed in place on first load.
+fn load_or_create_node_key(path: &Path, credential: Option<&[u8]>) -> Result<[u8; 32], String> {
     if path.exists() {
         let bytes = fs::read(path).map_err(|e| format!("read node key: {e}"))?;
+        if is_sealed_keystore(&bytes) {
+            let credential = credential.ok_or(
+                "node key is sealed; set node_keystore_passphrase or node_keystore_key_file",
+            )?;
+            let (keystore, _) = Keystore::open_with_credential(&bytes, credential)
+                .map_err(|e| format!("unlock node key: {e}"))?;
+            return keystore
+                .get_key(NODE_KEYSTORE_ENTRY)
+                .filter(|key| NostrSigner::from_secret(*key).is_ok())
+                .ok_or_else(|| "node keystore holds no valid node key".to_string());
+        }
+
+        let mut legacy = None;
         if bytes.len() == 32 {
             let mut out = [0_u8; 32];
             out.copy_from_slice(&bytes);
             if NostrSigner::from_secret(out).is_ok() {
-                return Ok(out);
+                legacy = Some(out);
             }
         }
-
-        if let Ok(content) = String::from_utf8(bytes) {
-            if let Some(key) = decode_nostr_secret_input(&content) {
-                return Ok(key);
+        if legacy.is_none() {
+            if let Ok(content) = String::from_utf8(bytes) {
+                legacy = decode_nostr_secret_input(&content);
+            }
+        }
+        if let Some(key) = legacy {
+            if credential.is_some() {
+                write_node_key(path, key, credential)?;
+                info!("sealed node key file into a keystore");
             }
+            return Ok(key);
         }
     }
 
@@ -643,14 +813,36 @@ fn load_or_create_node_key(path: &Path) -> Result<[u8; 32], String> {
             break candidate;
         }
     };
+    write_node_key(path, key, credential)?;
+    Ok(key)
+}
+
+/// Writes the node key raw, or sealed under `credential` when given.
+fn write_node_key(path: &Path, key: [u8; 32], credential: Option<&[u8]>) -> Result<(), String> {
+    let bytes = match credential {
+        Some(credential) => {
+            let mut salt = [0_u8; KEYSTORE_SALT_LEN];
+            let mut nonce = [0_u8; 24];
+            rand::thread_rng().fill_bytes(&mut salt);
+            rand::thread_rng().fill_bytes(&mut nonce);
+            let wrapping = KeystoreKey::derive(credential, salt, Argon2Params::default())
+                .map_err(|e| format!("derive keystore key: {e}"))?;
+            let mut keystore = Keystore::new();
+            keystore.insert(NODE_KEYSTORE_ENTRY, key.to_vec());
+            keystore
+                .seal(&wrapping, nonce)
+                .map_err(|e| format!("seal node key: {e}"))?
+        }
+        None => key.to_vec(),
+    };
     ensure_parent(path).map_err(|e| format!("create node key dir: {e}"))?;
-    fs::write(path, key).map_err(|e| format!("write node key: {e}"))?;
+    fs::write(path, bytes).map_err(|e| format!("write node key: {e}"))?;
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
     }
-    Ok(key)
+    Ok(())
 }
 
 #[derive(Debug, Default)]
@@ -817,6 +1009,7 @@ pub fn now_unix_secs() -> u64 {
         .unwrap_or(0)
 }
 
+/// Parses a node secret given as hex, `nsec`, or a 24-word mnemonic.
 pub fn decode_nostr_secret_input(value: &str) -> Option<[u8; 32]> {
     let trimmed = value.trim();
     if let Ok(bytes) = hex::decode(trimmed) {
@@ -824,19 +1017,12 @@ pub fn decode_nostr_secret_input(value: &str) -> Option<[u8; 32]> {
             return Some(key);
         }
     }
-    let (decoded_hrp, data) = bech32::decode(trimmed).ok()?;
-    if decoded_hrp.as_str() != "nsec" {
-        return None;
-    }
-    if let Ok(key) = <[u8; 32]>::try_from(data.as_slice()) {
-        return Some(key);
+    if trimmed.contains(char::is_whitespace) {
+        return IdentitySeed::from_mnemonic(trimmed)
+            .ok()
+            .map(|seed| seed.signing_secret());
     }
-    None
-}
-
-fn encode_nostr_nsec(secret: [u8; 32]) -> Option<String> {
-    let hrp = Hrp::parse("nsec").ok()?;
-    bech32::encode::<Bech32>(hrp, &secret).ok()
+    decode_nsec(trimmed).ok()
 }
 
 #[derive(Parser)]
@@ -866,8 +1052,18 @@ enum Commands {
         #[command(subcommand)]
         action: SettingsCommands,
     },
-    /// Export node identity (nsec)
+    /// Export node identity (nsec, hex and npub)
     Identity,
+    /// Re-seal the node key file under a new passphrase or key file
+    Rekey {
+        /// Read the new keystore passphrase from the first line of stdin
+        /// (otherwise VEIL_VPS_NEW_NODE_KEYSTORE_PASSPHRASE is used)
+        #[arg(long)]
+        new_passphrase_stdin: bool,
+        /// New keystore key file
+        #[arg(long)]
+        new_key_file: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1087,6 +1283,33 @@ async fn main() {
     #[cfg(feature = "ble")]
     let ble_mtu = config.ble_mtu;
     let peer_db_path = config.peer_db_path.clone();
+    let mailbox_max_fetch_shards = config.mailbox_max_fetch_shards;
+    let dht_enabled = config.dht_enabled;
+    let dht_advertise_addr = config.dht_advertise_addr.clone();
+    let mailbox = if config.mailbox_enabled {
+        let limits = MailboxLimits {
+            retention_secs: config.mailbox_retention.as_secs(),
+            max_shards_per_tag: config.mailbox_max_shards_per_tag,
+            max_bytes_per_tag: config.mailbox_max_bytes_per_tag,
+            max_tags_per_owner: config.mailbox_max_tags_per_owner,
+        };
+        match MailboxStore::open(&config.mailbox_db_path, limits) {
+            Ok(store) => {
+                info!(
+                    "mailbox: enabled at {} (retention={:?})",
+                    config.mailbox_db_path.display(),
+                    config.mailbox_retention
+                );
+                Some(Arc::new(Mutex::new(store)))
+            }
+            Err(err) => {
+                error!("mailbox: {err}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
     let max_dynamic_peers = config.max_dynamic_peers;
 
     let quic_bind = config.quic_bind.clone();
@@ -1119,6 +1342,10 @@ async fn main() {
     let nostr_bridge_max_seen = config.nostr_bridge_max_seen_ids;
     let nostr_bridge_persist_every = config.nostr_bridge_persist_every_updates;
     let required_signed = parse_required_signed_namespaces(&config.required_signed_namespaces);
+    let signature_batch = config.signature_batch;
+    let signature_batch_max = config.signature_batch_max;
+    let backfill_enabled = config.backfill_enabled;
+    let pow_stamp_difficulty = parse_pow_stamp_namespaces(&config.pow_stamp_namespaces);
 
     info!(
         "nostr bridge config: enabled={}, relays={:?}, channel={}, namespace={}, since={:?}, state={}",
@@ -1138,28 +1365,65 @@ async fn main() {
             }
             None => {
                 error!("fatal: invalid node_key provided in configuration/environment");
-                return;
+                std::process::exit(1);
             }
         }
     } else {
-        match load_or_create_node_key(&node_key_path) {
+        let credential = match node_keystore_credential(
+            config.node_keystore_passphrase.as_deref(),
+            config.node_keystore_key_file.as_deref(),
+        ) {
+            Ok(credential) => credential,
+            Err(err) => {
+                error!("fatal: {err}");
+                std::process::exit(1);
+            }
+        };
+        match load_or_create_node_key(&node_key_path, credential.as_deref()) {
             Ok(key) => key,
             Err(err) => {
                 error!("fatal: {err}");
-                return;
+                std::process::exit(1);
             }
         }
     };
     let node_signer = NostrSigner::from_secret(node_key).expect("node key validated");
     let node_pubkey = node_signer.public_key();
     let node_secret_hex = hex::encode(node_key);
-    let node_secret_nsec = encode_nostr_nsec(node_key).unwrap_or_default();
+    let node_secret_nsec = encode_nsec(&node_key);
     let node_pubkey_hex = hex::encode(node_pubkey);
     info!("node identity (nostr x-only pubkey): {node_pubkey_hex}");
 
     if let Some(Commands::Identity) = &cli.command {
         println!("nsec: {node_secret_nsec}");
         println!("hex:  {node_secret_hex}");
+        println!("npub: {}", encode_npub(&node_pubkey));
+        return;
+    }
+    if let Some(Commands::Rekey {
+        new_passphrase_stdin,
+        new_key_file,
+    }) = &cli.command
+    {
+        let resealed = rekey_passphrase(*new_passphrase_stdin)
+            .and_then(|passphrase| {
+                node_keystore_credential(passphrase.as_deref(), new_key_file.as_deref())
+            })
+            .and_then(|credential| {
+                credential.ok_or_else(|| {
+                    format!(
+                        "rekey needs --new-passphrase-stdin, {NEW_KEYSTORE_PASSPHRASE_ENV} or --new-key-file"
+                    )
+                })
+            })
+            .and_then(|credential| write_node_key(&node_key_path, node_key, Some(&credential)));
+        match resealed {
+            Ok(()) => println!("node key re-sealed at {}", node_key_path.display()),
+            Err(err) => {
+                error!("fatal: {err}");
+                std::process::exit(1);
+            }
+        }
         return;
     }
 
@@ -1192,6 +1456,13 @@ async fn main() {
     cfg.max_cache_shards = max_cache_shards;
     cfg.bucket_jitter_extra_levels = bucket_jitter;
     cfg.required_signed_namespaces = required_signed;
+    cfg.pow_stamp_difficulty = pow_stamp_difficulty;
+    cfg.signature_batch = SignatureBatchConfig {
+        enabled: signature_batch,
+        max_batch: signature_batch_max.max(1),
+        ..SignatureBatchConfig::default()
+    };
+    cfg.backfill.enabled = backfill_enabled;
     cfg.adaptive_lane_scoring = AdaptiveLaneScoringConfig {
         enabled: adaptive_scoring,
         ..AdaptiveLaneScoringConfig::default()
@@ -1340,9 +1611,26 @@ async fn main() {
     let discovered_fast = Arc::new(Mutex::new(HashSet::new()));
     let discovered_fallback = Arc::new(Mutex::new(HashSet::new()));
 
-    let fast_adapter = RecordingAdapter::new(fast_adapter_raw, Arc::clone(&discovered_fast));
+    let dht = dht_enabled.then(|| {
+        let addr = dht_advertise_addr.unwrap_or_else(|| quic_bind.clone());
+        info!("dht: serving rendezvous storage as {addr}");
+        DhtNode::storage(
+            DhtContact {
+                key: node_pubkey,
+                addr,
+            },
+            DhtConfig::default(),
+        )
+    });
+    let mailbox_capture = mailbox.clone().map(MailboxCapture::spawn);
+    let fast_adapter = RecordingAdapter::new(
+        DhtAdapter::new(fast_adapter_raw, dht),
+        Arc::clone(&discovered_fast),
+    )
+    .with_mailbox(mailbox_capture.clone());
     let fallback_adapter =
-        RecordingAdapter::new(fallback_adapter, Arc::clone(&discovered_fallback));
+        RecordingAdapter::new(fallback_adapter, Arc::clone(&discovered_fallback))
+            .with_mailbox(mailbox_capture);
 
     let peer_db = open_peer_db(&peer_db_path);
     let discovered_seed = peer_db
@@ -1467,6 +1755,8 @@ async fn main() {
             shutdown: Arc::clone(&shutdown),
             log_buffer: Arc::clone(&log_buffer),
             runtime_config: Arc::clone(&runtime_config),
+            mailbox: mailbox.clone(),
+            mailbox_max_fetch_shards,
         };
         let router = http_server::build_router(app_state);
         let bind_addr: std::net::SocketAddr =
@@ -1485,8 +1775,9 @@ async fn main() {
         });
     }
 
-    let mut now_step = 0_u64;
+    runtime.adopt_clock_steps();
     loop {
+        let now_step = runtime.now_step();
         if shutdown.load(Ordering::Relaxed) {
             if let Err(err) = save_state_to_path(&state_path, &mut runtime.state) {
                 error!("snapshot failed on shutdown: {err}");
@@ -1630,12 +1921,29 @@ async fn main() {
                         .ack_clears
                         .fetch_add(count as u64, Ordering::Relaxed);
                 }),
+                on_equivocation: Some(&mut |evidence| {
+                    warn!(
+                        "publisher {} signed two log entries at seq {}",
+                        hex::encode(evidence.publisher),
+                        evidence.seq
+                    );
+                }),
                 ..NodeRuntimeCallbacks::default()
             },
         );
-        now_step = now_step.saturating_add(1);
         metrics.ticks.fetch_add(1, Ordering::Relaxed);
 
+        let dht_seed = if runtime.fast_adapter.inner.dht.is_some() {
+            let guard = discovery_table.lock().unwrap_or_else(|e| e.into_inner());
+            guard
+                .values()
+                .filter_map(veil_android_node::dht_contact)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        runtime.fast_adapter.inner.poll_dht(dht_seed, now_step);
+
         if last_snapshot.elapsed() >= snapshot_interval {
             if let Err(err) = save_state_to_path(&state_path, &mut runtime.state) {
                 error!("snapshot failed: {err}");
@@ -1652,6 +1960,17 @@ async fn main() {
             if let Some(conn) = peer_db.as_ref() {
                 save_peer_list(conn, &merged);
             }
+            if let Some(mailbox) = &mailbox {
+                let mut store = mailbox.lock().unwrap_or_else(|e| e.into_inner());
+                match store.prune(now_unix_secs()).and_then(|removed| {
+                    store.counts().map(|counts| (removed, counts))
+                }) {
+                    Ok((removed, (mailboxes, shards))) => debug!(
+                        "mailbox: pruned={removed} mailboxes={mailboxes} retained_shards={shards}"
+                    ),
+                    Err(err) => warn!("mailbox: prune failed: {err}"),
+                }
+            }
             {
                 let mut guard = peer_snapshot.lock().unwrap_or_else(|e| e.into_inner());
                 *guard = merged;
@@ -1693,9 +2012,11 @@ async fn main() {
 #[cfg(test)]
 mod tests {
     use super::{
-        encode_fallback_peers, merge_peers, normalize_settings_key, parse_fallback_peer_strings,
-        Cli, Commands, FallbackPeer, SettingsCommands,
+        decode_nostr_secret_input, encode_fallback_peers, merge_peers, normalize_settings_key,
+        parse_fallback_peer_strings, Cli, Commands, FallbackPeer, SettingsCommands,
     };
+    use veil_crypto::mnemonic::IdentitySeed;
+    use veil_crypto::nip19::encode_nsec;
 
     #[test]
     fn parse_fallback_peer_strings_supports_websocket_server_prefix() {
@@ -1747,6 +2068,25 @@ mod tests {
         assert_eq!(normalize_settings_key("VEIL_VPS_UNKNOWN"), None);
     }
 
+    #[test]
+    fn node_secret_input_accepts_hex_nsec_and_mnemonic() {
+        let seed = IdentitySeed::from_entropy([0x33; 32]);
+        let secret = seed.signing_secret();
+        assert_eq!(
+            decode_nostr_secret_input(&hex::encode(secret)),
+            Some(secret)
+        );
+        assert_eq!(
+            decode_nostr_secret_input(&encode_nsec(&secret)),
+            Some(secret)
+        );
+        assert_eq!(
+            decode_nostr_secret_input(&format!(" {}\n", seed.mnemonic())),
+            Some(secret)
+        );
+        assert_eq!(decode_nostr_secret_input("abandon abandon abandon"), None);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn nostr_bridge_payload_publishes_and_android_receives_feed_bundle() {
         use std::net::TcpListener;
@@ -1964,5 +2304,35 @@ mod tests {
             }
             _ => panic!("expected Settings Get command"),
         }
+
+        // Test 'rekey' to a key file
+        let cli = Cli::try_parse_from(["veil-vps-node", "rekey", "--new-key-file", "node.keyfile"])
+            .unwrap();
+        match cli.command {
+            Some(Commands::Rekey {
+                new_passphrase_stdin: false,
+                ref new_key_file,
+            }) => {
+                assert_eq!(
+                    new_key_file.as_deref(),
+                    Some(std::path::Path::new("node.keyfile"))
+                );
+            }
+            _ => panic!("expected Rekey command"),
+        }
+
+        // The new passphrase never travels in argv
+        assert!(
+            Cli::try_parse_from(["veil-vps-node", "rekey", "--new-passphrase", "secret"]).is_err()
+        );
+        let cli =
+            Cli::try_parse_from(["veil-vps-node", "rekey", "--new-passphrase-stdin"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Rekey {
+                new_passphrase_stdin: true,
+                new_key_file: None,
+            })
+        ));
     }
 }
@@ -0,0 +1,218 @@
+//! Delegation certificates binding device signing subkeys to a root identity.
+//!
+//! A root identity signs a [`DelegationCertificate`] naming a device pubkey,
+//! a validity window, and the capabilities/namespaces the device may sign
+//! for. Objects signed by the device key carry the certificate in the
+//! `EXT_DELEGATION` extension so receivers can attribute them to the root.
+
+use serde::{Deserialize, Serialize};
+use veil_core::hash::blake3_32;
+use veil_core::types::Namespace;
+
+use crate::error::CodecError;
+use crate::object::Signature;
+
+/// Certificate schema version.
+pub const DELEGATION_V1_VERSION: u16 = 1;
+/// Device may sign published objects.
+pub const CAP_PUBLISH: u32 = 0x0001;
+/// Device may sign tombstones for the root's objects.
+pub const CAP_TOMBSTONE: u32 = 0x0002;
+/// Device may extend the root's publisher logs.
+pub const CAP_LOG: u32 = 0x0004;
+/// All currently defined capability bits.
+pub const CAP_ALL: u32 = CAP_PUBLISH | CAP_TOMBSTONE | CAP_LOG;
+/// Maximum namespaces a certificate may be scoped to.
+pub const MAX_DELEGATION_NAMESPACES: usize = 16;
+
+const DELEGATION_DOMAIN: &[u8] = b"veil/delegation/v1";
+
+/// Root-signed grant of signing authority to a device subkey.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationCertificate {
+    /// Wire version.
+    pub version: u16,
+    /// Long-lived root identity pubkey (feed tags are derived from this).
+    pub root_pubkey: [u8; 32],
+    /// Delegated device signing pubkey.
+    pub device_pubkey: [u8; 32],
+    /// Derivation index of the device key under the root.
+    pub device_index: u32,
+    /// UNIX seconds from which the certificate is valid.
+    pub not_before: u64,
+    /// UNIX seconds after which the certificate is no longer valid.
+    pub not_after: u64,
+    /// Granted capability bits (`CAP_*`).
+    pub capabilities: u32,
+    /// Namespaces the device may sign for, strictly ascending; empty means all.
+    pub namespaces: Vec<Namespace>,
+    /// Root signature over [`delegation_signature_message_digest`].
+    pub signature: Signature,
+}
+
+#[derive(Serialize)]
+struct SignedDelegationV1<'a> {
+    version: u16,
+    root_pubkey: [u8; 32],
+    device_pubkey: [u8; 32],
+    device_index: u32,
+    not_before: u64,
+    not_after: u64,
+    capabilities: u32,
+    namespaces: &'a [Namespace],
+}
+
+impl DelegationCertificate {
+    /// Validates schema and field consistency (not the signature).
+    pub fn validate(&self) -> Result<(), CodecError> {
+        if self.version != DELEGATION_V1_VERSION {
+            return Err(CodecError::InvalidObject("unsupported delegation version"));
+        }
+        if self.root_pubkey == self.device_pubkey {
+            return Err(CodecError::InvalidObject("delegation to the root key"));
+        }
+        if self.not_before >= self.not_after {
+            return Err(CodecError::InvalidObject(
+                "empty delegation validity window",
+            ));
+        }
+        if self.capabilities == 0 || self.capabilities & !CAP_ALL != 0 {
+            return Err(CodecError::InvalidObject("invalid delegation capabilities"));
+        }
+        if self.namespaces.len() > MAX_DELEGATION_NAMESPACES {
+            return Err(CodecError::InvalidObject("too many delegated namespaces"));
+        }
+        if self
+            .namespaces
+            .windows(2)
+            .any(|pair| pair[0].0 >= pair[1].0)
+        {
+            return Err(CodecError::InvalidObject(
+                "delegated namespaces must be strictly ordered",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the certificate is valid at `unix_secs`.
+    pub fn is_valid_at(&self, unix_secs: u64) -> bool {
+        self.not_before <= unix_secs && unix_secs < self.not_after
+    }
+
+    /// Whether the certificate grants every bit of `capability`.
+    pub fn grants(&self, capability: u32) -> bool {
+        self.capabilities & capability == capability
+    }
+
+    /// Whether the certificate covers `namespace`.
+    pub fn covers_namespace(&self, namespace: Namespace) -> bool {
+        self.namespaces.is_empty()
+            || self
+                .namespaces
+                .binary_search_by_key(&namespace.0, |ns| ns.0)
+                .is_ok()
+    }
+}
+
+/// Computes the digest the root key signs for `cert`.
+pub fn delegation_signature_message_digest(
+    cert: &DelegationCertificate,
+) -> Result<[u8; 32], CodecError> {
+    cert.validate()?;
+    let signed = SignedDelegationV1 {
+        version: cert.version,
+        root_pubkey: cert.root_pubkey,
+        device_pubkey: cert.device_pubkey,
+        device_index: cert.device_index,
+        not_before: cert.not_before,
+        not_after: cert.not_after,
+        capabilities: cert.capabilities,
+        namespaces: &cert.namespaces,
+    };
+    let mut preimage = DELEGATION_DOMAIN.to_vec();
+    ciborium::ser::into_writer(&signed, &mut preimage)
+        .map_err(|e| CodecError::Encode(e.to_string()))?;
+    Ok(blake3_32(&preimage))
+}
+
+/// Encodes a certificate as CBOR after validation.
+pub fn encode_delegation_cbor(cert: &DelegationCertificate) -> Result<Vec<u8>, CodecError> {
+    cert.validate()?;
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(cert, &mut bytes).map_err(|e| CodecError::Encode(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Decodes and validates a CBOR certificate.
+pub fn decode_delegation_cbor(bytes: &[u8]) -> Result<DelegationCertificate, CodecError> {
+    let cert: DelegationCertificate =
+        ciborium::de::from_reader(bytes).map_err(|e| CodecError::Decode(e.to_string()))?;
+    cert.validate()?;
+    Ok(cert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_delegation_cbor, delegation_signature_message_digest, encode_delegation_cbor,
+        DelegationCertificate, CAP_LOG, CAP_PUBLISH, DELEGATION_V1_VERSION,
+    };
+    use crate::object::Signature;
+    use veil_core::types::Namespace;
+
+    fn sample() -> DelegationCertificate {
+        DelegationCertificate {
+            version: DELEGATION_V1_VERSION,
+            root_pubkey: [1; 32],
+            device_pubkey: [2; 32],
+            device_index: 3,
+            not_before: 100,
+            not_after: 200,
+            capabilities: CAP_PUBLISH,
+            namespaces: vec![Namespace(7), Namespace(32)],
+            signature: Signature([9; 64]),
+        }
+    }
+
+    #[test]
+    fn round_trips_and_scopes_capabilities() {
+        let cert = sample();
+        let bytes = encode_delegation_cbor(&cert).expect("encode");
+        assert_eq!(decode_delegation_cbor(&bytes).expect("decode"), cert);
+        assert!(cert.is_valid_at(100) && !cert.is_valid_at(200));
+        assert!(cert.grants(CAP_PUBLISH) && !cert.grants(CAP_PUBLISH | CAP_LOG));
+        assert!(cert.covers_namespace(Namespace(32)));
+        assert!(!cert.covers_namespace(Namespace(8)));
+    }
+
+    #[test]
+    fn digest_ignores_signature_but_covers_scope() {
+        let cert = sample();
+        let digest = delegation_signature_message_digest(&cert).expect("digest");
+        let mut resigned = cert.clone();
+        resigned.signature = Signature([0; 64]);
+        assert_eq!(
+            delegation_signature_message_digest(&resigned).expect("digest"),
+            digest
+        );
+        let mut widened = cert;
+        widened.namespaces.clear();
+        assert_ne!(
+            delegation_signature_message_digest(&widened).expect("digest"),
+            digest
+        );
+    }
+
+    #[test]
+    fn rejects_inconsistent_certificates() {
+        let mut cert = sample();
+        cert.not_after = cert.not_before;
+        assert!(encode_delegation_cbor(&cert).is_err());
+        let mut cert = sample();
+        cert.namespaces = vec![Namespace(32), Namespace(7)];
+        assert!(encode_delegation_cbor(&cert).is_err());
+        let mut cert = sample();
+        cert.capabilities = 0x8000;
+        assert!(encode_delegation_cbor(&cert).is_err());
+    }
+}
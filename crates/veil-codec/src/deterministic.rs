@@ -0,0 +1,208 @@
+//! Deterministic CBOR checks (RFC 8949 §4.2) for strict decoding.
+//!
+//! The structural walk enforces the byte-level rules every VEIL encoding
+//! follows:
+//! - preferred (shortest-form) integer, length and simple-value arguments
+//! - definite-length strings, arrays and maps only
+//! - no duplicate map keys
+//! - no tags and no floats (VEIL schemas use neither)
+//! - bounded nesting depth
+//!
+//! Map key order is schema-defined: VEIL encoders emit struct fields in
+//! declaration order, so strict decoders additionally require the input to
+//! equal the re-encoding of the decoded value.
+
+use std::collections::HashSet;
+
+use crate::error::CodecError;
+
+/// Maximum nesting depth of arrays/maps accepted by the strict walker.
+pub const MAX_CBOR_DEPTH: usize = 16;
+
+/// Checks that `bytes` is exactly one deterministic CBOR item.
+pub fn check_deterministic_cbor(bytes: &[u8]) -> Result<(), CodecError> {
+    let len = deterministic_item_len(bytes)?;
+    if len != bytes.len() {
+        return Err(CodecError::NonDeterministic("trailing bytes after item"));
+    }
+    Ok(())
+}
+
+/// Checks the first CBOR item in `bytes` and returns its encoded length.
+pub fn deterministic_item_len(bytes: &[u8]) -> Result<usize, CodecError> {
+    let mut pos = 0;
+    walk_item(bytes, &mut pos, 0)?;
+    Ok(pos)
+}
+
+/// Requires `input` to equal the canonical re-encoding of its decoded value.
+pub(crate) fn ensure_canonical(input: &[u8], reencoded: &[u8]) -> Result<(), CodecError> {
+    if input != reencoded {
+        return Err(CodecError::NonDeterministic(
+            "encoding differs from canonical field order",
+        ));
+    }
+    Ok(())
+}
+
+fn walk_item(bytes: &[u8], pos: &mut usize, depth: usize) -> Result<(), CodecError> {
+    if depth > MAX_CBOR_DEPTH {
+        return Err(CodecError::NonDeterministic("nesting too deep"));
+    }
+    let initial = *bytes.get(*pos).ok_or(truncated())?;
+    *pos += 1;
+    let major = initial >> 5;
+    let info = initial & 0x1F;
+
+    if major == 7 {
+        return match info {
+            0..=23 => Ok(()),
+            24 => {
+                let value = *bytes.get(*pos).ok_or(truncated())?;
+                *pos += 1;
+                if value < 32 {
+                    Err(CodecError::NonDeterministic(
+                        "simple value not in shortest form",
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            25..=27 => Err(CodecError::NonDeterministic("floats are not allowed")),
+            31 => Err(CodecError::NonDeterministic("unexpected break")),
+            _ => Err(CodecError::Decode("reserved additional info".into())),
+        };
+    }
+
+    let argument = read_argument(bytes, pos, info)?;
+    match major {
+        0 | 1 => Ok(()),
+        2 | 3 => {
+            let len = to_len(argument)?;
+            let end = pos.checked_add(len).ok_or(truncated())?;
+            let text = bytes.get(*pos..end).ok_or(truncated())?;
+            if major == 3 && std::str::from_utf8(text).is_err() {
+                return Err(CodecError::Decode("text string is not UTF-8".into()));
+            }
+            *pos = end;
+            Ok(())
+        }
+        4 => {
+            for _ in 0..argument {
+                walk_item(bytes, pos, depth + 1)?;
+            }
+            Ok(())
+        }
+        5 => {
+            let mut keys = HashSet::new();
+            for _ in 0..argument {
+                let key_start = *pos;
+                walk_item(bytes, pos, depth + 1)?;
+                let key = &bytes[key_start..*pos];
+                if !keys.insert(key) {
+                    return Err(CodecError::NonDeterministic("duplicate map key"));
+                }
+                walk_item(bytes, pos, depth + 1)?;
+            }
+            Ok(())
+        }
+        _ => Err(CodecError::NonDeterministic("tags are not allowed")),
+    }
+}
+
+/// Reads a head argument, rejecting indefinite lengths and non-shortest forms.
+fn read_argument(bytes: &[u8], pos: &mut usize, info: u8) -> Result<u64, CodecError> {
+    let width = match info {
+        0..=23 => return Ok(u64::from(info)),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        31 => return Err(CodecError::NonDeterministic("indefinite-length item")),
+        _ => return Err(CodecError::Decode("reserved additional info".into())),
+    };
+    let end = *pos + width;
+    let raw = bytes.get(*pos..end).ok_or(truncated())?;
+    *pos = end;
+    let value = raw.iter().fold(0_u64, |acc, b| (acc << 8) | u64::from(*b));
+    let shortest = match width {
+        1 => value >= 24,
+        2 => value > 0xFF,
+        4 => value > 0xFFFF,
+        _ => value > 0xFFFF_FFFF,
+    };
+    if !shortest {
+        return Err(CodecError::NonDeterministic(
+            "argument not in shortest form",
+        ));
+    }
+    Ok(value)
+}
+
+fn to_len(argument: u64) -> Result<usize, CodecError> {
+    usize::try_from(argument).map_err(|_| truncated())
+}
+
+fn truncated() -> CodecError {
+    CodecError::Decode("truncated CBOR item".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_deterministic_cbor, deterministic_item_len, MAX_CBOR_DEPTH};
+    use crate::error::CodecError;
+
+    fn non_deterministic(bytes: &[u8]) -> bool {
+        matches!(
+            check_deterministic_cbor(bytes),
+            Err(CodecError::NonDeterministic(_))
+        )
+    }
+
+    #[test]
+    fn accepts_shortest_form_items() {
+        check_deterministic_cbor(&[0x17]).expect("small int");
+        check_deterministic_cbor(&[0x18, 0x18]).expect("one-byte int");
+        check_deterministic_cbor(&[0x19, 0x01, 0x00]).expect("two-byte int");
+        check_deterministic_cbor(&[0x42, 0xAA, 0xBB]).expect("bytes");
+        check_deterministic_cbor(&[0xA2, 0x61, 0x61, 0x01, 0x61, 0x62, 0xF6]).expect("map");
+        assert_eq!(
+            deterministic_item_len(&[0x82, 0x01, 0x02, 0xFF]).unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn rejects_non_deterministic_encodings() {
+        assert!(non_deterministic(&[0x18, 0x17]), "int with long head");
+        assert!(non_deterministic(&[0x19, 0x00, 0xFF]), "int with wide head");
+        assert!(
+            non_deterministic(&[0x58, 0x01, 0xAA]),
+            "bytes with long length"
+        );
+        assert!(
+            non_deterministic(&[0x5F, 0x41, 0xAA, 0xFF]),
+            "indefinite bytes"
+        );
+        assert!(non_deterministic(&[0x9F, 0x01, 0xFF]), "indefinite array");
+        assert!(
+            non_deterministic(&[0xA2, 0x01, 0x02, 0x01, 0x03]),
+            "duplicate key"
+        );
+        assert!(non_deterministic(&[0xF9, 0x3C, 0x00]), "float");
+        assert!(non_deterministic(&[0xC1, 0x01]), "tag");
+        assert!(non_deterministic(&[0xF8, 0x14]), "simple value long form");
+        assert!(non_deterministic(&[0x01, 0x00]), "trailing bytes");
+    }
+
+    #[test]
+    fn rejects_truncated_and_deep_items() {
+        assert!(matches!(
+            check_deterministic_cbor(&[0x42, 0xAA]),
+            Err(CodecError::Decode(_))
+        ));
+        let mut deep = vec![0x81_u8; MAX_CBOR_DEPTH + 1];
+        deep.push(0x00);
+        assert!(non_deterministic(&deep));
+    }
+}
@@ -9,6 +9,9 @@ pub enum CodecError {
     /// CBOR deserialization failure.
     #[error("decode error: {0}")]
     Decode(String),
+    /// Input is valid CBOR but not in deterministic form.
+    #[error("non-deterministic CBOR: {0}")]
+    NonDeterministic(&'static str),
     /// Object-level schema validation failure.
     #[error("invalid object: {0}")]
     InvalidObject(&'static str),
@@ -0,0 +1,331 @@
+//! Canonical JSON form of `ObjectV1` and `ShardV1`.
+//!
+//! The JSON form carries exactly the fields of the CBOR form, so decoding
+//! JSON and re-encoding as CBOR reproduces the original CBOR bytes. Canonical
+//! output has:
+//! - object keys sorted by byte order, no insignificant whitespace
+//! - integers as plain JSON numbers
+//! - byte fields as unpadded base64url strings
+//! - absent optional fields as `null`
+//! - `erasure_mode` as its numeric wire value (`0`, `1` or `2`)
+//!
+//! Decoding accepts any key order and whitespace but rejects unknown or
+//! missing fields, so `encode(decode(json))` yields the canonical form.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::{Map, Value};
+use veil_core::types::{Epoch, Namespace};
+
+use crate::error::CodecError;
+use crate::object::{ObjectV1, Signature};
+use crate::shard::{ShardErasureMode, ShardHeaderV1, ShardV1};
+
+const OBJECT_KEYS: [&str; 11] = [
+    "ciphertext",
+    "epoch",
+    "flags",
+    "namespace",
+    "nonce",
+    "object_root",
+    "padding",
+    "sender_pubkey",
+    "signature",
+    "tag",
+    "version",
+];
+const SHARD_KEYS: [&str; 2] = ["header", "payload"];
+const SHARD_HEADER_KEYS: [&str; 11] = [
+    "bucket_size",
+    "epoch",
+    "erasure_mode",
+    "index",
+    "k",
+    "n",
+    "namespace",
+    "object_root",
+    "profile_id",
+    "tag",
+    "version",
+];
+
+/// Encodes `ObjectV1` as canonical JSON after validation.
+pub fn encode_object_json(object: &ObjectV1) -> Result<String, CodecError> {
+    object.validate()?;
+    let mut map = Map::new();
+    map.insert("version".into(), object.version.into());
+    map.insert("namespace".into(), object.namespace.0.into());
+    map.insert("epoch".into(), object.epoch.0.into());
+    map.insert("flags".into(), object.flags.into());
+    map.insert("tag".into(), bytes_value(&object.tag));
+    map.insert("object_root".into(), bytes_value(&object.object_root));
+    map.insert(
+        "sender_pubkey".into(),
+        object
+            .sender_pubkey
+            .map(|k| bytes_value(&k))
+            .unwrap_or(Value::Null),
+    );
+    map.insert(
+        "signature".into(),
+        object
+            .signature
+            .as_ref()
+            .map(|s| bytes_value(&s.0))
+            .unwrap_or(Value::Null),
+    );
+    map.insert("nonce".into(), bytes_value(&object.nonce));
+    map.insert("ciphertext".into(), bytes_value(&object.ciphertext));
+    map.insert("padding".into(), bytes_value(&object.padding));
+    Ok(canonical_string(&Value::Object(map)))
+}
+
+/// Decodes and validates a JSON object.
+pub fn decode_object_json(json: &str) -> Result<ObjectV1, CodecError> {
+    let value = parse(json)?;
+    let map = exact_object(&value, &OBJECT_KEYS, "object")?;
+    let object = ObjectV1 {
+        version: int_field(map, "version")?,
+        namespace: Namespace(int_field(map, "namespace")?),
+        epoch: Epoch(int_field(map, "epoch")?),
+        flags: int_field(map, "flags")?,
+        tag: fixed_bytes_field(map, "tag")?,
+        object_root: fixed_bytes_field(map, "object_root")?,
+        sender_pubkey: optional(map, "sender_pubkey", || {
+            fixed_bytes_field(map, "sender_pubkey")
+        })?,
+        signature: optional(map, "signature", || {
+            fixed_bytes_field(map, "signature").map(Signature)
+        })?,
+        nonce: fixed_bytes_field(map, "nonce")?,
+        ciphertext: bytes_field(map, "ciphertext")?,
+        padding: bytes_field(map, "padding")?,
+    };
+    object.validate()?;
+    Ok(object)
+}
+
+/// Encodes `ShardV1` as canonical JSON after validation.
+pub fn encode_shard_json(shard: &ShardV1) -> Result<String, CodecError> {
+    shard.validate()?;
+    let h = &shard.header;
+    let mut header = Map::new();
+    header.insert("version".into(), h.version.into());
+    header.insert("namespace".into(), h.namespace.0.into());
+    header.insert("epoch".into(), h.epoch.0.into());
+    header.insert("tag".into(), bytes_value(&h.tag));
+    header.insert("object_root".into(), bytes_value(&h.object_root));
+    header.insert("profile_id".into(), h.profile_id.into());
+    header.insert("erasure_mode".into(), (h.erasure_mode as u8).into());
+    header.insert("bucket_size".into(), h.bucket_size.into());
+    header.insert("k".into(), h.k.into());
+    header.insert("n".into(), h.n.into());
+    header.insert("index".into(), h.index.into());
+
+    let mut map = Map::new();
+    map.insert("header".into(), Value::Object(header));
+    map.insert("payload".into(), bytes_value(&shard.payload));
+    Ok(canonical_string(&Value::Object(map)))
+}
+
+/// Decodes and validates a JSON shard.
+pub fn decode_shard_json(json: &str) -> Result<ShardV1, CodecError> {
+    let value = parse(json)?;
+    let map = exact_object(&value, &SHARD_KEYS, "shard")?;
+    let h = exact_object(&map["header"], &SHARD_HEADER_KEYS, "shard header")?;
+    let erasure_mode = match int_field::<u8>(h, "erasure_mode")? {
+        0 => ShardErasureMode::Systematic,
+        1 => ShardErasureMode::HardenedNonSystematic,
+        2 => ShardErasureMode::Fountain,
+        3 => ShardErasureMode::AllOrNothing,
+        _ => return Err(CodecError::Decode("unknown erasure_mode".into())),
+    };
+    let shard = ShardV1 {
+        header: ShardHeaderV1 {
+            version: int_field(h, "version")?,
+            namespace: Namespace(int_field(h, "namespace")?),
+            epoch: Epoch(int_field(h, "epoch")?),
+            tag: fixed_bytes_field(h, "tag")?,
+            object_root: fixed_bytes_field(h, "object_root")?,
+            profile_id: int_field(h, "profile_id")?,
+            erasure_mode,
+            bucket_size: int_field(h, "bucket_size")?,
+            k: int_field(h, "k")?,
+            n: int_field(h, "n")?,
+            index: int_field(h, "index")?,
+        },
+        payload: bytes_field(map, "payload")?,
+    };
+    shard.validate()?;
+    Ok(shard)
+}
+
+fn bytes_value(bytes: &[u8]) -> Value {
+    Value::String(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Serializes `value` with sorted keys and no whitespace.
+fn canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+fn parse(json: &str) -> Result<Value, CodecError> {
+    serde_json::from_str(json).map_err(|e| CodecError::Decode(e.to_string()))
+}
+
+fn exact_object<'a>(
+    value: &'a Value,
+    keys: &[&str],
+    what: &str,
+) -> Result<&'a Map<String, Value>, CodecError> {
+    let map = value
+        .as_object()
+        .ok_or_else(|| CodecError::Decode(format!("{what} must be a JSON object")))?;
+    if let Some(unknown) = map.keys().find(|k| !keys.contains(&k.as_str())) {
+        return Err(CodecError::Decode(format!(
+            "unknown {what} field `{unknown}`"
+        )));
+    }
+    if let Some(missing) = keys.iter().find(|k| !map.contains_key(**k)) {
+        return Err(CodecError::Decode(format!(
+            "missing {what} field `{missing}`"
+        )));
+    }
+    Ok(map)
+}
+
+fn int_field<T: TryFrom<u64>>(map: &Map<String, Value>, key: &str) -> Result<T, CodecError> {
+    map[key]
+        .as_u64()
+        .and_then(|v| T::try_from(v).ok())
+        .ok_or_else(|| CodecError::Decode(format!("`{key}` must be an in-range unsigned integer")))
+}
+
+fn bytes_field(map: &Map<String, Value>, key: &str) -> Result<Vec<u8>, CodecError> {
+    let text = map[key]
+        .as_str()
+        .ok_or_else(|| CodecError::Decode(format!("`{key}` must be a base64url string")))?;
+    URL_SAFE_NO_PAD
+        .decode(text)
+        .map_err(|e| CodecError::Decode(format!("`{key}`: {e}")))
+}
+
+fn fixed_bytes_field<const N: usize>(
+    map: &Map<String, Value>,
+    key: &str,
+) -> Result<[u8; N], CodecError> {
+    let bytes = bytes_field(map, key)?;
+    bytes
+        .try_into()
+        .map_err(|_| CodecError::Decode(format!("`{key}` must decode to {N} bytes")))
+}
+
+fn optional<T>(
+    map: &Map<String, Value>,
+    key: &str,
+    decode: impl FnOnce() -> Result<T, CodecError>,
+) -> Result<Option<T>, CodecError> {
+    match &map[key] {
+        Value::Null => Ok(None),
+        _ => decode().map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use veil_core::{Epoch, Namespace};
+
+    use super::{decode_object_json, encode_object_json};
+    use crate::object::{encode_object_cbor, ObjectV1, OBJECT_V1_VERSION};
+
+    fn unsigned_object() -> ObjectV1 {
+        ObjectV1 {
+            version: OBJECT_V1_VERSION,
+            namespace: Namespace(3),
+            epoch: Epoch(4),
+            flags: 0,
+            tag: [0xFB_u8; 32],
+            object_root: [0x01_u8; 32],
+            sender_pubkey: None,
+            signature: None,
+            nonce: [0x02_u8; 24],
+            ciphertext: vec![0xFF, 0xEE, 0x3E],
+            padding: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn object_json_is_canonical_and_round_trips() {
+        let object = unsigned_object();
+        let json = encode_object_json(&object).expect("encode");
+        assert!(json.starts_with("{\"ciphertext\":\"_-4-\",\"epoch\":4,"));
+        assert!(json.contains("\"sender_pubkey\":null"));
+        assert!(!json.contains(' '));
+
+        let decoded = decode_object_json(&json).expect("decode");
+        assert_eq!(decoded, object);
+        assert_eq!(
+            encode_object_cbor(&decoded).expect("cbor"),
+            encode_object_cbor(&object).expect("cbor")
+        );
+
+        let reordered = serde_json::to_string_pretty(
+            &serde_json::from_str::<serde_json::Value>(&json).expect("json"),
+        )
+        .expect("pretty");
+        assert_eq!(
+            encode_object_json(&decode_object_json(&reordered).expect("decode")).expect("encode"),
+            json
+        );
+    }
+
+    #[test]
+    fn object_json_rejects_unknown_missing_and_malformed_fields() {
+        let json = encode_object_json(&unsigned_object()).expect("encode");
+        let mut value: serde_json::Value = serde_json::from_str(&json).expect("json");
+
+        value["extra"] = 1.into();
+        assert!(decode_object_json(&value.to_string()).is_err());
+        value.as_object_mut().expect("object").remove("extra");
+
+        value["tag"] = "AAAA".into();
+        let err = decode_object_json(&value.to_string()).expect_err("short tag");
+        assert!(err.to_string().contains("32 bytes"));
+
+        value.as_object_mut().expect("object").remove("tag");
+        let err = decode_object_json(&value.to_string()).expect_err("missing tag");
+        assert!(err.to_string().contains("missing object field `tag`"));
+    }
+}
@@ -1,7 +1,13 @@
 //! VEIL wire codec primitives.
 //!
-//! Defines canonical object/shard schemas and CBOR encode/decode helpers.
+//! Defines canonical object/shard schemas, CBOR encode/decode helpers
+//! with optional strict deterministic-CBOR validation, and a
+//! canonical JSON form for debugging and non-CBOR clients.
 
+pub mod delegation;
+pub mod deterministic;
 pub mod error;
+pub mod json;
+pub mod multisig;
 pub mod object;
 pub mod shard;
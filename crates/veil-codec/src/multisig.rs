@@ -0,0 +1,236 @@
+//! Threshold multi-signature attestations for organisation feeds.
+//!
+//! A [`SignerSet`] names up to [`MAX_MULTISIG_SIGNERS`] editor pubkeys and
+//! how many must sign. Objects published by the set carry a
+//! [`MultisigAttestation`] in the `EXT_MULTISIG` extension: the set itself
+//! plus co-signatures over the object's co-signature digest. The set is
+//! addressed by [`SignerSet::set_id`], which stands in for the publisher
+//! pubkey.
+//!
+//! Wire layout (compact so eight signers fit one extension value):
+//!
+//! `threshold u8 || n u8 || n × pubkey[32] || m u8 || m × (index u8 || sig[64])`
+
+use serde::{Deserialize, Serialize};
+use veil_core::hash::blake3_32;
+
+use crate::error::CodecError;
+use crate::object::Signature;
+
+/// Maximum editors in one signer set.
+pub const MAX_MULTISIG_SIGNERS: usize = 8;
+
+const SIGNER_SET_DOMAIN: &[u8] = b"veil/signer-set/v1";
+
+/// Editors of a multi-signature feed and the signatures it needs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerSet {
+    /// Distinct signers required.
+    pub threshold: u8,
+    /// Signer pubkeys, strictly ascending.
+    pub signers: Vec<[u8; 32]>,
+}
+
+impl SignerSet {
+    /// Builds a set, sorting and de-duplicating `signers`.
+    pub fn new(threshold: u8, mut signers: Vec<[u8; 32]>) -> Result<Self, CodecError> {
+        signers.sort_unstable();
+        signers.dedup();
+        let set = Self { threshold, signers };
+        set.validate()?;
+        Ok(set)
+    }
+
+    /// Validates ordering, size, and threshold bounds.
+    pub fn validate(&self) -> Result<(), CodecError> {
+        if self.signers.is_empty() || self.signers.len() > MAX_MULTISIG_SIGNERS {
+            return Err(CodecError::InvalidObject("signer set size out of range"));
+        }
+        if self.signers.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(CodecError::InvalidObject(
+                "signers must be strictly ordered",
+            ));
+        }
+        if self.threshold == 0 || usize::from(self.threshold) > self.signers.len() {
+            return Err(CodecError::InvalidObject("signer threshold out of range"));
+        }
+        Ok(())
+    }
+
+    /// Stable identifier of the set, used as its publisher pubkey.
+    pub fn set_id(&self) -> [u8; 32] {
+        let mut preimage = SIGNER_SET_DOMAIN.to_vec();
+        preimage.push(self.threshold);
+        for signer in &self.signers {
+            preimage.extend_from_slice(signer);
+        }
+        blake3_32(&preimage)
+    }
+
+    /// Position of `pubkey` in the set.
+    pub fn index_of(&self, pubkey: &[u8; 32]) -> Option<u8> {
+        self.signers.binary_search(pubkey).ok().map(|i| i as u8)
+    }
+}
+
+/// One editor's signature, by position in the signer set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cosignature {
+    pub signer_index: u8,
+    pub signature: Signature,
+}
+
+/// Signer set and collected co-signatures carried by an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigAttestation {
+    pub set: SignerSet,
+    /// Co-signatures, strictly ascending by `signer_index`.
+    pub cosignatures: Vec<Cosignature>,
+}
+
+impl MultisigAttestation {
+    /// Attestation with no co-signatures yet.
+    pub fn unsigned(set: SignerSet) -> Self {
+        Self {
+            set,
+            cosignatures: Vec::new(),
+        }
+    }
+
+    /// Inserts or replaces the co-signature of `signer_index`.
+    pub fn add_cosignature(&mut self, cosignature: Cosignature) {
+        match self
+            .cosignatures
+            .binary_search_by_key(&cosignature.signer_index, |c| c.signer_index)
+        {
+            Ok(i) => self.cosignatures[i] = cosignature,
+            Err(i) => self.cosignatures.insert(i, cosignature),
+        }
+    }
+
+    /// Validates the set and co-signature indices.
+    pub fn validate(&self) -> Result<(), CodecError> {
+        self.set.validate()?;
+        if self
+            .cosignatures
+            .iter()
+            .any(|c| usize::from(c.signer_index) >= self.set.signers.len())
+        {
+            return Err(CodecError::InvalidObject("co-signer index out of range"));
+        }
+        if self
+            .cosignatures
+            .windows(2)
+            .any(|pair| pair[0].signer_index >= pair[1].signer_index)
+        {
+            return Err(CodecError::InvalidObject(
+                "co-signatures must be strictly ordered",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Encodes an attestation after validation.
+pub fn encode_multisig(attestation: &MultisigAttestation) -> Result<Vec<u8>, CodecError> {
+    attestation.validate()?;
+    let set = &attestation.set;
+    let mut bytes =
+        Vec::with_capacity(3 + set.signers.len() * 32 + attestation.cosignatures.len() * 65);
+    bytes.push(set.threshold);
+    bytes.push(set.signers.len() as u8);
+    for signer in &set.signers {
+        bytes.extend_from_slice(signer);
+    }
+    bytes.push(attestation.cosignatures.len() as u8);
+    for cosignature in &attestation.cosignatures {
+        bytes.push(cosignature.signer_index);
+        bytes.extend_from_slice(&cosignature.signature.0);
+    }
+    Ok(bytes)
+}
+
+/// Decodes and validates an attestation.
+pub fn decode_multisig(bytes: &[u8]) -> Result<MultisigAttestation, CodecError> {
+    let mut rest = bytes;
+    let threshold = take(&mut rest, 1)?[0];
+    let signer_count = usize::from(take(&mut rest, 1)?[0]);
+    let mut signers = Vec::with_capacity(signer_count);
+    for _ in 0..signer_count {
+        signers.push(take(&mut rest, 32)?.try_into().expect("32 bytes"));
+    }
+    let cosignature_count = usize::from(take(&mut rest, 1)?[0]);
+    let mut cosignatures = Vec::with_capacity(cosignature_count);
+    for _ in 0..cosignature_count {
+        let signer_index = take(&mut rest, 1)?[0];
+        let signature = Signature(take(&mut rest, 64)?.try_into().expect("64 bytes"));
+        cosignatures.push(Cosignature {
+            signer_index,
+            signature,
+        });
+    }
+    if !rest.is_empty() {
+        return Err(CodecError::Decode("trailing multisig bytes".to_string()));
+    }
+    let attestation = MultisigAttestation {
+        set: SignerSet { threshold, signers },
+        cosignatures,
+    };
+    attestation.validate()?;
+    Ok(attestation)
+}
+
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8], CodecError> {
+    if rest.len() < len {
+        return Err(CodecError::Decode(
+            "truncated multisig attestation".to_string(),
+        ));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_multisig, encode_multisig, Cosignature, MultisigAttestation, SignerSet,
+        MAX_MULTISIG_SIGNERS,
+    };
+    use crate::object::{Signature, MAX_EXTENSION_VALUE_LEN};
+
+    #[test]
+    fn round_trips_full_attestation_within_extension_limit() {
+        let signers = (0..MAX_MULTISIG_SIGNERS as u8)
+            .rev()
+            .map(|i| [i; 32])
+            .collect();
+        let set = SignerSet::new(5, signers).expect("set");
+        assert_eq!(set.index_of(&[0; 32]), Some(0));
+        let mut attestation = MultisigAttestation::unsigned(set);
+        for index in (0..MAX_MULTISIG_SIGNERS as u8).rev() {
+            attestation.add_cosignature(Cosignature {
+                signer_index: index,
+                signature: Signature([index; 64]),
+            });
+        }
+        let bytes = encode_multisig(&attestation).expect("encode");
+        assert!(bytes.len() <= MAX_EXTENSION_VALUE_LEN);
+        assert_eq!(decode_multisig(&bytes).expect("decode"), attestation);
+    }
+
+    #[test]
+    fn set_id_covers_threshold_and_rejects_bad_sets() {
+        let signers = vec![[1; 32], [2; 32], [3; 32]];
+        let two_of_three = SignerSet::new(2, signers.clone()).expect("set");
+        let three_of_three = SignerSet::new(3, signers.clone()).expect("set");
+        assert_ne!(two_of_three.set_id(), three_of_three.set_id());
+
+        assert!(SignerSet::new(4, signers).is_err());
+        assert!(SignerSet::new(0, vec![[1; 32]]).is_err());
+        let mut bytes =
+            encode_multisig(&MultisigAttestation::unsigned(two_of_three)).expect("encode");
+        bytes.push(0);
+        assert!(decode_multisig(&bytes).is_err());
+    }
+}
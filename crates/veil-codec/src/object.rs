@@ -3,7 +3,10 @@ use veil_core::hash::blake3_32;
 use veil_core::types::{Epoch, Namespace};
 use veil_core::{ObjectRoot, Tag};
 
+use crate::delegation::{decode_delegation_cbor, encode_delegation_cbor, DelegationCertificate};
+use crate::deterministic::{check_deterministic_cbor, deterministic_item_len, ensure_canonical};
 use crate::error::CodecError;
+use crate::multisig::{decode_multisig, encode_multisig, MultisigAttestation};
 
 /// Object schema version for `ObjectV1`.
 pub const OBJECT_V1_VERSION: u16 = 1;
@@ -98,34 +101,47 @@ impl ObjectV1 {
         if self.flags & !OBJECT_ALLOWED_FLAGS_MASK != 0 {
             return Err(CodecError::InvalidObject("unknown object flags"));
         }
-        if self.ciphertext.is_empty() {
-            return Err(CodecError::InvalidObject("ciphertext must not be empty"));
-        }
+        validate_body_and_signature_fields(
+            self.flags,
+            &self.ciphertext,
+            self.sender_pubkey.is_some(),
+            self.signature.is_some(),
+        )
+    }
+}
 
-        let signed = (self.flags & OBJECT_FLAG_SIGNED) != 0;
-        if signed && self.sender_pubkey.is_none() {
-            return Err(CodecError::InvalidObject(
-                "signed object requires sender_pubkey",
-            ));
-        }
-        if signed && self.signature.is_none() {
-            return Err(CodecError::InvalidObject(
-                "signed object requires signature",
-            ));
-        }
-        if !signed && (self.sender_pubkey.is_some() || self.signature.is_some()) {
-            return Err(CodecError::InvalidObject(
-                "signature fields require signed flag",
-            ));
-        }
-        if self.sender_pubkey.is_some() != self.signature.is_some() {
-            return Err(CodecError::InvalidObject(
-                "sender_pubkey and signature must be set together",
-            ));
-        }
+fn validate_body_and_signature_fields(
+    flags: u16,
+    ciphertext: &[u8],
+    has_sender: bool,
+    has_signature: bool,
+) -> Result<(), CodecError> {
+    if ciphertext.is_empty() {
+        return Err(CodecError::InvalidObject("ciphertext must not be empty"));
+    }
 
-        Ok(())
+    let signed = (flags & OBJECT_FLAG_SIGNED) != 0;
+    if signed && !has_sender {
+        return Err(CodecError::InvalidObject(
+            "signed object requires sender_pubkey",
+        ));
+    }
+    if signed && !has_signature {
+        return Err(CodecError::InvalidObject(
+            "signed object requires signature",
+        ));
+    }
+    if !signed && (has_sender || has_signature) {
+        return Err(CodecError::InvalidObject(
+            "signature fields require signed flag",
+        ));
     }
+    if has_sender != has_signature {
+        return Err(CodecError::InvalidObject(
+            "sender_pubkey and signature must be set together",
+        ));
+    }
+    Ok(())
 }
 
 /// Encodes the canonical signed-header subset used in signature preimages.
@@ -183,12 +199,623 @@ pub fn decode_object_cbor_prefix(bytes: &[u8]) -> Result<(ObjectV1, usize), Code
     Ok((object, cursor.position() as usize))
 }
 
+/// Decodes a full CBOR object, rejecting non-deterministic encodings.
+pub fn decode_object_cbor_strict(bytes: &[u8]) -> Result<ObjectV1, CodecError> {
+    check_deterministic_cbor(bytes)?;
+    let object = decode_object_cbor(bytes)?;
+    ensure_canonical(bytes, &encode_object_cbor(&object)?)?;
+    Ok(object)
+}
+
+/// Object schema version for `ObjectV2`.
+pub const OBJECT_V2_VERSION: u16 = 2;
+/// Extension: UNIX seconds after which the object should be dropped (u64 BE).
+pub const EXT_EXPIRES_AT: u16 = 1;
+/// Extension: UTF-8 media type of the decrypted payload.
+pub const EXT_CONTENT_TYPE: u16 = 2;
+/// Extension: root of the publisher's previous object.
+pub const EXT_PREV_ROOT: u16 = 3;
+/// Extension: root of the object this one replies to.
+pub const EXT_REPLY_TO: u16 = 4;
+/// Extension: payload compression algorithm identifier (one byte).
+pub const EXT_COMPRESSION: u16 = 5;
+/// Extension: CBOR [`DelegationCertificate`] for a device-signed object.
+pub const EXT_DELEGATION: u16 = 6;
+/// Extension: [`MultisigAttestation`] for an object published by a signer set.
+pub const EXT_MULTISIG: u16 = 7;
+/// Extension: proof-of-work stamp nonce over the object root and epoch.
+pub const EXT_POW_STAMP: u16 = 8;
+/// Extension: UNIX seconds the publisher created the object at (u64 BE).
+pub const EXT_CREATED_AT: u16 = 9;
+const MULTISIG_COSIGN_DOMAIN: &[u8] = b"veil/multisig-cosign/v1";
+const POW_STAMP_DOMAIN: &[u8] = b"veil/pow-stamp-subject/v1";
+/// Maximum extensions carried by one object.
+pub const MAX_OBJECT_EXTENSIONS: usize = 32;
+/// Maximum encoded value length of a single extension.
+pub const MAX_EXTENSION_VALUE_LEN: usize = 1024;
+
+/// Payload compression algorithms named by [`EXT_COMPRESSION`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CompressionAlgorithm {
+    None = 0,
+    Zstd = 1,
+    Deflate = 2,
+}
+
+impl CompressionAlgorithm {
+    fn from_byte(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Zstd),
+            2 => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// One type-length-value extension entry.
+///
+/// Receivers ignore (but preserve) unknown extensions unless `critical` is
+/// set, in which case the object is rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectExtension {
+    pub ext_type: u16,
+    pub critical: bool,
+    pub value: Vec<u8>,
+}
+
+impl ObjectExtension {
+    pub fn new(ext_type: u16, critical: bool, value: Vec<u8>) -> Self {
+        Self {
+            ext_type,
+            critical,
+            value,
+        }
+    }
+
+    pub fn expires_at(unix_secs: u64) -> Self {
+        Self::new(EXT_EXPIRES_AT, true, unix_secs.to_be_bytes().to_vec())
+    }
+
+    pub fn content_type(media_type: &str) -> Self {
+        Self::new(EXT_CONTENT_TYPE, false, media_type.as_bytes().to_vec())
+    }
+
+    pub fn prev_root(root: ObjectRoot) -> Self {
+        Self::new(EXT_PREV_ROOT, false, root.to_vec())
+    }
+
+    pub fn reply_to(root: ObjectRoot) -> Self {
+        Self::new(EXT_REPLY_TO, false, root.to_vec())
+    }
+
+    pub fn compression(algorithm: CompressionAlgorithm) -> Self {
+        Self::new(EXT_COMPRESSION, true, vec![algorithm as u8])
+    }
+
+    /// Critical, since receivers that ignore it would misattribute the
+    /// object to the device key.
+    pub fn delegation(cert: &DelegationCertificate) -> Result<Self, CodecError> {
+        Ok(Self::new(
+            EXT_DELEGATION,
+            true,
+            encode_delegation_cbor(cert)?,
+        ))
+    }
+
+    /// Critical, since receivers that ignore it would accept the object on
+    /// one editor's signature alone.
+    pub fn multisig(attestation: &MultisigAttestation) -> Result<Self, CodecError> {
+        Ok(Self::new(EXT_MULTISIG, true, encode_multisig(attestation)?))
+    }
+
+    /// Not critical: nodes that do not require stamps can ignore it.
+    pub fn pow_stamp(nonce: u64) -> Self {
+        Self::new(EXT_POW_STAMP, false, nonce.to_be_bytes().to_vec())
+    }
+
+    pub fn created_at(unix_secs: u64) -> Self {
+        Self::new(EXT_CREATED_AT, false, unix_secs.to_be_bytes().to_vec())
+    }
+
+    fn validate(&self) -> Result<(), CodecError> {
+        if self.value.len() > MAX_EXTENSION_VALUE_LEN {
+            return Err(CodecError::InvalidObject("extension value too long"));
+        }
+        let valid = match self.ext_type {
+            EXT_EXPIRES_AT | EXT_POW_STAMP | EXT_CREATED_AT => self.value.len() == 8,
+            EXT_CONTENT_TYPE => std::str::from_utf8(&self.value).is_ok(),
+            EXT_PREV_ROOT | EXT_REPLY_TO => self.value.len() == 32,
+            EXT_COMPRESSION => {
+                self.value.len() == 1 && CompressionAlgorithm::from_byte(self.value[0]).is_some()
+            }
+            EXT_DELEGATION => decode_delegation_cbor(&self.value).is_ok(),
+            EXT_MULTISIG => decode_multisig(&self.value).is_ok(),
+            _ if self.critical => {
+                return Err(CodecError::InvalidObject("unknown critical extension"));
+            }
+            _ => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(CodecError::InvalidObject("malformed extension value"))
+        }
+    }
+}
+
+/// Extensible object format.
+///
+/// Same body layout as [`ObjectV1`] plus an ordered extension list that is
+/// covered by the signature, so new metadata can be added without a new
+/// object version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectV2 {
+    /// Wire version.
+    pub version: u16,
+    /// Logical namespace.
+    pub namespace: Namespace,
+    /// Epoch window.
+    pub epoch: Epoch,
+    /// Bitfield flags.
+    pub flags: u16,
+    /// Subscription tag.
+    pub tag: Tag,
+    /// Root of plaintext payload/object bytes (producer-defined).
+    pub object_root: ObjectRoot,
+    /// Extensions, strictly ascending by type.
+    pub extensions: Vec<ObjectExtension>,
+    /// Optional sender pubkey (required when signed).
+    pub sender_pubkey: Option<[u8; 32]>,
+    /// Optional signature (required when signed).
+    pub signature: Option<Signature>,
+    /// XChaCha20-Poly1305 nonce.
+    pub nonce: [u8; 24],
+    /// AEAD ciphertext including tag.
+    pub ciphertext: Vec<u8>,
+    /// Opaque padding bytes.
+    pub padding: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SignedObjectHeaderV2<'a> {
+    version: u16,
+    namespace: Namespace,
+    epoch: Epoch,
+    flags: u16,
+    tag: Tag,
+    object_root: ObjectRoot,
+    extensions: &'a [ObjectExtension],
+    sender_pubkey: Option<[u8; 32]>,
+    nonce: [u8; 24],
+}
+
+impl ObjectV2 {
+    /// Validates object schema, extension ordering, and field consistency.
+    pub fn validate(&self) -> Result<(), CodecError> {
+        if self.version != OBJECT_V2_VERSION {
+            return Err(CodecError::InvalidObject("unsupported object version"));
+        }
+        if self.flags & !OBJECT_ALLOWED_FLAGS_MASK != 0 {
+            return Err(CodecError::InvalidObject("unknown object flags"));
+        }
+        if self.extensions.len() > MAX_OBJECT_EXTENSIONS {
+            return Err(CodecError::InvalidObject("too many extensions"));
+        }
+        if self
+            .extensions
+            .windows(2)
+            .any(|pair| pair[0].ext_type >= pair[1].ext_type)
+        {
+            return Err(CodecError::InvalidObject(
+                "extensions must be strictly ordered by type",
+            ));
+        }
+        for extension in &self.extensions {
+            extension.validate()?;
+        }
+        validate_body_and_signature_fields(
+            self.flags,
+            &self.ciphertext,
+            self.sender_pubkey.is_some(),
+            self.signature.is_some(),
+        )
+    }
+
+    /// Returns the extension of `ext_type`, if present.
+    pub fn extension(&self, ext_type: u16) -> Option<&ObjectExtension> {
+        self.extensions
+            .binary_search_by_key(&ext_type, |e| e.ext_type)
+            .ok()
+            .map(|i| &self.extensions[i])
+    }
+
+    /// Inserts or replaces an extension, keeping the list ordered.
+    pub fn set_extension(&mut self, extension: ObjectExtension) {
+        match self
+            .extensions
+            .binary_search_by_key(&extension.ext_type, |e| e.ext_type)
+        {
+            Ok(i) => self.extensions[i] = extension,
+            Err(i) => self.extensions.insert(i, extension),
+        }
+    }
+
+    pub fn expires_at(&self) -> Option<u64> {
+        let value = self.extension(EXT_EXPIRES_AT)?.value.as_slice();
+        Some(u64::from_be_bytes(value.try_into().ok()?))
+    }
+
+    /// Whether the object carries an expiry at or before `now_secs`.
+    pub fn is_expired(&self, now_secs: u64) -> bool {
+        self.expires_at().is_some_and(|at| at <= now_secs)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        std::str::from_utf8(&self.extension(EXT_CONTENT_TYPE)?.value).ok()
+    }
+
+    pub fn prev_root(&self) -> Option<ObjectRoot> {
+        self.extension(EXT_PREV_ROOT)?
+            .value
+            .as_slice()
+            .try_into()
+            .ok()
+    }
+
+    pub fn reply_to(&self) -> Option<ObjectRoot> {
+        self.extension(EXT_REPLY_TO)?
+            .value
+            .as_slice()
+            .try_into()
+            .ok()
+    }
+
+    pub fn compression(&self) -> Option<CompressionAlgorithm> {
+        CompressionAlgorithm::from_byte(*self.extension(EXT_COMPRESSION)?.value.first()?)
+    }
+
+    pub fn delegation(&self) -> Option<DelegationCertificate> {
+        decode_delegation_cbor(&self.extension(EXT_DELEGATION)?.value).ok()
+    }
+
+    pub fn multisig(&self) -> Option<MultisigAttestation> {
+        decode_multisig(&self.extension(EXT_MULTISIG)?.value).ok()
+    }
+
+    pub fn pow_stamp(&self) -> Option<u64> {
+        let value = self.extension(EXT_POW_STAMP)?.value.as_slice();
+        Some(u64::from_be_bytes(value.try_into().ok()?))
+    }
+
+    pub fn created_at(&self) -> Option<u64> {
+        let value = self.extension(EXT_CREATED_AT)?.value.as_slice();
+        Some(u64::from_be_bytes(value.try_into().ok()?))
+    }
+}
+
+/// Encodes the canonical signed-header subset of an `ObjectV2`, including
+/// its extension list.
+pub fn canonical_object_v2_header_cbor(object: &ObjectV2) -> Result<Vec<u8>, CodecError> {
+    object.validate()?;
+    let header = SignedObjectHeaderV2 {
+        version: object.version,
+        namespace: object.namespace,
+        epoch: object.epoch,
+        flags: object.flags,
+        tag: object.tag,
+        object_root: object.object_root,
+        extensions: &object.extensions,
+        sender_pubkey: object.sender_pubkey,
+        nonce: object.nonce,
+    };
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(&header, &mut bytes)
+        .map_err(|e| CodecError::Encode(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Computes the `ObjectV2` signature digest over canonical header and
+/// ciphertext hash.
+pub fn object_v2_signature_message_digest(object: &ObjectV2) -> Result<[u8; 32], CodecError> {
+    let header_cbor = canonical_object_v2_header_cbor(object)?;
+    let ciphertext_hash = blake3_32(&object.ciphertext);
+    let mut preimage = Vec::with_capacity(header_cbor.len() + ciphertext_hash.len());
+    preimage.extend_from_slice(&header_cbor);
+    preimage.extend_from_slice(&ciphertext_hash);
+    Ok(blake3_32(&preimage))
+}
+
+/// Computes the digest co-signers of an [`EXT_MULTISIG`] object sign.
+///
+/// Same as [`object_v2_signature_message_digest`] but with the co-signature
+/// list emptied and no sender, so co-signatures can be collected in any
+/// order before the submitting editor signs the finished object.
+pub fn object_v2_cosignature_digest(object: &ObjectV2) -> Result<[u8; 32], CodecError> {
+    let attestation = object.multisig().ok_or(CodecError::InvalidObject(
+        "object has no multisig extension",
+    ))?;
+    let mut extensions = object.extensions.clone();
+    let unsigned = ObjectExtension::multisig(&MultisigAttestation::unsigned(attestation.set))?;
+    for extension in &mut extensions {
+        if extension.ext_type == EXT_MULTISIG {
+            *extension = unsigned.clone();
+        }
+    }
+    let header = SignedObjectHeaderV2 {
+        version: object.version,
+        namespace: object.namespace,
+        epoch: object.epoch,
+        flags: object.flags,
+        tag: object.tag,
+        object_root: object.object_root,
+        extensions: &extensions,
+        sender_pubkey: None,
+        nonce: object.nonce,
+    };
+    let mut preimage = MULTISIG_COSIGN_DOMAIN.to_vec();
+    ciborium::ser::into_writer(&header, &mut preimage)
+        .map_err(|e| CodecError::Encode(e.to_string()))?;
+    preimage.extend_from_slice(&blake3_32(&object.ciphertext));
+    Ok(blake3_32(&preimage))
+}
+
+/// Computes the digest an [`EXT_POW_STAMP`] nonce is minted over.
+///
+/// Covers the signed header (without the stamp extension itself) and the
+/// ciphertext hash, so a stamp cannot be lifted onto another object, even
+/// one carrying the same payload.
+pub fn object_v2_pow_stamp_digest(object: &ObjectV2) -> Result<[u8; 32], CodecError> {
+    object.validate()?;
+    let extensions = object
+        .extensions
+        .iter()
+        .filter(|e| e.ext_type != EXT_POW_STAMP)
+        .cloned()
+        .collect::<Vec<_>>();
+    let header = SignedObjectHeaderV2 {
+        version: object.version,
+        namespace: object.namespace,
+        epoch: object.epoch,
+        flags: object.flags,
+        tag: object.tag,
+        object_root: object.object_root,
+        extensions: &extensions,
+        sender_pubkey: object.sender_pubkey,
+        nonce: object.nonce,
+    };
+    let mut preimage = POW_STAMP_DOMAIN.to_vec();
+    ciborium::ser::into_writer(&header, &mut preimage)
+        .map_err(|e| CodecError::Encode(e.to_string()))?;
+    preimage.extend_from_slice(&blake3_32(&object.ciphertext));
+    Ok(blake3_32(&preimage))
+}
+
+/// Encodes `ObjectV2` as CBOR after validation.
+pub fn encode_object_v2_cbor(object: &ObjectV2) -> Result<Vec<u8>, CodecError> {
+    object.validate()?;
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(object, &mut bytes)
+        .map_err(|e| CodecError::Encode(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Decodes and validates a full CBOR `ObjectV2`.
+pub fn decode_object_v2_cbor(bytes: &[u8]) -> Result<ObjectV2, CodecError> {
+    let object: ObjectV2 =
+        ciborium::de::from_reader(bytes).map_err(|e| CodecError::Decode(e.to_string()))?;
+    object.validate()?;
+    Ok(object)
+}
+
+/// An object of any supported wire version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyObject {
+    V1(ObjectV1),
+    V2(ObjectV2),
+}
+
+impl AnyObject {
+    pub fn version(&self) -> u16 {
+        match self {
+            Self::V1(o) => o.version,
+            Self::V2(o) => o.version,
+        }
+    }
+
+    pub fn namespace(&self) -> Namespace {
+        match self {
+            Self::V1(o) => o.namespace,
+            Self::V2(o) => o.namespace,
+        }
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        match self {
+            Self::V1(o) => o.epoch,
+            Self::V2(o) => o.epoch,
+        }
+    }
+
+    pub fn flags(&self) -> u16 {
+        match self {
+            Self::V1(o) => o.flags,
+            Self::V2(o) => o.flags,
+        }
+    }
+
+    pub fn tag(&self) -> Tag {
+        match self {
+            Self::V1(o) => o.tag,
+            Self::V2(o) => o.tag,
+        }
+    }
+
+    pub fn sender_pubkey(&self) -> Option<[u8; 32]> {
+        match self {
+            Self::V1(o) => o.sender_pubkey,
+            Self::V2(o) => o.sender_pubkey,
+        }
+    }
+
+    pub fn signature(&self) -> Option<&Signature> {
+        match self {
+            Self::V1(o) => o.signature.as_ref(),
+            Self::V2(o) => o.signature.as_ref(),
+        }
+    }
+
+    pub fn nonce(&self) -> [u8; 24] {
+        match self {
+            Self::V1(o) => o.nonce,
+            Self::V2(o) => o.nonce,
+        }
+    }
+
+    pub fn ciphertext(&self) -> &[u8] {
+        match self {
+            Self::V1(o) => &o.ciphertext,
+            Self::V2(o) => &o.ciphertext,
+        }
+    }
+
+    /// Signature digest for the object's wire version.
+    pub fn signature_message_digest(&self) -> Result<[u8; 32], CodecError> {
+        match self {
+            Self::V1(o) => object_signature_message_digest(o),
+            Self::V2(o) => object_v2_signature_message_digest(o),
+        }
+    }
+
+    pub fn object_root(&self) -> ObjectRoot {
+        match self {
+            Self::V1(o) => o.object_root,
+            Self::V2(o) => o.object_root,
+        }
+    }
+
+    /// Device delegation certificate; always `None` for `ObjectV1`.
+    pub fn delegation(&self) -> Option<DelegationCertificate> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(o) => o.delegation(),
+        }
+    }
+
+    /// Signer-set attestation; always `None` for `ObjectV1`.
+    pub fn multisig(&self) -> Option<MultisigAttestation> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(o) => o.multisig(),
+        }
+    }
+
+    /// Expiry time; always `None` for `ObjectV1`.
+    pub fn expires_at(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(o) => o.expires_at(),
+        }
+    }
+
+    /// Proof-of-work stamp nonce; always `None` for `ObjectV1`.
+    pub fn pow_stamp(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(o) => o.pow_stamp(),
+        }
+    }
+
+    /// Publisher-claimed creation time; always `None` for `ObjectV1`.
+    pub fn created_at(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(o) => o.created_at(),
+        }
+    }
+
+    /// Digest the stamp is minted over; always `None` for `ObjectV1`.
+    pub fn pow_stamp_digest(&self) -> Result<Option<[u8; 32]>, CodecError> {
+        match self {
+            Self::V1(_) => Ok(None),
+            Self::V2(o) => object_v2_pow_stamp_digest(o).map(Some),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: u16,
+}
+
+/// Decodes an object of any supported version, dispatching on its
+/// `version` field.
+pub fn decode_object_any(bytes: &[u8]) -> Result<AnyObject, CodecError> {
+    let probe: VersionProbe =
+        ciborium::de::from_reader(bytes).map_err(|e| CodecError::Decode(e.to_string()))?;
+    match probe.version {
+        OBJECT_V1_VERSION => decode_object_cbor(bytes).map(AnyObject::V1),
+        OBJECT_V2_VERSION => decode_object_v2_cbor(bytes).map(AnyObject::V2),
+        _ => Err(CodecError::InvalidObject("unsupported object version")),
+    }
+}
+
+/// Decodes one object prefix of any supported version, returning bytes
+/// consumed.
+pub fn decode_object_any_prefix(bytes: &[u8]) -> Result<(AnyObject, usize), CodecError> {
+    let probe: VersionProbe =
+        ciborium::de::from_reader(bytes).map_err(|e| CodecError::Decode(e.to_string()))?;
+    let mut cursor = std::io::Cursor::new(bytes);
+    let object = match probe.version {
+        OBJECT_V1_VERSION => {
+            let object: ObjectV1 = ciborium::de::from_reader(&mut cursor)
+                .map_err(|e| CodecError::Decode(e.to_string()))?;
+            object.validate()?;
+            AnyObject::V1(object)
+        }
+        OBJECT_V2_VERSION => {
+            let object: ObjectV2 = ciborium::de::from_reader(&mut cursor)
+                .map_err(|e| CodecError::Decode(e.to_string()))?;
+            object.validate()?;
+            AnyObject::V2(object)
+        }
+        _ => return Err(CodecError::InvalidObject("unsupported object version")),
+    };
+    Ok((object, cursor.position() as usize))
+}
+
+/// Decodes an object of any supported version, rejecting non-deterministic
+/// encodings and trailing bytes.
+pub fn decode_object_any_strict(bytes: &[u8]) -> Result<AnyObject, CodecError> {
+    check_deterministic_cbor(bytes)?;
+    let (object, _) = decode_object_any_prefix_strict(bytes)?;
+    Ok(object)
+}
+
+/// Decodes one deterministic object prefix of any supported version,
+/// returning bytes consumed. Trailing bytes after the object are ignored.
+pub fn decode_object_any_prefix_strict(bytes: &[u8]) -> Result<(AnyObject, usize), CodecError> {
+    let len = deterministic_item_len(bytes)?;
+    let item = &bytes[..len];
+    let object = decode_object_any(item)?;
+    let reencoded = match &object {
+        AnyObject::V1(object) => encode_object_cbor(object)?,
+        AnyObject::V2(object) => encode_object_v2_cbor(object)?,
+    };
+    ensure_canonical(item, &reencoded)?;
+    Ok((object, len))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        canonical_object_header_cbor, decode_object_cbor_prefix, encode_object_cbor,
-        object_signature_message_digest, ObjectV1, Signature, OBJECT_FLAG_SIGNED,
-        OBJECT_V1_VERSION,
+        canonical_object_header_cbor, decode_object_any, decode_object_any_prefix_strict,
+        decode_object_any_strict, decode_object_cbor, decode_object_cbor_prefix,
+        decode_object_cbor_strict, encode_object_cbor, encode_object_v2_cbor,
+        object_signature_message_digest, object_v2_signature_message_digest, AnyObject,
+        CompressionAlgorithm, ObjectExtension, ObjectV1, ObjectV2, Signature, OBJECT_FLAG_SIGNED,
+        OBJECT_V1_VERSION, OBJECT_V2_VERSION,
     };
     use veil_core::{Epoch, Namespace};
 
@@ -253,4 +880,113 @@ mod tests {
         assert_eq!(decoded, obj);
         assert_eq!(consumed, encoded.len());
     }
+
+    fn sample_object_v2() -> ObjectV2 {
+        let v1 = sample_object();
+        ObjectV2 {
+            version: OBJECT_V2_VERSION,
+            namespace: v1.namespace,
+            epoch: v1.epoch,
+            flags: v1.flags,
+            tag: v1.tag,
+            object_root: v1.object_root,
+            extensions: vec![
+                ObjectExtension::expires_at(1_700_000_000),
+                ObjectExtension::content_type("text/plain"),
+            ],
+            sender_pubkey: v1.sender_pubkey,
+            signature: v1.signature,
+            nonce: v1.nonce,
+            ciphertext: v1.ciphertext,
+            padding: v1.padding,
+        }
+    }
+
+    #[test]
+    fn v2_extensions_are_ordered_and_signed() {
+        let mut obj = sample_object_v2();
+        obj.set_extension(ObjectExtension::compression(CompressionAlgorithm::Zstd));
+        obj.set_extension(ObjectExtension::reply_to([0x88_u8; 32]));
+        assert_eq!(obj.expires_at(), Some(1_700_000_000));
+        assert!(obj.is_expired(1_700_000_000));
+        assert!(!obj.is_expired(1_699_999_999));
+        assert_eq!(obj.content_type(), Some("text/plain"));
+        assert_eq!(obj.reply_to(), Some([0x88_u8; 32]));
+        assert_eq!(obj.prev_root(), None);
+        assert_eq!(obj.compression(), Some(CompressionAlgorithm::Zstd));
+        obj.validate().expect("ordered extensions should validate");
+
+        let digest = object_v2_signature_message_digest(&obj).expect("digest");
+        let mut changed = obj.clone();
+        changed.set_extension(ObjectExtension::expires_at(1_800_000_000));
+        assert_ne!(
+            object_v2_signature_message_digest(&changed).expect("digest"),
+            digest
+        );
+
+        obj.extensions.swap(0, 1);
+        let err = obj
+            .validate()
+            .expect_err("unordered extensions should fail");
+        assert!(err.to_string().contains("strictly ordered"));
+    }
+
+    #[test]
+    fn v2_preserves_unknown_non_critical_extensions() {
+        let mut obj = sample_object_v2();
+        obj.set_extension(ObjectExtension::new(0x0400, false, vec![1, 2, 3]));
+        let encoded = encode_object_v2_cbor(&obj).expect("object should encode");
+        assert_eq!(
+            decode_object_any(&encoded).expect("decode"),
+            AnyObject::V2(obj.clone())
+        );
+
+        obj.set_extension(ObjectExtension::new(0x0401, true, Vec::new()));
+        let err = obj
+            .validate()
+            .expect_err("unknown critical extension should fail");
+        assert!(err.to_string().contains("unknown critical extension"));
+    }
+
+    #[test]
+    fn decode_any_dispatches_on_version() {
+        let v1 = sample_object();
+        let encoded = encode_object_cbor(&v1).expect("object should encode");
+        let decoded = decode_object_any(&encoded).expect("decode");
+        assert_eq!(decoded.version(), OBJECT_V1_VERSION);
+        assert_eq!(decoded.expires_at(), None);
+        assert_eq!(decoded, AnyObject::V1(v1));
+    }
+
+    #[test]
+    fn strict_decode_rejects_reordered_fields() {
+        let object = sample_object();
+        let canonical = encode_object_cbor(&object).expect("object should encode");
+        assert_eq!(
+            decode_object_cbor_strict(&canonical).expect("strict"),
+            object
+        );
+
+        let mut value: ciborium::Value = ciborium::de::from_reader(&canonical[..]).expect("value");
+        if let ciborium::Value::Map(entries) = &mut value {
+            entries.reverse();
+        }
+        let mut reordered = Vec::new();
+        ciborium::ser::into_writer(&value, &mut reordered).expect("reorder");
+        assert_eq!(decode_object_cbor(&reordered).expect("lenient"), object);
+        let err = decode_object_cbor_strict(&reordered).expect_err("reordered");
+        assert!(err.to_string().contains("canonical field order"));
+        assert!(decode_object_any_strict(&reordered).is_err());
+    }
+
+    #[test]
+    fn strict_prefix_decode_allows_trailing_bytes_only_after_item() {
+        let encoded = encode_object_v2_cbor(&sample_object_v2()).expect("object should encode");
+        let mut padded = encoded.clone();
+        padded.extend_from_slice(&[0_u8; 8]);
+        let (object, used) = decode_object_any_prefix_strict(&padded).expect("prefix");
+        assert_eq!(used, encoded.len());
+        assert_eq!(object, AnyObject::V2(sample_object_v2()));
+        assert!(decode_object_any_strict(&padded).is_err());
+    }
 }
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use veil_core::types::{Epoch, Namespace};
 use veil_core::{ObjectRoot, Tag};
 
+use crate::deterministic::{check_deterministic_cbor, ensure_canonical};
 use crate::error::CodecError;
 
 /// Shard schema version for `ShardV1`.
@@ -18,12 +19,26 @@ pub const SHARD_BUCKET_SIZES: [usize; 6] = [
     64 * 1024,
 ];
 
+/// Largest `n` (and so `k`) a shard set may declare. Reed-Solomon over
+/// GF(2^8) has at most 256 rows and every registered profile stays within
+/// it, fountain ones included; checking before any decoder state exists
+/// keeps hostile headers from sizing allocations.
+pub const MAX_SHARD_SET_N: u16 = 256;
+/// Fountain shard indices stay below this multiple of `n`, which leaves
+/// room for repair symbols emitted by ACK retries.
+pub const FOUNTAIN_INDEX_FACTOR: u16 = 8;
+
 /// Erasure coding mode carried on shard headers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum ShardErasureMode {
     Systematic = 0,
     HardenedNonSystematic = 1,
+    /// Rateless fountain code; `n` is the initial emission and `index` may
+    /// exceed it for repair symbols generated on demand.
+    Fountain = 2,
+    /// Systematic RS over an all-or-nothing package of the object.
+    AllOrNothing = 3,
 }
 
 /// Shard metadata header.
@@ -61,6 +76,14 @@ pub struct ShardV1 {
 }
 
 impl ShardHeaderV1 {
+    /// Exclusive upper bound on `index` for this shard set.
+    pub fn index_limit(&self) -> u16 {
+        match self.erasure_mode {
+            ShardErasureMode::Fountain => self.n.saturating_mul(FOUNTAIN_INDEX_FACTOR),
+            _ => self.n,
+        }
+    }
+
     /// Validates header invariants.
     pub fn validate(&self) -> Result<(), CodecError> {
         if self.version != SHARD_V1_VERSION {
@@ -72,7 +95,10 @@ impl ShardHeaderV1 {
         if self.k > self.n {
             return Err(CodecError::InvalidShard("k must be <= n"));
         }
-        if self.index >= self.n {
+        if self.n > MAX_SHARD_SET_N {
+            return Err(CodecError::InvalidShard("n exceeds shard-set limit"));
+        }
+        if self.index >= self.index_limit() {
             return Err(CodecError::InvalidShard("index out of range"));
         }
         if !SHARD_BUCKET_SIZES.contains(&(self.bucket_size as usize)) {
@@ -85,24 +111,28 @@ impl ShardHeaderV1 {
 impl ShardV1 {
     /// Validates header and payload bucket sizing.
     pub fn validate(&self) -> Result<(), CodecError> {
-        self.header.validate()?;
-        if self.payload.is_empty() {
-            return Err(CodecError::InvalidShard("payload must not be empty"));
-        }
+        validate_parts(&self.header, self.payload.len())
+    }
+}
 
-        let total_len = SHARD_HEADER_LEN + self.payload.len();
-        if total_len != self.header.bucket_size as usize {
-            return Err(CodecError::InvalidShard(
-                "payload/header length does not match declared bucket size",
-            ));
-        }
-        if !SHARD_BUCKET_SIZES.contains(&total_len) {
-            return Err(CodecError::InvalidShard(
-                "shard does not match allowed bucket size",
-            ));
-        }
-        Ok(())
+fn validate_parts(header: &ShardHeaderV1, payload_len: usize) -> Result<(), CodecError> {
+    header.validate()?;
+    if payload_len == 0 {
+        return Err(CodecError::InvalidShard("payload must not be empty"));
+    }
+
+    let total_len = SHARD_HEADER_LEN + payload_len;
+    if total_len != header.bucket_size as usize {
+        return Err(CodecError::InvalidShard(
+            "payload/header length does not match declared bucket size",
+        ));
+    }
+    if !SHARD_BUCKET_SIZES.contains(&total_len) {
+        return Err(CodecError::InvalidShard(
+            "shard does not match allowed bucket size",
+        ));
     }
+    Ok(())
 }
 
 /// Encodes `ShardV1` as CBOR after validation.
@@ -113,6 +143,99 @@ pub fn encode_shard_cbor(shard: &ShardV1) -> Result<Vec<u8>, CodecError> {
     Ok(bytes)
 }
 
+/// Encodes a shard from a header and borrowed payload directly into `out`.
+///
+/// Produces exactly the bytes of [`encode_shard_cbor`] without building an
+/// owned `ShardV1`, so callers can write parity blocks straight into reused
+/// wire buffers.
+pub fn encode_shard_cbor_into(
+    header: &ShardHeaderV1,
+    payload: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), CodecError> {
+    validate_parts(header, payload.len())?;
+    out.reserve(160 + 2 * payload.len());
+
+    write_head(out, MAJOR_MAP, 2);
+    write_text(out, "header");
+    write_head(out, MAJOR_MAP, 11);
+    write_text(out, "version");
+    write_head(out, MAJOR_UINT, u64::from(header.version));
+    write_text(out, "namespace");
+    write_head(out, MAJOR_UINT, u64::from(header.namespace.0));
+    write_text(out, "epoch");
+    write_head(out, MAJOR_UINT, u64::from(header.epoch.0));
+    write_text(out, "tag");
+    write_byte_array(out, &header.tag);
+    write_text(out, "object_root");
+    write_byte_array(out, &header.object_root);
+    write_text(out, "profile_id");
+    write_head(out, MAJOR_UINT, u64::from(header.profile_id));
+    write_text(out, "erasure_mode");
+    write_text(
+        out,
+        match header.erasure_mode {
+            ShardErasureMode::Systematic => "Systematic",
+            ShardErasureMode::HardenedNonSystematic => "HardenedNonSystematic",
+            ShardErasureMode::Fountain => "Fountain",
+            ShardErasureMode::AllOrNothing => "AllOrNothing",
+        },
+    );
+    write_text(out, "bucket_size");
+    write_head(out, MAJOR_UINT, u64::from(header.bucket_size));
+    write_text(out, "k");
+    write_head(out, MAJOR_UINT, u64::from(header.k));
+    write_text(out, "n");
+    write_head(out, MAJOR_UINT, u64::from(header.n));
+    write_text(out, "index");
+    write_head(out, MAJOR_UINT, u64::from(header.index));
+    write_text(out, "payload");
+    write_byte_array(out, payload);
+    Ok(())
+}
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+
+fn write_head(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => out.push(major | value as u8),
+        24..=0xFF => out.extend_from_slice(&[major | 24, value as u8]),
+        0x100..=0xFFFF => {
+            out.push(major | 25);
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xFFFF_FFFF => {
+            out.push(major | 26);
+            out.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+fn write_text(out: &mut Vec<u8>, text: &str) {
+    write_head(out, MAJOR_TEXT, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+/// Writes bytes the way serde sequences of `u8` serialize: an array of ints.
+fn write_byte_array(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_head(out, MAJOR_ARRAY, bytes.len() as u64);
+    for byte in bytes {
+        if *byte < 24 {
+            out.push(*byte);
+        } else {
+            out.extend_from_slice(&[24, *byte]);
+        }
+    }
+}
+
 /// Decodes and validates a CBOR shard.
 pub fn decode_shard_cbor(bytes: &[u8]) -> Result<ShardV1, CodecError> {
     let shard: ShardV1 =
@@ -121,9 +244,23 @@ pub fn decode_shard_cbor(bytes: &[u8]) -> Result<ShardV1, CodecError> {
     Ok(shard)
 }
 
+/// Decodes a CBOR shard, rejecting non-deterministic encodings so each
+/// shard has exactly one valid byte form (and thus one `shard_id`).
+pub fn decode_shard_cbor_strict(bytes: &[u8]) -> Result<ShardV1, CodecError> {
+    check_deterministic_cbor(bytes)?;
+    let shard = decode_shard_cbor(bytes)?;
+    ensure_canonical(bytes, &encode_shard_cbor(&shard)?)?;
+    Ok(shard)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ShardErasureMode, ShardHeaderV1, ShardV1, SHARD_HEADER_LEN, SHARD_V1_VERSION};
+    use super::{
+        decode_shard_cbor, decode_shard_cbor_strict, encode_shard_cbor, encode_shard_cbor_into,
+        ShardErasureMode, ShardHeaderV1, ShardV1, FOUNTAIN_INDEX_FACTOR, MAX_SHARD_SET_N,
+        SHARD_BUCKET_SIZES, SHARD_HEADER_LEN, SHARD_V1_VERSION,
+    };
+    use crate::error::CodecError;
     use veil_core::{Epoch, Namespace};
 
     fn sample_shard() -> ShardV1 {
@@ -160,6 +297,23 @@ mod tests {
         s.header.index = s.header.n;
         let err = s.validate().expect_err("index >= n should fail");
         assert!(err.to_string().contains("index out of range"));
+
+        s.header.erasure_mode = ShardErasureMode::Fountain;
+        assert!(s.validate().is_ok(), "fountain repair symbols exceed n");
+        s.header.index = s.header.n * FOUNTAIN_INDEX_FACTOR;
+        assert!(s.validate().is_err(), "fountain index is still bounded");
+    }
+
+    #[test]
+    fn validate_rejects_oversized_shard_sets() {
+        for mode in [ShardErasureMode::Systematic, ShardErasureMode::Fountain] {
+            let mut s = sample_shard();
+            s.header.erasure_mode = mode;
+            s.header.k = 60_000;
+            s.header.n = 60_000;
+            let err = s.validate().expect_err("oversized set should fail");
+            assert!(err.to_string().contains("shard-set limit"));
+        }
     }
 
     #[test]
@@ -177,4 +331,131 @@ mod tests {
         s64.header.bucket_size = (64 * 1024) as u32;
         assert!(s64.validate().is_ok());
     }
+
+    #[test]
+    fn encode_into_matches_serde_encoding() {
+        let mut shard = sample_shard();
+        shard.header.index = 255;
+        shard.header.n = MAX_SHARD_SET_N;
+        shard.header.epoch = Epoch(70_000);
+        for (i, byte) in shard.payload.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        for mode in [
+            ShardErasureMode::Systematic,
+            ShardErasureMode::HardenedNonSystematic,
+            ShardErasureMode::Fountain,
+            ShardErasureMode::AllOrNothing,
+        ] {
+            shard.header.erasure_mode = mode;
+            let mut out = vec![0xFF];
+            encode_shard_cbor_into(&shard.header, &shard.payload, &mut out).expect("encode");
+            assert_eq!(out[1..], encode_shard_cbor(&shard).expect("encode")[..]);
+        }
+
+        let short = &shard.payload[1..];
+        assert!(encode_shard_cbor_into(&shard.header, short, &mut Vec::new()).is_err());
+    }
+
+    /// SplitMix64, so the property test below is reproducible without a
+    /// `rand` dependency.
+    struct SplitMix(u64);
+
+    impl SplitMix {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        /// Uniform in `[0, max]`, or a CBOR head-width boundary half the time.
+        fn uint(&mut self, max: u64) -> u64 {
+            const EDGES: [u64; 8] = [0, 23, 24, 255, 256, 65_535, 65_536, u32::MAX as u64];
+            let edge = EDGES[(self.next() % 8) as usize];
+            if self.next() & 1 == 0 && edge <= max {
+                edge
+            } else {
+                self.next() % (max + 1)
+            }
+        }
+
+        fn bytes(&mut self, out: &mut [u8]) {
+            for byte in out {
+                *byte = self.next() as u8;
+            }
+        }
+    }
+
+    #[test]
+    fn encode_into_matches_serde_encoding_for_random_shards() {
+        let mut rng = SplitMix(0x5EED);
+        let modes = [
+            ShardErasureMode::Systematic,
+            ShardErasureMode::HardenedNonSystematic,
+            ShardErasureMode::Fountain,
+            ShardErasureMode::AllOrNothing,
+        ];
+        for round in 0..4 * SHARD_BUCKET_SIZES.len() * 8 {
+            let bucket_size = SHARD_BUCKET_SIZES[round % SHARD_BUCKET_SIZES.len()];
+            let erasure_mode = modes[(round / SHARD_BUCKET_SIZES.len()) % modes.len()];
+            let n = 1 + rng.uint(u64::from(MAX_SHARD_SET_N) - 1) as u16;
+            let k = 1 + rng.uint(u64::from(n) - 1) as u16;
+            let mut header = ShardHeaderV1 {
+                version: SHARD_V1_VERSION,
+                namespace: Namespace(rng.uint(u64::from(u16::MAX)) as u16),
+                epoch: Epoch(rng.uint(u64::from(u32::MAX)) as u32),
+                tag: [0; 32],
+                object_root: [0; 32],
+                profile_id: rng.uint(u64::from(u16::MAX)) as u16,
+                erasure_mode,
+                bucket_size: bucket_size as u32,
+                k,
+                n,
+                index: 0,
+            };
+            header.index = rng.uint(u64::from(header.index_limit()) - 1) as u16;
+            rng.bytes(&mut header.tag);
+            rng.bytes(&mut header.object_root);
+            let mut payload = vec![0; bucket_size - SHARD_HEADER_LEN];
+            rng.bytes(&mut payload);
+            let shard = ShardV1 { header, payload };
+
+            let mut out = Vec::new();
+            encode_shard_cbor_into(&shard.header, &shard.payload, &mut out).expect("encode");
+            assert_eq!(out, encode_shard_cbor(&shard).expect("encode"), "{shard:?}");
+            for len in [shard.payload.len() - 1, shard.payload.len() + 1] {
+                let payload = vec![0; len];
+                assert!(encode_shard_cbor_into(&shard.header, &payload, &mut Vec::new()).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn strict_decode_rejects_alternate_encodings_of_same_shard() {
+        let shard = sample_shard();
+        let canonical = encode_shard_cbor(&shard).expect("encode");
+        assert_eq!(decode_shard_cbor_strict(&canonical).expect("strict"), shard);
+
+        // `"k": 6` re-encoded with a one-byte argument head.
+        let at = canonical
+            .windows(3)
+            .position(|w| w == [0x61, b'k', 0x06])
+            .expect("k field");
+        let mut long_head = canonical.clone();
+        long_head.splice(at + 2..at + 3, [0x18, 0x06]);
+        assert_eq!(decode_shard_cbor(&long_head).expect("lenient"), shard);
+        assert!(matches!(
+            decode_shard_cbor_strict(&long_head),
+            Err(CodecError::NonDeterministic(_))
+        ));
+
+        let mut trailing = canonical;
+        trailing.push(0x00);
+        assert!(matches!(
+            decode_shard_cbor_strict(&trailing),
+            Err(CodecError::NonDeterministic(_))
+        ));
+    }
 }
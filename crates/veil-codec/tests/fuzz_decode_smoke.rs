@@ -1,12 +1,13 @@
 use std::panic;
 
 use veil_codec::object::{
-    decode_object_cbor, decode_object_cbor_prefix, encode_object_cbor, ObjectV1, Signature,
-    OBJECT_FLAG_SIGNED, OBJECT_V1_VERSION,
+    decode_object_any_prefix_strict, decode_object_cbor, decode_object_cbor_prefix,
+    decode_object_cbor_strict, encode_object_cbor, ObjectV1, Signature, OBJECT_FLAG_SIGNED,
+    OBJECT_V1_VERSION,
 };
 use veil_codec::shard::{
-    decode_shard_cbor, encode_shard_cbor, ShardErasureMode, ShardHeaderV1, ShardV1,
-    SHARD_HEADER_LEN, SHARD_V1_VERSION,
+    decode_shard_cbor, decode_shard_cbor_strict, encode_shard_cbor, ShardErasureMode,
+    ShardHeaderV1, ShardV1, SHARD_HEADER_LEN, SHARD_V1_VERSION,
 };
 use veil_core::{Epoch, Namespace};
 
@@ -112,3 +113,45 @@ fn fuzz_like_mutations_of_valid_vectors_do_not_panic() {
         );
     }
 }
+
+#[test]
+fn fuzz_like_strict_decoders_accept_only_canonical_bytes() {
+    let obj_bytes = encode_object_cbor(&sample_object()).expect("object should encode");
+    let shard_bytes = encode_shard_cbor(&sample_shard()).expect("shard should encode");
+    assert!(decode_object_cbor_strict(&obj_bytes).is_ok());
+    assert!(decode_shard_cbor_strict(&shard_bytes).is_ok());
+
+    for i in 0..1024_usize {
+        let mut data_obj = obj_bytes.clone();
+        let idx = (i * 7) % data_obj.len();
+        data_obj[idx] ^= (i as u8).wrapping_mul(29).wrapping_add(1);
+        if i % 3 == 0 {
+            data_obj.insert(idx, (i as u8).wrapping_mul(13));
+        }
+
+        let strict = panic::catch_unwind(|| decode_object_cbor_strict(&data_obj));
+        let strict = strict.unwrap_or_else(|_| panic!("strict object decode panicked at {i}"));
+        if let Ok(object) = strict {
+            assert_eq!(
+                encode_object_cbor(&object).expect("re-encode"),
+                data_obj,
+                "strict object decode accepted non-canonical bytes at case {i}",
+            );
+        }
+        let prefix = panic::catch_unwind(|| decode_object_any_prefix_strict(&data_obj));
+        assert!(prefix.is_ok(), "strict prefix decode panicked at case {i}");
+
+        let mut data_shard = shard_bytes.clone();
+        let idx = (i * 11) % 160;
+        data_shard[idx] ^= (i as u8).wrapping_mul(17).wrapping_add(3);
+        let strict = panic::catch_unwind(|| decode_shard_cbor_strict(&data_shard));
+        let strict = strict.unwrap_or_else(|_| panic!("strict shard decode panicked at {i}"));
+        if let Ok(shard) = strict {
+            assert_eq!(
+                encode_shard_cbor(&shard).expect("re-encode"),
+                data_shard,
+                "strict shard decode accepted non-canonical bytes at case {i}",
+            );
+        }
+    }
+}
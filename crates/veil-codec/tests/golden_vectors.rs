@@ -1,10 +1,15 @@
+use veil_codec::json::{
+    decode_object_json, decode_shard_json, encode_object_json, encode_shard_json,
+};
 use veil_codec::object::{
-    decode_object_cbor, encode_object_cbor, object_signature_message_digest, ObjectV1, Signature,
-    OBJECT_FLAG_ACK_REQUESTED, OBJECT_FLAG_SIGNED, OBJECT_V1_VERSION,
+    decode_object_any, decode_object_any_strict, decode_object_cbor, decode_object_cbor_strict,
+    encode_object_cbor, encode_object_v2_cbor, object_signature_message_digest, AnyObject,
+    ObjectExtension, ObjectV1, ObjectV2, Signature, OBJECT_FLAG_ACK_REQUESTED, OBJECT_FLAG_SIGNED,
+    OBJECT_V1_VERSION, OBJECT_V2_VERSION,
 };
 use veil_codec::shard::{
-    decode_shard_cbor, encode_shard_cbor, ShardErasureMode, ShardHeaderV1, ShardV1,
-    SHARD_HEADER_LEN, SHARD_V1_VERSION,
+    decode_shard_cbor, decode_shard_cbor_strict, encode_shard_cbor, ShardErasureMode,
+    ShardHeaderV1, ShardV1, SHARD_HEADER_LEN, SHARD_V1_VERSION,
 };
 use veil_core::hash::blake3_32;
 use veil_core::{Epoch, Namespace};
@@ -17,6 +22,13 @@ fn to_hex(bytes: &[u8]) -> String {
     out
 }
 
+fn hex_to_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("vector must be hex"))
+        .collect()
+}
+
 fn read_vector(name: &str) -> String {
     let path = format!("{}/tests/vectors/{name}", env!("CARGO_MANIFEST_DIR"));
     std::fs::read_to_string(path)
@@ -41,6 +53,28 @@ fn sample_object() -> ObjectV1 {
     }
 }
 
+fn sample_object_v2() -> ObjectV2 {
+    ObjectV2 {
+        version: OBJECT_V2_VERSION,
+        namespace: Namespace(42),
+        epoch: Epoch(123_456),
+        flags: OBJECT_FLAG_SIGNED | OBJECT_FLAG_ACK_REQUESTED,
+        tag: [0x11_u8; 32],
+        object_root: [0x22_u8; 32],
+        extensions: vec![
+            ObjectExtension::expires_at(1_700_000_000),
+            ObjectExtension::content_type("application/cbor"),
+            ObjectExtension::prev_root([0x66_u8; 32]),
+            ObjectExtension::new(0x0100, false, vec![0x01, 0x02]),
+        ],
+        sender_pubkey: Some([0xAA_u8; 32]),
+        signature: Some(Signature([0xBB_u8; 64])),
+        nonce: [0x33_u8; 24],
+        ciphertext: vec![0x44_u8; 48],
+        padding: vec![0x55_u8; 16],
+    }
+}
+
 fn sample_shard() -> ShardV1 {
     ShardV1 {
         header: ShardHeaderV1 {
@@ -71,6 +105,50 @@ fn golden_object_cbor_vector_matches() {
     );
 }
 
+#[test]
+fn golden_object_v2_cbor_vector_matches() {
+    let encoded = encode_object_v2_cbor(&sample_object_v2()).expect("object should encode");
+    let hex = to_hex(&encoded);
+    let expected = read_vector("object_v2_cbor.hex");
+    assert_eq!(
+        hex, expected,
+        "update tests/vectors/object_v2_cbor.hex to: {hex}"
+    );
+}
+
+#[test]
+fn golden_vectors_decode_through_version_dispatcher() {
+    let v1 = hex_to_bytes(&read_vector("object_v1_cbor.hex"));
+    let v2 = hex_to_bytes(&read_vector("object_v2_cbor.hex"));
+    assert_eq!(
+        decode_object_any(&v1).expect("v1 should decode"),
+        AnyObject::V1(sample_object())
+    );
+    assert_eq!(
+        decode_object_any(&v2).expect("v2 should decode"),
+        AnyObject::V2(sample_object_v2())
+    );
+}
+
+#[test]
+fn golden_vectors_pass_strict_decoding() {
+    let v1 = hex_to_bytes(&read_vector("object_v1_cbor.hex"));
+    let v2 = hex_to_bytes(&read_vector("object_v2_cbor.hex"));
+    assert_eq!(
+        decode_object_cbor_strict(&v1).expect("v1 should be deterministic"),
+        sample_object()
+    );
+    assert_eq!(
+        decode_object_any_strict(&v2).expect("v2 should be deterministic"),
+        AnyObject::V2(sample_object_v2())
+    );
+    let shard = encode_shard_cbor(&sample_shard()).expect("shard should encode");
+    assert_eq!(
+        decode_shard_cbor_strict(&shard).expect("shard should be deterministic"),
+        sample_shard()
+    );
+}
+
 #[test]
 fn golden_shard_cbor_vector_matches() {
     let encoded = encode_shard_cbor(&sample_shard()).expect("shard should encode");
@@ -91,6 +169,50 @@ fn golden_shard_cbor_vector_matches() {
     );
 }
 
+#[test]
+fn golden_object_json_vector_matches() {
+    let json = encode_object_json(&sample_object()).expect("object should encode");
+    let expected = read_vector("object_v1_json.txt");
+    assert_eq!(
+        json, expected,
+        "update tests/vectors/object_v1_json.txt to: {json}"
+    );
+}
+
+#[test]
+fn golden_object_json_vector_matches_cbor_vector() {
+    let decoded =
+        decode_object_json(&read_vector("object_v1_json.txt")).expect("json should decode");
+    let cbor = encode_object_cbor(&decoded).expect("object should encode");
+    assert_eq!(to_hex(&cbor), read_vector("object_v1_cbor.hex"));
+}
+
+#[test]
+fn golden_shard_json_vector_matches() {
+    let json = encode_shard_json(&sample_shard()).expect("shard should encode");
+    let digest_hex = to_hex(&blake3_32(json.as_bytes()));
+    let expected_len = read_vector("shard_v1_json.len")
+        .parse::<usize>()
+        .expect("length vector must parse as usize");
+    let expected_digest = read_vector("shard_v1_json.blake3hex");
+    assert_eq!(
+        json.len(),
+        expected_len,
+        "update tests/vectors/shard_v1_json.len to: {}",
+        json.len()
+    );
+    assert_eq!(
+        digest_hex, expected_digest,
+        "update tests/vectors/shard_v1_json.blake3hex to: {digest_hex}"
+    );
+
+    let decoded = decode_shard_json(&json).expect("shard should decode");
+    assert_eq!(
+        encode_shard_cbor(&decoded).expect("shard should encode"),
+        encode_shard_cbor(&sample_shard()).expect("shard should encode")
+    );
+}
+
 #[test]
 fn object_round_trip_is_lossless() {
     let obj = sample_object();
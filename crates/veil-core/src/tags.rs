@@ -78,6 +78,20 @@ pub fn derive_channel_rv_tag(
     )
 }
 
+/// Derives a rotating pairwise rendezvous tag from a contact shared secret:
+/// `H("rv-pair" || shared_secret || epoch_be || namespace_be)`.
+///
+/// Unlike [`derive_rv_tag`], the tag cannot be computed from the recipient's
+/// public key alone, so only the two contacts can link it to the inbox.
+pub fn derive_pairwise_rv_tag(shared_secret: &[u8; 32], epoch: Epoch, namespace: Namespace) -> Tag {
+    let mut buf = Vec::with_capacity(7 + 32 + 4 + 2);
+    buf.extend_from_slice(b"rv-pair");
+    buf.extend_from_slice(shared_secret);
+    buf.extend_from_slice(&epoch.0.to_be_bytes());
+    buf.extend_from_slice(&namespace.0.to_be_bytes());
+    blake3_32(&buf)
+}
+
 /// Returns the current epoch index for `now_seconds / epoch_seconds`.
 ///
 /// `epoch_seconds=0` is treated as `1` to avoid division-by-zero.
@@ -120,12 +134,34 @@ pub fn derive_rv_tag_window(
     }
 }
 
+/// Derives the current pairwise rendezvous tag and, during the overlap tail,
+/// also derives the next-epoch pairwise tag.
+pub fn derive_pairwise_rv_tag_window(
+    shared_secret: &[u8; 32],
+    now_seconds: u64,
+    epoch_seconds: u64,
+    overlap_seconds: u64,
+    namespace: Namespace,
+) -> (Tag, Option<Tag>) {
+    let cur_epoch = current_epoch(now_seconds, epoch_seconds);
+    let current = derive_pairwise_rv_tag(shared_secret, cur_epoch, namespace);
+    if in_next_epoch_overlap(now_seconds, epoch_seconds, overlap_seconds) {
+        let next_epoch = Epoch(cur_epoch.0.saturating_add(1));
+        (
+            current,
+            Some(derive_pairwise_rv_tag(shared_secret, next_epoch, namespace)),
+        )
+    } else {
+        (current, None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         current_epoch, derive_channel_feed_tag, derive_channel_namespace, derive_channel_rv_tag,
-        derive_feed_tag, derive_rv_tag, derive_rv_tag_window, in_next_epoch_overlap,
-        normalize_channel_id,
+        derive_feed_tag, derive_pairwise_rv_tag, derive_pairwise_rv_tag_window, derive_rv_tag,
+        derive_rv_tag_window, in_next_epoch_overlap, normalize_channel_id,
     };
     use crate::hash::blake3_32;
     use crate::types::{Epoch, Namespace};
@@ -161,6 +197,38 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn pairwise_rv_tag_uses_rv_pair_domain_separator_and_be_fields() {
+        let shared_secret = [0x33_u8; 32];
+
+        let mut expected_preimage = Vec::with_capacity(7 + 32 + 4 + 2);
+        expected_preimage.extend_from_slice(b"rv-pair");
+        expected_preimage.extend_from_slice(&shared_secret);
+        expected_preimage.extend_from_slice(&42_u32.to_be_bytes());
+        expected_preimage.extend_from_slice(&7_u16.to_be_bytes());
+
+        let expected = blake3_32(&expected_preimage);
+        let actual = derive_pairwise_rv_tag(&shared_secret, Epoch(42), Namespace(7));
+
+        assert_eq!(actual, expected);
+        assert_ne!(
+            actual,
+            derive_rv_tag(&shared_secret, Epoch(42), Namespace(7))
+        );
+    }
+
+    #[test]
+    fn pairwise_overlap_window_derives_next_tag_near_boundary() {
+        let secret = [0x5A; 32];
+        let ns = Namespace(2);
+        let (current, next) = derive_pairwise_rv_tag_window(&secret, 99, 100, 10, ns);
+        assert_eq!(current, derive_pairwise_rv_tag(&secret, Epoch(0), ns));
+        assert_eq!(next, Some(derive_pairwise_rv_tag(&secret, Epoch(1), ns)));
+
+        let (_, next) = derive_pairwise_rv_tag_window(&secret, 50, 100, 10, ns);
+        assert!(next.is_none());
+    }
+
     #[test]
     fn tag_derivation_has_domain_separation() {
         let key = [0x42_u8; 32];
@@ -0,0 +1,139 @@
+//! Argon2id passphrase key derivation (RFC 9106, version 0x13).
+//!
+//! Thin wrapper over the `argon2` crate that keeps VEIL's own parameter type
+//! and the limits this node accepts.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use thiserror::Error;
+
+/// Upper bound on memory cost accepted by [`argon2id`] (4 GiB).
+pub const MAX_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+/// Upper bound on passes accepted by [`argon2id`], so a stored cost header
+/// cannot stall the node in key derivation.
+pub const MAX_ITERATIONS: u32 = 64;
+
+/// Errors returned by key derivation helpers.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum KdfError {
+    #[error("invalid argon2 parameters: {0}")]
+    InvalidParams(&'static str),
+}
+
+/// Argon2id cost parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Params {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    /// Number of passes over memory.
+    pub iterations: u32,
+    /// Number of lanes.
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Params {
+    /// OWASP's minimum recommendation: 19 MiB, two passes, one lane.
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl Argon2Params {
+    /// Checks the costs are within what Argon2 and this node accept.
+    pub fn validate(&self) -> Result<(), KdfError> {
+        if self.parallelism == 0 || self.parallelism > 0x00FF_FFFF {
+            return Err(KdfError::InvalidParams("parallelism out of range"));
+        }
+        if self.iterations == 0 {
+            return Err(KdfError::InvalidParams("iterations must be non-zero"));
+        }
+        if self.iterations > MAX_ITERATIONS {
+            return Err(KdfError::InvalidParams("iterations too large"));
+        }
+        if self.memory_kib < 8 * self.parallelism {
+            return Err(KdfError::InvalidParams("memory below 8 KiB per lane"));
+        }
+        if self.memory_kib > MAX_MEMORY_KIB {
+            return Err(KdfError::InvalidParams("memory cost too large"));
+        }
+        Ok(())
+    }
+}
+
+/// Derives `out.len()` bytes from `password` and `salt` with Argon2id.
+pub fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    params: Argon2Params,
+    out: &mut [u8],
+) -> Result<(), KdfError> {
+    params.validate()?;
+    if salt.len() < 8 {
+        return Err(KdfError::InvalidParams("salt shorter than 8 bytes"));
+    }
+    if out.len() < 4 {
+        return Err(KdfError::InvalidParams("output shorter than 4 bytes"));
+    }
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(out.len()),
+    )
+    .map_err(|_| KdfError::InvalidParams("rejected by argon2"))?;
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password, salt, out)
+        .map_err(|_| KdfError::InvalidParams("rejected by argon2"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{argon2id, Argon2Params, KdfError, MAX_ITERATIONS};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn argon2id_matches_previous_keystore_output() {
+        // Tag from the in-tree implementation this replaced, which passed the
+        // RFC 9106 vector; keystores sealed by it must still open.
+        let params = Argon2Params {
+            memory_kib: 64,
+            iterations: 2,
+            parallelism: 1,
+        };
+        let mut tag = [0_u8; 32];
+        argon2id(b"password", b"somesalt", params, &mut tag).expect("argon2id");
+        assert_eq!(
+            hex(&tag),
+            "16a1a498734609dd01456da406de9f3d9da93e6c86c300a12fc1465214ce4922"
+        );
+    }
+
+    #[test]
+    fn rejects_weak_parameters() {
+        let mut out = [0_u8; 32];
+        let tiny = Argon2Params {
+            memory_kib: 4,
+            iterations: 1,
+            parallelism: 1,
+        };
+        assert!(matches!(
+            argon2id(b"pw", b"saltsalt", tiny, &mut out),
+            Err(KdfError::InvalidParams(_))
+        ));
+        assert!(argon2id(b"pw", b"short", Argon2Params::default(), &mut out).is_err());
+        let slow = Argon2Params {
+            iterations: MAX_ITERATIONS + 1,
+            ..Argon2Params::default()
+        };
+        assert!(matches!(
+            slow.validate(),
+            Err(KdfError::InvalidParams("iterations too large"))
+        ));
+    }
+}
@@ -1,7 +1,11 @@
+use k256::ecdh::diffie_hellman;
+use k256::{PublicKey, SecretKey};
 use veil_core::hash::blake3_32;
 
+use crate::signing::SigningError;
+
 /// Derives a deterministic 32-byte symmetric encryption key from a secret key.
-/// 
+///
 /// This ensures that nodes sharing the same identity (e.g. VPS and mobile)
 /// derive the same encryption key for their protocol runtimes.
 pub fn derive_encrypt_key(secret_key: &[u8; 32]) -> [u8; 32] {
@@ -11,9 +15,56 @@ pub fn derive_encrypt_key(secret_key: &[u8; 32]) -> [u8; 32] {
     blake3_32(&preimage)
 }
 
+/// Derives the secp256k1 secret of device subkey `device_index` under a root
+/// identity secret.
+///
+/// Derivation is hardened (it needs the root secret), so leaking one device
+/// key reveals neither the root nor sibling devices. Candidates outside the
+/// curve order are skipped with an internal counter.
+pub fn derive_device_secret(root_secret: &[u8; 32], device_index: u32) -> [u8; 32] {
+    let mut counter = 0_u32;
+    loop {
+        let mut preimage = Vec::with_capacity(17 + 32 + 8);
+        preimage.extend_from_slice(b"veil/hd-device/v1");
+        preimage.extend_from_slice(root_secret);
+        preimage.extend_from_slice(&device_index.to_be_bytes());
+        preimage.extend_from_slice(&counter.to_be_bytes());
+        let candidate = blake3_32(&preimage);
+        if SecretKey::from_slice(&candidate).is_ok() {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+/// Derives a pairwise contact secret via secp256k1 ECDH.
+///
+/// `remote_pubkey` is a BIP-340 x-only (Nostr) public key. Only the shared
+/// x-coordinate is used, so both contacts derive the same secret regardless
+/// of Y parity. The result is suitable for pairwise rendezvous tags.
+pub fn derive_pairwise_secret(
+    local_secret: &[u8; 32],
+    remote_pubkey: &[u8; 32],
+) -> Result<[u8; 32], SigningError> {
+    let secret_key =
+        SecretKey::from_slice(local_secret).map_err(|_| SigningError::InvalidSecretKey)?;
+    let mut sec1 = [0_u8; 33];
+    sec1[0] = 0x02;
+    sec1[1..].copy_from_slice(remote_pubkey);
+    let public_key =
+        PublicKey::from_sec1_bytes(&sec1).map_err(|_| SigningError::InvalidPublicKey)?;
+    let shared = diffie_hellman(secret_key.to_nonzero_scalar(), public_key.as_affine());
+
+    let mut preimage = Vec::with_capacity(19 + 32);
+    preimage.extend_from_slice(b"veil/pairwise-rv/v1");
+    preimage.extend_from_slice(shared.raw_secret_bytes().as_slice());
+    Ok(blake3_32(&preimage))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::signing::{NostrSigner, Signer};
 
     #[test]
     fn derivation_is_deterministic() {
@@ -30,4 +81,45 @@ mod tests {
         let k2 = derive_encrypt_key(&[0x02; 32]);
         assert_ne!(k1, k2);
     }
+
+    #[test]
+    fn device_secrets_are_deterministic_and_distinct() {
+        let root = [0x42; 32];
+        let d0 = derive_device_secret(&root, 0);
+        assert_eq!(d0, derive_device_secret(&root, 0));
+        assert_ne!(d0, derive_device_secret(&root, 1));
+        assert_ne!(d0, derive_device_secret(&[0x43; 32], 0));
+        assert_ne!(d0, root);
+        assert!(NostrSigner::from_secret(d0).is_ok());
+    }
+
+    #[test]
+    fn pairwise_secret_is_symmetric_between_contacts() {
+        let alice = [0x11; 32];
+        let bob = [0x22; 32];
+        let alice_pub = NostrSigner::from_secret(alice).expect("alice").public_key();
+        let bob_pub = NostrSigner::from_secret(bob).expect("bob").public_key();
+
+        let ab = derive_pairwise_secret(&alice, &bob_pub).expect("alice side");
+        let ba = derive_pairwise_secret(&bob, &alice_pub).expect("bob side");
+        assert_eq!(ab, ba);
+
+        let carol_pub = NostrSigner::from_secret([0x33; 32])
+            .expect("carol")
+            .public_key();
+        let ac = derive_pairwise_secret(&alice, &carol_pub).expect("alice-carol");
+        assert_ne!(ab, ac);
+    }
+
+    #[test]
+    fn pairwise_secret_rejects_invalid_keys() {
+        assert_eq!(
+            derive_pairwise_secret(&[0u8; 32], &[0x02; 32]),
+            Err(SigningError::InvalidSecretKey)
+        );
+        assert_eq!(
+            derive_pairwise_secret(&[0x11; 32], &[0xFF; 32]),
+            Err(SigningError::InvalidPublicKey)
+        );
+    }
 }
@@ -0,0 +1,340 @@
+//! Passphrase-protected keystore for node secrets.
+//!
+//! Named secrets are sealed with XChaCha20-Poly1305 under a key derived by
+//! Argon2id from a passphrase or the contents of a key file. Sealed layout:
+//!
+//! `"VKS1" || memory_kib u32 || iterations u32 || parallelism u32 ||
+//! salt[16] || nonce[24] || ciphertext`
+//!
+//! (integers little-endian). The header is authenticated as associated data.
+//! The plaintext is a sequence of `u16 name_len || name || u16 len || secret`
+//! records. Callers supply salts and nonces, as with [`crate::aead`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::aead::{AeadCipher, AeadError, XChaCha20Poly1305Cipher};
+use crate::kdf::{argon2id, Argon2Params, KdfError};
+
+/// Leading bytes of a sealed keystore.
+pub const KEYSTORE_MAGIC: &[u8; 4] = b"VKS1";
+/// Salt length used for keystore key derivation.
+pub const KEYSTORE_SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = KEYSTORE_MAGIC.len() + 12 + KEYSTORE_SALT_LEN + NONCE_LEN;
+
+/// Errors returned by keystore operations.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum KeystoreError {
+    #[error("kdf error: {0}")]
+    Kdf(#[from] KdfError),
+    #[error("aead error: {0}")]
+    Aead(#[from] AeadError),
+    #[error("malformed keystore: {0}")]
+    Malformed(&'static str),
+    #[error("keystore entry too large")]
+    EntryTooLarge,
+    #[error("wrong passphrase or key file")]
+    WrongCredential,
+    #[error("key was derived for a different keystore salt or cost")]
+    KeyMismatch,
+}
+
+/// Wrapping key derived from a credential, with the salt and costs used.
+///
+/// Deriving is deliberately slow; keep the key around to re-seal a keystore
+/// after edits without paying for Argon2id again.
+#[derive(Clone)]
+pub struct KeystoreKey {
+    key: [u8; 32],
+    salt: [u8; KEYSTORE_SALT_LEN],
+    params: Argon2Params,
+}
+
+impl fmt::Debug for KeystoreKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeystoreKey")
+            .field("salt", &self.salt)
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeystoreKey {
+    /// Derives a wrapping key from a passphrase or key-file contents.
+    pub fn derive(
+        credential: &[u8],
+        salt: [u8; KEYSTORE_SALT_LEN],
+        params: Argon2Params,
+    ) -> Result<Self, KeystoreError> {
+        let mut key = [0_u8; 32];
+        argon2id(credential, &salt, params, &mut key)?;
+        Ok(Self { key, salt, params })
+    }
+
+    /// Derives the wrapping key for an existing sealed keystore.
+    pub fn derive_for(sealed: &[u8], credential: &[u8]) -> Result<Self, KeystoreError> {
+        let header = parse_header(sealed)?;
+        Self::derive(credential, header.salt, header.params)
+    }
+
+    /// Salt the key was derived with.
+    pub fn salt(&self) -> [u8; KEYSTORE_SALT_LEN] {
+        self.salt
+    }
+
+    /// Argon2id costs the key was derived with.
+    pub fn params(&self) -> Argon2Params {
+        self.params
+    }
+}
+
+/// In-memory set of named secrets.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Keystore {
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl fmt::Debug for Keystore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.entries.keys()).finish()
+    }
+}
+
+impl Keystore {
+    /// Creates an empty keystore.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts or replaces a secret.
+    pub fn insert(&mut self, name: impl Into<String>, secret: impl Into<Vec<u8>>) {
+        self.entries.insert(name.into(), secret.into());
+    }
+
+    /// Returns a secret by name.
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        self.entries.get(name).map(Vec::as_slice)
+    }
+
+    /// Returns a 32-byte secret by name.
+    pub fn get_key(&self, name: &str) -> Option<[u8; 32]> {
+        self.get(name)?.try_into().ok()
+    }
+
+    /// Removes a secret, returning it.
+    pub fn remove(&mut self, name: &str) -> Option<Vec<u8>> {
+        self.entries.remove(name)
+    }
+
+    /// Iterates secrets in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_slice()))
+    }
+
+    /// Number of stored secrets.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no secrets are stored.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Seals all secrets under `key`.
+    pub fn seal(
+        &self,
+        key: &KeystoreKey,
+        nonce: [u8; NONCE_LEN],
+    ) -> Result<Vec<u8>, KeystoreError> {
+        let mut plaintext = Vec::new();
+        for (name, secret) in &self.entries {
+            for field in [name.as_bytes(), secret.as_slice()] {
+                let len = u16::try_from(field.len()).map_err(|_| KeystoreError::EntryTooLarge)?;
+                plaintext.extend_from_slice(&len.to_le_bytes());
+                plaintext.extend_from_slice(field);
+            }
+        }
+        let mut sealed = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+        sealed.extend_from_slice(KEYSTORE_MAGIC);
+        for word in [
+            key.params.memory_kib,
+            key.params.iterations,
+            key.params.parallelism,
+        ] {
+            sealed.extend_from_slice(&word.to_le_bytes());
+        }
+        sealed.extend_from_slice(&key.salt);
+        sealed.extend_from_slice(&nonce);
+        let envelope = XChaCha20Poly1305Cipher.encrypt(&key.key, nonce, &sealed, &plaintext)?;
+        sealed.extend_from_slice(&envelope.ciphertext);
+        Ok(sealed)
+    }
+
+    /// Opens a sealed keystore with a previously derived key.
+    pub fn open(sealed: &[u8], key: &KeystoreKey) -> Result<Self, KeystoreError> {
+        let header = parse_header(sealed)?;
+        if header.salt != key.salt || header.params != key.params {
+            return Err(KeystoreError::KeyMismatch);
+        }
+        let (aad, ciphertext) = sealed.split_at(HEADER_LEN);
+        let plaintext = XChaCha20Poly1305Cipher
+            .decrypt(&key.key, header.nonce, aad, ciphertext)
+            .map_err(|_| KeystoreError::WrongCredential)?;
+
+        let mut entries = BTreeMap::new();
+        let mut rest = plaintext.as_slice();
+        while !rest.is_empty() {
+            let name = take_field(&mut rest)?;
+            let secret = take_field(&mut rest)?;
+            let name = String::from_utf8(name.to_vec())
+                .map_err(|_| KeystoreError::Malformed("entry name is not utf-8"))?;
+            entries.insert(name, secret.to_vec());
+        }
+        Ok(Self { entries })
+    }
+
+    /// Derives the key from `credential` and opens `sealed`, returning the
+    /// key for re-sealing.
+    pub fn open_with_credential(
+        sealed: &[u8],
+        credential: &[u8],
+    ) -> Result<(Self, KeystoreKey), KeystoreError> {
+        let key = KeystoreKey::derive_for(sealed, credential)?;
+        Ok((Self::open(sealed, &key)?, key))
+    }
+}
+
+/// Whether `bytes` look like a sealed keystore.
+pub fn is_sealed_keystore(bytes: &[u8]) -> bool {
+    bytes.starts_with(KEYSTORE_MAGIC)
+}
+
+/// Re-seals a keystore under a new credential-derived key.
+pub fn rekey(
+    sealed: &[u8],
+    old_credential: &[u8],
+    new_key: &KeystoreKey,
+    nonce: [u8; NONCE_LEN],
+) -> Result<Vec<u8>, KeystoreError> {
+    let (keystore, _) = Keystore::open_with_credential(sealed, old_credential)?;
+    keystore.seal(new_key, nonce)
+}
+
+struct Header {
+    params: Argon2Params,
+    salt: [u8; KEYSTORE_SALT_LEN],
+    nonce: [u8; NONCE_LEN],
+}
+
+fn parse_header(sealed: &[u8]) -> Result<Header, KeystoreError> {
+    if !is_sealed_keystore(sealed) {
+        return Err(KeystoreError::Malformed("missing keystore magic"));
+    }
+    if sealed.len() < HEADER_LEN + 16 {
+        return Err(KeystoreError::Malformed("truncated keystore"));
+    }
+    let word = |at: usize| u32::from_le_bytes(sealed[at..at + 4].try_into().expect("4 bytes"));
+    let params = Argon2Params {
+        memory_kib: word(4),
+        iterations: word(8),
+        parallelism: word(12),
+    };
+    params.validate()?;
+    let salt_start = KEYSTORE_MAGIC.len() + 12;
+    let nonce_start = salt_start + KEYSTORE_SALT_LEN;
+    Ok(Header {
+        params,
+        salt: sealed[salt_start..nonce_start]
+            .try_into()
+            .expect("salt bytes"),
+        nonce: sealed[nonce_start..HEADER_LEN]
+            .try_into()
+            .expect("nonce bytes"),
+    })
+}
+
+fn take_field<'a>(rest: &mut &'a [u8]) -> Result<&'a [u8], KeystoreError> {
+    if rest.len() < 2 {
+        return Err(KeystoreError::Malformed("truncated entry length"));
+    }
+    let len = u16::from_le_bytes([rest[0], rest[1]]) as usize;
+    if rest.len() < 2 + len {
+        return Err(KeystoreError::Malformed("truncated entry"));
+    }
+    let field = &rest[2..2 + len];
+    *rest = &rest[2 + len..];
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_sealed_keystore, rekey, Keystore, KeystoreError, KeystoreKey};
+    use crate::kdf::{Argon2Params, KdfError};
+
+    const FAST: Argon2Params = Argon2Params {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn sample() -> Keystore {
+        let mut keystore = Keystore::new();
+        keystore.insert("identity/secret_key", [0x11; 32]);
+        keystore.insert("group_key/general/k1", [0x22; 32]);
+        keystore
+    }
+
+    #[test]
+    fn seals_and_opens_with_passphrase() {
+        let key = KeystoreKey::derive(b"correct horse", [7; 16], FAST).expect("derive");
+        let sealed = sample().seal(&key, [9; 24]).expect("seal");
+        assert!(is_sealed_keystore(&sealed));
+        assert!(!sealed.windows(32).any(|w| w == [0x11; 32]));
+
+        let (opened, reopened_key) =
+            Keystore::open_with_credential(&sealed, b"correct horse").expect("open");
+        assert_eq!(opened, sample());
+        assert_eq!(opened.get_key("identity/secret_key"), Some([0x11; 32]));
+        assert_eq!(
+            Keystore::open(&sealed, &reopened_key).expect("cached key"),
+            sample()
+        );
+        assert_eq!(
+            Keystore::open_with_credential(&sealed, b"wrong horse").unwrap_err(),
+            KeystoreError::WrongCredential
+        );
+    }
+
+    #[test]
+    fn rekeys_to_a_key_file_and_detects_tampering() {
+        let old = KeystoreKey::derive(b"passphrase", [1; 16], FAST).expect("derive");
+        let sealed = sample().seal(&old, [2; 24]).expect("seal");
+        let key_file = [0x5a_u8; 64];
+        let new = KeystoreKey::derive(&key_file, [3; 16], FAST).expect("derive");
+        let rekeyed = rekey(&sealed, b"passphrase", &new, [4; 24]).expect("rekey");
+
+        assert_eq!(
+            Keystore::open(&rekeyed, &old).unwrap_err(),
+            KeystoreError::KeyMismatch
+        );
+        let (opened, _) = Keystore::open_with_credential(&rekeyed, &key_file).expect("open");
+        assert_eq!(opened, sample());
+
+        // The cost header is authenticated along with the ciphertext.
+        let mut tampered = rekeyed.clone();
+        tampered[8] ^= 1;
+        assert!(Keystore::open_with_credential(&tampered, &key_file).is_err());
+
+        // Hostile costs are refused before any key derivation runs.
+        let mut slow = rekeyed;
+        slow[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            Keystore::open_with_credential(&slow, &key_file).unwrap_err(),
+            KeystoreError::Kdf(KdfError::InvalidParams("iterations too large"))
+        );
+    }
+}
@@ -1,7 +1,14 @@
 //! Cryptographic helpers used by VEIL.
 //!
-//! Includes AEAD envelope traits and Ed25519 signing/verification abstractions.
+//! Includes AEAD envelope traits, Ed25519 signing/verification abstractions,
+//! an Argon2id-protected keystore for node secrets, mnemonic / NIP-19
+//! encodings for backing up identities, and proof-of-work stamps.
 
 pub mod aead;
+pub mod kdf;
 pub mod keys;
+pub mod keystore;
+pub mod mnemonic;
+pub mod nip19;
+pub mod pow;
 pub mod signing;
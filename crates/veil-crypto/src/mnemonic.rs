@@ -0,0 +1,286 @@
+//! BIP-39 mnemonic backup of identity seeds.
+//!
+//! 16–32 bytes of entropy map to 12–24 words of the BIP-39 English list,
+//! the last word carrying a SHA-256 checksum so mistyped phrases are
+//! rejected. [`mnemonic_to_seed`] stretches a phrase into the standard
+//! 64-byte BIP-39 seed (PBKDF2-HMAC-SHA512, 2048 rounds); [`IdentitySeed`]
+//! derives a node's keys from it. Passphrases are used as given (ASCII is
+//! safe; no NFKD normalization is applied).
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use hmac::{Hmac, Mac};
+use k256::SecretKey;
+use sha2::{Digest, Sha256, Sha512};
+use thiserror::Error;
+use veil_core::hash::blake3_32;
+use veil_core::types::Namespace;
+
+use crate::keys::derive_encrypt_key;
+
+/// Entropy length of an [`IdentitySeed`] (24 words).
+pub const IDENTITY_SEED_LEN: usize = 32;
+
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// Errors returned when encoding or decoding mnemonics.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MnemonicError {
+    #[error("entropy must be 16-32 bytes in steps of 4, got {0}")]
+    InvalidEntropyLength(usize),
+    #[error("mnemonic must have 12-24 words in steps of 3, got {0}")]
+    InvalidWordCount(usize),
+    #[error("word {position} ({word:?}) is not in the wordlist")]
+    UnknownWord { position: usize, word: String },
+    #[error("mnemonic checksum mismatch")]
+    InvalidChecksum,
+}
+
+fn wordlist() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| include_str!("bip39_english.txt").lines().collect())
+}
+
+/// Encodes entropy as a space-separated mnemonic.
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, MnemonicError> {
+    if !(16..=32).contains(&entropy.len()) || !entropy.len().is_multiple_of(4) {
+        return Err(MnemonicError::InvalidEntropyLength(entropy.len()));
+    }
+    let mut bits = entropy.to_vec();
+    bits.push(Sha256::digest(entropy)[0]);
+    let word_count = entropy.len() * 3 / 4;
+    let words = wordlist();
+    let phrase: Vec<&str> = (0..word_count)
+        .map(|i| {
+            let index = (0..11).fold(0_usize, |acc, bit| {
+                let at = i * 11 + bit;
+                (acc << 1) | usize::from((bits[at / 8] >> (7 - at % 8)) & 1)
+            });
+            words[index]
+        })
+        .collect();
+    Ok(phrase.join(" "))
+}
+
+/// Decodes a mnemonic back into its entropy, verifying the checksum.
+///
+/// Words are matched case-insensitively and may be separated by any
+/// whitespace.
+pub fn mnemonic_to_entropy(phrase: &str) -> Result<Vec<u8>, MnemonicError> {
+    let words: Vec<String> = phrase.split_whitespace().map(str::to_lowercase).collect();
+    if !(12..=24).contains(&words.len()) || !words.len().is_multiple_of(3) {
+        return Err(MnemonicError::InvalidWordCount(words.len()));
+    }
+    let mut bits = vec![0_u8; (words.len() * 11).div_ceil(8)];
+    for (position, word) in words.iter().enumerate() {
+        let index =
+            wordlist()
+                .binary_search(&word.as_str())
+                .map_err(|_| MnemonicError::UnknownWord {
+                    position: position + 1,
+                    word: word.clone(),
+                })?;
+        for bit in 0..11 {
+            if (index >> (10 - bit)) & 1 == 1 {
+                let at = position * 11 + bit;
+                bits[at / 8] |= 0x80 >> (at % 8);
+            }
+        }
+    }
+    let entropy_len = words.len() * 4 / 3;
+    let checksum_bits = entropy_len / 4;
+    let entropy = bits[..entropy_len].to_vec();
+    let expected = Sha256::digest(&entropy)[0] >> (8 - checksum_bits);
+    if bits[entropy_len] >> (8 - checksum_bits) != expected {
+        return Err(MnemonicError::InvalidChecksum);
+    }
+    Ok(entropy)
+}
+
+/// Stretches a mnemonic and optional passphrase into the 64-byte BIP-39 seed.
+pub fn mnemonic_to_seed(phrase: &str, passphrase: &str) -> [u8; 64] {
+    let normalized = phrase
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mac = Hmac::<Sha512>::new_from_slice(normalized.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    let mut block = mac.clone();
+    block.update(b"mnemonic");
+    block.update(passphrase.as_bytes());
+    block.update(&1_u32.to_be_bytes());
+    let mut u = block.finalize().into_bytes();
+    let mut seed: [u8; 64] = u.into();
+    for _ in 1..PBKDF2_ROUNDS {
+        let mut round = mac.clone();
+        round.update(&u);
+        u = round.finalize().into_bytes();
+        for (out, byte) in seed.iter_mut().zip(u.iter()) {
+            *out ^= byte;
+        }
+    }
+    seed
+}
+
+/// 256-bit identity seed, backed up as a 24-word mnemonic.
+///
+/// The signing secret, its [`derive_encrypt_key`] feed key and per-namespace
+/// keys all derive from the BIP-39 seed of the mnemonic (empty passphrase),
+/// so importing the phrase restores every key bit-for-bit.
+#[derive(Clone, PartialEq, Eq)]
+pub struct IdentitySeed {
+    entropy: [u8; IDENTITY_SEED_LEN],
+    seed: [u8; 64],
+}
+
+impl fmt::Debug for IdentitySeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdentitySeed").finish_non_exhaustive()
+    }
+}
+
+impl IdentitySeed {
+    /// Wraps freshly generated or restored entropy.
+    pub fn from_entropy(entropy: [u8; IDENTITY_SEED_LEN]) -> Self {
+        let phrase = entropy_to_mnemonic(&entropy).expect("32 bytes is a valid entropy length");
+        Self {
+            entropy,
+            seed: mnemonic_to_seed(&phrase, ""),
+        }
+    }
+
+    /// Restores a seed from its 24-word mnemonic.
+    pub fn from_mnemonic(phrase: &str) -> Result<Self, MnemonicError> {
+        let entropy = mnemonic_to_entropy(phrase)?;
+        let entropy = <[u8; IDENTITY_SEED_LEN]>::try_from(entropy.as_slice())
+            .map_err(|_| MnemonicError::InvalidWordCount(entropy.len() * 3 / 4))?;
+        Ok(Self::from_entropy(entropy))
+    }
+
+    /// Raw entropy, for storage.
+    pub fn entropy(&self) -> [u8; IDENTITY_SEED_LEN] {
+        self.entropy
+    }
+
+    /// The 24-word backup phrase.
+    pub fn mnemonic(&self) -> String {
+        entropy_to_mnemonic(&self.entropy).expect("32 bytes is a valid entropy length")
+    }
+
+    /// Nostr (secp256k1) identity secret.
+    ///
+    /// Candidates outside the curve order are skipped with a counter, as in
+    /// [`crate::keys::derive_device_secret`].
+    pub fn signing_secret(&self) -> [u8; 32] {
+        let mut counter = 0_u32;
+        loop {
+            let candidate = self.derive(b"veil/seed-signing/v1", &counter.to_be_bytes());
+            if SecretKey::from_slice(&candidate).is_ok() {
+                return candidate;
+            }
+            counter += 1;
+        }
+    }
+
+    /// Feed encryption key, equal to [`derive_encrypt_key`] of the signing
+    /// secret so seedless imports of the same secret agree.
+    pub fn encrypt_key(&self) -> [u8; 32] {
+        derive_encrypt_key(&self.signing_secret())
+    }
+
+    /// Symmetric key scoped to one namespace.
+    pub fn namespace_key(&self, namespace: Namespace) -> [u8; 32] {
+        self.derive(b"veil/seed-namespace/v1", &namespace.0.to_be_bytes())
+    }
+
+    fn derive(&self, domain: &[u8], suffix: &[u8]) -> [u8; 32] {
+        let mut preimage = Vec::with_capacity(domain.len() + 64 + suffix.len());
+        preimage.extend_from_slice(domain);
+        preimage.extend_from_slice(&self.seed);
+        preimage.extend_from_slice(suffix);
+        blake3_32(&preimage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        entropy_to_mnemonic, mnemonic_to_entropy, mnemonic_to_seed, wordlist, IdentitySeed,
+        MnemonicError,
+    };
+    use crate::keys::derive_encrypt_key;
+    use veil_core::types::Namespace;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn matches_bip39_vectors() {
+        assert_eq!(wordlist().len(), 2048);
+        let phrase = entropy_to_mnemonic(&[0; 16]).expect("encode");
+        assert_eq!(phrase, format!("{}about", "abandon ".repeat(11)));
+        assert_eq!(
+            hex(&mnemonic_to_seed(&phrase, "TREZOR")),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f\
+             09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        assert_eq!(
+            entropy_to_mnemonic(&[0x7f; 16]).expect("encode"),
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+        );
+        assert_eq!(
+            entropy_to_mnemonic(&[0xff; 32]).expect("encode"),
+            format!("{}vote", "zoo ".repeat(23))
+        );
+    }
+
+    #[test]
+    fn round_trips_and_rejects_typos() {
+        let entropy: Vec<u8> = (0..32).collect();
+        let phrase = entropy_to_mnemonic(&entropy).expect("encode");
+        let shouty = phrase.to_uppercase().replace(' ', "\n  ");
+        assert_eq!(mnemonic_to_entropy(&shouty).expect("decode"), entropy);
+
+        let mut words: Vec<&str> = phrase.split(' ').collect();
+        words.swap(0, 1);
+        assert_eq!(
+            mnemonic_to_entropy(&words.join(" ")),
+            Err(MnemonicError::InvalidChecksum)
+        );
+        words[2] = "veil";
+        assert!(matches!(
+            mnemonic_to_entropy(&words.join(" ")),
+            Err(MnemonicError::UnknownWord { position: 3, .. })
+        ));
+        assert_eq!(
+            mnemonic_to_entropy("abandon abandon"),
+            Err(MnemonicError::InvalidWordCount(2))
+        );
+    }
+
+    #[test]
+    fn identity_seed_restores_keys_from_mnemonic() {
+        let seed = IdentitySeed::from_entropy([0x5a; 32]);
+        let restored = IdentitySeed::from_mnemonic(&seed.mnemonic()).expect("restore");
+        assert_eq!(restored, seed);
+        assert_eq!(restored.signing_secret(), seed.signing_secret());
+        assert_eq!(
+            seed.encrypt_key(),
+            derive_encrypt_key(&seed.signing_secret())
+        );
+        assert_ne!(
+            seed.namespace_key(Namespace(1)),
+            seed.namespace_key(Namespace(2))
+        );
+        assert_ne!(seed.signing_secret(), seed.entropy());
+
+        let short = entropy_to_mnemonic(&[0x5a; 16]).expect("encode");
+        assert_eq!(
+            IdentitySeed::from_mnemonic(&short),
+            Err(MnemonicError::InvalidWordCount(12))
+        );
+    }
+}
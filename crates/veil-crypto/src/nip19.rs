@@ -0,0 +1,117 @@
+//! NIP-19 bech32 encoding of Nostr keys (`nsec` / `npub`).
+//!
+//! Node identities are Nostr secp256k1 keys, so these strings round-trip
+//! with other Nostr clients and carry a checksum that catches typos.
+
+use bech32::{Bech32, Hrp};
+use thiserror::Error;
+
+use crate::signing::NostrSigner;
+
+/// Human-readable prefix of a secret key.
+pub const NSEC_HRP: &str = "nsec";
+/// Human-readable prefix of a public key.
+pub const NPUB_HRP: &str = "npub";
+
+/// Errors returned when decoding NIP-19 strings.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Nip19Error {
+    #[error("invalid bech32 string")]
+    InvalidEncoding,
+    #[error("expected {expected} prefix, got {found}")]
+    WrongPrefix {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("expected 32 key bytes, got {0}")]
+    InvalidLength(usize),
+    #[error("not a valid secp256k1 secret key")]
+    InvalidSecretKey,
+}
+
+/// Encodes a secret key as `nsec1…`.
+pub fn encode_nsec(secret: &[u8; 32]) -> String {
+    encode(NSEC_HRP, secret)
+}
+
+/// Encodes an x-only public key as `npub1…`.
+pub fn encode_npub(pubkey: &[u8; 32]) -> String {
+    encode(NPUB_HRP, pubkey)
+}
+
+/// Decodes an `nsec1…` string, checking the key is a valid scalar.
+pub fn decode_nsec(value: &str) -> Result<[u8; 32], Nip19Error> {
+    let secret = decode(NSEC_HRP, value)?;
+    NostrSigner::from_secret(secret).map_err(|_| Nip19Error::InvalidSecretKey)?;
+    Ok(secret)
+}
+
+/// Decodes an `npub1…` string.
+pub fn decode_npub(value: &str) -> Result<[u8; 32], Nip19Error> {
+    decode(NPUB_HRP, value)
+}
+
+fn encode(hrp: &str, key: &[u8; 32]) -> String {
+    bech32::encode::<Bech32>(Hrp::parse_unchecked(hrp), key)
+        .expect("32-byte payload fits in a bech32 string")
+}
+
+fn decode(expected: &'static str, value: &str) -> Result<[u8; 32], Nip19Error> {
+    let (hrp, data) = bech32::decode(value.trim()).map_err(|_| Nip19Error::InvalidEncoding)?;
+    if hrp.as_str() != expected {
+        return Err(Nip19Error::WrongPrefix {
+            expected,
+            found: hrp.to_string(),
+        });
+    }
+    <[u8; 32]>::try_from(data.as_slice()).map_err(|_| Nip19Error::InvalidLength(data.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_npub, decode_nsec, encode_npub, encode_nsec, Nip19Error};
+
+    fn key(hex: &str) -> [u8; 32] {
+        let mut out = [0_u8; 32];
+        for (byte, chunk) in out.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn matches_nip19_vectors() {
+        let pubkey = key("7e7e9c42a91bfef19fa929e5fda1b72e0ebc1a4c1141673e2794234d86addf4e");
+        let npub = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+        assert_eq!(encode_npub(&pubkey), npub);
+        assert_eq!(decode_npub(npub), Ok(pubkey));
+
+        let secret = key("67dea2ed018072d675f5415ecfaed7d2597555e202d85b3d65ea4e58d2d92ffa");
+        let nsec = "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5";
+        assert_eq!(encode_nsec(&secret), nsec);
+        assert_eq!(decode_nsec(nsec), Ok(secret));
+    }
+
+    #[test]
+    fn rejects_wrong_prefix_and_typos() {
+        let npub = encode_npub(&[7; 32]);
+        assert!(matches!(
+            decode_nsec(&npub),
+            Err(Nip19Error::WrongPrefix {
+                expected: "nsec",
+                ..
+            })
+        ));
+        let mut typo = npub.into_bytes();
+        let last = typo.len() - 1;
+        typo[last] = if typo[last] == b'q' { b'p' } else { b'q' };
+        assert_eq!(
+            decode_npub(std::str::from_utf8(&typo).unwrap()),
+            Err(Nip19Error::InvalidEncoding)
+        );
+        assert_eq!(
+            decode_nsec(&encode_nsec(&[0; 32])),
+            Err(Nip19Error::InvalidSecretKey)
+        );
+    }
+}
@@ -1,13 +1,38 @@
+//! Object signing backends with batch verification.
+//!
+//! [`Verifier::verify_batch`] checks many signatures with one multi-scalar
+//! multiplication: each equation is weighted by a 128-bit coefficient
+//! derived from a hash of the whole batch, so a forged signature cannot
+//! cancel out against another. A failed batch only says that *some*
+//! signature is bad; [`verify_batch_each`] falls back to individual checks
+//! to locate it.
+
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar as EdwardsScalar;
+use curve25519_dalek::traits::{IsIdentity, VartimeMultiscalarMul};
 use ed25519_dalek::{
     Signature as DalekSignature, Signer as DalekSignerTrait, SigningKey,
     Verifier as DalekVerifierTrait, VerifyingKey,
 };
+use k256::elliptic_curve::bigint::U256;
+use k256::elliptic_curve::group::Group;
+use k256::elliptic_curve::ops::{LinearCombinationExt, Reduce};
+use k256::elliptic_curve::point::DecompressPoint;
+use k256::elliptic_curve::subtle::Choice;
+use k256::elliptic_curve::PrimeField;
 use k256::schnorr::{
     Signature as SchnorrSignature, SigningKey as SchnorrSigningKey,
     VerifyingKey as SchnorrVerifyingKey,
 };
+use k256::{AffinePoint, FieldBytes, ProjectivePoint, Scalar as SecpScalar};
+use sha2::{Digest, Sha256, Sha512};
 use std::fmt;
 use thiserror::Error;
+use veil_core::hash::blake3_32;
+
+const BATCH_COEFFICIENT_DOMAIN: &[u8] = b"veil/batch-verify/v1";
+const BIP340_CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
 
 /// Errors returned by signing/verification helpers.
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -31,10 +56,57 @@ pub trait Signer {
     fn public_key(&self) -> [u8; 32];
 }
 
+/// One `(pubkey, msg, sig)` triple checked by [`Verifier::verify_batch`].
+#[derive(Debug, Clone, Copy)]
+pub struct BatchItem<'a> {
+    pub pubkey: [u8; 32],
+    pub msg: &'a [u8],
+    pub sig: [u8; 64],
+}
+
 /// Trait for signature verification backends.
 pub trait Verifier {
     /// Verifies a signature against `(pubkey, msg)`.
     fn verify(&self, pubkey: [u8; 32], msg: &[u8], sig: [u8; 64]) -> Result<bool, SigningError>;
+
+    /// Returns `true` only if every item verifies; malformed keys or
+    /// signatures count as invalid. The default checks items one by one.
+    fn verify_batch(&self, items: &[BatchItem<'_>]) -> bool {
+        items
+            .iter()
+            .all(|item| matches!(self.verify(item.pubkey, item.msg, item.sig), Ok(true)))
+    }
+}
+
+/// Verifies `items` as one batch, falling back to individual checks when
+/// the batch fails. Returns one verdict per item.
+pub fn verify_batch_each(verifier: &impl Verifier, items: &[BatchItem<'_>]) -> Vec<bool> {
+    if items.len() > 1 && verifier.verify_batch(items) {
+        return vec![true; items.len()];
+    }
+    items
+        .iter()
+        .map(|item| matches!(verifier.verify(item.pubkey, item.msg, item.sig), Ok(true)))
+        .collect()
+}
+
+/// Coefficient `i` of a batch: 128 bits of a hash over every item.
+fn batch_coefficients(items: &[BatchItem<'_>]) -> impl Fn(usize) -> [u8; 16] {
+    let mut preimage = BATCH_COEFFICIENT_DOMAIN.to_vec();
+    for item in items {
+        preimage.extend_from_slice(&item.pubkey);
+        preimage.extend_from_slice(&(item.msg.len() as u64).to_be_bytes());
+        preimage.extend_from_slice(item.msg);
+        preimage.extend_from_slice(&item.sig);
+    }
+    let seed = blake3_32(&preimage);
+    move |index| {
+        let mut input = seed.to_vec();
+        input.extend_from_slice(&(index as u64).to_be_bytes());
+        let mut coefficient = [0_u8; 16];
+        coefficient.copy_from_slice(&blake3_32(&input)[..16]);
+        coefficient
+    }
 }
 
 /// Ed25519 signing implementation backed by `ed25519-dalek`.
@@ -74,6 +146,57 @@ impl Verifier for Ed25519Verifier {
         let signature = DalekSignature::from_bytes(&sig);
         Ok(verifying_key.verify(msg, &signature).is_ok())
     }
+
+    /// Checks `[8](Σ z·R + Σ z·h·A − (Σ z·s)·B) = 0`. Non-canonical or
+    /// torsion-carrying `R`/`A` fail the batch up front: over prime-order
+    /// points the cofactored equation holds only where the cofactorless one
+    /// [`Self::verify`] checks does, so batch and single verdicts agree.
+    fn verify_batch(&self, items: &[BatchItem<'_>]) -> bool {
+        let coefficient = batch_coefficients(items);
+        let mut scalars = Vec::with_capacity(items.len() * 2 + 1);
+        let mut points = Vec::with_capacity(items.len() * 2 + 1);
+        let mut base_scalar = EdwardsScalar::ZERO;
+        for (index, item) in items.iter().enumerate() {
+            let (r_bytes, s_bytes) = item.sig.split_at(32);
+            let r_bytes: [u8; 32] = r_bytes.try_into().expect("32-byte half");
+            let Some(pubkey) = decompress_prime_order(item.pubkey) else {
+                return false;
+            };
+            let Some(r) = decompress_prime_order(r_bytes) else {
+                return false;
+            };
+            let s = EdwardsScalar::from_canonical_bytes(s_bytes.try_into().expect("32-byte half"));
+            let Some(s) = Option::<EdwardsScalar>::from(s) else {
+                return false;
+            };
+            let challenge: [u8; 64] = Sha512::new()
+                .chain_update(r_bytes)
+                .chain_update(item.pubkey)
+                .chain_update(item.msg)
+                .finalize()
+                .into();
+            let h = EdwardsScalar::from_bytes_mod_order_wide(&challenge);
+            let mut z = [0_u8; 32];
+            z[..16].copy_from_slice(&coefficient(index));
+            let z = EdwardsScalar::from_bytes_mod_order(z);
+            base_scalar -= z * s;
+            scalars.extend([z, z * h]);
+            points.extend([r, pubkey]);
+        }
+        scalars.push(base_scalar);
+        points.push(ED25519_BASEPOINT_POINT);
+        EdwardsPoint::vartime_multiscalar_mul(scalars, points)
+            .mul_by_cofactor()
+            .is_identity()
+    }
+}
+
+/// Decodes a canonically encoded point of the prime-order subgroup; small-
+/// order and mixed-order points are refused.
+fn decompress_prime_order(bytes: [u8; 32]) -> Option<EdwardsPoint> {
+    let point = CompressedEdwardsY(bytes).decompress()?;
+    let canonical = point.compress().to_bytes() == bytes;
+    (canonical && !point.is_small_order() && point.is_torsion_free()).then_some(point)
 }
 
 /// Nostr-compatible secp256k1 Schnorr signer (BIP-340 x-only pubkeys).
@@ -122,11 +245,153 @@ impl Verifier for NostrVerifier {
             .map_err(|_| SigningError::InvalidSignature)?;
         Ok(verifying_key.verify(msg, &signature).is_ok())
     }
+
+    /// BIP-340 batch verification of `SHA-256(msg)`, the prehash
+    /// [`NostrSigner`] signs: checks `Σ a·R + Σ a·e·P − (Σ a·s)·G = 0`.
+    fn verify_batch(&self, items: &[BatchItem<'_>]) -> bool {
+        let coefficient = batch_coefficients(items);
+        let mut terms = Vec::with_capacity(items.len() * 2 + 1);
+        let mut base_scalar = SecpScalar::ZERO;
+        for (index, item) in items.iter().enumerate() {
+            let Ok(verifying_key) = SchnorrVerifyingKey::from_bytes(&item.pubkey) else {
+                return false;
+            };
+            if SchnorrSignature::try_from(item.sig.as_slice()).is_err() {
+                return false;
+            }
+            let r_bytes = FieldBytes::clone_from_slice(&item.sig[..32]);
+            let Some(r) =
+                Option::<AffinePoint>::from(AffinePoint::decompress(&r_bytes, Choice::from(0)))
+            else {
+                return false;
+            };
+            let s = SecpScalar::from_repr(FieldBytes::clone_from_slice(&item.sig[32..]));
+            let Some(s) = Option::<SecpScalar>::from(s) else {
+                return false;
+            };
+            let tag = Sha256::digest(BIP340_CHALLENGE_TAG);
+            let e = <SecpScalar as Reduce<U256>>::reduce_bytes(
+                &Sha256::new()
+                    .chain_update(tag)
+                    .chain_update(tag)
+                    .chain_update(r_bytes)
+                    .chain_update(item.pubkey)
+                    .chain_update(Sha256::digest(item.msg))
+                    .finalize(),
+            );
+            let mut a = FieldBytes::default();
+            a[16..].copy_from_slice(&coefficient(index));
+            let a = <SecpScalar as Reduce<U256>>::reduce_bytes(&a);
+            base_scalar -= a * s;
+            terms.push((ProjectivePoint::from(r), a));
+            terms.push((ProjectivePoint::from(*verifying_key.as_affine()), a * e));
+        }
+        terms.push((ProjectivePoint::GENERATOR, base_scalar));
+        ProjectivePoint::lincomb_ext(terms.as_slice())
+            .is_identity()
+            .into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Ed25519Signer, Ed25519Verifier, NostrSigner, NostrVerifier, Signer, Verifier};
+    use super::{
+        verify_batch_each, BatchItem, Ed25519Signer, Ed25519Verifier, NostrSigner, NostrVerifier,
+        Signer, Verifier,
+    };
+
+    fn batch_locates_forgery(signers: &[impl Signer], verifier: &impl Verifier) {
+        let msgs: Vec<Vec<u8>> = (0..signers.len())
+            .map(|i| format!("object {i}").into_bytes())
+            .collect();
+        let mut items: Vec<BatchItem<'_>> = signers
+            .iter()
+            .zip(&msgs)
+            .map(|(signer, msg)| BatchItem {
+                pubkey: signer.public_key(),
+                msg,
+                sig: signer.sign(msg).expect("sign should succeed"),
+            })
+            .collect();
+        assert!(verifier.verify_batch(&items));
+        assert_eq!(verify_batch_each(verifier, &items), vec![true; items.len()]);
+
+        items[2].msg = b"tampered";
+        assert!(!verifier.verify_batch(&items));
+        let verdicts = verify_batch_each(verifier, &items);
+        assert_eq!(verdicts.iter().filter(|ok| !**ok).count(), 1);
+        assert!(!verdicts[2]);
+
+        items[2].msg = &msgs[2];
+        items[1].sig[40] ^= 0x01;
+        assert!(!verifier.verify_batch(&items));
+    }
+
+    #[test]
+    fn ed25519_batch_verification_locates_forgery() {
+        let signers: Vec<Ed25519Signer> = (1..=5)
+            .map(|i| Ed25519Signer::from_secret([i; 32]))
+            .collect();
+        batch_locates_forgery(&signers, &Ed25519Verifier);
+    }
+
+    #[test]
+    fn ed25519_batch_agrees_with_single_verify_on_torsion_components() {
+        use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+        use curve25519_dalek::edwards::CompressedEdwardsY;
+        use curve25519_dalek::scalar::Scalar;
+        use sha2::{Digest, Sha512};
+
+        // (0, -1), the point of order 2.
+        let mut order_two = [0xFF_u8; 32];
+        order_two[0] = 0xEC;
+        order_two[31] = 0x7F;
+        let torsion = CompressedEdwardsY(order_two)
+            .decompress()
+            .expect("order-2 point");
+        let secret = Scalar::from(7_u64);
+        let nonce = Scalar::from(11_u64);
+        let pubkey = (ED25519_BASEPOINT_POINT * secret).compress().to_bytes();
+        let r = (ED25519_BASEPOINT_POINT * nonce + torsion)
+            .compress()
+            .to_bytes();
+        let msg = b"mixed-order nonce";
+        let challenge: [u8; 64] = Sha512::new()
+            .chain_update(r)
+            .chain_update(pubkey)
+            .chain_update(msg)
+            .finalize()
+            .into();
+        let s = nonce + Scalar::from_bytes_mod_order_wide(&challenge) * secret;
+        let mut sig = [0_u8; 64];
+        sig[..32].copy_from_slice(&r);
+        sig[32..].copy_from_slice(s.as_bytes());
+        // The cofactored equation alone would accept this; `verify` does not.
+        assert_eq!(Ed25519Verifier.verify(pubkey, msg, sig), Ok(false));
+
+        let signer = Ed25519Signer::from_secret([0x33; 32]);
+        let items = [
+            BatchItem {
+                pubkey: signer.public_key(),
+                msg: b"honest",
+                sig: signer.sign(b"honest").expect("sign should succeed"),
+            },
+            BatchItem { pubkey, msg, sig },
+        ];
+        assert!(!Ed25519Verifier.verify_batch(&items));
+        assert_eq!(
+            verify_batch_each(&Ed25519Verifier, &items),
+            vec![true, false]
+        );
+    }
+
+    #[test]
+    fn nostr_batch_verification_locates_forgery() {
+        let signers: Vec<NostrSigner> = (1..=5)
+            .map(|i| NostrSigner::from_secret([i; 32]).expect("key should be valid"))
+            .collect();
+        batch_locates_forgery(&signers, &NostrVerifier);
+    }
 
     #[test]
     fn sign_and_verify_round_trip() {
@@ -0,0 +1,142 @@
+//! All-or-nothing package transform for the `AllOrNothing` erasure mode.
+//!
+//! The object is padded to `k * chunk_len - AONT_KEY_LEN` bytes and encrypted
+//! with a BLAKE3 keystream under a per-object package key. The key is then
+//! appended masked by a hash of the whole encrypted body:
+//!
+//! ```text
+//! body    = pad(object) XOR keystream(K)
+//! trailer = K XOR H(body)
+//! package = body || trailer            (split into k source blocks)
+//! ```
+//!
+//! Recovering `K` requires every byte of `body`, so fewer than `k` shards of a
+//! systematic RS code over the package reveal nothing about any block.
+//!
+//! `K` is convergent, not keyed: it is a hash of the object bytes, which keeps
+//! sharding deterministic for identical objects. Anyone who already holds (or
+//! can guess) the object can derive `K`, so the transform only hides content
+//! from holders of fewer than `k` shards; confidentiality still rests on the
+//! object's own encryption.
+
+use crate::sharder::FecError;
+
+/// Bytes of each package reserved for the masked package key.
+pub const AONT_KEY_LEN: usize = 32;
+
+const KEY_CONTEXT: &str = "veil aont package key v1";
+const MASK_CONTEXT: &str = "veil aont package mask v1";
+
+/// Packages `object_bytes` into `k` source blocks of `chunk_len` bytes.
+pub fn package_blocks(
+    object_bytes: &[u8],
+    k: usize,
+    chunk_len: usize,
+) -> Result<Vec<Vec<u8>>, FecError> {
+    let mut blocks = vec![vec![0_u8; chunk_len]; k];
+    package_into(object_bytes, &mut blocks)?;
+    Ok(blocks)
+}
+
+/// Packages `object_bytes` into caller-provided, equally sized blocks
+/// (for example pooled buffers), overwriting their contents.
+pub fn package_into(object_bytes: &[u8], blocks: &mut [Vec<u8>]) -> Result<(), FecError> {
+    let chunk_len = blocks.first().map_or(0, Vec::len);
+    if chunk_len < AONT_KEY_LEN || blocks.iter().any(|b| b.len() != chunk_len) {
+        return Err(FecError::InvalidShardSet("package blocks must be equal"));
+    }
+    let body_len = blocks.len() * chunk_len - AONT_KEY_LEN;
+    if object_bytes.len() > body_len {
+        return Err(FecError::ObjectTooLarge);
+    }
+
+    let key = blake3::derive_key(KEY_CONTEXT, object_bytes);
+    let mut keystream = blake3::Hasher::new_keyed(&key).finalize_xof();
+    let mut mask = blake3::Hasher::new_derive_key(MASK_CONTEXT);
+    let mut stream = vec![0_u8; chunk_len];
+    for (i, block) in blocks.iter_mut().enumerate() {
+        let start = i * chunk_len;
+        let plain = object_bytes.get(start..).unwrap_or_default();
+        let plain = &plain[..plain.len().min(chunk_len)];
+        block[..plain.len()].copy_from_slice(plain);
+        block[plain.len()..].fill(0);
+
+        let body = body_len.saturating_sub(start).min(chunk_len);
+        keystream.fill(&mut stream[..body]);
+        for (byte, ks) in block[..body].iter_mut().zip(&stream) {
+            *byte ^= ks;
+        }
+        mask.update(&block[..body]);
+    }
+
+    let mask = mask.finalize();
+    let trailer = &mut blocks[blocks.len() - 1][chunk_len - AONT_KEY_LEN..];
+    for ((out, k), m) in trailer.iter_mut().zip(key).zip(mask.as_bytes()) {
+        *out = k ^ m;
+    }
+    Ok(())
+}
+
+/// Inverts [`package_blocks`], returning the padded object bytes.
+pub fn unpackage_blocks(blocks: Vec<Vec<u8>>) -> Result<Vec<u8>, FecError> {
+    let mut package = blocks.concat();
+    let body_len = package
+        .len()
+        .checked_sub(AONT_KEY_LEN)
+        .ok_or(FecError::InvalidShardSet("package shorter than key"))?;
+    let trailer = package.split_off(body_len);
+
+    let mask = body_mask(&package);
+    let mut key = [0_u8; AONT_KEY_LEN];
+    for (out, (t, m)) in key.iter_mut().zip(trailer.iter().zip(mask.iter())) {
+        *out = t ^ m;
+    }
+    apply_keystream(&key, &mut package);
+    Ok(package)
+}
+
+fn body_mask(body: &[u8]) -> [u8; AONT_KEY_LEN] {
+    blake3::derive_key(MASK_CONTEXT, body)
+}
+
+fn apply_keystream(key: &[u8; 32], data: &mut [u8]) {
+    let mut stream = vec![0_u8; data.len()];
+    blake3::Hasher::new_keyed(key)
+        .finalize_xof()
+        .fill(&mut stream);
+    for (byte, ks) in data.iter_mut().zip(stream) {
+        *byte ^= ks;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{package_blocks, unpackage_blocks, AONT_KEY_LEN};
+
+    #[test]
+    fn package_round_trips_and_hides_plaintext_blocks() {
+        let object = vec![0x5A_u8; 3 * 64 - AONT_KEY_LEN];
+        let blocks = package_blocks(&object, 3, 64).expect("package");
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks.iter().all(|b| b.len() == 64));
+        assert!(blocks.iter().all(|b| b[..16] != object[..16]));
+        assert_eq!(unpackage_blocks(blocks).expect("unpackage"), object);
+    }
+
+    #[test]
+    fn any_missing_block_garbles_every_block() {
+        let object = (0..150_u8).collect::<Vec<_>>();
+        let mut blocks = package_blocks(&object, 3, 64).expect("package");
+        // Guessing one block wrong changes the recovered key and thus the
+        // keystream over all other blocks.
+        blocks[2][0] ^= 1;
+        let garbled = unpackage_blocks(blocks).expect("unpackage");
+        assert_ne!(garbled[..64], object[..64]);
+        assert_ne!(garbled[64..128], object[64..128]);
+    }
+
+    #[test]
+    fn rejects_objects_without_room_for_key() {
+        assert!(package_blocks(&[0_u8; 3 * 64 - AONT_KEY_LEN + 1], 3, 64).is_err());
+    }
+}
@@ -1,6 +1,11 @@
 //! Forward-error-correction helpers for shard production and reconstruction.
 //!
-//! Exposes profile selection and Reed-Solomon sharding/recovery entry points.
+//! Exposes profile selection, Reed-Solomon/RaptorQ sharding (owned or pooled
+//! straight-to-wire), and batch or progressive (shard-at-a-time) recovery
+//! entry points. The `simd` feature enables SIMD GF(256) kernels.
 
+pub mod aont;
+pub mod pooled;
 pub mod profile;
+pub mod progressive;
 pub mod sharder;
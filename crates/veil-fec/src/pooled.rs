@@ -0,0 +1,449 @@
+//! Buffer-reusing sharding path that writes shards straight into wire bytes.
+//!
+//! [`object_to_wire_shards`] produces the same encoded shards as
+//! `object_to_shards_with_profile` followed by `encode_shard_cbor`, but:
+//! - systematic source blocks borrow the object bytes instead of copying
+//! - block, parity and wire buffers come from a [`ShardBufferPool`]
+//! - shards are CBOR-encoded directly from borrowed payloads
+//! - RS parity and wire encoding may be split across threads for large sets
+
+use std::thread;
+
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use veil_codec::shard::{
+    encode_shard_cbor_into, ShardHeaderV1, SHARD_HEADER_LEN, SHARD_V1_VERSION,
+};
+use veil_core::types::{Epoch, Namespace};
+use veil_core::{ObjectRoot, Tag};
+
+use crate::aont::{package_into, AONT_KEY_LEN};
+use crate::profile::{ErasureCodingMode, Profile};
+use crate::sharder::{
+    choose_bucket_with_jitter, erasure_mode_to_wire, hardened_forward_in_place,
+    object_to_shards_with_profile, FecError,
+};
+
+/// Default cap on buffers retained by a [`ShardBufferPool`].
+pub const DEFAULT_MAX_POOLED_BUFFERS: usize = 256;
+
+/// Free list of byte buffers reused across sharding calls.
+#[derive(Debug, Clone)]
+pub struct ShardBufferPool {
+    free: Vec<Vec<u8>>,
+    max_pooled: usize,
+}
+
+impl Default for ShardBufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_POOLED_BUFFERS)
+    }
+}
+
+impl ShardBufferPool {
+    /// Creates a pool retaining at most `max_pooled` buffers.
+    pub fn new(max_pooled: usize) -> Self {
+        Self {
+            free: Vec::new(),
+            max_pooled,
+        }
+    }
+
+    /// Takes a zero-filled buffer of exactly `len` bytes.
+    pub fn take_zeroed(&mut self, len: usize) -> Vec<u8> {
+        let mut buf = self.free.pop().unwrap_or_default();
+        buf.clear();
+        buf.resize(len, 0);
+        buf
+    }
+
+    /// Takes an empty buffer with room for at least `capacity` bytes.
+    pub fn take_empty(&mut self, capacity: usize) -> Vec<u8> {
+        let mut buf = self.free.pop().unwrap_or_default();
+        buf.clear();
+        buf.reserve(capacity);
+        buf
+    }
+
+    /// Returns a buffer to the pool (dropped once the pool is full).
+    pub fn recycle(&mut self, buf: Vec<u8>) {
+        if self.free.len() < self.max_pooled {
+            self.free.push(buf);
+        }
+    }
+
+    /// Returns several buffers to the pool.
+    pub fn recycle_all(&mut self, bufs: impl IntoIterator<Item = Vec<u8>>) {
+        for buf in bufs {
+            self.recycle(buf);
+        }
+    }
+
+    /// Number of buffers currently available for reuse.
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns whether no buffers are available for reuse.
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+}
+
+/// Threading controls for [`object_to_wire_shards`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardingOptions {
+    /// Maximum worker threads (`1` keeps encoding on the calling thread).
+    pub max_threads: usize,
+    /// Minimum total shard bytes (`n * chunk_len`) before threads are used.
+    pub parallel_min_bytes: usize,
+}
+
+impl Default for ShardingOptions {
+    fn default() -> Self {
+        Self {
+            max_threads: 1,
+            parallel_min_bytes: 256 * 1024,
+        }
+    }
+}
+
+impl ShardingOptions {
+    /// Uses every available core for shard sets above the size threshold.
+    pub fn parallel() -> Self {
+        Self {
+            max_threads: thread::available_parallelism().map_or(1, usize::from),
+            ..Self::default()
+        }
+    }
+
+    fn threads_for(&self, total_bytes: usize, units: usize) -> usize {
+        if total_bytes < self.parallel_min_bytes {
+            1
+        } else {
+            self.max_threads.clamp(1, units.max(1))
+        }
+    }
+}
+
+/// Encoded shards of one object plus the shared header (with `index = 0`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WireShardSet {
+    pub header: ShardHeaderV1,
+    pub shards: Vec<Vec<u8>>,
+}
+
+/// Splits an object into encoded shard bytes using pooled buffers.
+///
+/// Returned wire buffers come from `pool`; hand them back with
+/// [`ShardBufferPool::recycle_all`] once sent to keep steady-state publishing
+/// allocation-free.
+#[allow(clippy::too_many_arguments)]
+pub fn object_to_wire_shards(
+    object_bytes: &[u8],
+    namespace: Namespace,
+    epoch: Epoch,
+    tag: Tag,
+    object_root: ObjectRoot,
+    mode: ErasureCodingMode,
+    bucket_jitter_extra_levels: usize,
+    profile: &Profile,
+    pool: &mut ShardBufferPool,
+    options: &ShardingOptions,
+) -> Result<WireShardSet, FecError> {
+    if mode == ErasureCodingMode::Fountain {
+        let shards = object_to_shards_with_profile(
+            object_bytes,
+            namespace,
+            epoch,
+            tag,
+            object_root,
+            mode,
+            bucket_jitter_extra_levels,
+            profile,
+        )?;
+        let header = ShardHeaderV1 {
+            index: 0,
+            ..shards[0].header.clone()
+        };
+        let payloads = shards
+            .iter()
+            .map(|s| s.payload.as_slice())
+            .collect::<Vec<_>>();
+        let wire = encode_wires(&header, &payloads, pool, options)?;
+        return Ok(WireShardSet {
+            header,
+            shards: wire,
+        });
+    }
+    if profile.n as usize > 256 {
+        return Err(FecError::InvalidProfile("n must be <= 256"));
+    }
+
+    let packaged_len = if mode == ErasureCodingMode::AllOrNothing {
+        object_bytes.len() + AONT_KEY_LEN
+    } else {
+        object_bytes.len()
+    };
+    let bucket = choose_bucket_with_jitter(
+        profile,
+        packaged_len,
+        object_root,
+        bucket_jitter_extra_levels,
+    )?;
+    let k = profile.k as usize;
+    let n = profile.n as usize;
+    let chunk_len = bucket - SHARD_HEADER_LEN;
+    if packaged_len > k * chunk_len {
+        return Err(FecError::ObjectTooLarge);
+    }
+
+    // Systematic mode borrows every full chunk; only padded tails are owned.
+    let borrowed = if mode == ErasureCodingMode::Systematic {
+        object_bytes.len() / chunk_len
+    } else {
+        0
+    };
+    let mut owned = (borrowed..k)
+        .map(|_| pool.take_zeroed(chunk_len))
+        .collect::<Vec<_>>();
+    match mode {
+        ErasureCodingMode::AllOrNothing => package_into(object_bytes, &mut owned)?,
+        _ => {
+            for (block, chunk) in owned
+                .iter_mut()
+                .zip(object_bytes[borrowed * chunk_len..].chunks(chunk_len))
+            {
+                block[..chunk.len()].copy_from_slice(chunk);
+            }
+            if mode == ErasureCodingMode::HardenedNonSystematic {
+                hardened_forward_in_place(&mut owned, object_root);
+            }
+        }
+    }
+    let mut parity = (k..n)
+        .map(|_| pool.take_zeroed(chunk_len))
+        .collect::<Vec<_>>();
+
+    let header = ShardHeaderV1 {
+        version: SHARD_V1_VERSION,
+        namespace,
+        epoch,
+        tag,
+        object_root,
+        profile_id: profile.id,
+        erasure_mode: erasure_mode_to_wire(mode),
+        bucket_size: bucket as u32,
+        k: profile.k,
+        n: profile.n,
+        index: 0,
+    };
+    let wire = {
+        let data = object_bytes
+            .chunks(chunk_len)
+            .take(borrowed)
+            .chain(owned.iter().map(Vec::as_slice))
+            .collect::<Vec<_>>();
+        if n > k {
+            let rs = ReedSolomon::new(k, n - k).map_err(|_| FecError::ReedSolomon)?;
+            encode_parity(&rs, &data, &mut parity, chunk_len, options)?;
+        }
+        let payloads = data
+            .into_iter()
+            .chain(parity.iter().map(Vec::as_slice))
+            .collect::<Vec<_>>();
+        encode_wires(&header, &payloads, pool, options)?
+    };
+
+    pool.recycle_all(owned);
+    pool.recycle_all(parity);
+    Ok(WireShardSet {
+        header,
+        shards: wire,
+    })
+}
+
+/// Computes RS parity, striping byte columns across threads when enabled.
+fn encode_parity(
+    rs: &ReedSolomon,
+    data: &[&[u8]],
+    parity: &mut [Vec<u8>],
+    chunk_len: usize,
+    options: &ShardingOptions,
+) -> Result<(), FecError> {
+    let total = chunk_len * (data.len() + parity.len());
+    let threads = options.threads_for(total, chunk_len / 64);
+    if threads <= 1 {
+        return rs
+            .encode_sep(data, parity)
+            .map_err(|_| FecError::ReedSolomon);
+    }
+
+    // RS works per byte column, so disjoint column stripes encode independently.
+    let stripe = chunk_len.div_ceil(threads);
+    let mut stripes: Vec<Vec<&mut [u8]>> = (0..threads).map(|_| Vec::new()).collect();
+    for buf in parity.iter_mut() {
+        for (t, piece) in buf.chunks_mut(stripe).enumerate() {
+            stripes[t].push(piece);
+        }
+    }
+    thread::scope(|scope| {
+        let workers = stripes
+            .into_iter()
+            .enumerate()
+            .filter(|(_, pieces)| !pieces.is_empty())
+            .map(|(t, mut pieces)| {
+                let start = t * stripe;
+                let end = (start + stripe).min(chunk_len);
+                let columns = data.iter().map(|d| &d[start..end]).collect::<Vec<_>>();
+                scope.spawn(move || rs.encode_sep(&columns, &mut pieces))
+            })
+            .collect::<Vec<_>>();
+        workers.into_iter().try_for_each(|worker| {
+            worker
+                .join()
+                .map_err(|_| FecError::WorkerPanicked)?
+                .map_err(|_| FecError::ReedSolomon)
+        })
+    })
+}
+
+/// CBOR-encodes every shard into pooled wire buffers.
+fn encode_wires(
+    header: &ShardHeaderV1,
+    payloads: &[&[u8]],
+    pool: &mut ShardBufferPool,
+    options: &ShardingOptions,
+) -> Result<Vec<Vec<u8>>, FecError> {
+    let chunk_len = payloads.first().map_or(0, |p| p.len());
+    let mut wire = payloads
+        .iter()
+        .map(|p| pool.take_empty(160 + 2 * p.len()))
+        .collect::<Vec<_>>();
+    let threads = options.threads_for(chunk_len * payloads.len(), payloads.len());
+    if threads <= 1 {
+        encode_shard_payloads(header, 0, payloads, &mut wire)?;
+        return Ok(wire);
+    }
+
+    let per_thread = payloads.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let workers = wire
+            .chunks_mut(per_thread)
+            .zip(payloads.chunks(per_thread))
+            .enumerate()
+            .map(|(t, (out, payloads))| {
+                scope.spawn(move || encode_shard_payloads(header, t * per_thread, payloads, out))
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().map_err(|_| FecError::WorkerPanicked)?)
+    })?;
+    Ok(wire)
+}
+
+/// Encodes `payloads` as shards `first_index..` of the set into `out`.
+fn encode_shard_payloads(
+    header: &ShardHeaderV1,
+    first_index: usize,
+    payloads: &[&[u8]],
+    out: &mut [Vec<u8>],
+) -> Result<(), FecError> {
+    for (offset, (payload, buf)) in payloads.iter().zip(out.iter_mut()).enumerate() {
+        let header = ShardHeaderV1 {
+            index: (first_index + offset) as u16,
+            ..header.clone()
+        };
+        encode_shard_cbor_into(&header, payload, buf)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{object_to_wire_shards, ShardBufferPool, ShardingOptions};
+    use crate::profile::{ErasureCodingMode, PROFILE_LARGE, PROFILE_MICRO};
+    use crate::sharder::{derive_object_root, object_to_shards_with_profile};
+    use veil_codec::shard::encode_shard_cbor;
+    use veil_core::types::{Epoch, Namespace};
+
+    #[test]
+    fn wire_shards_match_owned_encoding_in_every_mode() {
+        let object = (0..200_000_u32)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        let root = derive_object_root(&object);
+        let mut pool = ShardBufferPool::default();
+        let threaded = ShardingOptions {
+            max_threads: 4,
+            parallel_min_bytes: 0,
+        };
+        for mode in [
+            ErasureCodingMode::Systematic,
+            ErasureCodingMode::HardenedNonSystematic,
+            ErasureCodingMode::AllOrNothing,
+            ErasureCodingMode::Fountain,
+        ] {
+            let expected = object_to_shards_with_profile(
+                &object,
+                Namespace(5),
+                Epoch(6),
+                [0x77; 32],
+                root,
+                mode,
+                0,
+                &PROFILE_LARGE,
+            )
+            .expect("shard")
+            .iter()
+            .map(|s| encode_shard_cbor(s).expect("encode"))
+            .collect::<Vec<_>>();
+            for options in [ShardingOptions::default(), threaded] {
+                let set = object_to_wire_shards(
+                    &object,
+                    Namespace(5),
+                    Epoch(6),
+                    [0x77; 32],
+                    root,
+                    mode,
+                    0,
+                    &PROFILE_LARGE,
+                    &mut pool,
+                    &options,
+                )
+                .expect("wire shards");
+                assert_eq!(set.shards, expected, "{mode:?} {options:?}");
+                assert_eq!(set.header.n, PROFILE_LARGE.n);
+                pool.recycle_all(set.shards);
+            }
+        }
+    }
+
+    #[test]
+    fn pool_reuses_buffers_between_calls() {
+        let object = b"pooled".repeat(100);
+        let root = derive_object_root(&object);
+        let mut pool = ShardBufferPool::new(8);
+        let shard = |pool: &mut ShardBufferPool| {
+            object_to_wire_shards(
+                &object,
+                Namespace(1),
+                Epoch(1),
+                [0x01; 32],
+                root,
+                ErasureCodingMode::AllOrNothing,
+                0,
+                &PROFILE_MICRO,
+                pool,
+                &ShardingOptions::default(),
+            )
+            .expect("wire shards")
+        };
+        let first = shard(&mut pool);
+        assert!(!pool.is_empty(), "scratch blocks return to the pool");
+        let first_copy = first.shards.clone();
+        pool.recycle_all(first.shards);
+        assert!(pool.len() <= 8);
+        assert_eq!(shard(&mut pool).shards, first_copy);
+    }
+}
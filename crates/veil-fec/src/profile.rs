@@ -1,5 +1,11 @@
+use std::borrow::Cow;
+
+use veil_codec::shard::SHARD_BUCKET_SIZES;
+
+use crate::sharder::FecError;
+
 /// Erasure coding profile parameters.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Profile {
     /// Stable profile identifier carried on shard headers.
     pub id: u16,
@@ -8,7 +14,24 @@ pub struct Profile {
     /// Total shard count.
     pub n: u16,
     /// Allowed shard buckets for this profile.
-    pub buckets: &'static [usize],
+    pub buckets: Cow<'static, [usize]>,
+}
+
+impl Profile {
+    /// Builds a profile with runtime-owned bucket sizes.
+    pub fn custom(id: u16, k: u16, n: u16, buckets: Vec<usize>) -> Self {
+        Self {
+            id,
+            k,
+            n,
+            buckets: Cow::Owned(buckets),
+        }
+    }
+
+    /// Returns the shard expansion ratio `n / k`.
+    pub fn redundancy(&self) -> f64 {
+        f64::from(self.n) / f64::from(self.k.max(1))
+    }
 }
 
 /// Erasure coding mode used by shard split/reconstruct routines.
@@ -18,6 +41,13 @@ pub enum ErasureCodingMode {
     Systematic,
     /// Hardened mode: deterministic non-systematic pre-transform before RS.
     HardenedNonSystematic,
+    /// Rateless RaptorQ over hardened source blocks; repair symbols can be
+    /// generated beyond `n` on demand.
+    Fountain,
+    /// Systematic RS over an all-or-nothing package of the object; fewer than
+    /// `k` shards reveal nothing about any block. Opt-in: nodes that predate
+    /// it cannot decode these shards.
+    AllOrNothing,
 }
 
 /// Default profile for smaller objects.
@@ -25,7 +55,7 @@ pub const PROFILE_SMALL: Profile = Profile {
     id: 2,
     k: 6,
     n: 10,
-    buckets: &[16 * 1024, 32 * 1024],
+    buckets: Cow::Borrowed(&[16 * 1024, 32 * 1024]),
 };
 
 /// Default profile for micro objects (reactions/votes/ACK-like payloads).
@@ -33,7 +63,7 @@ pub const PROFILE_MICRO: Profile = Profile {
     id: 1,
     k: 2,
     n: 3,
-    buckets: &[2 * 1024, 4 * 1024, 8 * 1024],
+    buckets: Cow::Borrowed(&[2 * 1024, 4 * 1024, 8 * 1024]),
 };
 
 /// Default profile for larger objects.
@@ -41,14 +71,184 @@ pub const PROFILE_LARGE: Profile = Profile {
     id: 3,
     k: 10,
     n: 16,
-    buckets: &[32 * 1024, 64 * 1024],
+    buckets: Cow::Borrowed(&[32 * 1024, 64 * 1024]),
+};
+
+/// Loss-resilient variant of [`PROFILE_MICRO`] for lossy lanes.
+pub const PROFILE_MICRO_RESILIENT: Profile = Profile {
+    id: 4,
+    k: 2,
+    n: 6,
+    buckets: Cow::Borrowed(&[2 * 1024, 4 * 1024, 8 * 1024]),
+};
+
+/// Loss-resilient variant of [`PROFILE_SMALL`] for lossy lanes.
+pub const PROFILE_SMALL_RESILIENT: Profile = Profile {
+    id: 5,
+    k: 6,
+    n: 18,
+    buckets: Cow::Borrowed(&[16 * 1024, 32 * 1024]),
+};
+
+/// Loss-resilient variant of [`PROFILE_LARGE`] for lossy lanes.
+pub const PROFILE_LARGE_RESILIENT: Profile = Profile {
+    id: 6,
+    k: 10,
+    n: 30,
+    buckets: Cow::Borrowed(&[32 * 1024, 64 * 1024]),
 };
 
+/// Size limit for the micro profile class.
+pub const MICRO_OBJECT_MAX_LEN: usize = 8 * 1024;
+/// Size limit for the small profile class.
+pub const SMALL_OBJECT_MAX_LEN: usize = 128 * 1024;
+
+/// One registered profile and the largest object length it serves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileEntry {
+    /// Objects up to this many bytes fall into this entry's size class.
+    pub max_object_len: usize,
+    /// Profile parameters.
+    pub profile: Profile,
+}
+
+/// Registry of selectable profiles grouped into size classes.
+///
+/// Entries sharing a `max_object_len` form one size class; an object uses the
+/// smallest class that fits it. Within a class, selection prefers the lowest
+/// redundancy that still covers the observed lane loss.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileRegistry {
+    entries: Vec<ProfileEntry>,
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ProfileRegistry {
+    /// Returns an empty registry.
+    pub fn empty() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the built-in profiles and their resilient variants.
+    pub fn builtin() -> Self {
+        let entry = |max_object_len, profile| ProfileEntry {
+            max_object_len,
+            profile,
+        };
+        Self {
+            entries: vec![
+                entry(MICRO_OBJECT_MAX_LEN, PROFILE_MICRO),
+                entry(MICRO_OBJECT_MAX_LEN, PROFILE_MICRO_RESILIENT),
+                entry(SMALL_OBJECT_MAX_LEN, PROFILE_SMALL),
+                entry(SMALL_OBJECT_MAX_LEN, PROFILE_SMALL_RESILIENT),
+                entry(usize::MAX, PROFILE_LARGE),
+                entry(usize::MAX, PROFILE_LARGE_RESILIENT),
+            ],
+        }
+    }
+
+    /// Registers a profile for objects up to `max_object_len` bytes.
+    pub fn register(&mut self, max_object_len: usize, profile: Profile) -> Result<(), FecError> {
+        if profile.k == 0 || profile.k > profile.n {
+            return Err(FecError::InvalidProfile("k must be in 1..=n"));
+        }
+        if profile.n as usize > 256 {
+            return Err(FecError::InvalidProfile("n must be <= 256"));
+        }
+        if profile.buckets.is_empty()
+            || profile
+                .buckets
+                .iter()
+                .any(|bucket| !SHARD_BUCKET_SIZES.contains(bucket))
+        {
+            return Err(FecError::InvalidProfile("unsupported bucket size"));
+        }
+        if self.get(profile.id).is_some() {
+            return Err(FecError::InvalidProfile("duplicate profile id"));
+        }
+        self.entries.push(ProfileEntry {
+            max_object_len,
+            profile,
+        });
+        Ok(())
+    }
+
+    /// Looks up a registered profile by id.
+    pub fn get(&self, id: u16) -> Option<&Profile> {
+        self.entries
+            .iter()
+            .map(|entry| &entry.profile)
+            .find(|profile| profile.id == id)
+    }
+
+    /// Returns all registered entries in registration order.
+    pub fn entries(&self) -> &[ProfileEntry] {
+        &self.entries
+    }
+
+    /// Chooses the lowest-redundancy profile for an object length.
+    pub fn choose(&self, object_len: usize) -> Option<&Profile> {
+        self.choose_for_delivery_rate(object_len, 1.0, 0.0)
+    }
+
+    /// Chooses a profile covering an observed shard delivery rate.
+    ///
+    /// Picks the lowest-redundancy profile in the object's size class whose
+    /// `n / k` is at least `(1 + headroom) / delivery_rate`; if none is
+    /// redundant enough, the most redundant profile in the class is used.
+    pub fn choose_for_delivery_rate(
+        &self,
+        object_len: usize,
+        delivery_rate: f64,
+        headroom: f64,
+    ) -> Option<&Profile> {
+        let class = self
+            .entries
+            .iter()
+            .map(|entry| entry.max_object_len)
+            .filter(|max| *max >= object_len)
+            .min()?;
+        let candidates = self
+            .entries
+            .iter()
+            .filter(|entry| entry.max_object_len == class)
+            .map(|entry| &entry.profile);
+
+        let delivery_rate = if delivery_rate.is_finite() {
+            delivery_rate.clamp(0.01, 1.0)
+        } else {
+            1.0
+        };
+        let required = (1.0 + headroom.max(0.0)) / delivery_rate;
+        let mut best_covering: Option<&Profile> = None;
+        let mut most_redundant: Option<&Profile> = None;
+        for profile in candidates {
+            let redundancy = profile.redundancy();
+            if redundancy >= required
+                && best_covering.is_none_or(|best| redundancy < best.redundancy())
+            {
+                best_covering = Some(profile);
+            }
+            if most_redundant.is_none_or(|best| redundancy > best.redundancy()) {
+                most_redundant = Some(profile);
+            }
+        }
+        best_covering.or(most_redundant)
+    }
+}
+
 /// Chooses profile by object length threshold.
 pub fn choose_profile(object_len: usize) -> Profile {
-    if object_len <= 8 * 1024 {
+    if object_len <= MICRO_OBJECT_MAX_LEN {
         PROFILE_MICRO
-    } else if object_len <= 128 * 1024 {
+    } else if object_len <= SMALL_OBJECT_MAX_LEN {
         PROFILE_SMALL
     } else {
         PROFILE_LARGE
@@ -57,7 +257,11 @@ pub fn choose_profile(object_len: usize) -> Profile {
 
 #[cfg(test)]
 mod tests {
-    use super::{choose_profile, PROFILE_LARGE, PROFILE_MICRO, PROFILE_SMALL};
+    use super::{
+        choose_profile, Profile, ProfileRegistry, PROFILE_LARGE, PROFILE_LARGE_RESILIENT,
+        PROFILE_MICRO, PROFILE_MICRO_RESILIENT, PROFILE_SMALL,
+    };
+    use crate::sharder::FecError;
 
     #[test]
     fn chooses_small_at_and_below_boundary() {
@@ -81,4 +285,55 @@ mod tests {
         assert_eq!(p.n, PROFILE_LARGE.n);
         assert_eq!(p.buckets, PROFILE_LARGE.buckets);
     }
+
+    #[test]
+    fn registry_matches_size_thresholds_without_loss() {
+        let registry = ProfileRegistry::builtin();
+        for len in [1, 8 * 1024, 8 * 1024 + 1, 128 * 1024, 128 * 1024 + 1] {
+            assert_eq!(registry.choose(len), Some(&choose_profile(len)));
+        }
+    }
+
+    #[test]
+    fn registry_escalates_redundancy_with_observed_loss() {
+        let registry = ProfileRegistry::builtin();
+        // 1.5x covers 70% delivery only without headroom.
+        assert_eq!(
+            registry.choose_for_delivery_rate(1024, 0.7, 0.0),
+            Some(&PROFILE_MICRO)
+        );
+        assert_eq!(
+            registry.choose_for_delivery_rate(1024, 0.7, 0.1),
+            Some(&PROFILE_MICRO_RESILIENT)
+        );
+        // Nothing covers 10% delivery; fall back to the most redundant.
+        assert_eq!(
+            registry.choose_for_delivery_rate(200 * 1024, 0.1, 0.0),
+            Some(&PROFILE_LARGE_RESILIENT)
+        );
+    }
+
+    #[test]
+    fn registry_accepts_valid_custom_profiles_only() {
+        let mut registry = ProfileRegistry::builtin();
+        let ble = Profile::custom(40, 4, 16, vec![4 * 1024, 8 * 1024]);
+        registry.register(16 * 1024, ble.clone()).expect("register");
+        assert_eq!(registry.get(40), Some(&ble));
+        assert_eq!(
+            registry.choose_for_delivery_rate(12 * 1024, 0.5, 0.0),
+            Some(&ble)
+        );
+
+        for bad in [
+            Profile::custom(40, 4, 16, vec![4 * 1024]),
+            Profile::custom(41, 0, 4, vec![4 * 1024]),
+            Profile::custom(42, 8, 4, vec![4 * 1024]),
+            Profile::custom(43, 2, 4, vec![3000]),
+        ] {
+            assert!(matches!(
+                registry.register(1024, bad),
+                Err(FecError::InvalidProfile(_))
+            ));
+        }
+    }
 }
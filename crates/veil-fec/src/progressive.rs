@@ -0,0 +1,430 @@
+//! Streaming shard decoder that does elimination work as shards arrive.
+//!
+//! RS-based modes keep a reduced row-echelon system over GF(2^8): every
+//! accepted shard contributes one pivot row (coefficients plus payload) and
+//! linearly dependent shards are dropped, so at most `k` payloads are held and
+//! the source blocks fall out as soon as the rank reaches `k`. Fountain mode
+//! feeds symbols into a persistent RaptorQ block decoder instead.
+//!
+//! Parity matrices are derived once per `(k, n)` and shared between
+//! decoders, so opening a decoder for a new root costs no RS encoding.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use raptorq::{extended_source_block_symbols, EncodingPacket, PayloadId, SourceBlockDecoder};
+use reed_solomon_erasure::galois_8::{self, ReedSolomon};
+use veil_codec::shard::{
+    ShardErasureMode, ShardHeaderV1, ShardV1, MAX_SHARD_SET_N, SHARD_HEADER_LEN,
+};
+
+use crate::aont::unpackage_blocks;
+use crate::sharder::{fountain_config, hardened_inverse_transform, FecError};
+
+/// Distinct `(k, n)` parity matrices kept for reuse.
+const MAX_CACHED_PARITY_MATRICES: usize = 64;
+
+type ParityRows = Arc<Vec<Vec<u8>>>;
+
+/// Outcome of feeding one shard to a [`ProgressiveDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeProgress {
+    /// The shard was absorbed; more independent shards are required.
+    NeedMore { have: usize, need: usize },
+    /// The shard added no information (duplicate index, dependent row, or the
+    /// object was already decoded).
+    Redundant,
+    /// The object became decodable; carries padded object bytes.
+    Complete(Vec<u8>),
+}
+
+/// Incremental decoder for one shard set (one object root).
+#[derive(Debug, Clone)]
+pub struct ProgressiveDecoder {
+    header: ShardHeaderV1,
+    chunk_len: usize,
+    seen: HashSet<u16>,
+    state: DecoderState,
+    complete: bool,
+}
+
+#[derive(Debug, Clone)]
+enum DecoderState {
+    Linear(LinearSystem),
+    Fountain {
+        decoder: Box<SourceBlockDecoder>,
+        received: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct LinearSystem {
+    /// Parity coefficient rows of the systematic RS matrix, `(n - k) x k`.
+    parity_rows: ParityRows,
+    /// Pivot row per column, kept fully reduced against all other pivots.
+    pivots: Vec<Option<(Vec<u8>, Vec<u8>)>>,
+    rank: usize,
+}
+
+impl ProgressiveDecoder {
+    /// Starts a decoder for the shard set described by `header`.
+    pub fn new(header: &ShardHeaderV1) -> Result<Self, FecError> {
+        header.validate()?;
+        let k = header.k as usize;
+        let n = header.n as usize;
+        let chunk_len = (header.bucket_size as usize)
+            .checked_sub(SHARD_HEADER_LEN)
+            .ok_or(FecError::InvalidShardSet("bucket smaller than header"))?;
+        let state = if header.erasure_mode == ShardErasureMode::Fountain {
+            let config = fountain_config(k, chunk_len)?;
+            DecoderState::Fountain {
+                decoder: Box::new(SourceBlockDecoder::new2(
+                    0,
+                    &config,
+                    config.transfer_length(),
+                )),
+                received: 0,
+            }
+        } else {
+            DecoderState::Linear(LinearSystem::new(k, n)?)
+        };
+        Ok(Self {
+            header: ShardHeaderV1 {
+                index: 0,
+                ..header.clone()
+            },
+            chunk_len,
+            seen: HashSet::new(),
+            state,
+            complete: false,
+        })
+    }
+
+    /// Independent shards absorbed so far.
+    pub fn have(&self) -> usize {
+        match &self.state {
+            DecoderState::Linear(system) => system.rank,
+            DecoderState::Fountain { received, .. } => *received,
+        }
+    }
+
+    /// Shards required before the next decode attempt can succeed.
+    pub fn need(&self) -> usize {
+        let k = self.header.k as usize;
+        match &self.state {
+            DecoderState::Linear(_) => k,
+            // Fountain decodes from `k` symbols only with high probability.
+            DecoderState::Fountain { received, .. } => k.max(received + 1),
+        }
+    }
+
+    /// Returns whether the object has already been decoded.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Absorbs one shard, returning the padded object once decodable.
+    pub fn push(&mut self, shard: &ShardV1) -> Result<DecodeProgress, FecError> {
+        let h = &shard.header;
+        if h.object_root != self.header.object_root
+            || h.k != self.header.k
+            || h.n != self.header.n
+            || h.namespace != self.header.namespace
+            || h.epoch != self.header.epoch
+            || h.tag != self.header.tag
+            || h.profile_id != self.header.profile_id
+            || h.erasure_mode != self.header.erasure_mode
+            || h.bucket_size != self.header.bucket_size
+        {
+            return Err(FecError::InvalidShardSet("mixed shard set"));
+        }
+        if shard.payload.len() != self.chunk_len {
+            return Err(FecError::InvalidShardSet("payload lengths differ"));
+        }
+        if self.complete || !self.seen.insert(h.index) {
+            return Ok(DecodeProgress::Redundant);
+        }
+
+        let k = self.header.k;
+        let decoded = match &mut self.state {
+            DecoderState::Linear(system) => {
+                if h.index >= self.header.n {
+                    return Err(FecError::InvalidShardSet("index out of range"));
+                }
+                if !system.insert(h.index as usize, shard.payload.clone()) {
+                    return Ok(DecodeProgress::Redundant);
+                }
+                system.solved_blocks()
+            }
+            DecoderState::Fountain { decoder, received } => {
+                *received += 1;
+                let index = u32::from(h.index);
+                let esi = if h.index < k {
+                    index
+                } else {
+                    extended_source_block_symbols(u32::from(k)) + (index - u32::from(k))
+                };
+                let packet = EncodingPacket::new(PayloadId::new(0, esi), shard.payload.clone());
+                decoder.decode(std::iter::once(packet)).map(|bytes| {
+                    bytes
+                        .chunks(self.chunk_len)
+                        .map(<[u8]>::to_vec)
+                        .collect::<Vec<_>>()
+                })
+            }
+        };
+
+        let Some(source_blocks) = decoded else {
+            return Ok(DecodeProgress::NeedMore {
+                have: self.have(),
+                need: self.need(),
+            });
+        };
+        self.complete = true;
+        let root = self.header.object_root;
+        let bytes = match self.header.erasure_mode {
+            ShardErasureMode::Systematic => source_blocks.concat(),
+            ShardErasureMode::HardenedNonSystematic | ShardErasureMode::Fountain => {
+                hardened_inverse_transform(source_blocks, root).concat()
+            }
+            ShardErasureMode::AllOrNothing => unpackage_blocks(source_blocks)?,
+        };
+        Ok(DecodeProgress::Complete(bytes))
+    }
+}
+
+/// Returns the shared parity matrix for `(k, n)`, deriving it on first use.
+fn cached_parity_rows(k: usize, n: usize) -> Result<ParityRows, FecError> {
+    static CACHE: OnceLock<Mutex<HashMap<(usize, usize), ParityRows>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some(rows) = cache.lock().ok().and_then(|c| c.get(&(k, n)).cloned()) {
+        return Ok(rows);
+    }
+    let rows = Arc::new(parity_rows(k, n)?);
+    if let Ok(mut cache) = cache.lock() {
+        if cache.len() >= MAX_CACHED_PARITY_MATRICES {
+            cache.clear();
+        }
+        cache.insert((k, n), Arc::clone(&rows));
+    }
+    Ok(rows)
+}
+
+fn parity_rows(k: usize, n: usize) -> Result<Vec<Vec<u8>>, FecError> {
+    let mut parity_rows = vec![vec![0_u8; k]; n - k];
+    if n > k {
+        let rs = ReedSolomon::new(k, n - k).map_err(|_| FecError::ReedSolomon)?;
+        // Encoding unit data vectors reads the parity matrix off column by
+        // column.
+        for col in 0..k {
+            let data = (0..k).map(|i| [u8::from(i == col)]).collect::<Vec<_>>();
+            let mut parity = vec![[0_u8]; n - k];
+            rs.encode_sep(&data, &mut parity)
+                .map_err(|_| FecError::ReedSolomon)?;
+            for (row, value) in parity_rows.iter_mut().zip(parity) {
+                row[col] = value[0];
+            }
+        }
+    }
+    Ok(parity_rows)
+}
+
+impl LinearSystem {
+    fn new(k: usize, n: usize) -> Result<Self, FecError> {
+        if n > usize::from(MAX_SHARD_SET_N) {
+            return Err(FecError::InvalidShardSet("n exceeds shard-set limit"));
+        }
+        Ok(Self {
+            parity_rows: cached_parity_rows(k, n)?,
+            pivots: vec![None; k],
+            rank: 0,
+        })
+    }
+
+    /// Adds the row for shard `index`; returns `false` if it was dependent.
+    fn insert(&mut self, index: usize, mut payload: Vec<u8>) -> bool {
+        let k = self.pivots.len();
+        let mut coeffs = if index < k {
+            let mut unit = vec![0_u8; k];
+            unit[index] = 1;
+            unit
+        } else {
+            self.parity_rows[index - k].clone()
+        };
+
+        for col in 0..k {
+            let factor = coeffs[col];
+            if factor == 0 {
+                continue;
+            }
+            if let Some((row, row_payload)) = &self.pivots[col] {
+                galois_8::mul_slice_xor(factor, row, &mut coeffs);
+                galois_8::mul_slice_xor(factor, row_payload, &mut payload);
+            }
+        }
+        let Some(pivot) = coeffs.iter().position(|c| *c != 0) else {
+            return false;
+        };
+
+        let inverse = galois_8::div(1, coeffs[pivot]);
+        if inverse != 1 {
+            let scaled = coeffs.clone();
+            galois_8::mul_slice(inverse, &scaled, &mut coeffs);
+            let scaled = payload.clone();
+            galois_8::mul_slice(inverse, &scaled, &mut payload);
+        }
+        for (row, row_payload) in self.pivots.iter_mut().flatten() {
+            let factor = row[pivot];
+            if factor != 0 {
+                galois_8::mul_slice_xor(factor, &coeffs, row);
+                galois_8::mul_slice_xor(factor, &payload, row_payload);
+            }
+        }
+        self.pivots[pivot] = Some((coeffs, payload));
+        self.rank += 1;
+        true
+    }
+
+    /// Returns the source blocks once the system has full rank.
+    fn solved_blocks(&mut self) -> Option<Vec<Vec<u8>>> {
+        if self.rank < self.pivots.len() {
+            return None;
+        }
+        self.pivots
+            .iter_mut()
+            .map(|pivot| pivot.take().map(|(_, payload)| payload))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{DecodeProgress, DecoderState, ProgressiveDecoder};
+    use crate::profile::{ErasureCodingMode, PROFILE_SMALL};
+    use crate::sharder::{derive_object_root, object_to_shards_with_profile, FecError};
+    use veil_codec::shard::ShardV1;
+    use veil_core::types::{Epoch, Namespace};
+
+    fn shards(object: &[u8], mode: ErasureCodingMode) -> Vec<ShardV1> {
+        object_to_shards_with_profile(
+            object,
+            Namespace(3),
+            Epoch(4),
+            [0x33; 32],
+            derive_object_root(object),
+            mode,
+            0,
+            &PROFILE_SMALL,
+        )
+        .expect("object should shard")
+    }
+
+    #[test]
+    fn decodes_every_mode_from_parity_heavy_subsets() {
+        let object = b"progressive decoder input ".repeat(900);
+        for mode in [
+            ErasureCodingMode::Systematic,
+            ErasureCodingMode::HardenedNonSystematic,
+            ErasureCodingMode::AllOrNothing,
+        ] {
+            let set = shards(&object, mode);
+            let k = set[0].header.k as usize;
+            let mut decoder = ProgressiveDecoder::new(&set[0].header).expect("decoder");
+            let mut order = set.iter().rev().collect::<Vec<_>>();
+            order.push(&set[0]);
+            let mut out = None;
+            for (i, shard) in order.into_iter().enumerate() {
+                match decoder.push(shard).expect("push") {
+                    DecodeProgress::NeedMore { have, need } => {
+                        assert_eq!((have, need), (i + 1, k));
+                    }
+                    DecodeProgress::Complete(bytes) => {
+                        assert_eq!(i + 1, k, "complete exactly at rank k");
+                        out = Some(bytes);
+                        break;
+                    }
+                    DecodeProgress::Redundant => panic!("no duplicates fed"),
+                }
+            }
+            let bytes = out.expect("decoded");
+            assert_eq!(&bytes[..object.len()], &object[..], "{mode:?}");
+            assert!(decoder.is_complete());
+            assert_eq!(
+                decoder.push(&set[1]).expect("push"),
+                DecodeProgress::Redundant
+            );
+        }
+    }
+
+    #[test]
+    fn fountain_mode_decodes_incrementally() {
+        let object = b"progressive fountain ".repeat(700);
+        let set = shards(&object, ErasureCodingMode::Fountain);
+        let mut decoder = ProgressiveDecoder::new(&set[0].header).expect("decoder");
+        let mut out = None;
+        for shard in set.iter().skip(2) {
+            if let DecodeProgress::Complete(bytes) = decoder.push(shard).expect("push") {
+                out = Some(bytes);
+                break;
+            }
+        }
+        let bytes = out.expect("decoded from n - 2 symbols");
+        assert_eq!(&bytes[..object.len()], &object[..]);
+    }
+
+    #[test]
+    fn rejects_duplicates_and_mixed_sets() {
+        let object = b"mixed set".repeat(10);
+        let set = shards(&object, ErasureCodingMode::AllOrNothing);
+        let mut decoder = ProgressiveDecoder::new(&set[0].header).expect("decoder");
+        assert!(matches!(
+            decoder.push(&set[3]).expect("push"),
+            DecodeProgress::NeedMore { have: 1, .. }
+        ));
+        assert_eq!(
+            decoder.push(&set[3]).expect("push"),
+            DecodeProgress::Redundant
+        );
+
+        let mut foreign = set[4].clone();
+        foreign.header.object_root = [0xEE; 32];
+        assert!(matches!(
+            decoder.push(&foreign),
+            Err(FecError::InvalidShardSet("mixed shard set"))
+        ));
+    }
+
+    #[test]
+    fn rejects_hostile_headers_before_allocating() {
+        let object = b"hostile".repeat(10);
+        let set = shards(&object, ErasureCodingMode::Systematic);
+        let mut header = set[0].header.clone();
+        (header.k, header.n) = (32_767, 65_534);
+        assert!(ProgressiveDecoder::new(&header).is_err());
+
+        let set = shards(&object, ErasureCodingMode::Fountain);
+        let mut header = set[0].header.clone();
+        (header.k, header.n) = (60_000, 60_000);
+        assert!(ProgressiveDecoder::new(&header).is_err());
+    }
+
+    #[test]
+    fn decoders_share_parity_matrices_per_shape() {
+        let set = shards(
+            &b"shared".repeat(40),
+            ErasureCodingMode::HardenedNonSystematic,
+        );
+        let first = ProgressiveDecoder::new(&set[0].header).expect("decoder");
+        let mut header = set[0].header.clone();
+        header.object_root = [0x11; 32];
+        let second = ProgressiveDecoder::new(&header).expect("decoder");
+        match (&first.state, &second.state) {
+            (DecoderState::Linear(a), DecoderState::Linear(b)) => {
+                assert!(Arc::ptr_eq(&a.parity_rows, &b.parity_rows));
+            }
+            _ => panic!("expected linear decoders"),
+        }
+    }
+}
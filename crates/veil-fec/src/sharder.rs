@@ -1,20 +1,38 @@
+use raptorq::{
+    extended_source_block_symbols, EncodingPacket, ObjectTransmissionInformation, PayloadId,
+    SourceBlockDecoder, SourceBlockEncoder,
+};
 use reed_solomon_erasure::galois_8::ReedSolomon;
 use thiserror::Error;
 use veil_codec::error::CodecError;
 use veil_codec::shard::{
-    encode_shard_cbor, ShardErasureMode, ShardHeaderV1, ShardV1, SHARD_BUCKET_SIZES,
-    SHARD_HEADER_LEN, SHARD_V1_VERSION,
+    encode_shard_cbor, ShardErasureMode, ShardHeaderV1, ShardV1, MAX_SHARD_SET_N,
+    SHARD_BUCKET_SIZES, SHARD_HEADER_LEN, SHARD_V1_VERSION,
 };
 use veil_core::hash::blake3_32;
 use veil_core::types::{Epoch, Namespace};
 use veil_core::{ObjectRoot, ShardId, Tag};
 
+use crate::aont::{package_blocks, unpackage_blocks, AONT_KEY_LEN};
 use crate::profile::{choose_profile, ErasureCodingMode, Profile};
 
-fn mode_to_wire(mode: ErasureCodingMode) -> ShardErasureMode {
+/// Maps a coding mode to the mode carried on shard headers.
+pub fn erasure_mode_to_wire(mode: ErasureCodingMode) -> ShardErasureMode {
     match mode {
         ErasureCodingMode::Systematic => ShardErasureMode::Systematic,
         ErasureCodingMode::HardenedNonSystematic => ShardErasureMode::HardenedNonSystematic,
+        ErasureCodingMode::Fountain => ShardErasureMode::Fountain,
+        ErasureCodingMode::AllOrNothing => ShardErasureMode::AllOrNothing,
+    }
+}
+
+/// Maps a shard-header mode to the coding mode used to reconstruct it.
+pub fn erasure_mode_from_wire(mode: ShardErasureMode) -> ErasureCodingMode {
+    match mode {
+        ShardErasureMode::Systematic => ErasureCodingMode::Systematic,
+        ShardErasureMode::HardenedNonSystematic => ErasureCodingMode::HardenedNonSystematic,
+        ShardErasureMode::Fountain => ErasureCodingMode::Fountain,
+        ShardErasureMode::AllOrNothing => ErasureCodingMode::AllOrNothing,
     }
 }
 
@@ -29,6 +47,12 @@ pub enum FecError {
     InvalidShardSet(&'static str),
     #[error("reed-solomon error")]
     ReedSolomon,
+    #[error("invalid profile: {0}")]
+    InvalidProfile(&'static str),
+    #[error("sharding worker thread panicked")]
+    WorkerPanicked,
+    #[error("fountain decode needs more symbols")]
+    NeedMoreSymbols,
     #[error("codec error: {0}")]
     Codec(#[from] CodecError),
 }
@@ -49,11 +73,31 @@ pub fn choose_profile_and_bucket_with_jitter(
     jitter_seed: [u8; 32],
     bucket_jitter_extra_levels: usize,
 ) -> Result<(Profile, usize), FecError> {
+    let profile = choose_profile(object_len_bytes);
+    let bucket = choose_bucket_with_jitter(
+        &profile,
+        object_len_bytes,
+        jitter_seed,
+        bucket_jitter_extra_levels,
+    )?;
+    Ok((profile, bucket))
+}
+
+/// Chooses a bucket of `profile` for an object length with optional jitter
+/// over larger-fitting buckets.
+pub fn choose_bucket_with_jitter(
+    profile: &Profile,
+    object_len_bytes: usize,
+    jitter_seed: [u8; 32],
+    bucket_jitter_extra_levels: usize,
+) -> Result<usize, FecError> {
     if object_len_bytes == 0 {
         return Err(FecError::EmptyObject);
     }
+    if profile.k == 0 {
+        return Err(FecError::InvalidProfile("k must be > 0"));
+    }
 
-    let profile = choose_profile(object_len_bytes);
     let per_shard_target = object_len_bytes.div_ceil(profile.k as usize);
     let needed = per_shard_target + SHARD_HEADER_LEN;
 
@@ -70,7 +114,7 @@ pub fn choose_profile_and_bucket_with_jitter(
         } else {
             jitter_seed[0] as usize % (max_extra + 1)
         };
-        return Ok((profile, candidates[idx]));
+        return Ok(candidates[idx]);
     }
 
     let largest = profile
@@ -83,7 +127,7 @@ pub fn choose_profile_and_bucket_with_jitter(
     if object_len_bytes > capacity {
         return Err(FecError::ObjectTooLarge);
     }
-    Ok((profile, largest))
+    Ok(largest)
 }
 
 /// Derives object root hash.
@@ -91,7 +135,7 @@ pub fn derive_object_root(object_bytes: &[u8]) -> ObjectRoot {
     blake3_32(object_bytes)
 }
 
-/// Splits encoded object bytes into `n` shards using Reed-Solomon.
+/// Splits encoded object bytes into `n` shards using hardened Reed-Solomon.
 pub fn object_to_shards(
     object_bytes: &[u8],
     namespace: Namespace,
@@ -141,8 +185,42 @@ pub fn object_to_shards_with_mode_and_padding(
     mode: ErasureCodingMode,
     bucket_jitter_extra_levels: usize,
 ) -> Result<Vec<ShardV1>, FecError> {
-    let (profile, bucket) = choose_profile_and_bucket_with_jitter(
-        object_bytes.len(),
+    object_to_shards_with_profile(
+        object_bytes,
+        namespace,
+        epoch,
+        tag,
+        object_root,
+        mode,
+        bucket_jitter_extra_levels,
+        &choose_profile(object_bytes.len()),
+    )
+}
+
+/// Splits encoded object bytes into shards using an explicit profile (for
+/// example one chosen from a `ProfileRegistry`).
+#[allow(clippy::too_many_arguments)]
+pub fn object_to_shards_with_profile(
+    object_bytes: &[u8],
+    namespace: Namespace,
+    epoch: Epoch,
+    tag: Tag,
+    object_root: ObjectRoot,
+    mode: ErasureCodingMode,
+    bucket_jitter_extra_levels: usize,
+    profile: &Profile,
+) -> Result<Vec<ShardV1>, FecError> {
+    if profile.n > MAX_SHARD_SET_N {
+        return Err(FecError::InvalidProfile("n must be <= 256"));
+    }
+    let packaged_len = if mode == ErasureCodingMode::AllOrNothing {
+        object_bytes.len() + AONT_KEY_LEN
+    } else {
+        object_bytes.len()
+    };
+    let bucket = choose_bucket_with_jitter(
+        profile,
+        packaged_len,
         object_root,
         bucket_jitter_extra_levels,
     )?;
@@ -151,23 +229,34 @@ pub fn object_to_shards_with_mode_and_padding(
     let chunk_len = bucket - SHARD_HEADER_LEN;
 
     let total_capacity = k * chunk_len;
-    if object_bytes.len() > total_capacity {
+    if packaged_len > total_capacity {
         return Err(FecError::ObjectTooLarge);
     }
 
-    let mut padded = vec![0_u8; total_capacity];
-    padded[..object_bytes.len()].copy_from_slice(object_bytes);
-
-    let mut source_blocks: Vec<Vec<u8>> = Vec::with_capacity(k);
-    for i in 0..k {
-        let start = i * chunk_len;
-        let end = start + chunk_len;
-        source_blocks.push(padded[start..end].to_vec());
+    if mode == ErasureCodingMode::Fountain {
+        let template = ShardHeaderV1 {
+            version: SHARD_V1_VERSION,
+            namespace,
+            epoch,
+            tag,
+            object_root,
+            profile_id: profile.id,
+            erasure_mode: ShardErasureMode::Fountain,
+            bucket_size: bucket as u32,
+            k: profile.k,
+            n: profile.n,
+            index: 0,
+        };
+        return fountain_shards(object_bytes, &template, 0, profile.n);
     }
 
-    if mode == ErasureCodingMode::HardenedNonSystematic {
-        source_blocks = hardened_forward_transform(source_blocks, object_root);
-    }
+    let source_blocks = match mode {
+        ErasureCodingMode::AllOrNothing => package_blocks(object_bytes, k, chunk_len)?,
+        ErasureCodingMode::HardenedNonSystematic => {
+            hardened_forward_transform(split_source_blocks(object_bytes, k, chunk_len), object_root)
+        }
+        _ => split_source_blocks(object_bytes, k, chunk_len),
+    };
 
     let mut shards_data: Vec<Vec<u8>> = Vec::with_capacity(n);
     shards_data.extend(source_blocks);
@@ -189,7 +278,7 @@ pub fn object_to_shards_with_mode_and_padding(
                 tag,
                 object_root,
                 profile_id: profile.id,
-                erasure_mode: mode_to_wire(mode),
+                erasure_mode: erasure_mode_to_wire(mode),
                 bucket_size: bucket as u32,
                 k: profile.k,
                 n: profile.n,
@@ -207,11 +296,7 @@ pub fn reconstruct_object(
     object_len: usize,
     expected_root: ObjectRoot,
 ) -> Result<Vec<u8>, FecError> {
-    let mut out = reconstruct_object_padded_with_mode(
-        shards,
-        expected_root,
-        ErasureCodingMode::HardenedNonSystematic,
-    )?;
+    let mut out = reconstruct_object_padded(shards, expected_root)?;
     if object_len > out.len() {
         return Err(FecError::InvalidShardSet(
             "requested object length too large",
@@ -221,15 +306,19 @@ pub fn reconstruct_object(
     Ok(out)
 }
 
-/// Reconstructs padded object block bytes from a shard subset.
+/// Reconstructs padded object block bytes from a shard subset, using the
+/// erasure mode carried on the shard headers.
 pub fn reconstruct_object_padded(
     shards: &[ShardV1],
     expected_root: ObjectRoot,
 ) -> Result<Vec<u8>, FecError> {
+    let first = shards
+        .first()
+        .ok_or(FecError::InvalidShardSet("no shards"))?;
     reconstruct_object_padded_with_mode(
         shards,
         expected_root,
-        ErasureCodingMode::HardenedNonSystematic,
+        erasure_mode_from_wire(first.header.erasure_mode),
     )
 }
 
@@ -269,6 +358,9 @@ pub fn reconstruct_object_padded_with_mode(
     if k == 0 || n == 0 || k > n {
         return Err(FecError::InvalidShardSet("invalid k/n in header"));
     }
+    if mode == ErasureCodingMode::Fountain {
+        return reconstruct_fountain(shards, expected_root);
+    }
 
     let chunk_len = shards[0].payload.len();
     let mut slots: Vec<Option<Vec<u8>>> = vec![None; n];
@@ -314,15 +406,184 @@ pub fn reconstruct_object_padded_with_mode(
         let bytes = shard.ok_or(FecError::ReedSolomon)?;
         source_blocks.push(bytes);
     }
-    if mode == ErasureCodingMode::HardenedNonSystematic {
-        source_blocks = hardened_inverse_transform(source_blocks, expected_root);
+    match mode {
+        ErasureCodingMode::AllOrNothing => unpackage_blocks(source_blocks),
+        ErasureCodingMode::HardenedNonSystematic => {
+            Ok(hardened_inverse_transform(source_blocks, expected_root).concat())
+        }
+        _ => Ok(source_blocks.concat()),
     }
+}
+
+/// Generates fountain-mode shards with indices `[start, start + count)`.
+///
+/// `template` carries the shard-set header (as produced by
+/// `object_to_shards_with_mode*` with `ErasureCodingMode::Fountain`); its
+/// `index` is ignored. Indices below `k` are source symbols, the rest are
+/// fresh RaptorQ repair symbols, so a publisher can keep calling this with
+/// increasing `start` to emit symbols no receiver has seen. Use a
+/// [`FountainEncoder`] to keep the encoder between calls.
+pub fn fountain_shards(
+    object_bytes: &[u8],
+    template: &ShardHeaderV1,
+    start: u16,
+    count: u16,
+) -> Result<Vec<ShardV1>, FecError> {
+    FountainEncoder::new(object_bytes, template)?.shards(start, count)
+}
+
+/// Fountain-mode shard generator for one object.
+///
+/// The RaptorQ encoder is built on the first repair request and reused, so
+/// repeated ACK-retry batches skip re-running the precode.
+#[derive(Debug, Clone)]
+pub struct FountainEncoder {
+    template: ShardHeaderV1,
+    source_blocks: Vec<Vec<u8>>,
+    chunk_len: usize,
+    encoder: Option<SourceBlockEncoder>,
+}
+
+impl FountainEncoder {
+    /// Splits and transforms `object_bytes` for the shard set in `template`.
+    pub fn new(object_bytes: &[u8], template: &ShardHeaderV1) -> Result<Self, FecError> {
+        if template.erasure_mode != ShardErasureMode::Fountain {
+            return Err(FecError::InvalidShardSet("template is not fountain mode"));
+        }
+        let k = template.k as usize;
+        let chunk_len = (template.bucket_size as usize)
+            .checked_sub(SHARD_HEADER_LEN)
+            .ok_or(FecError::InvalidShardSet("bucket smaller than header"))?;
+        if k == 0 || object_bytes.len() > k * chunk_len {
+            return Err(FecError::ObjectTooLarge);
+        }
+        Ok(Self {
+            template: ShardHeaderV1 {
+                index: 0,
+                ..template.clone()
+            },
+            source_blocks: hardened_forward_transform(
+                split_source_blocks(object_bytes, k, chunk_len),
+                template.object_root,
+            ),
+            chunk_len,
+            encoder: None,
+        })
+    }
+
+    /// Generates shards with indices `[start, start + count)`.
+    pub fn shards(&mut self, start: u16, count: u16) -> Result<Vec<ShardV1>, FecError> {
+        let k = self.template.k;
+        let end = start
+            .checked_add(count)
+            .filter(|end| *end <= self.template.index_limit())
+            .ok_or(FecError::InvalidShardSet("fountain index space exhausted"))?;
+
+        let mut packets: Vec<Vec<u8>> = (start..end.min(k))
+            .map(|idx| self.source_blocks[idx as usize].clone())
+            .collect();
+        if end > k {
+            let encoder = match &mut self.encoder {
+                Some(encoder) => encoder,
+                slot => {
+                    let config = fountain_config(k as usize, self.chunk_len)?;
+                    slot.insert(SourceBlockEncoder::new2(
+                        0,
+                        &config,
+                        &self.source_blocks.concat(),
+                    ))
+                }
+            };
+            let first_repair = start.max(k) - k;
+            packets.extend(
+                encoder
+                    .repair_packets(u32::from(first_repair), u32::from(end - start.max(k)))
+                    .into_iter()
+                    .map(|packet| packet.split().1),
+            );
+        }
 
-    let mut out = Vec::with_capacity(k * chunk_len);
-    for block in source_blocks {
-        out.extend_from_slice(&block);
+        Ok(packets
+            .into_iter()
+            .zip(start..end)
+            .map(|(payload, index)| ShardV1 {
+                header: ShardHeaderV1 {
+                    index,
+                    ..self.template.clone()
+                },
+                payload,
+            })
+            .collect())
     }
-    Ok(out)
+}
+
+pub(crate) fn fountain_config(
+    k: usize,
+    chunk_len: usize,
+) -> Result<ObjectTransmissionInformation, FecError> {
+    let symbol_size =
+        u16::try_from(chunk_len).map_err(|_| FecError::InvalidShardSet("symbol too large"))?;
+    Ok(ObjectTransmissionInformation::new(
+        (k * chunk_len) as u64,
+        symbol_size,
+        1,
+        1,
+        1,
+    ))
+}
+
+fn reconstruct_fountain(
+    shards: &[ShardV1],
+    expected_root: ObjectRoot,
+) -> Result<Vec<u8>, FecError> {
+    let first = &shards[0].header;
+    let k = first.k as usize;
+    let chunk_len = shards[0].payload.len();
+    let mut packets = Vec::with_capacity(shards.len());
+    let mut seen = std::collections::HashSet::new();
+    for shard in shards {
+        if shard.header.object_root != expected_root
+            || shard.header.k != first.k
+            || shard.header.namespace != first.namespace
+            || shard.header.epoch != first.epoch
+            || shard.header.tag != first.tag
+            || shard.header.profile_id != first.profile_id
+            || shard.header.erasure_mode != ShardErasureMode::Fountain
+            || shard.header.bucket_size != first.bucket_size
+        {
+            return Err(FecError::InvalidShardSet("mixed shard set"));
+        }
+        if shard.payload.len() != chunk_len {
+            return Err(FecError::InvalidShardSet("payload lengths differ"));
+        }
+        if !seen.insert(shard.header.index) {
+            continue;
+        }
+        let index = u32::from(shard.header.index);
+        let esi = if index < first.k as u32 {
+            index
+        } else {
+            extended_source_block_symbols(k as u32) + (index - first.k as u32)
+        };
+        packets.push(EncodingPacket::new(
+            PayloadId::new(0, esi),
+            shard.payload.clone(),
+        ));
+    }
+    if packets.len() < k {
+        return Err(FecError::InvalidShardSet(
+            "not enough shards to reconstruct",
+        ));
+    }
+
+    let config = fountain_config(k, chunk_len)?;
+    let mut decoder = SourceBlockDecoder::new2(0, &config, config.transfer_length());
+    let decoded = decoder.decode(packets).ok_or(FecError::NeedMoreSymbols)?;
+    let source_blocks = decoded
+        .chunks(chunk_len)
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>();
+    Ok(hardened_inverse_transform(source_blocks, expected_root).concat())
 }
 
 /// Computes deterministic shard identifier from encoded shard bytes.
@@ -337,6 +598,12 @@ pub fn is_valid_bucket_size(payload_len: usize) -> bool {
     SHARD_BUCKET_SIZES.contains(&total)
 }
 
+fn split_source_blocks(object_bytes: &[u8], k: usize, chunk_len: usize) -> Vec<Vec<u8>> {
+    let mut padded = vec![0_u8; k * chunk_len];
+    padded[..object_bytes.len()].copy_from_slice(object_bytes);
+    padded.chunks(chunk_len).map(<[u8]>::to_vec).collect()
+}
+
 fn hardened_rotation(root: ObjectRoot, len: usize) -> usize {
     if len <= 1 {
         return 0;
@@ -356,35 +623,38 @@ fn xor_in_place(lhs: &mut [u8], rhs: &[u8]) {
 }
 
 fn hardened_forward_transform(mut source_blocks: Vec<Vec<u8>>, root: ObjectRoot) -> Vec<Vec<u8>> {
-    let k = source_blocks.len();
+    hardened_forward_in_place(&mut source_blocks, root);
+    source_blocks
+}
+
+/// Applies the hardened pre-transform to equally sized blocks in place.
+pub(crate) fn hardened_forward_in_place(blocks: &mut [Vec<u8>], root: ObjectRoot) {
+    let k = blocks.len();
     if k <= 1 {
-        return source_blocks;
+        return;
     }
     let rot = hardened_rotation(root, k);
-    source_blocks.rotate_left(rot);
+    blocks.rotate_left(rot);
 
-    let mut mixed = Vec::with_capacity(k);
-    for i in 0..k {
-        if i == 0 {
-            mixed.push(source_blocks[0].clone());
-            continue;
-        }
-        let mut out = source_blocks[i].clone();
-        xor_in_place(&mut out, &source_blocks[i - 1]);
-        mixed.push(out);
+    // Walk backwards so each block is mixed with its untouched predecessor.
+    for i in (1..k).rev() {
+        let (head, tail) = blocks.split_at_mut(i);
+        xor_in_place(&mut tail[0], &head[i - 1]);
     }
-    mixed
 }
 
-fn hardened_inverse_transform(mut transformed: Vec<Vec<u8>>, root: ObjectRoot) -> Vec<Vec<u8>> {
+pub(crate) fn hardened_inverse_transform(
+    mut transformed: Vec<Vec<u8>>,
+    root: ObjectRoot,
+) -> Vec<Vec<u8>> {
     let k = transformed.len();
     if k <= 1 {
         return transformed;
     }
 
     for i in 1..k {
-        let prev = transformed[i - 1].clone();
-        xor_in_place(&mut transformed[i], &prev);
+        let (head, tail) = transformed.split_at_mut(i);
+        xor_in_place(&mut tail[0], &head[i - 1]);
     }
 
     let rot = hardened_rotation(root, k);
@@ -396,10 +666,11 @@ fn hardened_inverse_transform(mut transformed: Vec<Vec<u8>>, root: ObjectRoot) -
 mod tests {
     use super::{
         choose_profile_and_bucket, choose_profile_and_bucket_with_jitter, derive_object_root,
-        is_valid_bucket_size, object_to_shards, object_to_shards_with_mode, reconstruct_object,
-        reconstruct_object_padded, reconstruct_object_with_mode, shard_id, FecError,
+        fountain_shards, is_valid_bucket_size, object_to_shards, object_to_shards_with_mode,
+        object_to_shards_with_profile, reconstruct_object, reconstruct_object_padded,
+        reconstruct_object_with_mode, shard_id, FecError, FountainEncoder,
     };
-    use crate::profile::ErasureCodingMode;
+    use crate::profile::{ErasureCodingMode, PROFILE_MICRO_RESILIENT};
     use veil_core::types::{Epoch, Namespace};
 
     #[test]
@@ -438,6 +709,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn explicit_profile_shards_reconstruct_from_any_k() {
+        let object = b"resilient profile survives heavy loss".to_vec();
+        let root = derive_object_root(&object);
+        let shards = object_to_shards_with_profile(
+            &object,
+            Namespace(1),
+            Epoch(2),
+            [0x22; 32],
+            root,
+            ErasureCodingMode::HardenedNonSystematic,
+            0,
+            &PROFILE_MICRO_RESILIENT,
+        )
+        .expect("object should shard");
+
+        assert_eq!(shards.len(), PROFILE_MICRO_RESILIENT.n as usize);
+        assert_eq!(shards[0].header.profile_id, PROFILE_MICRO_RESILIENT.id);
+        let survivors = vec![shards[3].clone(), shards[5].clone()];
+        let recovered = reconstruct_object(&survivors, object.len(), root).expect("reconstruct");
+        assert_eq!(recovered, object);
+    }
+
     #[test]
     fn reconstructs_from_any_k_shards() {
         let object = b"this object is reconstructed from any k shards".to_vec();
@@ -550,4 +844,99 @@ mod tests {
             .count();
         assert!(differing > 0);
     }
+
+    #[test]
+    fn all_or_nothing_mode_hides_every_plain_chunk() {
+        let object = b"0123456789abcdefghijklmnopqrstuvwxyz".repeat(512);
+        let root = derive_object_root(&object);
+        let shards = object_to_shards_with_mode(
+            &object,
+            Namespace(14),
+            Epoch(7),
+            [0xBE; 32],
+            root,
+            ErasureCodingMode::AllOrNothing,
+        )
+        .expect("aont should shard");
+        assert_eq!(
+            shards[0].header.erasure_mode,
+            veil_codec::shard::ShardErasureMode::AllOrNothing
+        );
+
+        let chunk_len = shards[0].payload.len();
+        for shard in &shards {
+            for plain in object.chunks(chunk_len) {
+                assert_ne!(&shard.payload[..plain.len()], plain);
+            }
+        }
+
+        let k = shards[0].header.k as usize;
+        let selected: Vec<_> = shards.iter().rev().take(k).cloned().collect();
+        let recovered = reconstruct_object(&selected, object.len(), root).expect("reconstruct");
+        assert_eq!(recovered, object);
+    }
+
+    #[test]
+    fn fountain_mode_reconstructs_from_fresh_repair_symbols_beyond_n() {
+        let object = b"rateless fountain object ".repeat(700);
+        let root = derive_object_root(&object);
+        let initial = object_to_shards_with_mode(
+            &object,
+            Namespace(15),
+            Epoch(8),
+            [0xF0; 32],
+            root,
+            ErasureCodingMode::Fountain,
+        )
+        .expect("object should shard");
+        let header = initial[0].header.clone();
+        assert_eq!(initial.len(), header.n as usize);
+        assert_ne!(initial[0].payload, object[..initial[0].payload.len()]);
+
+        // Pretend every initially emitted shard was lost.
+        let extra = header.k + 2;
+        let fresh = fountain_shards(&object, &header, header.n, extra).expect("repair");
+        assert!(fresh.iter().all(|s| s.header.index >= header.n));
+        assert!(fresh.iter().all(|s| s.validate().is_ok()));
+        let recovered =
+            reconstruct_object_with_mode(&fresh, object.len(), root, ErasureCodingMode::Fountain)
+                .expect("reconstruction should work");
+        assert_eq!(recovered, object);
+
+        let again = fountain_shards(&object, &header, 0, header.n).expect("regenerate");
+        assert_eq!(again, initial);
+
+        let mut encoder = FountainEncoder::new(&object, &header).expect("encoder");
+        assert_eq!(encoder.shards(header.n, extra).expect("repair"), fresh);
+        let next = encoder.shards(header.n + extra, 2).expect("reused encoder");
+        assert_eq!(
+            next,
+            fountain_shards(&object, &header, header.n + extra, 2).expect("repair")
+        );
+        assert!(encoder.shards(header.index_limit() - 1, 2).is_err());
+    }
+
+    #[test]
+    fn fountain_mode_rejects_too_few_symbols() {
+        let object = b"short fountain".to_vec();
+        let root = derive_object_root(&object);
+        let shards = object_to_shards_with_mode(
+            &object,
+            Namespace(15),
+            Epoch(8),
+            [0xF1; 32],
+            root,
+            ErasureCodingMode::Fountain,
+        )
+        .expect("object should shard");
+        let k = shards[0].header.k as usize;
+        let err = reconstruct_object_with_mode(
+            &shards[1..k],
+            object.len(),
+            root,
+            ErasureCodingMode::Fountain,
+        )
+        .expect_err("too few symbols");
+        assert!(err.to_string().contains("not enough shards"));
+    }
 }
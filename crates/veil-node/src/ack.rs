@@ -7,9 +7,11 @@ use veil_core::ObjectRoot;
 use veil_core::{Epoch, Namespace, Tag};
 use veil_crypto::aead::{build_veil_aad, AeadCipher, AeadError};
 use veil_fec::profile::ErasureCodingMode;
-use veil_fec::sharder::{derive_object_root, object_to_shards_with_mode_and_padding, FecError};
+use veil_fec::sharder::{
+    derive_object_root, object_to_shards_with_mode_and_padding, FecError, FountainEncoder,
+};
 
-use crate::state::{NodeState, PendingAck};
+use crate::state::{FountainRetrySource, NodeState, PendingAck};
 
 const ACK_PAYLOAD_MAGIC: &[u8] = b"VEIL_ACK_V1";
 
@@ -28,7 +30,8 @@ pub enum AckBuildError {
 pub struct AckRetryPolicy {
     /// Steps to wait before the first retry batch is sent.
     pub initial_timeout_steps: u64,
-    /// Number of unsent shard payloads to include in each retry.
+    /// Number of unsent (or, in fountain mode, freshly generated) shard
+    /// payloads to include in each retry.
     pub retry_batch_size: usize,
     /// Step delay between subsequent retry attempts.
     pub backoff_step: u64,
@@ -53,10 +56,28 @@ pub fn register_pending_ack(
             max_retries: retry_policy.max_retries,
             retry_batch_size: retry_policy.retry_batch_size,
             backoff_step: retry_policy.backoff_step,
+            fountain: None,
         },
     );
 }
 
+/// Registers pending ACK state for a fountain-mode object.
+///
+/// Retries generate `retry_batch_size` fresh repair shards per attempt,
+/// starting at `next_index`, until the ACK arrives or `max_retries` is hit.
+pub fn register_pending_fountain_ack(
+    node: &mut NodeState,
+    object_root: ObjectRoot,
+    source: FountainRetrySource,
+    now_step: u64,
+    retry_policy: AckRetryPolicy,
+) {
+    register_pending_ack(node, object_root, Vec::new(), now_step, retry_policy);
+    if let Some(pending) = node.pending_acks.get_mut(&object_root) {
+        pending.fountain = Some(source);
+    }
+}
+
 /// Marks an ACK as received for `object_root`, clearing pending retry state.
 pub fn ack_received(node: &mut NodeState, object_root: ObjectRoot) -> bool {
     node.pending_acks.remove(&object_root).is_some()
@@ -183,6 +204,8 @@ pub fn build_ack_shard_bytes_with_mode_and_padding(
 /// Returns the next due ACK-timeout retry batch, if any.
 ///
 /// The returned batch contains shard bytes ready to send over a fallback lane.
+/// Fountain-mode entries never run dry: each batch is newly generated repair
+/// symbols with indices no peer has seen yet.
 pub fn next_ack_escalation_batch(
     node: &mut NodeState,
     now_step: u64,
@@ -193,6 +216,21 @@ pub fn next_ack_escalation_batch(
         .find_map(|(root, pending)| (pending.next_retry_step <= now_step).then_some(*root))?;
 
     let pending = node.pending_acks.get_mut(&due_root)?;
+    if let Some(fountain) = pending.fountain.as_mut() {
+        let batch = (pending.retries < pending.max_retries)
+            .then(|| fresh_fountain_batch(fountain, pending.retry_batch_size))
+            .flatten();
+        let Some(batch) = batch else {
+            node.pending_acks.remove(&due_root);
+            return None;
+        };
+        pending.retries += 1;
+        pending.next_retry_step = now_step + pending.backoff_step;
+        if pending.retries >= pending.max_retries {
+            node.pending_acks.remove(&due_root);
+        }
+        return Some((due_root, batch));
+    }
     if pending.unsent_shards.is_empty() || pending.retries >= pending.max_retries {
         node.pending_acks.remove(&due_root);
         return None;
@@ -210,15 +248,40 @@ pub fn next_ack_escalation_batch(
     Some((due_root, batch))
 }
 
+/// Generates the next `count` never-sent fountain shards as encoded bytes.
+fn fresh_fountain_batch(source: &mut FountainRetrySource, count: usize) -> Option<Vec<Vec<u8>>> {
+    let remaining = source
+        .header
+        .index_limit()
+        .saturating_sub(source.next_index);
+    let count = u16::try_from(count).unwrap_or(u16::MAX).min(remaining);
+    if count == 0 {
+        return None;
+    }
+    let encoder = match &mut source.encoder {
+        Some(encoder) => encoder,
+        slot => slot.insert(FountainEncoder::new(&source.encoded_object, &source.header).ok()?),
+    };
+    let shards = encoder.shards(source.next_index, count).ok()?;
+    source.next_index += count;
+    shards.iter().map(|s| encode_shard_cbor(s).ok()).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         ack_received, build_ack_shard_bytes, decode_ack_payload, encode_ack_payload,
-        next_ack_escalation_batch, register_pending_ack, AckRetryPolicy,
+        next_ack_escalation_batch, register_pending_ack, register_pending_fountain_ack,
+        AckRetryPolicy,
     };
-    use crate::state::NodeState;
+    use crate::state::{FountainRetrySource, NodeState};
+    use veil_codec::shard::decode_shard_cbor;
     use veil_core::{Epoch, Namespace};
     use veil_crypto::aead::XChaCha20Poly1305Cipher;
+    use veil_fec::profile::ErasureCodingMode;
+    use veil_fec::sharder::{
+        derive_object_root, object_to_shards_with_mode, reconstruct_object_with_mode,
+    };
 
     #[test]
     fn no_escalation_before_timeout() {
@@ -315,4 +378,53 @@ mod tests {
         .expect("ack shards should be built");
         assert!(!shards.is_empty());
     }
+
+    #[test]
+    fn fountain_escalation_generates_fresh_symbols_past_n() {
+        let object = b"fountain ack escalation".repeat(40);
+        let root = derive_object_root(&object);
+        let shards = object_to_shards_with_mode(
+            &object,
+            Namespace(4),
+            Epoch(5),
+            [0x44; 32],
+            root,
+            ErasureCodingMode::Fountain,
+        )
+        .expect("object should shard");
+        let header = shards[0].header.clone();
+        let mut node = NodeState::default();
+        register_pending_fountain_ack(
+            &mut node,
+            root,
+            FountainRetrySource::new(object.clone(), header.clone(), header.n),
+            0,
+            AckRetryPolicy {
+                initial_timeout_steps: 1,
+                retry_batch_size: header.k as usize,
+                backoff_step: 1,
+                max_retries: 3,
+            },
+        );
+
+        let mut received = Vec::new();
+        for step in 1..=3 {
+            let (_, batch) = next_ack_escalation_batch(&mut node, step).expect("fresh batch");
+            assert_eq!(batch.len(), header.k as usize);
+            received.extend(batch.iter().map(|b| decode_shard_cbor(b).expect("shard")));
+        }
+        assert!(!node.pending_acks.contains_key(&root));
+
+        let indices = received.iter().map(|s| s.header.index).collect::<Vec<_>>();
+        let expected = (header.n..header.n + 3 * header.k).collect::<Vec<_>>();
+        assert_eq!(indices, expected);
+        let recovered = reconstruct_object_with_mode(
+            &received[..header.k as usize + 1],
+            object.len(),
+            root,
+            ErasureCodingMode::Fountain,
+        )
+        .expect("fresh symbols should decode");
+        assert_eq!(recovered, object);
+    }
 }
@@ -0,0 +1,354 @@
+//! Feed history backfill for newly subscribed tags.
+//!
+//! A subscriber asks peers for cached objects of a tag within an epoch range.
+//! Peers answer by sending the matching cached shards back as ordinary shard
+//! packets, so the requester reconstructs and delivers them through the normal
+//! receive path. Shards that answer an open request are not re-forwarded.
+//!
+//! Requests may instead name specific content roots, which is how gaps found
+//! in publisher logs are repaired. A request can cover several tags at once so
+//! a subscriber catching up on many feeds spends one unit of each peer's
+//! serving budget rather than one per tag.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+use veil_codec::shard::decode_shard_cbor;
+use veil_core::{Epoch, ObjectRoot, Tag};
+use veil_transport::adapter::TransportAdapter;
+
+use crate::config::BackfillConfig;
+use crate::forwarding::peer_key;
+use crate::state::NodeState;
+
+const BACKFILL_MAGIC: &[u8] = b"VEIL_BACKFILL_V1";
+const BACKFILL_VERSION: u8 = 1;
+/// Upper bound on tags named by a single request.
+pub const MAX_BACKFILL_TAGS: usize = 64;
+
+/// Request for cached objects of `tag` (and `extra_tags`) published in
+/// `epoch_from..=epoch_to`, or for the objects carrying `roots` when that
+/// list is non-empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackfillRequest {
+    pub version: u8,
+    pub tag: Tag,
+    /// Further tags answered from the same response budget.
+    #[serde(default)]
+    pub extra_tags: Vec<Tag>,
+    pub epoch_from: Epoch,
+    pub epoch_to: Epoch,
+    /// Requester-side cap; responders also apply their own limit.
+    pub max_shards: u32,
+    /// Content or wire roots to fetch; overrides the epoch range when set.
+    #[serde(default)]
+    pub roots: Vec<ObjectRoot>,
+}
+
+impl BackfillRequest {
+    pub fn new(tag: Tag, epoch_from: Epoch, epoch_to: Epoch, max_shards: u32) -> Self {
+        Self {
+            version: BACKFILL_VERSION,
+            tag,
+            extra_tags: Vec::new(),
+            epoch_from,
+            epoch_to,
+            max_shards,
+            roots: Vec::new(),
+        }
+    }
+
+    /// Request covering every tag in `tags`, or `None` when it is empty.
+    ///
+    /// At most [`MAX_BACKFILL_TAGS`] tags fit in one request; callers batch
+    /// longer lists with `chunks(MAX_BACKFILL_TAGS)`.
+    pub fn for_tags(
+        tags: &[Tag],
+        epoch_from: Epoch,
+        epoch_to: Epoch,
+        max_shards: u32,
+    ) -> Option<Self> {
+        let (&tag, rest) = tags.split_first()?;
+        Some(Self {
+            extra_tags: rest.iter().take(MAX_BACKFILL_TAGS - 1).copied().collect(),
+            ..Self::new(tag, epoch_from, epoch_to, max_shards)
+        })
+    }
+
+    /// Request for the objects of `tag` carrying the given content roots.
+    pub fn for_roots(tag: Tag, roots: Vec<ObjectRoot>, max_shards: u32) -> Self {
+        Self {
+            roots,
+            ..Self::new(tag, Epoch(0), Epoch(0), max_shards)
+        }
+    }
+
+    /// Every tag this request covers.
+    pub fn tags(&self) -> impl Iterator<Item = &Tag> {
+        std::iter::once(&self.tag).chain(&self.extra_tags)
+    }
+}
+
+/// Encodes a backfill request as a control-plane packet.
+pub fn encode_backfill_request(request: &BackfillRequest) -> Result<Vec<u8>, String> {
+    let mut out = BACKFILL_MAGIC.to_vec();
+    ciborium::ser::into_writer(request, &mut out).map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// Decodes a backfill request packet, returning `None` for other payloads.
+pub fn decode_backfill_request(bytes: &[u8]) -> Option<BackfillRequest> {
+    let body = bytes.strip_prefix(BACKFILL_MAGIC)?;
+    let request: BackfillRequest = ciborium::de::from_reader(body).ok()?;
+    (request.version == BACKFILL_VERSION && request.extra_tags.len() < MAX_BACKFILL_TAGS)
+        .then_some(request)
+}
+
+#[derive(Debug, Clone)]
+struct PendingBackfill {
+    peers: HashSet<u64>,
+    expires_step: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ServeWindow {
+    start_step: u64,
+    served: u32,
+}
+
+/// Outstanding backfill requests and per-peer serving budgets.
+#[derive(Debug, Default)]
+pub struct BackfillState {
+    pending: HashMap<Tag, PendingBackfill>,
+    served: HashMap<u64, ServeWindow>,
+}
+
+impl BackfillState {
+    /// Number of tags with an open outgoing request.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn record_request<P: Hash>(&mut self, tag: Tag, peer: &P, expires_step: u64) {
+        let entry = self.pending.entry(tag).or_insert_with(|| PendingBackfill {
+            peers: HashSet::new(),
+            expires_step,
+        });
+        entry.expires_step = entry.expires_step.max(expires_step);
+        entry.peers.insert(peer_key(peer));
+    }
+
+    /// Whether a shard for `tag` from `peer` answers an open request.
+    pub fn is_response<P: Hash>(&self, peer: &P, tag: &Tag, now_step: u64) -> bool {
+        self.pending
+            .get(tag)
+            .is_some_and(|p| p.expires_step > now_step && p.peers.contains(&peer_key(peer)))
+    }
+
+    /// Consumes one unit of `peer`'s serving budget, if any remains.
+    pub fn allow_serve<P: Hash>(&mut self, peer: &P, now_step: u64, cfg: BackfillConfig) -> bool {
+        let window = self.served.entry(peer_key(peer)).or_insert(ServeWindow {
+            start_step: now_step,
+            served: 0,
+        });
+        if now_step.saturating_sub(window.start_step) >= cfg.rate_window_steps {
+            *window = ServeWindow {
+                start_step: now_step,
+                served: 0,
+            };
+        }
+        if window.served >= cfg.max_requests_per_window {
+            return false;
+        }
+        window.served += 1;
+        true
+    }
+
+    /// Drops expired requests and stale rate windows.
+    pub fn prune(&mut self, now_step: u64, cfg: BackfillConfig) {
+        self.pending.retain(|_, p| p.expires_step > now_step);
+        self.served
+            .retain(|_, w| now_step.saturating_sub(w.start_step) < cfg.rate_window_steps);
+    }
+}
+
+/// Sends a backfill request to `peers` and records each of its tags as open.
+///
+/// Returns the number of peers the request was sent to.
+pub fn request_backfill<A: TransportAdapter>(
+    node: &mut NodeState,
+    adapter: &mut A,
+    peers: &[A::Peer],
+    request: &BackfillRequest,
+    now_step: u64,
+    cfg: BackfillConfig,
+) -> usize {
+    let Ok(packet) = encode_backfill_request(request) else {
+        return 0;
+    };
+    node.backfill.prune(now_step, cfg);
+    let expires_step = now_step.saturating_add(cfg.pending_ttl_steps);
+    let mut sent = 0;
+    for peer in peers {
+        if adapter.send(peer, &packet).is_ok() {
+            for tag in request.tags() {
+                node.backfill.record_request(*tag, peer, expires_step);
+            }
+            sent += 1;
+        }
+    }
+    sent
+}
+
+/// Collects cached shards answering `request`, newest epoch first.
+///
+/// Objects are returned whole: an object whose shards would exceed the
+/// response budget is left out rather than split.
+pub fn collect_backfill_shards(
+    node: &NodeState,
+    request: &BackfillRequest,
+    now_step: u64,
+    cfg: BackfillConfig,
+) -> Vec<Vec<u8>> {
+    let epoch_to = request
+        .epoch_to
+        .0
+        .min(request.epoch_from.0.saturating_add(cfg.max_epoch_span));
+    let budget = cfg.max_shards_per_response.min(request.max_shards as usize);
+    let wanted = request
+        .roots
+        .iter()
+        .map(|root| node.content_index.get(root).copied().unwrap_or(*root))
+        .collect::<HashSet<_>>();
+    let tags = request.tags().collect::<HashSet<_>>();
+
+    let mut objects: Vec<(Epoch, ObjectRoot, Vec<&[u8]>)> = Vec::new();
+    for (root, shard_ids) in &node.shard_index {
+        if !wanted.is_empty() && !wanted.contains(root) {
+            continue;
+        }
+        let live = shard_ids
+            .iter()
+            .filter_map(|sid| node.cache.get(sid))
+            .filter(|cached| cached.expiry_step > now_step)
+            .map(|cached| cached.bytes.as_slice())
+            .collect::<Vec<_>>();
+        let Some(header) = live
+            .first()
+            .and_then(|bytes| decode_shard_cbor(bytes).ok())
+            .map(|shard| shard.header)
+        else {
+            continue;
+        };
+        let in_range = header.epoch.0 >= request.epoch_from.0 && header.epoch.0 <= epoch_to;
+        if tags.contains(&header.tag) && (in_range || !wanted.is_empty()) {
+            objects.push((header.epoch, *root, live));
+        }
+    }
+    objects.sort_by(|a, b| b.0 .0.cmp(&a.0 .0).then(a.1.cmp(&b.1)));
+
+    let mut out = Vec::new();
+    for (_, _, shards) in objects {
+        if out.len() + shards.len() > budget {
+            break;
+        }
+        let mut shards = shards;
+        shards.sort_unstable();
+        out.extend(shards.into_iter().map(<[u8]>::to_vec));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use veil_codec::shard::encode_shard_cbor;
+    use veil_core::hash::blake3_32;
+    use veil_core::{Epoch, Namespace, Tag};
+    use veil_fec::sharder::object_to_shards;
+
+    use super::{
+        collect_backfill_shards, decode_backfill_request, encode_backfill_request, BackfillRequest,
+        BackfillState,
+    };
+    use crate::cache::cache_put;
+    use crate::config::BackfillConfig;
+    use crate::state::NodeState;
+
+    fn cache_object(node: &mut NodeState, tag: Tag, epoch: u32, payload: &[u8]) -> usize {
+        let shards = object_to_shards(payload, Namespace(1), Epoch(epoch), tag, blake3_32(payload))
+            .expect("shard");
+        for shard in &shards {
+            let bytes = encode_shard_cbor(shard).expect("encode");
+            cache_put(node, blake3_32(&bytes), bytes, 0, 100);
+        }
+        shards.len()
+    }
+
+    #[test]
+    fn backfill_request_packet_roundtrips() {
+        let request = BackfillRequest::new([3u8; 32], Epoch(10), Epoch(12), 64);
+        let packet = encode_backfill_request(&request).expect("encode");
+        assert_eq!(decode_backfill_request(&packet), Some(request));
+        let by_root = BackfillRequest::for_roots([3u8; 32], vec![[4u8; 32]], 8);
+        let packet = encode_backfill_request(&by_root).expect("encode");
+        assert_eq!(decode_backfill_request(&packet), Some(by_root));
+        assert_eq!(decode_backfill_request(b"not a request"), None);
+        let batched = BackfillRequest::for_tags(&[[3u8; 32], [5u8; 32]], Epoch(10), Epoch(12), 64)
+            .expect("non-empty");
+        let packet = encode_backfill_request(&batched).expect("encode");
+        assert_eq!(decode_backfill_request(&packet), Some(batched));
+        assert!(BackfillRequest::for_tags(&[], Epoch(10), Epoch(12), 64).is_none());
+    }
+
+    #[test]
+    fn collect_filters_by_tag_and_epoch_and_keeps_objects_whole() {
+        let tag = [7u8; 32];
+        let mut node = NodeState::default();
+        let per_object = cache_object(&mut node, tag, 10, b"old post");
+        cache_object(&mut node, tag, 12, b"new post");
+        cache_object(&mut node, tag, 20, b"out of range");
+        cache_object(&mut node, [8u8; 32], 11, b"other feed");
+
+        let request = BackfillRequest::new(tag, Epoch(9), Epoch(15), 1_000);
+        let cfg = BackfillConfig::default();
+        let shards = collect_backfill_shards(&node, &request, 1, cfg);
+        assert_eq!(shards.len(), 2 * per_object);
+        let both = BackfillRequest::for_tags(&[tag, [8u8; 32]], Epoch(9), Epoch(15), 1_000)
+            .expect("non-empty");
+        assert_eq!(
+            collect_backfill_shards(&node, &both, 1, cfg).len(),
+            3 * per_object
+        );
+        let first = veil_codec::shard::decode_shard_cbor(&shards[0]).expect("decode");
+        assert_eq!(first.header.epoch, Epoch(12));
+
+        let capped = BackfillRequest::new(tag, Epoch(9), Epoch(15), per_object as u32 + 1);
+        assert_eq!(
+            collect_backfill_shards(&node, &capped, 1, cfg).len(),
+            per_object
+        );
+        assert!(collect_backfill_shards(&node, &request, 100, cfg).is_empty());
+
+        let by_root = BackfillRequest::for_roots(tag, vec![blake3_32(b"out of range")], 1_000);
+        let shards = collect_backfill_shards(&node, &by_root, 1, cfg);
+        assert_eq!(shards.len(), per_object);
+        let shard = veil_codec::shard::decode_shard_cbor(&shards[0]).expect("decode");
+        assert_eq!(shard.header.epoch, Epoch(20));
+    }
+
+    #[test]
+    fn serving_is_rate_limited_per_peer() {
+        let cfg = BackfillConfig {
+            max_requests_per_window: 2,
+            rate_window_steps: 10,
+            ..BackfillConfig::default()
+        };
+        let mut state = BackfillState::default();
+        assert!(state.allow_serve(&"peer-a", 0, cfg));
+        assert!(state.allow_serve(&"peer-a", 1, cfg));
+        assert!(!state.allow_serve(&"peer-a", 2, cfg));
+        assert!(state.allow_serve(&"peer-b", 2, cfg));
+        assert!(state.allow_serve(&"peer-a", 10, cfg));
+    }
+}
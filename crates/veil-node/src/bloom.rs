@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
 use veil_core::hash::blake3_32;
-use veil_core::ShardId;
+use veil_core::{ShardId, Tag};
 
 const BLOOM_EXCHANGE_V1: u16 = 1;
 const BLOOM_PACKET_MAGIC: &[u8] = b"VEIL_BLOOM_V1";
+const INTEREST_ADVERT_V1: u16 = 1;
+const INTEREST_PACKET_MAGIC: &[u8] = b"VEIL_INTEREST_V1";
+/// Most hash rounds a peer-supplied filter may ask for.
+const MAX_BLOOM_HASH_COUNT: u8 = 32;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BloomFilter {
@@ -34,6 +38,14 @@ impl BloomFilter {
         Self::new(m.max(256), k, salt)
     }
 
+    /// Whether a decoded filter is safe to query: a non-zero bit length
+    /// backed by exactly enough bytes, and a bounded hash count.
+    pub fn is_well_formed(&self) -> bool {
+        self.bit_len > 0
+            && self.bits.len() == self.bit_len.div_ceil(8)
+            && (1..=MAX_BLOOM_HASH_COUNT).contains(&self.hash_count)
+    }
+
     pub fn insert(&mut self, item: &ShardId) {
         let indices: Vec<usize> = self.bit_indices(item).collect();
         for idx in indices {
@@ -87,7 +99,12 @@ pub fn encode_bloom_exchange_cbor(epoch: u32, filter: BloomFilter) -> Result<Vec
 }
 
 pub fn decode_bloom_exchange_cbor(bytes: &[u8]) -> Result<BloomExchangeMessage, String> {
-    ciborium::de::from_reader(bytes).map_err(|e| e.to_string())
+    let message: BloomExchangeMessage =
+        ciborium::de::from_reader(bytes).map_err(|e| e.to_string())?;
+    if !message.filter.is_well_formed() {
+        return Err("malformed bloom filter".to_string());
+    }
+    Ok(message)
 }
 
 pub fn encode_bloom_exchange_packet(epoch: u32, filter: BloomFilter) -> Result<Vec<u8>, String> {
@@ -108,6 +125,52 @@ pub fn decode_bloom_exchange_packet(bytes: &[u8]) -> Option<BloomExchangeMessage
     decode_bloom_exchange_cbor(&bytes[BLOOM_PACKET_MAGIC.len()..]).ok()
 }
 
+/// Control message advertising a Bloom summary of the sender's subscribed tags.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InterestAdvertisement {
+    pub version: u16,
+    /// Sender-local step the advertisement was built at.
+    pub issued_step: u64,
+    pub filter: BloomFilter,
+}
+
+/// Builds a subscription-interest filter over `tags`.
+pub fn interest_filter_for_tags<'a>(
+    tags: impl IntoIterator<Item = &'a Tag>,
+    false_positive_rate: f64,
+    salt: [u8; 16],
+) -> BloomFilter {
+    let tags = tags.into_iter().collect::<Vec<_>>();
+    let mut bf = BloomFilter::recommended(tags.len(), false_positive_rate, salt);
+    for tag in tags {
+        bf.insert(tag);
+    }
+    bf
+}
+
+pub fn encode_interest_packet(issued_step: u64, filter: BloomFilter) -> Result<Vec<u8>, String> {
+    let mut out = INTEREST_PACKET_MAGIC.to_vec();
+    ciborium::ser::into_writer(
+        &InterestAdvertisement {
+            version: INTEREST_ADVERT_V1,
+            issued_step,
+            filter,
+        },
+        &mut out,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+pub fn decode_interest_packet(bytes: &[u8]) -> Option<InterestAdvertisement> {
+    let payload = bytes.strip_prefix(INTEREST_PACKET_MAGIC)?;
+    if payload.is_empty() {
+        return None;
+    }
+    let advert: InterestAdvertisement = ciborium::de::from_reader(payload).ok()?;
+    (advert.version == INTEREST_ADVERT_V1 && advert.filter.is_well_formed()).then_some(advert)
+}
+
 pub fn missing_against_filter(
     local_shards: impl IntoIterator<Item = ShardId>,
     remote_filter: &BloomFilter,
@@ -121,8 +184,9 @@ pub fn missing_against_filter(
 #[cfg(test)]
 mod tests {
     use super::{
-        decode_bloom_exchange_cbor, decode_bloom_exchange_packet, encode_bloom_exchange_cbor,
-        encode_bloom_exchange_packet, missing_against_filter, BloomFilter,
+        decode_bloom_exchange_cbor, decode_bloom_exchange_packet, decode_interest_packet,
+        encode_bloom_exchange_cbor, encode_bloom_exchange_packet, encode_interest_packet,
+        interest_filter_for_tags, missing_against_filter, BloomFilter,
     };
 
     #[test]
@@ -163,4 +227,52 @@ mod tests {
         let out = missing_against_filter([known, missing], &bf);
         assert_eq!(out, vec![missing]);
     }
+
+    #[test]
+    fn interest_packet_round_trip_and_is_distinct_from_bloom_exchange() {
+        let subscribed = [0x51_u8; 32];
+        let bf = interest_filter_for_tags([&subscribed], 0.01, [0x07; 16]);
+        let packet = encode_interest_packet(99, bf).expect("encode interest");
+        let decoded = decode_interest_packet(&packet).expect("decode interest");
+        assert_eq!(decoded.issued_step, 99);
+        assert!(decoded.filter.might_contain(&subscribed));
+        assert!(!decoded.filter.might_contain(&[0x52_u8; 32]));
+        assert!(decode_bloom_exchange_packet(&packet).is_none());
+
+        let bloom = encode_bloom_exchange_packet(1, BloomFilter::new(64, 2, [0; 16]))
+            .expect("encode bloom");
+        assert!(decode_interest_packet(&bloom).is_none());
+    }
+
+    #[test]
+    fn malformed_advertisements_are_rejected() {
+        let well_formed = BloomFilter::new(64, 2, [0; 16]);
+        let malformed = [
+            BloomFilter {
+                bit_len: 0,
+                bits: Vec::new(),
+                ..well_formed.clone()
+            },
+            BloomFilter {
+                bit_len: 4096,
+                ..well_formed.clone()
+            },
+            BloomFilter {
+                hash_count: 0,
+                ..well_formed.clone()
+            },
+            BloomFilter {
+                hash_count: u8::MAX,
+                ..well_formed.clone()
+            },
+        ];
+        for filter in malformed {
+            let packet = encode_interest_packet(1, filter.clone()).expect("encode interest");
+            assert!(decode_interest_packet(&packet).is_none());
+            let packet = encode_bloom_exchange_packet(1, filter).expect("encode bloom");
+            assert!(decode_bloom_exchange_packet(&packet).is_none());
+        }
+        let packet = encode_interest_packet(1, well_formed).expect("encode interest");
+        assert!(decode_interest_packet(&packet).is_some());
+    }
 }
@@ -190,6 +190,7 @@ fn remove_shard(node: &mut NodeState, shard_id: ShardId) {
             shards.remove(&shard_id);
             if shards.is_empty() {
                 node.shard_index.remove(&root);
+                node.tombstones.forget(&root);
             }
         }
     }
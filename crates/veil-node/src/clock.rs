@@ -0,0 +1,154 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default wall-clock length of one runtime step.
+///
+/// Clock-driven runtimes derive `now_step` as `unix_time / step_duration`, so
+/// step values stored in persisted state stay meaningful across restarts and
+/// independent of how often the host app calls `tick`.
+pub const DEFAULT_STEP_DURATION: Duration = Duration::from_millis(50);
+
+/// Wall-clock source used by node runtimes.
+pub trait Clock {
+    /// Elapsed time since the UNIX epoch.
+    fn now(&self) -> Duration;
+
+    /// Whole seconds since the UNIX epoch.
+    fn now_seconds(&self) -> u64 {
+        self.now().as_secs()
+    }
+}
+
+/// System wall clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Manually advanced clock for tests and simulations.
+///
+/// Clones share the same underlying time, so a test can keep one handle and
+/// move a clock owned by a runtime forward.
+#[derive(Debug, Default, Clone)]
+pub struct MockClock {
+    millis: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new(start: Duration) -> Self {
+        let clock = Self::default();
+        clock.set(start);
+        clock
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.millis.store(duration_millis(now), Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.millis.fetch_add(duration_millis(by), Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.millis.load(Ordering::SeqCst))
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Box<C> {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+fn duration_millis(d: Duration) -> u64 {
+    d.as_millis().min(u64::MAX as u128) as u64
+}
+
+fn step_millis(step_duration: Duration) -> u64 {
+    duration_millis(step_duration).max(1)
+}
+
+/// Current runtime step for `clock` at the given step resolution.
+pub fn clock_step(clock: &(impl Clock + ?Sized), step_duration: Duration) -> u64 {
+    duration_millis(clock.now()) / step_millis(step_duration)
+}
+
+/// Converts a duration into whole steps, rounding up so short non-zero
+/// durations never collapse to zero.
+pub fn duration_to_steps(duration: Duration, step_duration: Duration) -> u64 {
+    duration_millis(duration).div_ceil(step_millis(step_duration))
+}
+
+/// Converts a step count into wall-clock duration.
+pub fn steps_to_duration(steps: u64, step_duration: Duration) -> Duration {
+    Duration::from_millis(steps.saturating_mul(step_millis(step_duration)))
+}
+
+/// Rescales a step value recorded at `from` resolution to `to` resolution.
+pub fn rescale_step(step: u64, from: Duration, to: Duration) -> u64 {
+    let from_ms = u128::from(step_millis(from));
+    let to_ms = u128::from(step_millis(to));
+    ((u128::from(step) * from_ms) / to_ms).min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        clock_step, duration_to_steps, rescale_step, steps_to_duration, Clock, MockClock,
+        SystemClock,
+    };
+
+    #[test]
+    fn mock_clock_is_shared_between_clones() {
+        let clock = MockClock::new(Duration::from_secs(10));
+        let handle = clock.clone();
+        handle.advance(Duration::from_millis(1_500));
+        assert_eq!(clock.now(), Duration::from_millis(11_500));
+        assert_eq!(clock.now_seconds(), 11);
+    }
+
+    #[test]
+    fn clock_step_divides_wall_time_by_step_duration() {
+        let clock = MockClock::new(Duration::from_secs(1));
+        assert_eq!(clock_step(&clock, Duration::from_millis(50)), 20);
+        assert_eq!(clock_step(&clock, Duration::from_millis(250)), 4);
+        assert_eq!(clock_step(&clock, Duration::ZERO), 1_000);
+    }
+
+    #[test]
+    fn duration_step_conversions_round_up() {
+        let step = Duration::from_millis(50);
+        assert_eq!(duration_to_steps(Duration::from_secs(10), step), 200);
+        assert_eq!(duration_to_steps(Duration::from_millis(1), step), 1);
+        assert_eq!(duration_to_steps(Duration::ZERO, step), 0);
+        assert_eq!(steps_to_duration(200, step), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn rescale_step_preserves_wall_time() {
+        let step = rescale_step(2_000, Duration::from_millis(50), Duration::from_millis(100));
+        assert_eq!(step, 1_000);
+    }
+
+    #[test]
+    fn system_clock_is_past_2020() {
+        assert!(SystemClock.now_seconds() > 1_577_836_800);
+    }
+}
@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::time::Duration;
 
 use crate::ack::AckRetryPolicy;
+use crate::clock::{duration_to_steps, steps_to_duration, DEFAULT_STEP_DURATION};
 use crate::policy::{
     fanout_for_tier as fanout_for_tier_impl, LocalWotPolicy, TrustTier, WotConfig, WotPolicy,
 };
+use veil_codec::multisig::SignerSet;
 use veil_core::types::NAMESPACE_PUBLIC_FEED;
-use veil_fec::profile::ErasureCodingMode;
+use veil_fec::pooled::ShardingOptions;
+use veil_fec::profile::{ErasureCodingMode, ProfileRegistry};
 
 #[derive(Debug, Clone, Copy)]
 pub struct AdaptiveLaneScoringConfig {
@@ -24,6 +28,18 @@ pub struct AdaptiveLaneScoringConfig {
     pub min_fallback_fanout: usize,
 }
 
+/// Loss-adaptive FEC profile selection on publish.
+///
+/// Uses the lane-scoring send/ACK success EWMAs as a shard delivery estimate
+/// and picks a higher-`n` profile from [`NodeRuntimeConfig::fec_profiles`]
+/// when the default profile would not cover the observed loss.
+#[derive(Debug, Clone, Copy)]
+pub struct LossAdaptiveFecConfig {
+    pub enabled: bool,
+    /// Extra redundancy margin over the loss-implied minimum `n / k`.
+    pub headroom: f64,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct ProbabilisticForwardingConfig {
     pub enabled: bool,
@@ -42,6 +58,176 @@ pub struct BloomExchangeConfig {
     pub false_positive_rate: f64,
 }
 
+/// Subscription-interest advertisements and interest-aware forwarding.
+#[derive(Debug, Clone, Copy)]
+pub struct InterestForwardingConfig {
+    pub enabled: bool,
+    /// Periodic re-advertisement interval; changed subscriptions advertise immediately.
+    pub advertise_interval_steps: u64,
+    /// Target false positive rate for outgoing interest filters.
+    pub false_positive_rate: f64,
+    /// Steps after which a peer's advertisement is treated as unknown.
+    pub advert_ttl_steps: u64,
+    /// Upper bound on peers forwarded to because their filter matched.
+    pub max_matched_fanout: usize,
+}
+
+/// Feed history backfill served to and requested from peers.
+#[derive(Debug, Clone, Copy)]
+pub struct BackfillConfig {
+    /// Serve inbound backfill requests from the local shard cache. Off by
+    /// default: serving turns every request into a burst of cached shards,
+    /// so relays opt in.
+    pub enabled: bool,
+    /// Upper bound on shards returned for a single request.
+    pub max_shards_per_response: usize,
+    /// Widest epoch range served for a single request.
+    pub max_epoch_span: u32,
+    /// Requests served per peer within one rate window.
+    pub max_requests_per_window: u32,
+    /// Length of the per-peer rate window.
+    pub rate_window_steps: u64,
+    /// Steps an outgoing request stays open for responses.
+    pub pending_ttl_steps: u64,
+}
+
+/// Signed deletions (tombstones) and object expiry enforcement.
+#[derive(Debug, Clone, Copy)]
+pub struct TombstoneConfig {
+    /// Apply and forward inbound tombstones.
+    pub enabled: bool,
+    /// How long a deleted object is refused after its tombstone is applied.
+    pub retention_steps: u64,
+}
+
+/// Batched verification of signed objects reconstructed at ingest.
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureBatchConfig {
+    /// Defer object signature checks and verify them together.
+    pub enabled: bool,
+    /// Pending objects that trigger a flush.
+    pub max_batch: usize,
+    /// Longest an object waits for its batch before a flush.
+    pub max_wait_steps: u64,
+}
+
+/// Random delay distribution applied to shaped sends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DelayDistribution {
+    /// Release on the same step the send was requested.
+    None,
+    /// Uniform delay in `[min_steps, max_steps]`.
+    Uniform { min_steps: u64, max_steps: u64 },
+    /// Exponential (Poisson-process) delay with the given mean, capped at `max_steps`.
+    Exponential { mean_steps: f64, max_steps: u64 },
+}
+
+/// Per-lane traffic-shaping limits and cover emission rate.
+#[derive(Debug, Clone, Copy)]
+pub struct LaneShapingConfig {
+    /// Maximum bytes released per step on this lane (`None` disables the cap).
+    pub max_bytes_per_step: Option<usize>,
+    /// Mean number of cover shards emitted per step (Poisson rate).
+    pub cover_rate_per_step: f64,
+    /// Maximum queued sends before new sends are rejected.
+    pub max_queued_sends: usize,
+}
+
+/// Cover traffic and delayed-forwarding controls.
+#[derive(Debug, Clone)]
+pub struct TrafficShapingConfig {
+    pub enabled: bool,
+    /// Delay used for namespaces without an explicit entry.
+    pub default_delay: DelayDistribution,
+    /// Per-namespace delay distributions for forwarded shards.
+    pub namespace_delays: HashMap<u16, DelayDistribution>,
+    /// Namespaces cover shards are drawn from.
+    pub cover_namespaces: Vec<u16>,
+    pub fast_lane: LaneShapingConfig,
+    pub fallback_lane: LaneShapingConfig,
+}
+
+impl TrafficShapingConfig {
+    /// Resolves the delay distribution for a namespace.
+    pub fn delay_for_namespace(&self, namespace: u16) -> DelayDistribution {
+        self.namespace_delays
+            .get(&namespace)
+            .copied()
+            .unwrap_or(self.default_delay)
+    }
+}
+
+impl Default for LaneShapingConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes_per_step: None,
+            cover_rate_per_step: 0.0,
+            max_queued_sends: 4_096,
+        }
+    }
+}
+
+impl Default for TrafficShapingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_delay: DelayDistribution::Exponential {
+                mean_steps: 2.0,
+                max_steps: 16,
+            },
+            namespace_delays: HashMap::new(),
+            cover_namespaces: vec![NAMESPACE_PUBLIC_FEED.0],
+            fast_lane: LaneShapingConfig::default(),
+            fallback_lane: LaneShapingConfig::default(),
+        }
+    }
+}
+
+impl Default for InterestForwardingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            advertise_interval_steps: 1_200,
+            false_positive_rate: 0.01,
+            advert_ttl_steps: 3_600,
+            max_matched_fanout: 32,
+        }
+    }
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_shards_per_response: 512,
+            max_epoch_span: 30,
+            max_requests_per_window: 4,
+            rate_window_steps: 1_200,
+            pending_ttl_steps: 600,
+        }
+    }
+}
+
+impl Default for TombstoneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            // Seven days at the default 50 ms step.
+            retention_steps: 12_096_000,
+        }
+    }
+}
+
+impl Default for SignatureBatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_batch: 64,
+            max_wait_steps: 2,
+        }
+    }
+}
+
 impl Default for BloomExchangeConfig {
     fn default() -> Self {
         Self {
@@ -62,6 +248,15 @@ impl Default for ProbabilisticForwardingConfig {
     }
 }
 
+impl Default for LossAdaptiveFecConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            headroom: 0.10,
+        }
+    }
+}
+
 impl Default for AdaptiveLaneScoringConfig {
     fn default() -> Self {
         Self {
@@ -79,13 +274,17 @@ impl Default for AdaptiveLaneScoringConfig {
 
 #[derive(Debug, Clone)]
 pub struct NodeRuntimeConfig {
+    /// Wall-clock length of one step for clock-driven runtimes.
+    ///
+    /// All `*_steps` fields are interpreted in units of this duration.
+    pub step_duration: Duration,
     /// Base fanout used for fast-lane forwarding.
     pub base_fast_fanout: usize,
     /// Base fanout used for fallback-lane forwarding.
     pub base_fallback_fanout: usize,
     /// Extra fallback fanout used for fast-lane redundancy sends.
     pub fallback_redundancy_fanout: usize,
-    /// Cache TTL (in abstract steps) for shard entries.
+    /// Cache TTL (in steps) for shard entries.
     pub ttl_steps: u64,
     /// Steps to wait before first ACK-timeout retry.
     pub ack_initial_timeout_steps: u64,
@@ -103,16 +302,41 @@ pub struct NodeRuntimeConfig {
     pub systematic_namespaces: HashSet<u16>,
     /// Accept all inbound tags without requiring local subscription entries.
     pub accept_all_tags: bool,
+    /// Reject inbound shards and objects not in deterministic CBOR form.
+    pub strict_cbor: bool,
     /// Optional upward bucket jitter levels (0 disables jitter).
     pub bucket_jitter_extra_levels: usize,
     /// Adaptive lane-scoring policy for fanout rebalancing.
     pub adaptive_lane_scoring: AdaptiveLaneScoringConfig,
+    /// Loss-adaptive FEC profile selection for published objects.
+    pub loss_adaptive_fec: LossAdaptiveFecConfig,
+    /// FEC profiles selectable on publish (built-ins plus custom entries).
+    pub fec_profiles: ProfileRegistry,
+    /// Buffer-reuse and threading controls for publish-time sharding.
+    pub sharding: ShardingOptions,
     /// Replica-estimate based probabilistic forwarding.
     pub probabilistic_forwarding: ProbabilisticForwardingConfig,
     /// Periodic Bloom filter exchange controls.
     pub bloom_exchange: BloomExchangeConfig,
+    /// Subscription-interest advertisements and interest-aware forwarding.
+    pub interest_forwarding: InterestForwardingConfig,
+    /// Feed history backfill requests and responses.
+    pub backfill: BackfillConfig,
+    /// Tombstone application, forwarding, and retention.
+    pub tombstones: TombstoneConfig,
+    /// Chain signed published objects into per-tag publisher logs.
+    pub publisher_log: bool,
+    /// Cover traffic, forwarding delay, and per-lane bandwidth shaping.
+    pub traffic_shaping: TrafficShapingConfig,
     /// Namespaces that require signed objects at ingest.
     pub required_signed_namespaces: HashSet<u16>,
+    /// Namespaces whose objects must be signed by a threshold of a signer set.
+    pub required_multisig_namespaces: HashMap<u16, SignerSet>,
+    /// Proof-of-work stamp difficulty, in leading zero bits, required per
+    /// namespace from unsigned objects and `Unknown` publishers.
+    pub pow_stamp_difficulty: HashMap<u16, u8>,
+    /// Batched signature verification at ingest.
+    pub signature_batch: SignatureBatchConfig,
     /// Local WoT policy used for trust classification and quotas.
     pub wot_policy: LocalWotPolicy,
     peer_publishers: HashMap<String, [u8; 32]>,
@@ -121,6 +345,7 @@ pub struct NodeRuntimeConfig {
 impl Default for NodeRuntimeConfig {
     fn default() -> Self {
         Self {
+            step_duration: DEFAULT_STEP_DURATION,
             base_fast_fanout: 2,
             base_fallback_fanout: 1,
             fallback_redundancy_fanout: 1,
@@ -133,11 +358,23 @@ impl Default for NodeRuntimeConfig {
             erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
             systematic_namespaces: HashSet::from([NAMESPACE_PUBLIC_FEED.0]),
             accept_all_tags: false,
+            strict_cbor: true,
             bucket_jitter_extra_levels: 0,
             adaptive_lane_scoring: AdaptiveLaneScoringConfig::default(),
+            loss_adaptive_fec: LossAdaptiveFecConfig::default(),
+            fec_profiles: ProfileRegistry::builtin(),
+            sharding: ShardingOptions::default(),
             probabilistic_forwarding: ProbabilisticForwardingConfig::default(),
             bloom_exchange: BloomExchangeConfig::default(),
+            interest_forwarding: InterestForwardingConfig::default(),
+            backfill: BackfillConfig::default(),
+            tombstones: TombstoneConfig::default(),
+            publisher_log: false,
+            traffic_shaping: TrafficShapingConfig::default(),
             required_signed_namespaces: HashSet::new(),
+            required_multisig_namespaces: HashMap::new(),
+            pow_stamp_difficulty: HashMap::new(),
+            signature_batch: SignatureBatchConfig::default(),
             wot_policy: LocalWotPolicy::default(),
             peer_publishers: HashMap::new(),
         }
@@ -204,6 +441,21 @@ impl NodeRuntimeConfig {
             .build()
     }
 
+    /// Converts a wall-clock duration into steps at this config's resolution.
+    pub fn steps_for(&self, duration: Duration) -> u64 {
+        duration_to_steps(duration, self.step_duration)
+    }
+
+    /// Wall-clock length of `steps` at this config's resolution.
+    pub fn duration_of(&self, steps: u64) -> Duration {
+        steps_to_duration(steps, self.step_duration)
+    }
+
+    /// Cache TTL as wall-clock duration.
+    pub fn ttl(&self) -> Duration {
+        self.duration_of(self.ttl_steps)
+    }
+
     /// Binds a transport peer identifier to a publisher pubkey.
     pub fn bind_peer_publisher(&mut self, peer: impl Into<String>, publisher: [u8; 32]) {
         self.peer_publishers.insert(peer.into(), publisher);
@@ -214,6 +466,23 @@ impl NodeRuntimeConfig {
         self.required_signed_namespaces.insert(namespace.0);
     }
 
+    /// Requires objects in `namespace` to carry a threshold attestation
+    /// from `signers`.
+    pub fn require_multisig_namespace(
+        &mut self,
+        namespace: veil_core::Namespace,
+        signers: SignerSet,
+    ) {
+        self.required_multisig_namespaces
+            .insert(namespace.0, signers);
+    }
+
+    /// Requires unsigned objects and `Unknown`-tier publishers in
+    /// `namespace` to attach a proof-of-work stamp of `difficulty` bits.
+    pub fn require_pow_stamp(&mut self, namespace: veil_core::Namespace, difficulty: u8) {
+        self.pow_stamp_difficulty.insert(namespace.0, difficulty);
+    }
+
     /// Enables systematic erasure mode for a namespace.
     pub fn enable_systematic_namespace(&mut self, namespace: veil_core::Namespace) {
         self.systematic_namespaces.insert(namespace.0);
@@ -281,6 +550,12 @@ pub struct NodeRuntimeConfigBuilder {
 }
 
 impl NodeRuntimeConfigBuilder {
+    /// Sets step resolution; call before duration-based setters.
+    pub fn step_duration(mut self, value: Duration) -> Self {
+        self.cfg.step_duration = value;
+        self
+    }
+
     pub fn base_fast_fanout(mut self, value: usize) -> Self {
         self.cfg.base_fast_fanout = value;
         self
@@ -301,6 +576,11 @@ impl NodeRuntimeConfigBuilder {
         self
     }
 
+    pub fn ttl(mut self, value: Duration) -> Self {
+        self.cfg.ttl_steps = self.cfg.steps_for(value);
+        self
+    }
+
     pub fn max_cache_shards(mut self, value: usize) -> Self {
         self.cfg.max_cache_shards = value;
         self
@@ -321,6 +601,11 @@ impl NodeRuntimeConfigBuilder {
         self
     }
 
+    pub fn strict_cbor(mut self, value: bool) -> Self {
+        self.cfg.strict_cbor = value;
+        self
+    }
+
     pub fn with_systematic_namespace(mut self, namespace: veil_core::Namespace) -> Self {
         self.cfg.systematic_namespaces.insert(namespace.0);
         self
@@ -340,6 +625,21 @@ impl NodeRuntimeConfigBuilder {
         self
     }
 
+    /// Duration-based variant of `ack_retry`.
+    pub fn ack_retry_timing(
+        mut self,
+        initial_timeout: Duration,
+        retry_batch_size: usize,
+        backoff: Duration,
+        max_retries: u32,
+    ) -> Self {
+        self.cfg.ack_initial_timeout_steps = self.cfg.steps_for(initial_timeout);
+        self.cfg.ack_retry_batch_size = retry_batch_size;
+        self.cfg.ack_backoff_steps = self.cfg.steps_for(backoff);
+        self.cfg.ack_max_retries = max_retries;
+        self
+    }
+
     pub fn with_peer_publisher(mut self, peer: impl Into<String>, publisher: [u8; 32]) -> Self {
         self.cfg.bind_peer_publisher(peer, publisher);
         self
@@ -355,6 +655,21 @@ impl NodeRuntimeConfigBuilder {
         self
     }
 
+    pub fn loss_adaptive_fec(mut self, value: LossAdaptiveFecConfig) -> Self {
+        self.cfg.loss_adaptive_fec = value;
+        self
+    }
+
+    pub fn fec_profiles(mut self, value: ProfileRegistry) -> Self {
+        self.cfg.fec_profiles = value;
+        self
+    }
+
+    pub fn sharding(mut self, value: ShardingOptions) -> Self {
+        self.cfg.sharding = value;
+        self
+    }
+
     pub fn probabilistic_forwarding(mut self, value: ProbabilisticForwardingConfig) -> Self {
         self.cfg.probabilistic_forwarding = value;
         self
@@ -365,11 +680,90 @@ impl NodeRuntimeConfigBuilder {
         self
     }
 
+    pub fn bloom_exchange_interval(mut self, value: Duration) -> Self {
+        self.cfg.bloom_exchange.interval_steps = self.cfg.steps_for(value);
+        self
+    }
+
+    pub fn interest_forwarding(mut self, value: InterestForwardingConfig) -> Self {
+        self.cfg.interest_forwarding = value;
+        self
+    }
+
+    pub fn backfill(mut self, value: BackfillConfig) -> Self {
+        self.cfg.backfill = value;
+        self
+    }
+
+    pub fn tombstones(mut self, value: TombstoneConfig) -> Self {
+        self.cfg.tombstones = value;
+        self
+    }
+
+    pub fn signature_batch(mut self, value: SignatureBatchConfig) -> Self {
+        self.cfg.signature_batch = value;
+        self
+    }
+
+    pub fn tombstone_retention(mut self, value: Duration) -> Self {
+        self.cfg.tombstones.retention_steps = self.cfg.steps_for(value);
+        self
+    }
+
+    pub fn publisher_log(mut self, value: bool) -> Self {
+        self.cfg.publisher_log = value;
+        self
+    }
+
+    /// Sets WoT endorsement windows and age decay from wall-clock durations.
+    pub fn wot_timing(
+        mut self,
+        age_decay_window: Duration,
+        endorsement_window: Duration,
+        endorsement_max_age: Duration,
+    ) -> Self {
+        let age_decay = self.cfg.steps_for(age_decay_window);
+        let window = self.cfg.steps_for(endorsement_window);
+        let max_age = self.cfg.steps_for(endorsement_max_age);
+        let wot = &mut self.cfg.wot_policy.config;
+        wot.age_decay_window_steps = age_decay;
+        wot.endorsement_window_steps = window;
+        wot.endorsement_max_age_steps = max_age;
+        self
+    }
+
+    pub fn traffic_shaping(mut self, value: TrafficShapingConfig) -> Self {
+        self.cfg.traffic_shaping = value;
+        self
+    }
+
     pub fn with_required_signed_namespace(mut self, namespace: veil_core::Namespace) -> Self {
         self.cfg.required_signed_namespaces.insert(namespace.0);
         self
     }
 
+    pub fn with_required_multisig_namespace(
+        mut self,
+        namespace: veil_core::Namespace,
+        signers: SignerSet,
+    ) -> Self {
+        self.cfg
+            .required_multisig_namespaces
+            .insert(namespace.0, signers);
+        self
+    }
+
+    pub fn with_required_pow_stamp(
+        mut self,
+        namespace: veil_core::Namespace,
+        difficulty: u8,
+    ) -> Self {
+        self.cfg
+            .pow_stamp_difficulty
+            .insert(namespace.0, difficulty);
+        self
+    }
+
     pub fn build(self) -> NodeRuntimeConfig {
         self.cfg
     }
@@ -378,11 +772,14 @@ impl NodeRuntimeConfigBuilder {
 #[cfg(test)]
 mod tests {
     use super::{
-        AdaptiveLaneScoringConfig, BloomExchangeConfig, NodeRuntimeConfig,
-        ProbabilisticForwardingConfig,
+        AdaptiveLaneScoringConfig, BackfillConfig, BloomExchangeConfig, DelayDistribution,
+        InterestForwardingConfig, LossAdaptiveFecConfig, NodeRuntimeConfig,
+        ProbabilisticForwardingConfig, TombstoneConfig, TrafficShapingConfig,
     };
     use crate::policy::TrustTier;
-    use veil_fec::profile::ErasureCodingMode;
+    use std::time::Duration;
+    use veil_fec::pooled::ShardingOptions;
+    use veil_fec::profile::{ErasureCodingMode, Profile, ProfileRegistry};
 
     #[test]
     fn classify_peer_uses_bound_publisher_tier() {
@@ -434,10 +831,26 @@ mod tests {
             .erasure_coding_mode(ErasureCodingMode::HardenedNonSystematic)
             .bucket_jitter_extra_levels(1)
             .accept_all_tags(true)
+            .strict_cbor(false)
             .adaptive_lane_scoring(AdaptiveLaneScoringConfig {
                 enabled: true,
                 ..AdaptiveLaneScoringConfig::default()
             })
+            .loss_adaptive_fec(LossAdaptiveFecConfig {
+                enabled: true,
+                headroom: 0.25,
+            })
+            .fec_profiles({
+                let mut registry = ProfileRegistry::empty();
+                registry
+                    .register(usize::MAX, Profile::custom(9, 4, 12, vec![64 * 1024]))
+                    .expect("register");
+                registry
+            })
+            .sharding(ShardingOptions {
+                max_threads: 4,
+                parallel_min_bytes: 1024,
+            })
             .probabilistic_forwarding(ProbabilisticForwardingConfig {
                 enabled: true,
                 min_probability: 0.2,
@@ -448,7 +861,22 @@ mod tests {
                 interval_steps: 32,
                 false_positive_rate: 0.02,
             })
+            .interest_forwarding(InterestForwardingConfig {
+                enabled: true,
+                ..InterestForwardingConfig::default()
+            })
+            .backfill(BackfillConfig {
+                enabled: true,
+                max_shards_per_response: 64,
+                ..BackfillConfig::default()
+            })
+            .tombstones(TombstoneConfig {
+                retention_steps: 99,
+                ..TombstoneConfig::default()
+            })
+            .publisher_log(true)
             .with_required_signed_namespace(veil_core::Namespace(7))
+            .with_required_pow_stamp(veil_core::Namespace(8), 12)
             .with_peer_publisher("peer-a", [0x99; 32])
             .build();
 
@@ -461,11 +889,24 @@ mod tests {
             ErasureCodingMode::HardenedNonSystematic
         );
         assert!(cfg.accept_all_tags);
+        assert!(!cfg.strict_cbor);
         assert_eq!(cfg.bucket_jitter_extra_levels, 1);
         assert!(cfg.adaptive_lane_scoring.enabled);
+        assert!(cfg.loss_adaptive_fec.enabled);
+        assert_eq!(cfg.loss_adaptive_fec.headroom, 0.25);
+        assert_eq!(cfg.fec_profiles.entries().len(), 1);
+        assert!(cfg.fec_profiles.get(9).is_some());
+        assert_eq!(cfg.sharding.max_threads, 4);
+        assert_eq!(cfg.sharding.parallel_min_bytes, 1024);
         assert!(cfg.probabilistic_forwarding.enabled);
         assert!(cfg.bloom_exchange.enabled);
+        assert!(cfg.interest_forwarding.enabled);
+        assert!(cfg.backfill.enabled);
+        assert_eq!(cfg.backfill.max_shards_per_response, 64);
+        assert!(cfg.publisher_log);
+        assert_eq!(cfg.tombstones.retention_steps, 99);
         assert!(cfg.required_signed_namespaces.contains(&7));
+        assert_eq!(cfg.pow_stamp_difficulty.get(&8), Some(&12));
         assert_eq!(cfg.classify_peer_tier("peer-a", 0), TrustTier::Unknown);
         let p = cfg.ack_retry_policy();
         assert_eq!(p.initial_timeout_steps, 3);
@@ -474,6 +915,61 @@ mod tests {
         assert_eq!(p.max_retries, 6);
     }
 
+    #[test]
+    fn traffic_shaping_resolves_namespace_delay_overrides() {
+        let mut shaping = TrafficShapingConfig {
+            default_delay: DelayDistribution::None,
+            ..TrafficShapingConfig::default()
+        };
+        shaping.namespace_delays.insert(
+            7,
+            DelayDistribution::Uniform {
+                min_steps: 1,
+                max_steps: 3,
+            },
+        );
+        let cfg = NodeRuntimeConfig::builder()
+            .traffic_shaping(shaping)
+            .build();
+
+        assert!(!cfg.traffic_shaping.enabled);
+        assert_eq!(
+            cfg.traffic_shaping.delay_for_namespace(7),
+            DelayDistribution::Uniform {
+                min_steps: 1,
+                max_steps: 3
+            }
+        );
+        assert_eq!(
+            cfg.traffic_shaping.delay_for_namespace(1),
+            DelayDistribution::None
+        );
+    }
+
+    #[test]
+    fn duration_setters_convert_using_step_duration() {
+        let cfg = NodeRuntimeConfig::builder()
+            .step_duration(Duration::from_millis(100))
+            .ttl(Duration::from_secs(60))
+            .ack_retry_timing(Duration::from_secs(1), 3, Duration::from_millis(250), 5)
+            .bloom_exchange_interval(Duration::from_secs(30))
+            .wot_timing(
+                Duration::from_secs(3_600),
+                Duration::from_secs(600),
+                Duration::from_secs(86_400),
+            )
+            .build();
+
+        assert_eq!(cfg.ttl_steps, 600);
+        assert_eq!(cfg.ttl(), Duration::from_secs(60));
+        assert_eq!(cfg.ack_initial_timeout_steps, 10);
+        assert_eq!(cfg.ack_backoff_steps, 3);
+        assert_eq!(cfg.bloom_exchange.interval_steps, 300);
+        assert_eq!(cfg.wot_policy.config.age_decay_window_steps, 36_000);
+        assert_eq!(cfg.wot_policy.config.endorsement_window_steps, 6_000);
+        assert_eq!(cfg.wot_policy.config.endorsement_max_age_steps, 864_000);
+    }
+
     #[test]
     fn profile_defaults_are_conservative_and_nonzero() {
         let edge = NodeRuntimeConfig::edge_forwarder_hot_cache_defaults();
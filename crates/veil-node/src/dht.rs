@@ -0,0 +1,1135 @@
+//! Kademlia-style DHT over the 32-byte tag/node-key space.
+//!
+//! Storage nodes (typically VPS relays) keep shards for rendezvous tags they
+//! are among the `k` closest to, so a recipient that was offline can later
+//! look up its current-epoch tags and fetch what it missed. The DHT is
+//! transport-agnostic: peers are addressed by string and all traffic is
+//! surfaced as `(addr, packet)` pairs for the host to send.
+//!
+//! Storage nodes only accept a `Store` that echoes a token they handed the
+//! same address in a recent `Nodes` response for that tag, so writes come
+//! from a peer that actually performed the lookup rather than a spoofed or
+//! drive-by sender.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use veil_codec::shard::decode_shard_cbor_strict;
+use veil_core::hash::blake3_32;
+use veil_core::{ShardId, Tag};
+
+const DHT_V1: u16 = 1;
+const DHT_PACKET_MAGIC: &[u8] = b"VEIL_DHT_V1";
+const STORE_PRUNE_INTERVAL_STEPS: u64 = 256;
+/// Steps a store token stays valid; the previous window is also accepted.
+const STORE_TOKEN_WINDOW_STEPS: u64 = 1_200;
+const STORE_TOKEN_DOMAIN: &[u8] = b"veil/dht/store-token/v1";
+
+/// Position of a node in the DHT key space.
+pub type NodeKey = [u8; 32];
+
+/// XOR distance between two keys, compared lexicographically.
+pub fn xor_distance(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0_u8; 32];
+    for (o, (x, y)) in out.iter_mut().zip(a.iter().zip(b.iter())) {
+        *o = x ^ y;
+    }
+    out
+}
+
+/// Routing bucket for `other` relative to `local`: the number of leading
+/// bits the two keys share. Returns `None` for identical keys.
+pub fn bucket_index(local: &NodeKey, other: &NodeKey) -> Option<usize> {
+    let distance = xor_distance(local, other);
+    let mut prefix = 0;
+    for byte in distance {
+        if byte == 0 {
+            prefix += 8;
+            continue;
+        }
+        return Some(prefix + byte.leading_zeros() as usize);
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DhtConfig {
+    /// Bucket size and replication factor.
+    pub k: usize,
+    /// Parallel queries in flight per lookup.
+    pub alpha: usize,
+    /// Steps before an unanswered query is treated as failed.
+    pub request_timeout_steps: u64,
+    /// Retention for stored shards.
+    pub store_ttl_steps: u64,
+    /// Maximum shards kept per tag. A full tag evicts the storing peer's own
+    /// oldest shard, or else the oldest shard of the tag's largest contributor.
+    pub max_shards_per_tag: usize,
+    /// Maximum shards kept across all tags. A full store evicts by the same
+    /// rule as a full tag, counted across every tag.
+    pub max_stored_shards: usize,
+    /// Maximum shards returned in a single response.
+    pub max_shards_per_response: usize,
+}
+
+impl Default for DhtConfig {
+    fn default() -> Self {
+        Self {
+            k: 8,
+            alpha: 3,
+            request_timeout_steps: 40,
+            store_ttl_steps: 1_728_000,
+            max_shards_per_tag: 256,
+            max_stored_shards: 100_000,
+            max_shards_per_response: 64,
+        }
+    }
+}
+
+/// Reachable storage node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DhtContact {
+    pub key: NodeKey,
+    pub addr: String,
+}
+
+#[derive(Debug, Clone)]
+struct RoutingEntry {
+    contact: DhtContact,
+    last_seen_step: u64,
+}
+
+/// k-bucket routing table keyed by prefix length to the local key.
+#[derive(Debug, Clone)]
+pub struct RoutingTable {
+    local_key: NodeKey,
+    bucket_size: usize,
+    buckets: Vec<Vec<RoutingEntry>>,
+}
+
+impl RoutingTable {
+    pub fn new(local_key: NodeKey, bucket_size: usize) -> Self {
+        Self {
+            local_key,
+            bucket_size: bucket_size.max(1),
+            buckets: vec![Vec::new(); 256],
+        }
+    }
+
+    pub fn local_key(&self) -> &NodeKey {
+        &self.local_key
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.iter().all(Vec::is_empty)
+    }
+
+    /// Inserts or refreshes a contact.
+    ///
+    /// Known contacts move to the tail of their bucket. New contacts are
+    /// dropped when the bucket is full, preferring long-lived entries.
+    pub fn insert(&mut self, contact: DhtContact, now_step: u64) -> bool {
+        let Some(idx) = bucket_index(&self.local_key, &contact.key) else {
+            return false;
+        };
+        let bucket = &mut self.buckets[idx];
+        if let Some(pos) = bucket.iter().position(|e| e.contact.key == contact.key) {
+            bucket.remove(pos);
+        } else if bucket.len() >= self.bucket_size {
+            return false;
+        }
+        bucket.push(RoutingEntry {
+            contact,
+            last_seen_step: now_step,
+        });
+        true
+    }
+
+    pub fn remove(&mut self, key: &NodeKey) -> bool {
+        let Some(idx) = bucket_index(&self.local_key, key) else {
+            return false;
+        };
+        let bucket = &mut self.buckets[idx];
+        let before = bucket.len();
+        bucket.retain(|e| e.contact.key != *key);
+        bucket.len() != before
+    }
+
+    /// Last step `key` was heard from, if it is in the table.
+    pub fn last_seen(&self, key: &NodeKey) -> Option<u64> {
+        let idx = bucket_index(&self.local_key, key)?;
+        self.buckets[idx]
+            .iter()
+            .find(|e| e.contact.key == *key)
+            .map(|e| e.last_seen_step)
+    }
+
+    /// Up to `count` contacts ordered by XOR distance to `target`.
+    pub fn closest(&self, target: &[u8; 32], count: usize) -> Vec<DhtContact> {
+        let mut all = self
+            .buckets
+            .iter()
+            .flatten()
+            .map(|e| (xor_distance(target, &e.contact.key), &e.contact))
+            .collect::<Vec<_>>();
+        all.sort_by_key(|(distance, _)| *distance);
+        all.into_iter()
+            .take(count)
+            .map(|(_, c)| c.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StoredShard {
+    shard_id: ShardId,
+    source: String,
+    bytes: Vec<u8>,
+    expiry_step: u64,
+    /// Arrival order across the whole store.
+    seq: u64,
+}
+
+/// Per-tag shard storage held by DHT storage nodes.
+#[derive(Debug, Default, Clone)]
+pub struct ShardStore {
+    by_tag: HashMap<Tag, Vec<StoredShard>>,
+    by_source: HashMap<String, usize>,
+    total: usize,
+    next_seq: u64,
+}
+
+impl ShardStore {
+    pub fn len(&self) -> usize {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Stores shard bytes for `tag` on behalf of `source`. Returns `false` for
+    /// malformed, non-canonical or duplicate shards and shards whose header
+    /// names another tag.
+    ///
+    /// A full tag evicts `source`'s own oldest shard there if it has one,
+    /// otherwise the oldest shard of whichever source holds the most there. A
+    /// full store applies the same rule across all tags, so one peer can never
+    /// flush shards other peers stored, per tag or globally.
+    pub fn insert(
+        &mut self,
+        tag: &Tag,
+        source: &str,
+        bytes: Vec<u8>,
+        now_step: u64,
+        cfg: &DhtConfig,
+    ) -> bool {
+        // Strict decoding gives each shard one byte form, so the byte hash
+        // is its canonical id and re-encodings cannot dodge deduplication.
+        let Ok(shard) = decode_shard_cbor_strict(&bytes) else {
+            return false;
+        };
+        if shard.header.tag != *tag {
+            return false;
+        }
+        let shard_id = blake3_32(&bytes);
+        let entries = self.by_tag.get(tag).map(Vec::as_slice).unwrap_or_default();
+        if entries.iter().any(|e| e.shard_id == shard_id) {
+            return false;
+        }
+        if entries.len() >= cfg.max_shards_per_tag.max(1) {
+            let mut counts = HashMap::<&str, usize>::new();
+            for entry in entries {
+                *counts.entry(entry.source.as_str()).or_default() += 1;
+            }
+            let victims = eviction_candidates(&counts, source);
+            self.evict_oldest(Some(tag), &victims);
+        } else if self.total >= cfg.max_stored_shards.max(1) {
+            let counts = self
+                .by_source
+                .iter()
+                .map(|(s, n)| (s.as_str(), *n))
+                .collect::<HashMap<_, _>>();
+            let victims = eviction_candidates(&counts, source);
+            self.evict_oldest(None, &victims);
+        }
+        self.by_tag.entry(*tag).or_default().push(StoredShard {
+            shard_id,
+            source: source.to_string(),
+            bytes,
+            expiry_step: now_step.saturating_add(cfg.store_ttl_steps),
+            seq: self.next_seq,
+        });
+        self.next_seq += 1;
+        *self.by_source.entry(source.to_string()).or_default() += 1;
+        self.total += 1;
+        true
+    }
+
+    /// Removes the oldest shard any of `victims` holds under `tag`, or across
+    /// all tags.
+    fn evict_oldest(&mut self, tag: Option<&Tag>, victims: &[String]) {
+        // Entries within a tag are in arrival order.
+        let oldest = self
+            .by_tag
+            .iter()
+            .filter(|(t, _)| tag.is_none_or(|tag| tag == *t))
+            .filter_map(|(t, entries)| {
+                let pos = entries.iter().position(|e| victims.contains(&e.source))?;
+                Some((entries[pos].seq, *t, pos))
+            })
+            .min_by_key(|(seq, _, _)| *seq);
+        let Some((_, tag, pos)) = oldest else {
+            return;
+        };
+        let Some(entries) = self.by_tag.get_mut(&tag) else {
+            return;
+        };
+        let evicted = entries.remove(pos);
+        if entries.is_empty() {
+            self.by_tag.remove(&tag);
+        }
+        if let Some(count) = self.by_source.get_mut(&evicted.source) {
+            *count -= 1;
+            if *count == 0 {
+                self.by_source.remove(&evicted.source);
+            }
+        }
+        self.total -= 1;
+    }
+
+    /// Unexpired shard bytes stored for `tag`.
+    pub fn get(&self, tag: &Tag, now_step: u64) -> Vec<&[u8]> {
+        self.by_tag
+            .get(tag)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|e| e.expiry_step > now_step)
+                    .map(|e| e.bytes.as_slice())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn prune(&mut self, now_step: u64) {
+        let mut total = 0;
+        let mut by_source = HashMap::<String, usize>::new();
+        self.by_tag.retain(|_, entries| {
+            entries.retain(|e| e.expiry_step > now_step);
+            for entry in entries.iter() {
+                *by_source.entry(entry.source.clone()).or_default() += 1;
+            }
+            total += entries.len();
+            !entries.is_empty()
+        });
+        self.by_source = by_source;
+        self.total = total;
+    }
+}
+
+/// Sources to evict from when a quota is full: `source` itself if it holds a
+/// shard there, otherwise every source tied for the most shards (the oldest
+/// shard among them goes).
+fn eviction_candidates(counts: &HashMap<&str, usize>, source: &str) -> Vec<String> {
+    if counts.contains_key(source) {
+        return vec![source.to_string()];
+    }
+    let top = counts.values().copied().max().unwrap_or(0);
+    counts
+        .iter()
+        .filter(|(_, n)| **n == top)
+        .map(|(s, _)| (*s).to_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DhtMessage {
+    FindNode {
+        request_id: u64,
+        target: NodeKey,
+    },
+    FindShards {
+        request_id: u64,
+        tag: Tag,
+    },
+    Nodes {
+        request_id: u64,
+        contacts: Vec<DhtContact>,
+        /// Token the requester must echo to store under the target; only
+        /// storage nodes issue one.
+        store_token: Option<[u8; 32]>,
+    },
+    Shards {
+        request_id: u64,
+        shards: Vec<Vec<u8>>,
+        contacts: Vec<DhtContact>,
+    },
+    Store {
+        tag: Tag,
+        token: [u8; 32],
+        shards: Vec<Vec<u8>>,
+    },
+}
+
+/// Wire envelope; `sender` is set only by nodes that serve storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DhtEnvelope {
+    pub version: u16,
+    pub sender: Option<DhtContact>,
+    pub message: DhtMessage,
+}
+
+pub fn encode_dht_packet(
+    sender: Option<DhtContact>,
+    message: DhtMessage,
+) -> Result<Vec<u8>, String> {
+    let mut out = DHT_PACKET_MAGIC.to_vec();
+    ciborium::ser::into_writer(
+        &DhtEnvelope {
+            version: DHT_V1,
+            sender,
+            message,
+        },
+        &mut out,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+pub fn decode_dht_packet(bytes: &[u8]) -> Option<DhtEnvelope> {
+    let payload = bytes.strip_prefix(DHT_PACKET_MAGIC)?;
+    if payload.is_empty() {
+        return None;
+    }
+    let envelope: DhtEnvelope = ciborium::de::from_reader(payload).ok()?;
+    (envelope.version == DHT_V1).then_some(envelope)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DhtLookupKind {
+    /// Locate the `k` closest storage nodes.
+    FindNode,
+    /// Collect shards stored for a tag.
+    FindShards,
+    /// Replicate shards to the `k` closest storage nodes.
+    Store,
+}
+
+/// Finished lookup.
+#[derive(Debug, Clone)]
+pub struct DhtLookupResult {
+    pub lookup_id: u64,
+    pub target: [u8; 32],
+    pub kind: DhtLookupKind,
+    /// Closest responsive storage nodes, nearest first.
+    pub closest: Vec<DhtContact>,
+    /// Shards collected by `FindShards` lookups.
+    pub shards: Vec<Vec<u8>>,
+    /// Nodes a `Store` lookup replicated to.
+    pub stored_at: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueryState {
+    Pending,
+    InFlight(u64),
+    Responded,
+    Failed,
+}
+
+#[derive(Debug)]
+struct Lookup {
+    target: [u8; 32],
+    kind: DhtLookupKind,
+    shortlist: Vec<(DhtContact, QueryState)>,
+    payload: Vec<Vec<u8>>,
+    found: HashSet<ShardId>,
+    shards: Vec<Vec<u8>>,
+    tokens: HashMap<String, [u8; 32]>,
+}
+
+impl Lookup {
+    fn add_candidates(&mut self, contacts: impl IntoIterator<Item = DhtContact>, local: &NodeKey) {
+        for contact in contacts {
+            if contact.key == *local || self.shortlist.iter().any(|(c, _)| c.key == contact.key) {
+                continue;
+            }
+            self.shortlist.push((contact, QueryState::Pending));
+        }
+        let target = self.target;
+        self.shortlist
+            .sort_by_cached_key(|(c, _)| xor_distance(&target, &c.key));
+    }
+
+    /// Keeps new shards whose header matches the lookup tag; responders
+    /// cannot inject shards for other tags.
+    fn add_shards(&mut self, shards: Vec<Vec<u8>>) {
+        for bytes in shards {
+            let for_target =
+                decode_shard_cbor_strict(&bytes).is_ok_and(|s| s.header.tag == self.target);
+            if for_target && self.found.insert(blake3_32(&bytes)) {
+                self.shards.push(bytes);
+            }
+        }
+    }
+
+    /// The `k` nearest contacts that have not failed.
+    fn frontier(&mut self, k: usize) -> impl Iterator<Item = &mut (DhtContact, QueryState)> {
+        self.shortlist
+            .iter_mut()
+            .filter(|(_, s)| *s != QueryState::Failed)
+            .take(k)
+    }
+}
+
+/// DHT participant: a storage node when constructed with a contact, or a
+/// client that only issues lookups otherwise.
+#[derive(Debug)]
+pub struct DhtNode {
+    pub config: DhtConfig,
+    pub routing: RoutingTable,
+    pub store: ShardStore,
+    local: Option<DhtContact>,
+    lookups: HashMap<u64, Lookup>,
+    next_lookup_id: u64,
+    outbox: Vec<(String, Vec<u8>)>,
+    completed: Vec<DhtLookupResult>,
+    last_prune_step: u64,
+    token_secret: [u8; 32],
+}
+
+impl DhtNode {
+    /// Lookup-only participant (e.g. a mobile client).
+    pub fn client(local_key: NodeKey, config: DhtConfig) -> Self {
+        Self::with_local(local_key, None, config)
+    }
+
+    /// Participant that stores shards and answers queries.
+    pub fn storage(local: DhtContact, config: DhtConfig) -> Self {
+        Self::with_local(local.key, Some(local), config)
+    }
+
+    fn with_local(local_key: NodeKey, local: Option<DhtContact>, config: DhtConfig) -> Self {
+        Self {
+            config,
+            routing: RoutingTable::new(local_key, config.k),
+            store: ShardStore::default(),
+            local,
+            lookups: HashMap::new(),
+            next_lookup_id: 1,
+            outbox: Vec::new(),
+            completed: Vec::new(),
+            last_prune_step: 0,
+            token_secret: rand::random(),
+        }
+    }
+
+    pub fn local_key(&self) -> &NodeKey {
+        self.routing.local_key()
+    }
+
+    pub fn serves_storage(&self) -> bool {
+        self.local.is_some()
+    }
+
+    /// Adds bootstrap contacts (e.g. from discovery) to the routing table.
+    pub fn seed(&mut self, contacts: impl IntoIterator<Item = DhtContact>, now_step: u64) -> usize {
+        contacts
+            .into_iter()
+            .filter(|c| self.routing.insert(c.clone(), now_step))
+            .count()
+    }
+
+    pub fn find_node(&mut self, target: NodeKey, now_step: u64) -> u64 {
+        self.start_lookup(target, DhtLookupKind::FindNode, Vec::new(), now_step)
+    }
+
+    pub fn find_shards(&mut self, tag: Tag, now_step: u64) -> u64 {
+        self.start_lookup(tag, DhtLookupKind::FindShards, Vec::new(), now_step)
+    }
+
+    /// Replicates shard bytes for `tag` to the `k` closest storage nodes.
+    pub fn store_shards(&mut self, tag: Tag, shards: Vec<Vec<u8>>, now_step: u64) -> u64 {
+        self.start_lookup(tag, DhtLookupKind::Store, shards, now_step)
+    }
+
+    pub fn pending_lookups(&self) -> usize {
+        self.lookups.len()
+    }
+
+    /// Drains packets queued for sending.
+    pub fn take_outbound(&mut self) -> Vec<(String, Vec<u8>)> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Drains finished lookups.
+    pub fn take_completed(&mut self) -> Vec<DhtLookupResult> {
+        std::mem::take(&mut self.completed)
+    }
+
+    /// Handles an inbound packet. Returns `false` if `bytes` is not a DHT packet.
+    pub fn handle_packet(&mut self, from: &str, bytes: &[u8], now_step: u64) -> bool {
+        let Some(envelope) = decode_dht_packet(bytes) else {
+            return false;
+        };
+        if let Some(sender) = envelope.sender {
+            if sender.addr == from {
+                self.routing.insert(sender, now_step);
+            }
+        }
+        match envelope.message {
+            DhtMessage::FindNode { request_id, target } => {
+                let contacts = self.routing.closest(&target, self.config.k);
+                let store_token = self
+                    .serves_storage()
+                    .then(|| self.store_token(from, &target, now_step / STORE_TOKEN_WINDOW_STEPS));
+                self.send(
+                    from,
+                    DhtMessage::Nodes {
+                        request_id,
+                        contacts,
+                        store_token,
+                    },
+                );
+            }
+            DhtMessage::FindShards { request_id, tag } => {
+                let shards = self
+                    .store
+                    .get(&tag, now_step)
+                    .into_iter()
+                    .take(self.config.max_shards_per_response)
+                    .map(<[u8]>::to_vec)
+                    .collect();
+                let contacts = self.routing.closest(&tag, self.config.k);
+                self.send(
+                    from,
+                    DhtMessage::Shards {
+                        request_id,
+                        shards,
+                        contacts,
+                    },
+                );
+            }
+            DhtMessage::Store { tag, token, shards } => {
+                if self.serves_storage() && self.store_token_valid(from, &tag, &token, now_step) {
+                    for bytes in shards {
+                        self.store.insert(&tag, from, bytes, now_step, &self.config);
+                    }
+                }
+            }
+            DhtMessage::Nodes {
+                request_id,
+                contacts,
+                store_token,
+            } => self.on_response(
+                request_id,
+                from,
+                contacts,
+                Vec::new(),
+                store_token,
+                now_step,
+            ),
+            DhtMessage::Shards {
+                request_id,
+                shards,
+                contacts,
+            } => self.on_response(request_id, from, contacts, shards, None, now_step),
+        }
+        true
+    }
+
+    /// Expires timed-out queries, issues follow-up queries, and completes
+    /// converged lookups. Call once per runtime step.
+    pub fn poll(&mut self, now_step: u64) {
+        let timeout = self.config.request_timeout_steps.max(1);
+        let mut failed = Vec::new();
+        for lookup in self.lookups.values_mut() {
+            for (contact, state) in &mut lookup.shortlist {
+                if let QueryState::InFlight(sent) = *state {
+                    if now_step.saturating_sub(sent) >= timeout {
+                        *state = QueryState::Failed;
+                        failed.push(contact.key);
+                    }
+                }
+            }
+        }
+        for key in failed {
+            self.routing.remove(&key);
+        }
+        let ids = self.lookups.keys().copied().collect::<Vec<_>>();
+        for id in ids {
+            self.advance(id, now_step);
+        }
+        if now_step.saturating_sub(self.last_prune_step) >= STORE_PRUNE_INTERVAL_STEPS {
+            self.store.prune(now_step);
+            self.last_prune_step = now_step;
+        }
+    }
+
+    fn start_lookup(
+        &mut self,
+        target: [u8; 32],
+        kind: DhtLookupKind,
+        payload: Vec<Vec<u8>>,
+        now_step: u64,
+    ) -> u64 {
+        let id = self.next_lookup_id;
+        self.next_lookup_id += 1;
+        let mut lookup = Lookup {
+            target,
+            kind,
+            shortlist: Vec::new(),
+            payload,
+            found: HashSet::new(),
+            shards: Vec::new(),
+            tokens: HashMap::new(),
+        };
+        lookup.add_candidates(
+            self.routing.closest(&target, self.config.k),
+            self.local_key(),
+        );
+        if lookup.kind == DhtLookupKind::FindShards {
+            let local = self
+                .store
+                .get(&target, now_step)
+                .into_iter()
+                .map(<[u8]>::to_vec)
+                .collect();
+            lookup.add_shards(local);
+        }
+        self.lookups.insert(id, lookup);
+        self.advance(id, now_step);
+        id
+    }
+
+    fn on_response(
+        &mut self,
+        request_id: u64,
+        from: &str,
+        contacts: Vec<DhtContact>,
+        shards: Vec<Vec<u8>>,
+        store_token: Option<[u8; 32]>,
+        now_step: u64,
+    ) {
+        let local_key = *self.local_key();
+        let Some(lookup) = self.lookups.get_mut(&request_id) else {
+            return;
+        };
+        let Some((contact, state)) = lookup
+            .shortlist
+            .iter_mut()
+            .find(|(c, s)| c.addr == from && matches!(s, QueryState::InFlight(_)))
+        else {
+            return;
+        };
+        *state = QueryState::Responded;
+        let responder = contact.clone();
+        self.routing.insert(responder, now_step);
+        lookup.add_candidates(contacts, &local_key);
+        if lookup.kind == DhtLookupKind::FindShards {
+            lookup.add_shards(shards);
+        }
+        if let Some(token) = store_token {
+            lookup.tokens.insert(from.to_string(), token);
+        }
+        self.advance(request_id, now_step);
+    }
+
+    fn advance(&mut self, id: u64, now_step: u64) {
+        let k = self.config.k.max(1);
+        let alpha = self.config.alpha.max(1);
+        let Some(lookup) = self.lookups.get_mut(&id) else {
+            return;
+        };
+        let mut in_flight = lookup
+            .frontier(k)
+            .filter(|(_, s)| matches!(s, QueryState::InFlight(_)))
+            .count();
+        let mut queries = Vec::new();
+        for (contact, state) in lookup.frontier(k) {
+            if in_flight >= alpha {
+                break;
+            }
+            if *state == QueryState::Pending {
+                *state = QueryState::InFlight(now_step);
+                in_flight += 1;
+                queries.push(contact.addr.clone());
+            }
+        }
+        let message = match lookup.kind {
+            DhtLookupKind::FindShards => DhtMessage::FindShards {
+                request_id: id,
+                tag: lookup.target,
+            },
+            _ => DhtMessage::FindNode {
+                request_id: id,
+                target: lookup.target,
+            },
+        };
+        for addr in &queries {
+            self.send(addr, message.clone());
+        }
+        if in_flight == 0 {
+            self.finish(id, now_step);
+        }
+    }
+
+    fn finish(&mut self, id: u64, now_step: u64) {
+        let k = self.config.k.max(1);
+        let Some(mut lookup) = self.lookups.remove(&id) else {
+            return;
+        };
+        let closest = lookup
+            .frontier(k)
+            .filter(|(_, s)| *s == QueryState::Responded)
+            .map(|(c, _)| c.clone())
+            .collect::<Vec<_>>();
+        let mut stored_at = 0;
+        if lookup.kind == DhtLookupKind::Store {
+            let payload = std::mem::take(&mut lookup.payload);
+            let local_is_close = self.serves_storage()
+                && (closest.len() < k
+                    || closest.last().is_some_and(|far| {
+                        xor_distance(&lookup.target, self.local_key())
+                            < xor_distance(&lookup.target, &far.key)
+                    }));
+            let remote = if local_is_close { k - 1 } else { k };
+            if local_is_close {
+                let source = self
+                    .local
+                    .as_ref()
+                    .map(|c| c.addr.clone())
+                    .unwrap_or_default();
+                for bytes in &payload {
+                    self.store.insert(
+                        &lookup.target,
+                        &source,
+                        bytes.clone(),
+                        now_step,
+                        &self.config,
+                    );
+                }
+                stored_at += 1;
+            }
+            for contact in closest.iter().take(remote) {
+                let Some(&token) = lookup.tokens.get(&contact.addr) else {
+                    continue;
+                };
+                self.send(
+                    &contact.addr,
+                    DhtMessage::Store {
+                        tag: lookup.target,
+                        token,
+                        shards: payload.clone(),
+                    },
+                );
+                stored_at += 1;
+            }
+        }
+        self.completed.push(DhtLookupResult {
+            lookup_id: id,
+            target: lookup.target,
+            kind: lookup.kind,
+            closest,
+            shards: lookup.shards,
+            stored_at,
+        });
+    }
+
+    /// Store token for `addr` writing under `target` in token window `window`.
+    fn store_token(&self, addr: &str, target: &[u8; 32], window: u64) -> [u8; 32] {
+        let mut preimage = STORE_TOKEN_DOMAIN.to_vec();
+        preimage.extend_from_slice(&self.token_secret);
+        preimage.extend_from_slice(&window.to_be_bytes());
+        preimage.extend_from_slice(target);
+        preimage.extend_from_slice(addr.as_bytes());
+        blake3_32(&preimage)
+    }
+
+    fn store_token_valid(&self, addr: &str, tag: &Tag, token: &[u8; 32], now_step: u64) -> bool {
+        let window = now_step / STORE_TOKEN_WINDOW_STEPS;
+        [Some(window), window.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .any(|w| self.store_token(addr, tag, w) == *token)
+    }
+
+    fn send(&mut self, addr: &str, message: DhtMessage) {
+        if let Ok(packet) = encode_dht_packet(self.local.clone(), message) {
+            self.outbox.push((addr.to_string(), packet));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use veil_codec::shard::encode_shard_cbor;
+    use veil_core::{Epoch, Namespace};
+    use veil_fec::sharder::object_to_shards;
+
+    use super::{
+        bucket_index, decode_dht_packet, encode_dht_packet, xor_distance, DhtConfig, DhtContact,
+        DhtLookupKind, DhtMessage, DhtNode, RoutingTable, ShardStore,
+    };
+
+    fn contact(byte: u8) -> DhtContact {
+        DhtContact {
+            key: [byte; 32],
+            addr: format!("node-{byte}"),
+        }
+    }
+
+    fn shard_bytes(tag: [u8; 32], count: usize) -> Vec<Vec<u8>> {
+        let shards = object_to_shards(b"dht shard", Namespace(3), Epoch(1), tag, [0x77; 32])
+            .expect("sharding should succeed");
+        shards
+            .iter()
+            .take(count)
+            .map(|s| encode_shard_cbor(s).expect("shard should encode"))
+            .collect()
+    }
+
+    fn addr_of(node: &DhtNode) -> String {
+        node.local
+            .as_ref()
+            .map_or_else(|| "client".to_string(), |c| c.addr.clone())
+    }
+
+    /// Delivers queued packets between nodes until the network is idle.
+    fn run_network(nodes: &mut [DhtNode], now_step: u64) {
+        loop {
+            let mut moved = false;
+            for i in 0..nodes.len() {
+                let from = addr_of(&nodes[i]);
+                for (to, packet) in nodes[i].take_outbound() {
+                    if let Some(dst) = nodes.iter_mut().find(|n| addr_of(n) == to) {
+                        dst.handle_packet(&from, &packet, now_step);
+                        moved = true;
+                    }
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn bucket_index_counts_shared_prefix_bits() {
+        let a = [0_u8; 32];
+        let mut b = [0_u8; 32];
+        b[0] = 0x80;
+        assert_eq!(bucket_index(&a, &b), Some(0));
+        b[0] = 0x01;
+        assert_eq!(bucket_index(&a, &b), Some(7));
+        assert_eq!(bucket_index(&a, &a), None);
+        assert_eq!(xor_distance(&[0xF0; 32], &[0x0F; 32]), [0xFF; 32]);
+    }
+
+    #[test]
+    fn routing_table_keeps_long_lived_contacts_when_bucket_is_full() {
+        let mut table = RoutingTable::new([0_u8; 32], 2);
+        let mut keys = Vec::new();
+        for i in 0..3_u8 {
+            let mut key = [0_u8; 32];
+            key[0] = 0x80;
+            key[31] = i;
+            keys.push(key);
+            table.insert(
+                DhtContact {
+                    key,
+                    addr: format!("n{i}"),
+                },
+                u64::from(i),
+            );
+        }
+        assert_eq!(table.len(), 2);
+        assert!(table.last_seen(&keys[2]).is_none());
+        assert_eq!(table.closest(&keys[1], 1)[0].key, keys[1]);
+    }
+
+    #[test]
+    fn dht_packet_round_trip() {
+        let packet = encode_dht_packet(
+            Some(contact(1)),
+            DhtMessage::FindShards {
+                request_id: 9,
+                tag: [0x22; 32],
+            },
+        )
+        .expect("encode");
+        let decoded = decode_dht_packet(&packet).expect("decode");
+        assert_eq!(decoded.sender, Some(contact(1)));
+        assert!(decode_dht_packet(b"VEIL_DHT_V1").is_none());
+    }
+
+    #[test]
+    fn shard_store_enforces_per_tag_quota_and_expiry() {
+        let cfg = DhtConfig {
+            max_shards_per_tag: 2,
+            store_ttl_steps: 10,
+            ..DhtConfig::default()
+        };
+        let tag = [0x31_u8; 32];
+        let mut store = ShardStore::default();
+        for bytes in shard_bytes(tag, 3) {
+            assert!(store.insert(&tag, "a", bytes, 0, &cfg));
+        }
+        assert_eq!(store.len(), 2);
+        assert!(!store.insert(&tag, "a", b"not a shard".to_vec(), 0, &cfg));
+        assert!(!store.insert(&[0x32; 32], "a", shard_bytes(tag, 1).remove(0), 0, &cfg));
+        assert_eq!(store.get(&tag, 5).len(), 2);
+        assert!(store.get(&tag, 10).is_empty());
+        store.prune(10);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn store_then_find_shards_across_storage_nodes() {
+        let cfg = DhtConfig {
+            k: 3,
+            ..DhtConfig::default()
+        };
+        let mut nodes = (1..=6_u8)
+            .map(|b| DhtNode::storage(contact(b * 40), cfg))
+            .collect::<Vec<_>>();
+        for i in 0..nodes.len() {
+            let next = nodes[(i + 1) % nodes.len()].local.clone().expect("contact");
+            nodes[i].seed([next], 0);
+        }
+
+        let tag = [0xF1_u8; 32];
+        let shards = shard_bytes(tag, 2);
+        nodes[0].store_shards(tag, shards.clone(), 0);
+        run_network(&mut nodes, 0);
+        let stored = nodes[0].take_completed();
+        assert_eq!(stored[0].kind, DhtLookupKind::Store);
+        assert_eq!(stored[0].stored_at, 3);
+        run_network(&mut nodes, 0);
+        let holders = nodes.iter().filter(|n| !n.store.is_empty()).count();
+        assert_eq!(holders, 3);
+
+        let mut client = DhtNode::client([0x05; 32], cfg);
+        client.seed([contact(80)], 1);
+        nodes.push(client);
+        let last = nodes.len() - 1;
+        nodes[last].find_shards(tag, 1);
+        run_network(&mut nodes, 1);
+        let found = nodes[last].take_completed();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].shards.len(), shards.len());
+    }
+
+    #[test]
+    fn full_tag_evicts_the_largest_contributor_not_other_peers() {
+        let cfg = DhtConfig {
+            max_shards_per_tag: 3,
+            ..DhtConfig::default()
+        };
+        let tag = [0x41_u8; 32];
+        let honest = shard_bytes(tag, 1).remove(0);
+        let spam = object_to_shards(b"flood", Namespace(3), Epoch(2), tag, [0x78; 32])
+            .expect("shard")
+            .iter()
+            .map(|s| encode_shard_cbor(s).expect("encode"))
+            .collect::<Vec<_>>();
+        let mut store = ShardStore::default();
+        assert!(store.insert(&tag, "honest", honest.clone(), 0, &cfg));
+        for bytes in spam {
+            store.insert(&tag, "flooder", bytes, 0, &cfg);
+        }
+        assert_eq!(store.len(), 3);
+        assert!(store.get(&tag, 0).contains(&honest.as_slice()));
+    }
+
+    #[test]
+    fn full_store_evicts_the_largest_contributor_across_tags() {
+        let cfg = DhtConfig {
+            max_stored_shards: 4,
+            ..DhtConfig::default()
+        };
+        let honest_tag = [0x42_u8; 32];
+        let honest = shard_bytes(honest_tag, 1).remove(0);
+        let mut store = ShardStore::default();
+        assert!(store.insert(&honest_tag, "honest", honest.clone(), 0, &cfg));
+        for i in 0..8_u8 {
+            let tag = [0x60 + i; 32];
+            assert!(store.insert(&tag, "flooder", shard_bytes(tag, 1).remove(0), 0, &cfg));
+        }
+        assert_eq!(store.len(), 4);
+        assert_eq!(store.get(&honest_tag, 0), vec![honest.as_slice()]);
+        assert!(store.get(&[0x60; 32], 0).is_empty());
+
+        // A newcomer still gets in, at the flooder's expense.
+        let late_tag = [0x43_u8; 32];
+        assert!(store.insert(
+            &late_tag,
+            "late",
+            shard_bytes(late_tag, 1).remove(0),
+            1,
+            &cfg
+        ));
+        assert_eq!(store.len(), 4);
+        assert_eq!(store.get(&honest_tag, 1).len(), 1);
+        assert_eq!(store.get(&late_tag, 1).len(), 1);
+    }
+
+    #[test]
+    fn store_rejects_non_canonical_shard_encodings() {
+        let tag = [0x44_u8; 32];
+        let canonical = shard_bytes(tag, 1).remove(0);
+        let mut padded = canonical.clone();
+        padded.push(0x00);
+        let mut store = ShardStore::default();
+        assert!(!store.insert(&tag, "a", padded, 0, &DhtConfig::default()));
+        assert!(store.insert(&tag, "a", canonical, 0, &DhtConfig::default()));
+    }
+
+    #[test]
+    fn store_requires_a_token_issued_to_the_sender() {
+        let mut node = DhtNode::storage(contact(9), DhtConfig::default());
+        let tag = [0x51_u8; 32];
+        let shards = shard_bytes(tag, 1);
+        let forged = encode_dht_packet(
+            None,
+            DhtMessage::Store {
+                tag,
+                token: [0; 32],
+                shards: shards.clone(),
+            },
+        )
+        .expect("encode");
+        node.handle_packet("mallory", &forged, 0);
+        assert!(node.store.is_empty());
+
+        let find = encode_dht_packet(
+            None,
+            DhtMessage::FindNode {
+                request_id: 1,
+                target: tag,
+            },
+        )
+        .expect("encode");
+        node.handle_packet("alice", &find, 0);
+        let (_, reply) = node.take_outbound().remove(0);
+        let Some(DhtMessage::Nodes {
+            store_token: Some(token),
+            ..
+        }) = decode_dht_packet(&reply).map(|e| e.message)
+        else {
+            panic!("storage node should issue a store token");
+        };
+        let store = |shards| {
+            encode_dht_packet(None, DhtMessage::Store { tag, token, shards }).expect("encode")
+        };
+        node.handle_packet("mallory", &store(shards.clone()), 1);
+        assert!(node.store.is_empty());
+        node.handle_packet("alice", &store(shards), 1);
+        assert_eq!(node.store.len(), 1);
+    }
+}
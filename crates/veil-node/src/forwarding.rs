@@ -1,5 +1,12 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::bloom::{BloomFilter, InterestAdvertisement};
+use crate::config::InterestForwardingConfig;
 use crate::state::NodeState;
-use veil_core::ShardId;
+use veil_core::hash::blake3_32;
+use veil_core::{ShardId, Tag};
 
 /// Returns whether a shard should be forwarded:
 /// not already seen and tag is subscribed.
@@ -7,11 +14,130 @@ pub fn should_forward(node: &NodeState, shard_id: ShardId, tag: &[u8; 32]) -> bo
     !node.seen_shards.contains_key(&shard_id) && node.subscriptions.contains(tag)
 }
 
+/// Most recent interest filter advertised by a peer.
+#[derive(Debug, Clone)]
+pub struct PeerInterest {
+    pub filter: BloomFilter,
+    /// Local step the advertisement was received at.
+    pub received_step: u64,
+}
+
+/// Advertised subscription filters keyed by a hash of the transport peer.
+#[derive(Debug, Default, Clone)]
+pub struct PeerInterestTable {
+    entries: HashMap<u64, PeerInterest>,
+}
+
+impl PeerInterestTable {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Replaces the stored filter for `peer`; malformed filters are ignored.
+    pub fn record<P: Hash>(&mut self, peer: &P, advert: InterestAdvertisement, now_step: u64) {
+        if !advert.filter.is_well_formed() {
+            return;
+        }
+        self.entries.insert(
+            peer_key(peer),
+            PeerInterest {
+                filter: advert.filter,
+                received_step: now_step,
+            },
+        );
+    }
+
+    /// Returns whether `peer` advertised interest in `tag`, or `None` when the
+    /// peer has no advertisement younger than `ttl_steps`.
+    pub fn matches<P: Hash>(
+        &self,
+        peer: &P,
+        tag: &Tag,
+        now_step: u64,
+        ttl_steps: u64,
+    ) -> Option<bool> {
+        let entry = self.entries.get(&peer_key(peer))?;
+        if now_step.saturating_sub(entry.received_step) >= ttl_steps {
+            return None;
+        }
+        Some(entry.filter.might_contain(tag))
+    }
+
+    /// Drops advertisements older than `ttl_steps`.
+    pub fn prune(&mut self, now_step: u64, ttl_steps: u64) {
+        self.entries
+            .retain(|_, e| now_step.saturating_sub(e.received_step) < ttl_steps);
+    }
+}
+
+pub(crate) fn peer_key<P: Hash>(peer: &P) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    peer.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Per-shard pseudo-random ordering key for a peer, used to spread fallback
+/// fanout across peers instead of always picking the head of the list.
+pub(crate) fn shuffle_key<P: Hash>(shard_id: &ShardId, peer: &P) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(7 + 32 + 8);
+    preimage.extend_from_slice(b"ifwd-v1");
+    preimage.extend_from_slice(shard_id);
+    preimage.extend_from_slice(&peer_key(peer).to_be_bytes());
+    blake3_32(&preimage)
+}
+
+/// Chooses forwarding targets for a shard with `tag` from priority-ordered
+/// `candidates`.
+///
+/// Peers whose advertised filter matches are preferred (up to
+/// `max_matched_fanout`), topped up to `fanout` with peers that have not
+/// advertised. Peers whose advertisement excludes the tag are skipped. If no
+/// peer matches, the first `fanout` candidates are used.
+pub fn select_interest_targets<'p, P: Hash>(
+    table: &PeerInterestTable,
+    candidates: Vec<&'p P>,
+    tag: &Tag,
+    fanout: usize,
+    now_step: u64,
+    cfg: InterestForwardingConfig,
+) -> Vec<&'p P> {
+    let mut matched = Vec::new();
+    let mut unknown = Vec::new();
+    for peer in &candidates {
+        match table.matches(*peer, tag, now_step, cfg.advert_ttl_steps) {
+            Some(true) => matched.push(*peer),
+            Some(false) => {}
+            None => unknown.push(*peer),
+        }
+    }
+    if matched.is_empty() {
+        return candidates.into_iter().take(fanout).collect();
+    }
+    matched.truncate(cfg.max_matched_fanout.max(1));
+    let fill = fanout.saturating_sub(matched.len());
+    matched.extend(unknown.into_iter().take(fill));
+    matched
+}
+
 #[cfg(test)]
 mod tests {
-    use super::should_forward;
+    use super::{select_interest_targets, should_forward, PeerInterestTable};
+    use crate::bloom::{interest_filter_for_tags, InterestAdvertisement};
+    use crate::config::InterestForwardingConfig;
     use crate::state::NodeState;
 
+    fn advert(tags: &[[u8; 32]]) -> InterestAdvertisement {
+        InterestAdvertisement {
+            version: 1,
+            issued_step: 0,
+            filter: interest_filter_for_tags(tags, 0.01, [0x01; 16]),
+        }
+    }
+
     #[test]
     fn forwards_when_new_and_subscribed() {
         let mut node = NodeState::default();
@@ -41,4 +167,33 @@ mod tests {
 
         assert!(!should_forward(&node, shard_id, &tag));
     }
+
+    #[test]
+    fn interest_selection_prefers_matching_peers_and_skips_non_matching() {
+        let tag = [0x10_u8; 32];
+        let other = [0x20_u8; 32];
+        let mut table = PeerInterestTable::default();
+        table.record(&"wants", advert(&[tag]), 0);
+        table.record(&"ignores", advert(&[other]), 0);
+
+        let peers = ["ignores", "silent", "wants"];
+        let cfg = InterestForwardingConfig::default();
+        let targets = select_interest_targets(&table, peers.iter().collect(), &tag, 2, 5, cfg);
+        assert_eq!(targets, vec![&"wants", &"silent"]);
+
+        let fallback =
+            select_interest_targets(&table, peers.iter().collect(), &[0x30; 32], 2, 5, cfg);
+        assert_eq!(fallback, vec![&"ignores", &"silent"]);
+    }
+
+    #[test]
+    fn stale_interest_adverts_are_treated_as_unknown() {
+        let tag = [0x10_u8; 32];
+        let mut table = PeerInterestTable::default();
+        table.record(&"peer", advert(&[tag]), 10);
+        assert_eq!(table.matches(&"peer", &tag, 15, 10), Some(true));
+        assert_eq!(table.matches(&"peer", &tag, 20, 10), None);
+        table.prune(20, 10);
+        assert!(table.is_empty());
+    }
 }
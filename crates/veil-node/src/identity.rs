@@ -0,0 +1,207 @@
+//! Root identities with delegated device signing subkeys.
+//!
+//! A long-lived root key anchors feed tags and authorship. Each device signs
+//! with a hardened subkey derived from the root secret and carries a
+//! root-signed [`DelegationCertificate`] in its objects, so rotating a
+//! device key never changes the root pubkey followers subscribe to.
+
+use thiserror::Error;
+use veil_codec::delegation::{
+    delegation_signature_message_digest, DelegationCertificate, DELEGATION_V1_VERSION,
+};
+use veil_codec::error::CodecError;
+use veil_codec::object::Signature;
+use veil_core::types::Namespace;
+use veil_crypto::keys::derive_device_secret;
+use veil_crypto::signing::{NostrSigner, Signer, SigningError, Verifier};
+
+/// Default validity of a freshly issued device certificate (90 days).
+pub const DEFAULT_DELEGATION_LIFETIME_SECS: u64 = 90 * 24 * 60 * 60;
+
+#[derive(Debug, Error)]
+pub enum DelegationError {
+    #[error("codec error: {0}")]
+    Codec(#[from] CodecError),
+    #[error("signing error: {0}")]
+    Signing(#[from] SigningError),
+    #[error("delegation names a different device key")]
+    DeviceMismatch,
+    #[error("delegation signature verification failed")]
+    SignatureInvalid,
+    #[error("delegation is not valid at this time")]
+    OutsideValidity,
+    #[error("delegation does not cover namespace {0}")]
+    NamespaceNotDelegated(u16),
+    #[error("delegation does not grant the required capability")]
+    CapabilityNotDelegated,
+}
+
+/// Scope and validity window granted to a device key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegationScope {
+    /// Granted `CAP_*` bits.
+    pub capabilities: u32,
+    /// Namespaces the device may sign for (empty means all).
+    pub namespaces: Vec<Namespace>,
+    /// UNIX seconds from which the grant is valid.
+    pub not_before: u64,
+    /// UNIX seconds after which the grant expires.
+    pub not_after: u64,
+}
+
+/// Signs a certificate delegating `scope` to `device_pubkey`.
+pub fn issue_delegation(
+    root: &impl Signer,
+    device_pubkey: [u8; 32],
+    device_index: u32,
+    scope: DelegationScope,
+) -> Result<DelegationCertificate, DelegationError> {
+    let mut namespaces = scope.namespaces;
+    namespaces.sort_by_key(|ns| ns.0);
+    namespaces.dedup();
+    let mut cert = DelegationCertificate {
+        version: DELEGATION_V1_VERSION,
+        root_pubkey: root.public_key(),
+        device_pubkey,
+        device_index,
+        not_before: scope.not_before,
+        not_after: scope.not_after,
+        capabilities: scope.capabilities,
+        namespaces,
+        signature: Signature([0_u8; 64]),
+    };
+    let digest = delegation_signature_message_digest(&cert)?;
+    cert.signature = Signature(root.sign(&digest)?);
+    Ok(cert)
+}
+
+/// Derives device subkey `device_index` from a Nostr root secret and issues
+/// its certificate.
+pub fn delegate_device(
+    root_secret: &[u8; 32],
+    device_index: u32,
+    scope: DelegationScope,
+) -> Result<(NostrSigner, DelegationCertificate), DelegationError> {
+    let root = NostrSigner::from_secret(*root_secret)?;
+    let device = NostrSigner::from_secret(derive_device_secret(root_secret, device_index))?;
+    let cert = issue_delegation(&root, device.public_key(), device_index, scope)?;
+    Ok((device, cert))
+}
+
+/// Verifies that `cert` lets `signer_pubkey` sign `capability` in
+/// `namespace` at `unix_secs`, returning the root pubkey it chains to.
+pub fn verify_delegation(
+    cert: &DelegationCertificate,
+    signer_pubkey: [u8; 32],
+    namespace: Namespace,
+    capability: u32,
+    unix_secs: u64,
+    verifier: &impl Verifier,
+) -> Result<[u8; 32], DelegationError> {
+    let root = verify_delegation_grant(cert, signer_pubkey, capability, unix_secs, verifier)?;
+    if !cert.covers_namespace(namespace) {
+        return Err(DelegationError::NamespaceNotDelegated(namespace.0));
+    }
+    Ok(root)
+}
+
+/// [`verify_delegation`] without the namespace check, for signatures over
+/// something whose namespace is only learned later (e.g. a tombstone for an
+/// object not yet seen). Callers must check `covers_namespace` once known.
+pub fn verify_delegation_grant(
+    cert: &DelegationCertificate,
+    signer_pubkey: [u8; 32],
+    capability: u32,
+    unix_secs: u64,
+    verifier: &(impl Verifier + ?Sized),
+) -> Result<[u8; 32], DelegationError> {
+    if cert.device_pubkey != signer_pubkey {
+        return Err(DelegationError::DeviceMismatch);
+    }
+    let digest = delegation_signature_message_digest(cert)?;
+    if !verifier.verify(cert.root_pubkey, &digest, cert.signature.0)? {
+        return Err(DelegationError::SignatureInvalid);
+    }
+    if !cert.is_valid_at(unix_secs) {
+        return Err(DelegationError::OutsideValidity);
+    }
+    if !cert.grants(capability) {
+        return Err(DelegationError::CapabilityNotDelegated);
+    }
+    Ok(cert.root_pubkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{delegate_device, verify_delegation, DelegationError, DelegationScope};
+    use veil_codec::delegation::{CAP_PUBLISH, CAP_TOMBSTONE};
+    use veil_core::types::Namespace;
+    use veil_crypto::signing::{NostrSigner, NostrVerifier, Signer};
+
+    fn scope() -> DelegationScope {
+        DelegationScope {
+            capabilities: CAP_PUBLISH,
+            namespaces: vec![Namespace(32)],
+            not_before: 1_000,
+            not_after: 2_000,
+        }
+    }
+
+    #[test]
+    fn device_certificate_chains_to_root() {
+        let root_secret = [0x11; 32];
+        let root = NostrSigner::from_secret(root_secret).expect("root");
+        let (device, cert) = delegate_device(&root_secret, 0, scope()).expect("delegate");
+        assert_ne!(device.public_key(), root.public_key());
+
+        let chained = verify_delegation(
+            &cert,
+            device.public_key(),
+            Namespace(32),
+            CAP_PUBLISH,
+            1_500,
+            &NostrVerifier,
+        )
+        .expect("verify");
+        assert_eq!(chained, root.public_key());
+    }
+
+    #[test]
+    fn rejects_out_of_scope_use() {
+        let (device, cert) = delegate_device(&[0x11; 32], 1, scope()).expect("delegate");
+        let check = |pubkey, ns, cap, at| {
+            verify_delegation(&cert, pubkey, Namespace(ns), cap, at, &NostrVerifier)
+        };
+        let pubkey = device.public_key();
+        assert!(matches!(
+            check([7; 32], 32, CAP_PUBLISH, 1_500),
+            Err(DelegationError::DeviceMismatch)
+        ));
+        assert!(matches!(
+            check(pubkey, 32, CAP_PUBLISH, 2_000),
+            Err(DelegationError::OutsideValidity)
+        ));
+        assert!(matches!(
+            check(pubkey, 32, CAP_TOMBSTONE, 1_500),
+            Err(DelegationError::CapabilityNotDelegated)
+        ));
+        assert!(matches!(
+            check(pubkey, 33, CAP_PUBLISH, 1_500),
+            Err(DelegationError::NamespaceNotDelegated(33))
+        ));
+
+        let mut forged = cert.clone();
+        forged.not_after = 9_000;
+        assert!(matches!(
+            verify_delegation(
+                &forged,
+                pubkey,
+                Namespace(32),
+                CAP_PUBLISH,
+                5_000,
+                &NostrVerifier
+            ),
+            Err(DelegationError::SignatureInvalid)
+        ));
+    }
+}
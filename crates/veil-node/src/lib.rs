@@ -13,16 +13,26 @@
 //!    retry state.
 
 pub mod ack;
+pub mod backfill;
 pub mod batch;
 pub mod bloom;
 pub mod cache;
+pub mod clock;
 pub mod config;
+pub mod dht;
 pub mod forwarding;
+pub mod identity;
+pub mod mailbox;
+pub mod multisig;
 pub mod persistence;
 pub mod policy;
 pub mod publish;
+pub mod publisher_log;
 pub mod receive;
+pub mod revocation;
 pub mod runtime;
 pub mod service;
+pub mod shaping;
 pub mod state;
 pub mod subscriptions;
+pub mod tombstone;
@@ -0,0 +1,334 @@
+//! Signed control objects for offline mailbox relays.
+//!
+//! A mailbox relay retains shards for registered rendezvous tags well past the
+//! hot-cache window so a recipient that was offline can catch up later. The
+//! owner authorizes retention with a signed [`MailboxRegistration`] and drains
+//! retained shards with a signed [`MailboxFetchRequest`]; both are verified by
+//! the relay with the same [`Verifier`] used for signed objects.
+//!
+//! Registrations name rendezvous slots rather than raw tags: each tag is
+//! derived from the owner key with [`derive_rv_tag`], so a registration can
+//! only ever claim tags that belong to its signer.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use veil_codec::object::Signature;
+use veil_core::hash::blake3_32;
+use veil_core::tags::derive_rv_tag;
+use veil_core::{Epoch, Namespace, Tag};
+use veil_crypto::signing::{Signer, SigningError, Verifier};
+
+/// Current mailbox control object version.
+pub const MAILBOX_VERSION: u8 = 1;
+/// Upper bound on tags carried by a single registration or fetch request.
+pub const MAX_MAILBOX_TAGS: usize = 256;
+
+const REGISTRATION_DOMAIN: &[u8] = b"veil/mailbox/register/v1";
+const FETCH_DOMAIN: &[u8] = b"veil/mailbox/fetch/v1";
+
+/// Errors raised while building or verifying mailbox control objects.
+#[derive(Debug, Error)]
+pub enum MailboxError {
+    #[error("unsupported mailbox object version {0}")]
+    UnsupportedVersion(u8),
+    #[error("mailbox object lists no tags")]
+    NoTags,
+    #[error("mailbox object lists {0} tags, more than the allowed maximum")]
+    TooManyTags(usize),
+    #[error("mailbox object is expired or not yet valid")]
+    OutsideValidityWindow,
+    #[error("mailbox object signature is invalid")]
+    SignatureInvalid,
+    #[error("encode error: {0}")]
+    Encode(String),
+    #[error("signing error: {0}")]
+    Signing(#[from] SigningError),
+}
+
+/// Rendezvous slot whose owner-derived tag a registration asks to retain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailboxSlot {
+    pub namespace: Namespace,
+    pub epoch: Epoch,
+}
+
+impl MailboxSlot {
+    /// Rendezvous tag of this slot for `owner_pubkey`.
+    pub fn tag(&self, owner_pubkey: &[u8; 32]) -> Tag {
+        derive_rv_tag(owner_pubkey, self.epoch, self.namespace)
+    }
+}
+
+/// Owner-signed request asking a relay to retain shards for `slots`.
+///
+/// Times are UNIX seconds. Relays clamp `expires_at` to their own maximum
+/// retention window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailboxRegistration {
+    pub version: u8,
+    pub owner_pubkey: [u8; 32],
+    pub slots: Vec<MailboxSlot>,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub signature: Signature,
+}
+
+#[derive(Serialize)]
+struct RegistrationPreimage<'a> {
+    version: u8,
+    owner_pubkey: [u8; 32],
+    slots: &'a [MailboxSlot],
+    issued_at: u64,
+    expires_at: u64,
+}
+
+impl MailboxRegistration {
+    /// Builds and signs a registration for `slots` with `signer` as owner.
+    pub fn sign(
+        signer: &dyn Signer,
+        slots: Vec<MailboxSlot>,
+        issued_at: u64,
+        expires_at: u64,
+    ) -> Result<Self, MailboxError> {
+        let mut registration = Self {
+            version: MAILBOX_VERSION,
+            owner_pubkey: signer.public_key(),
+            slots,
+            issued_at,
+            expires_at,
+            signature: Signature([0u8; 64]),
+        };
+        check_count(registration.slots.len())?;
+        registration.signature = Signature(signer.sign(&registration.signing_digest()?)?);
+        Ok(registration)
+    }
+
+    /// Domain-separated digest covered by the owner signature.
+    pub fn signing_digest(&self) -> Result<[u8; 32], MailboxError> {
+        domain_digest(
+            REGISTRATION_DOMAIN,
+            &RegistrationPreimage {
+                version: self.version,
+                owner_pubkey: self.owner_pubkey,
+                slots: &self.slots,
+                issued_at: self.issued_at,
+                expires_at: self.expires_at,
+            },
+        )
+    }
+
+    /// Tags claimed by this registration, derived from the owner key.
+    pub fn tags(&self) -> Vec<Tag> {
+        self.slots
+            .iter()
+            .map(|slot| slot.tag(&self.owner_pubkey))
+            .collect()
+    }
+
+    /// Checks version, slot bounds, validity window, and owner signature.
+    pub fn verify(
+        &self,
+        verifier: &dyn Verifier,
+        now_secs: u64,
+        max_clock_skew_secs: u64,
+    ) -> Result<(), MailboxError> {
+        if self.version != MAILBOX_VERSION {
+            return Err(MailboxError::UnsupportedVersion(self.version));
+        }
+        check_count(self.slots.len())?;
+        if self.issued_at > now_secs.saturating_add(max_clock_skew_secs)
+            || self.expires_at <= now_secs
+        {
+            return Err(MailboxError::OutsideValidityWindow);
+        }
+        verify_signature(
+            verifier,
+            self.owner_pubkey,
+            self.signing_digest()?,
+            &self.signature,
+        )
+    }
+}
+
+/// Owner-signed request to fetch and drain retained shards for `tags`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailboxFetchRequest {
+    pub version: u8,
+    pub owner_pubkey: [u8; 32],
+    pub tags: Vec<Tag>,
+    pub issued_at: u64,
+    pub max_shards: u32,
+    pub signature: Signature,
+}
+
+#[derive(Serialize)]
+struct FetchPreimage<'a> {
+    version: u8,
+    owner_pubkey: [u8; 32],
+    tags: &'a [Tag],
+    issued_at: u64,
+    max_shards: u32,
+}
+
+impl MailboxFetchRequest {
+    /// Builds and signs a fetch request for `tags` with `signer` as owner.
+    pub fn sign(
+        signer: &dyn Signer,
+        tags: Vec<Tag>,
+        issued_at: u64,
+        max_shards: u32,
+    ) -> Result<Self, MailboxError> {
+        let mut request = Self {
+            version: MAILBOX_VERSION,
+            owner_pubkey: signer.public_key(),
+            tags,
+            issued_at,
+            max_shards,
+            signature: Signature([0u8; 64]),
+        };
+        check_count(request.tags.len())?;
+        request.signature = Signature(signer.sign(&request.signing_digest()?)?);
+        Ok(request)
+    }
+
+    /// Domain-separated digest covered by the owner signature.
+    pub fn signing_digest(&self) -> Result<[u8; 32], MailboxError> {
+        domain_digest(
+            FETCH_DOMAIN,
+            &FetchPreimage {
+                version: self.version,
+                owner_pubkey: self.owner_pubkey,
+                tags: &self.tags,
+                issued_at: self.issued_at,
+                max_shards: self.max_shards,
+            },
+        )
+    }
+
+    /// Checks version, tag bounds, freshness, and owner signature.
+    ///
+    /// Fetch requests are only accepted within `max_clock_skew_secs` of
+    /// `now_secs`. Because a fetch drains the mailbox, relays must also
+    /// refuse a [`signing_digest`](Self::signing_digest) they have already
+    /// served inside that window.
+    pub fn verify(
+        &self,
+        verifier: &dyn Verifier,
+        now_secs: u64,
+        max_clock_skew_secs: u64,
+    ) -> Result<(), MailboxError> {
+        if self.version != MAILBOX_VERSION {
+            return Err(MailboxError::UnsupportedVersion(self.version));
+        }
+        check_count(self.tags.len())?;
+        if self.issued_at.abs_diff(now_secs) > max_clock_skew_secs {
+            return Err(MailboxError::OutsideValidityWindow);
+        }
+        verify_signature(
+            verifier,
+            self.owner_pubkey,
+            self.signing_digest()?,
+            &self.signature,
+        )
+    }
+}
+
+fn check_count(count: usize) -> Result<(), MailboxError> {
+    if count == 0 {
+        return Err(MailboxError::NoTags);
+    }
+    if count > MAX_MAILBOX_TAGS {
+        return Err(MailboxError::TooManyTags(count));
+    }
+    Ok(())
+}
+
+fn domain_digest(domain: &[u8], preimage: &impl Serialize) -> Result<[u8; 32], MailboxError> {
+    let mut bytes = domain.to_vec();
+    ciborium::ser::into_writer(preimage, &mut bytes)
+        .map_err(|e| MailboxError::Encode(e.to_string()))?;
+    Ok(blake3_32(&bytes))
+}
+
+fn verify_signature(
+    verifier: &dyn Verifier,
+    pubkey: [u8; 32],
+    digest: [u8; 32],
+    signature: &Signature,
+) -> Result<(), MailboxError> {
+    if verifier.verify(pubkey, &digest, signature.0)? {
+        Ok(())
+    } else {
+        Err(MailboxError::SignatureInvalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use veil_core::tags::derive_rv_tag;
+    use veil_core::{Epoch, Namespace};
+    use veil_crypto::signing::{Ed25519Signer, Ed25519Verifier, Signer};
+
+    use super::{MailboxError, MailboxFetchRequest, MailboxRegistration, MailboxSlot};
+
+    fn slot(namespace: u16, epoch: u32) -> MailboxSlot {
+        MailboxSlot {
+            namespace: Namespace(namespace),
+            epoch: Epoch(epoch),
+        }
+    }
+
+    #[test]
+    fn registration_verifies_and_rejects_tampering() {
+        let signer = Ed25519Signer::from_secret([7u8; 32]);
+        let registration =
+            MailboxRegistration::sign(&signer, vec![slot(1, 10), slot(1, 11)], 1_000, 90_000)
+                .expect("sign");
+        registration
+            .verify(&Ed25519Verifier, 1_000, 60)
+            .expect("valid registration");
+        assert_eq!(
+            registration.tags()[0],
+            derive_rv_tag(&signer.public_key(), Epoch(10), Namespace(1))
+        );
+
+        let mut tampered = registration.clone();
+        tampered.slots.push(slot(1, 12));
+        assert!(matches!(
+            tampered.verify(&Ed25519Verifier, 1_000, 60),
+            Err(MailboxError::SignatureInvalid)
+        ));
+
+        // Re-signing under another key re-derives the tags for that key, so a
+        // registration can never claim someone else's rendezvous tags.
+        let other = Ed25519Signer::from_secret([8u8; 32]);
+        let foreign = MailboxRegistration::sign(&other, registration.slots.clone(), 1_000, 90_000)
+            .expect("sign");
+        assert!(foreign
+            .tags()
+            .iter()
+            .all(|tag| !registration.tags().contains(tag)));
+        assert!(matches!(
+            registration.verify(&Ed25519Verifier, 90_000, 60),
+            Err(MailboxError::OutsideValidityWindow)
+        ));
+    }
+
+    #[test]
+    fn fetch_request_is_bound_to_a_freshness_window() {
+        let signer = Ed25519Signer::from_secret([9u8; 32]);
+        let request =
+            MailboxFetchRequest::sign(&signer, vec![[4u8; 32]], 5_000, 128).expect("sign");
+        request
+            .verify(&Ed25519Verifier, 5_030, 60)
+            .expect("fresh request");
+        assert!(matches!(
+            request.verify(&Ed25519Verifier, 5_100, 60),
+            Err(MailboxError::OutsideValidityWindow)
+        ));
+        assert!(matches!(
+            MailboxFetchRequest::sign(&signer, Vec::new(), 5_000, 128),
+            Err(MailboxError::NoTags)
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+//! Threshold multi-signature objects for organisation feeds.
+//!
+//! Editors of a [`SignerSet`] each [`cosign`] a draft `ObjectV2` carrying an
+//! unsigned `EXT_MULTISIG` attestation; one editor then collects the
+//! co-signatures and signs the finished object as its sender with
+//! [`finalize_multisig_object`]. Receivers attribute the object to the set's
+//! [`SignerSet::set_id`] once [`verify_multisig`] counts enough distinct
+//! signers.
+
+use thiserror::Error;
+use veil_codec::error::CodecError;
+use veil_codec::multisig::{Cosignature, MultisigAttestation, SignerSet};
+use veil_codec::object::{
+    encode_object_v2_cbor, object_v2_cosignature_digest, object_v2_signature_message_digest,
+    ObjectExtension, ObjectV2, Signature,
+};
+use veil_crypto::signing::{Signer, SigningError, Verifier};
+
+#[derive(Debug, Error)]
+pub enum MultisigError {
+    #[error("codec error: {0}")]
+    Codec(#[from] CodecError),
+    #[error("signing error: {0}")]
+    Signing(#[from] SigningError),
+    #[error("object carries no multisig attestation")]
+    MissingAttestation,
+    #[error("signer is not a member of the signer set")]
+    NotInSignerSet,
+    #[error("co-signature of signer {0} failed verification")]
+    CosignatureInvalid(u8),
+    #[error("only {have} of {need} required signers signed")]
+    BelowThreshold { have: usize, need: usize },
+}
+
+/// Signs `draft` as a member of its signer set.
+pub fn cosign(signer: &impl Signer, draft: &ObjectV2) -> Result<Cosignature, MultisigError> {
+    let attestation = draft.multisig().ok_or(MultisigError::MissingAttestation)?;
+    let signer_index = attestation
+        .set
+        .index_of(&signer.public_key())
+        .ok_or(MultisigError::NotInSignerSet)?;
+    let digest = object_v2_cosignature_digest(draft)?;
+    Ok(Cosignature {
+        signer_index,
+        signature: Signature(signer.sign(&digest)?),
+    })
+}
+
+/// Attaches `cosignatures` to `draft`, signs it as `sender`, and encodes it.
+pub fn finalize_multisig_object(
+    mut draft: ObjectV2,
+    cosignatures: impl IntoIterator<Item = Cosignature>,
+    sender: &impl Signer,
+) -> Result<Vec<u8>, MultisigError> {
+    let mut attestation = draft.multisig().ok_or(MultisigError::MissingAttestation)?;
+    for cosignature in cosignatures {
+        attestation.add_cosignature(cosignature);
+    }
+    draft.set_extension(ObjectExtension::multisig(&attestation)?);
+    draft.sender_pubkey = Some(sender.public_key());
+    draft.signature = Some(Signature([0_u8; 64]));
+    let digest = object_v2_signature_message_digest(&draft)?;
+    draft.signature = Some(Signature(sender.sign(&digest)?));
+    Ok(encode_object_v2_cbor(&draft)?)
+}
+
+/// Checks the attestation of an object whose sender signature (by
+/// `sender_pubkey`, or the root it is delegated from) is already verified,
+/// returning the signer set it speaks for.
+///
+/// The sender counts toward the threshold when it is a set member. Any
+/// co-signature that fails verification rejects the whole object.
+pub fn verify_multisig(
+    object: &ObjectV2,
+    sender_pubkey: [u8; 32],
+    verifier: &impl Verifier,
+) -> Result<SignerSet, MultisigError> {
+    let MultisigAttestation { set, cosignatures } =
+        object.multisig().ok_or(MultisigError::MissingAttestation)?;
+    let digest = object_v2_cosignature_digest(object)?;
+    let mut signed = vec![false; set.signers.len()];
+    if let Some(index) = set.index_of(&sender_pubkey) {
+        signed[usize::from(index)] = true;
+    }
+    for cosignature in &cosignatures {
+        let index = cosignature.signer_index;
+        let pubkey = set.signers[usize::from(index)];
+        if !verifier.verify(pubkey, &digest, cosignature.signature.0)? {
+            return Err(MultisigError::CosignatureInvalid(index));
+        }
+        signed[usize::from(index)] = true;
+    }
+    let have = signed.iter().filter(|s| **s).count();
+    let need = usize::from(set.threshold);
+    if have < need {
+        return Err(MultisigError::BelowThreshold { have, need });
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cosign, finalize_multisig_object, verify_multisig, MultisigError};
+    use veil_codec::multisig::{MultisigAttestation, SignerSet};
+    use veil_codec::object::{
+        decode_object_v2_cbor, ObjectExtension, ObjectV2, OBJECT_FLAG_SIGNED, OBJECT_V2_VERSION,
+    };
+    use veil_core::{Epoch, Namespace};
+    use veil_crypto::signing::{NostrSigner, NostrVerifier, Signer};
+
+    fn editors() -> Vec<NostrSigner> {
+        (1..=3)
+            .map(|i| NostrSigner::from_secret([i; 32]).expect("signer"))
+            .collect()
+    }
+
+    fn draft(set: SignerSet) -> ObjectV2 {
+        let attestation = MultisigAttestation::unsigned(set);
+        ObjectV2 {
+            version: OBJECT_V2_VERSION,
+            namespace: Namespace(40),
+            epoch: Epoch(1),
+            flags: OBJECT_FLAG_SIGNED,
+            tag: [9; 32],
+            object_root: [4; 32],
+            extensions: vec![ObjectExtension::multisig(&attestation).expect("ext")],
+            sender_pubkey: None,
+            signature: None,
+            nonce: [5; 24],
+            ciphertext: vec![6; 48],
+            padding: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn sender_and_cosigner_meet_two_of_three() {
+        let editors = editors();
+        let set = SignerSet::new(2, editors.iter().map(|e| e.public_key()).collect()).expect("set");
+        let draft = draft(set.clone());
+        let cosignature = cosign(&editors[1], &draft).expect("cosign");
+        let bytes = finalize_multisig_object(draft, [cosignature], &editors[0]).expect("finalize");
+        let object = decode_object_v2_cbor(&bytes).expect("decode");
+
+        let verified =
+            verify_multisig(&object, editors[0].public_key(), &NostrVerifier).expect("verify");
+        assert_eq!(verified.set_id(), set.set_id());
+        assert!(matches!(
+            verify_multisig(&object, [0xee; 32], &NostrVerifier),
+            Err(MultisigError::BelowThreshold { have: 1, need: 2 })
+        ));
+    }
+
+    #[test]
+    fn rejects_outsiders_and_forged_cosignatures() {
+        let editors = editors();
+        let set =
+            SignerSet::new(2, editors[..2].iter().map(|e| e.public_key()).collect()).expect("set");
+        let draft = draft(set);
+        assert!(matches!(
+            cosign(&editors[2], &draft),
+            Err(MultisigError::NotInSignerSet)
+        ));
+
+        let mut forged = cosign(&editors[1], &draft).expect("cosign");
+        forged.signature.0[0] ^= 1;
+        let bytes = finalize_multisig_object(draft, [forged], &editors[0]).expect("finalize");
+        let object = decode_object_v2_cbor(&bytes).expect("decode");
+        assert!(matches!(
+            verify_multisig(&object, editors[0].public_key(), &NostrVerifier),
+            Err(MultisigError::CosignatureInvalid(_))
+        ));
+    }
+}
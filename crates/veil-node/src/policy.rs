@@ -3,6 +3,9 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
+use crate::clock::{Clock, SystemClock};
+use crate::revocation::{Migration, Revocation};
+
 /// Local trust tiers used for prioritization decisions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TrustTier {
@@ -22,6 +25,8 @@ pub struct TrustScoreExplanation {
     pub blocked_override: bool,
     pub trusted_override: bool,
     pub muted_override: bool,
+    #[serde(default)]
+    pub revoked_at_unix_secs: Option<u64>,
     pub direct_endorser_count: usize,
     pub direct_score: f64,
     pub second_hop_endorser_count: usize,
@@ -34,6 +39,10 @@ pub struct WotSummary {
     pub muted: usize,
     pub blocked: usize,
     pub endorsements: usize,
+    #[serde(default)]
+    pub revoked: usize,
+    #[serde(default)]
+    pub migrated: usize,
     pub config: WotConfig,
 }
 
@@ -45,6 +54,14 @@ pub enum EndorsementIngestResult {
     IgnoredStale,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEventIngestResult {
+    Applied,
+    IgnoredDuplicate,
+    /// Migration away from a key that has been revoked.
+    IgnoredRevoked,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ParsedEndorsement {
     pub endorser: [u8; 32],
@@ -65,6 +82,12 @@ pub struct ShardMeta {
 pub trait WotPolicy {
     /// Classifies a publisher pubkey into a trust tier.
     fn classify_publisher(&self, pubkey: [u8; 32], now_step: u64) -> TrustTier;
+    /// Classifies `pubkey` for an object dated `dated_unix_secs`; policies
+    /// without key revocation ignore the date.
+    fn classify_object(&self, pubkey: [u8; 32], dated_unix_secs: u64, now_step: u64) -> TrustTier {
+        let _ = dated_unix_secs;
+        self.classify_publisher(pubkey, now_step)
+    }
     /// Returns forwarding quota fraction for the given tier.
     fn forwarding_quota(&self, tier: TrustTier) -> f32;
     /// Returns storage budget in shard entries for the given tier.
@@ -147,6 +170,35 @@ pub struct LocalWotPolicy {
     blocked: HashSet<[u8; 32]>,
     // endorser -> endorsements they issued
     endorsements_by_endorser: HashMap<[u8; 32], Vec<Endorsement>>,
+    // revoked key -> earliest revocation time (unix seconds)
+    #[serde(default)]
+    revoked: HashMap<[u8; 32], u64>,
+    // old key -> what its migration transferred, so a revocation can undo it
+    #[serde(default)]
+    migrations: HashMap<[u8; 32], MigrationRecord>,
+    // whether stored steps are clock-derived rather than tick counts
+    #[serde(default)]
+    clock_steps: bool,
+    // wall clock override for revocation checks (tests, simulations)
+    #[serde(skip)]
+    wall_clock_secs: Option<u64>,
+}
+
+/// What a migration handed from the old key to the new one.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct MigrationRecord {
+    new: [u8; 32],
+    // explicit tiers the new key gained
+    #[serde(default)]
+    tiers: Vec<TrustTier>,
+    // endorsements the old key had issued; `moved` were added under the new key
+    #[serde(default)]
+    issued: Vec<Endorsement>,
+    #[serde(default)]
+    moved: Vec<[u8; 32]>,
+    // endorsers that gained an edge to the new key, with its step
+    #[serde(default)]
+    inherited: Vec<([u8; 32], u64)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,6 +215,25 @@ struct LocalWotPolicyJson {
     muted: Vec<[u8; 32]>,
     blocked: Vec<[u8; 32]>,
     endorsements: Vec<EndorsementEdge>,
+    #[serde(default)]
+    revocations: Vec<RevocationEntry>,
+    #[serde(default)]
+    migrations: Vec<MigrationEntry>,
+    #[serde(default)]
+    clock_steps: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RevocationEntry {
+    pubkey: [u8; 32],
+    at_unix_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MigrationEntry {
+    old: [u8; 32],
+    #[serde(flatten)]
+    record: MigrationRecord,
 }
 
 #[derive(Debug, Deserialize)]
@@ -267,6 +338,8 @@ impl LocalWotPolicy {
             muted: self.muted.len(),
             blocked: self.blocked.len(),
             endorsements,
+            revoked: self.revoked.len(),
+            migrated: self.migrations.len(),
             config: self.config,
         }
     }
@@ -287,6 +360,35 @@ impl LocalWotPolicy {
         });
     }
 
+    /// Moves endorsement steps recorded by a tick-counting runtime onto the
+    /// clock step scale, shifting them so the latest lands on `now_step`. Does
+    /// nothing once the policy is clock-derived.
+    pub fn adopt_clock_steps(&mut self, now_step: u64) {
+        if self.clock_steps {
+            return;
+        }
+        let latest = self
+            .endorsements_by_endorser
+            .values()
+            .flatten()
+            .map(|e| e.at_step)
+            .max()
+            .unwrap_or(0);
+        let offset = now_step.saturating_sub(latest);
+        for edge in self.endorsements_by_endorser.values_mut().flatten() {
+            edge.at_step = edge.at_step.saturating_add(offset);
+        }
+        for record in self.migrations.values_mut() {
+            for edge in &mut record.issued {
+                edge.at_step = edge.at_step.saturating_add(offset);
+            }
+            for (_, at_step) in &mut record.inherited {
+                *at_step = at_step.saturating_add(offset);
+            }
+        }
+        self.clock_steps = true;
+    }
+
     /// Ingests one endorsement with duplicate/rate-limit/staleness checks.
     pub fn ingest_endorsement(
         &mut self,
@@ -316,9 +418,158 @@ impl LocalWotPolicy {
         EndorsementIngestResult::Applied
     }
 
+    /// Overrides the wall clock used for revocation checks; the system clock
+    /// is used otherwise.
+    pub fn set_wall_clock_secs(&mut self, now_unix_secs: u64) {
+        self.wall_clock_secs = Some(now_unix_secs);
+    }
+
+    fn now_unix_secs(&self) -> u64 {
+        self.wall_clock_secs
+            .unwrap_or_else(|| SystemClock.now_seconds())
+    }
+
+    /// Records a verified key revocation, keeping the earliest time seen.
+    ///
+    /// Any migration away from the key is rolled back whatever its date: the
+    /// key may have been compromised before the holder noticed, and whoever
+    /// held it could have co-signed the migration.
+    pub fn ingest_revocation(&mut self, revocation: &Revocation) -> KeyEventIngestResult {
+        let result = match self.revoked.get_mut(&revocation.revoked) {
+            Some(at) if *at <= revocation.at_unix_secs => KeyEventIngestResult::IgnoredDuplicate,
+            Some(at) => {
+                *at = revocation.at_unix_secs;
+                KeyEventIngestResult::Applied
+            }
+            None => {
+                self.revoked
+                    .insert(revocation.revoked, revocation.at_unix_secs);
+                KeyEventIngestResult::Applied
+            }
+        };
+        self.roll_back_migration(revocation.revoked);
+        result
+    }
+
+    /// Returns the unix time from which `pubkey` is revoked, if any.
+    pub fn revoked_at(&self, pubkey: [u8; 32]) -> Option<u64> {
+        self.revoked.get(&pubkey).copied()
+    }
+
+    /// Returns the key `pubkey` migrated to, if any.
+    pub fn migrated_to(&self, pubkey: [u8; 32]) -> Option<[u8; 32]> {
+        self.migrations.get(&pubkey).map(|record| record.new)
+    }
+
+    /// Applies a verified migration: the new key inherits the old key's
+    /// explicit trust tier, the endorsements it issued, and the endorsements
+    /// pointing at it.
+    ///
+    /// A migration from a revoked key is ignored whatever its date, since
+    /// whoever holds a compromised key could co-sign one.
+    pub fn ingest_migration(&mut self, migration: &Migration) -> KeyEventIngestResult {
+        let (old, new) = (migration.old, migration.new);
+        if self.revoked.contains_key(&old) {
+            return KeyEventIngestResult::IgnoredRevoked;
+        }
+        if self.migrated_to(old) == Some(new) {
+            return KeyEventIngestResult::IgnoredDuplicate;
+        }
+        self.roll_back_migration(old);
+
+        let mut record = MigrationRecord {
+            new,
+            ..MigrationRecord::default()
+        };
+        for (tier, set) in [
+            (TrustTier::Trusted, &mut self.trusted),
+            (TrustTier::Muted, &mut self.muted),
+            (TrustTier::Blocked, &mut self.blocked),
+        ] {
+            if set.contains(&old) && set.insert(new) {
+                record.tiers.push(tier);
+            }
+        }
+        if let Some(issued) = self.endorsements_by_endorser.remove(&old) {
+            let edges = self.endorsements_by_endorser.entry(new).or_default();
+            for e in &issued {
+                if !edges.iter().any(|x| x.publisher == e.publisher) {
+                    edges.push(*e);
+                    record.moved.push(e.publisher);
+                }
+            }
+            record.issued = issued;
+        }
+        for (endorser, edges) in self.endorsements_by_endorser.iter_mut() {
+            let inherited = edges
+                .iter()
+                .filter(|e| e.publisher == old)
+                .map(|e| e.at_step)
+                .max();
+            if let Some(at_step) = inherited {
+                if !edges.iter().any(|e| e.publisher == new) {
+                    edges.push(Endorsement {
+                        publisher: new,
+                        at_step,
+                    });
+                    record.inherited.push((*endorser, at_step));
+                }
+            }
+        }
+        self.migrations.insert(old, record);
+        KeyEventIngestResult::Applied
+    }
+
+    /// Undoes what a migration away from `old` transferred, if one applied.
+    fn roll_back_migration(&mut self, old: [u8; 32]) {
+        let Some(record) = self.migrations.remove(&old) else {
+            return;
+        };
+        let new = record.new;
+        for tier in record.tiers {
+            match tier {
+                TrustTier::Trusted => self.trusted.remove(&new),
+                TrustTier::Muted => self.muted.remove(&new),
+                _ => self.blocked.remove(&new),
+            };
+        }
+        if let Some(edges) = self.endorsements_by_endorser.get_mut(&new) {
+            edges.retain(|e| {
+                !(record.moved.contains(&e.publisher)
+                    && record
+                        .issued
+                        .iter()
+                        .any(|x| x.publisher == e.publisher && x.at_step == e.at_step))
+            });
+        }
+        if !record.issued.is_empty() {
+            self.endorsements_by_endorser
+                .entry(old)
+                .or_default()
+                .extend(record.issued);
+        }
+        for (endorser, at_step) in record.inherited {
+            if let Some(edges) = self.endorsements_by_endorser.get_mut(&endorser) {
+                if let Some(idx) = edges
+                    .iter()
+                    .position(|e| e.publisher == new && e.at_step == at_step)
+                {
+                    edges.remove(idx);
+                }
+            }
+        }
+        self.endorsements_by_endorser
+            .retain(|_, edges| !edges.is_empty());
+    }
+
+    fn is_revoked_by(&self, pubkey: [u8; 32], unix_secs: u64) -> bool {
+        self.revoked_at(pubkey).is_some_and(|at| at <= unix_secs)
+    }
+
     /// Returns deterministic bounded WoT score in `[0.0, 1.0]`.
     pub fn score_publisher(&self, publisher: [u8; 32], now_step: u64) -> f64 {
-        if self.blocked.contains(&publisher) {
+        if self.blocked.contains(&publisher) || self.is_revoked_by(publisher, self.now_unix_secs())
+        {
             return 0.0;
         }
         if self.trusted.contains(&publisher) {
@@ -333,7 +584,10 @@ impl LocalWotPolicy {
             self.direct_trusted_endorsers_score_with_count(publisher, now_step);
         let (second_hop_score, second_hop_endorser_count) =
             self.second_hop_score_with_count(publisher, now_step);
-        let score = if self.blocked.contains(&publisher) {
+        let revoked_at_unix_secs = self.revoked_at(publisher);
+        let score = if self.blocked.contains(&publisher)
+            || self.is_revoked_by(publisher, self.now_unix_secs())
+        {
             0.0
         } else if self.trusted.contains(&publisher) {
             1.0
@@ -349,6 +603,7 @@ impl LocalWotPolicy {
             blocked_override: self.blocked.contains(&publisher),
             trusted_override: self.trusted.contains(&publisher),
             muted_override: self.muted.contains(&publisher),
+            revoked_at_unix_secs,
             direct_endorser_count,
             direct_score,
             second_hop_endorser_count,
@@ -375,6 +630,23 @@ impl LocalWotPolicy {
             muted: self.muted.iter().copied().collect(),
             blocked: self.blocked.iter().copied().collect(),
             endorsements,
+            revocations: self
+                .revoked
+                .iter()
+                .map(|(pubkey, at)| RevocationEntry {
+                    pubkey: *pubkey,
+                    at_unix_secs: *at,
+                })
+                .collect(),
+            migrations: self
+                .migrations
+                .iter()
+                .map(|(old, record)| MigrationEntry {
+                    old: *old,
+                    record: record.clone(),
+                })
+                .collect(),
+            clock_steps: self.clock_steps,
         };
         serde_json::to_string_pretty(&snapshot)
     }
@@ -398,6 +670,18 @@ impl LocalWotPolicy {
             muted: snapshot.muted.into_iter().collect(),
             blocked: snapshot.blocked.into_iter().collect(),
             endorsements_by_endorser,
+            revoked: snapshot
+                .revocations
+                .into_iter()
+                .map(|r| (r.pubkey, r.at_unix_secs))
+                .collect(),
+            migrations: snapshot
+                .migrations
+                .into_iter()
+                .map(|m| (m.old, m.record))
+                .collect(),
+            clock_steps: snapshot.clock_steps,
+            wall_clock_secs: None,
         })
     }
 
@@ -492,7 +776,13 @@ impl LocalWotPolicy {
 
 impl WotPolicy for LocalWotPolicy {
     fn classify_publisher(&self, pubkey: [u8; 32], now_step: u64) -> TrustTier {
-        if self.blocked.contains(&pubkey) {
+        self.classify_object(pubkey, self.now_unix_secs(), now_step)
+    }
+
+    /// Classifies `pubkey` for an object dated `dated_unix_secs`: a revoked
+    /// key is `Blocked` for anything dated at or after its revocation.
+    fn classify_object(&self, pubkey: [u8; 32], dated_unix_secs: u64, now_step: u64) -> TrustTier {
+        if self.blocked.contains(&pubkey) || self.is_revoked_by(pubkey, dated_unix_secs) {
             return TrustTier::Blocked;
         }
         if self.trusted.contains(&pubkey) {
@@ -565,9 +855,10 @@ pub fn fanout_for_tier(base_fanout: usize, tier: TrustTier, policy: &impl WotPol
 #[cfg(test)]
 mod tests {
     use super::{
-        fanout_for_tier, parse_endorsement_payload, EndorsementIngestResult, LocalWotPolicy,
-        ShardMeta, TrustTier, WotPolicy,
+        fanout_for_tier, parse_endorsement_payload, EndorsementIngestResult, KeyEventIngestResult,
+        LocalWotPolicy, ShardMeta, TrustTier, WotPolicy,
     };
+    use crate::revocation::{Migration, Revocation};
     use std::path::PathBuf;
 
     fn temp_path(name: &str) -> PathBuf {
@@ -730,6 +1021,27 @@ mod tests {
         let _ = std::fs::remove_file(file);
     }
 
+    #[test]
+    fn adopting_clock_steps_keeps_endorsement_ages() {
+        let mut policy = LocalWotPolicy::default();
+        let trusted = [0x61; 32];
+        let target = [0x62; 32];
+        policy.trust(trusted);
+        policy.add_endorsement(trusted, target, 900);
+        policy.add_endorsement(trusted, [0x63; 32], 1_000);
+        let before = policy.score_publisher(target, 1_000);
+
+        let now_step = 35_000_000_000;
+        policy.adopt_clock_steps(now_step);
+        assert_eq!(policy.score_publisher(target, now_step), before);
+
+        // The flag survives a snapshot, so a reload does not shift again.
+        let mut imported =
+            LocalWotPolicy::import_json(&policy.export_json().expect("export")).expect("import");
+        imported.adopt_clock_steps(now_step + 5_000);
+        assert_eq!(imported.score_publisher(target, now_step), before);
+    }
+
     #[test]
     fn ingestion_dedupes_and_rate_limits_and_rejects_stale() {
         let mut policy = LocalWotPolicy::default();
@@ -777,4 +1089,133 @@ mod tests {
         assert_eq!(parsed.publisher, [0x22; 32]);
         assert_eq!(parsed.at_step, 123);
     }
+
+    #[test]
+    fn revoked_key_is_blocked_for_objects_dated_after_revocation() {
+        let mut policy = LocalWotPolicy::default();
+        policy.set_wall_clock_secs(100);
+        let key = [0x51; 32];
+        policy.trust(key);
+        let revocation = Revocation {
+            revoked: key,
+            at_unix_secs: 50,
+        };
+        assert_eq!(
+            policy.ingest_revocation(&revocation),
+            KeyEventIngestResult::Applied
+        );
+        assert_eq!(
+            policy.ingest_revocation(&Revocation {
+                revoked: key,
+                at_unix_secs: 80,
+            }),
+            KeyEventIngestResult::IgnoredDuplicate
+        );
+
+        assert_eq!(policy.classify_object(key, 49, 100), TrustTier::Trusted);
+        assert_eq!(policy.classify_object(key, 50, 100), TrustTier::Blocked);
+        assert_eq!(policy.classify_publisher(key, 100), TrustTier::Blocked);
+        assert_eq!(
+            policy.explain_publisher(key, 100).revoked_at_unix_secs,
+            Some(50)
+        );
+
+        let json = policy.export_json().expect("export should succeed");
+        let imported = LocalWotPolicy::import_json(&json).expect("import should succeed");
+        assert_eq!(imported.revoked_at(key), Some(50));
+        assert_eq!(imported.summary().revoked, 1);
+    }
+
+    #[test]
+    fn migration_transfers_tier_and_endorsements() {
+        let mut policy = LocalWotPolicy::default();
+        let (old, new) = ([0x61; 32], [0x62; 32]);
+        let (endorser, endorsed) = ([0x63; 32], [0x64; 32]);
+        policy.trust(old);
+        policy.trust(endorser);
+        policy.add_endorsement(old, endorsed, 90);
+        policy.add_endorsement(endorser, old, 91);
+
+        let migration = Migration {
+            old,
+            new,
+            at_unix_secs: 95,
+        };
+        assert_eq!(
+            policy.ingest_migration(&migration),
+            KeyEventIngestResult::Applied
+        );
+        assert_eq!(
+            policy.ingest_migration(&migration),
+            KeyEventIngestResult::IgnoredDuplicate
+        );
+        assert_eq!(policy.classify_publisher(new, 100), TrustTier::Trusted);
+        assert_eq!(policy.migrated_to(old), Some(new));
+        let json = policy.export_json().expect("export should succeed");
+        let imported = LocalWotPolicy::import_json(&json).expect("import should succeed");
+        assert_eq!(imported.migrated_to(old), Some(new));
+        let explanation = policy.explain_publisher(endorsed, 100);
+        assert_eq!(explanation.direct_endorser_count, 1);
+        assert_eq!(policy.explain_publisher(new, 100).direct_endorser_count, 1);
+
+        // A revoked key cannot hand its trust over, whatever the date.
+        let (stolen, attacker) = ([0x65; 32], [0x66; 32]);
+        policy.trust(stolen);
+        policy.ingest_revocation(&Revocation {
+            revoked: stolen,
+            at_unix_secs: 30,
+        });
+        assert_eq!(
+            policy.ingest_migration(&Migration {
+                old: stolen,
+                new: attacker,
+                at_unix_secs: 20,
+            }),
+            KeyEventIngestResult::IgnoredRevoked
+        );
+        assert_eq!(policy.classify_publisher(attacker, 100), TrustTier::Unknown);
+    }
+
+    #[test]
+    fn revocation_rolls_back_an_earlier_migration() {
+        let mut policy = LocalWotPolicy::default();
+        let (old, new) = ([0x71; 32], [0x72; 32]);
+        let (endorser, endorsed) = ([0x73; 32], [0x74; 32]);
+        policy.trust(old);
+        policy.trust(endorser);
+        policy.add_endorsement(old, endorsed, 90);
+        policy.add_endorsement(endorser, old, 91);
+        let before = policy.export_json().expect("export");
+
+        // Migration ingested first, revocation (dated after it) arrives later.
+        policy.ingest_migration(&Migration {
+            old,
+            new,
+            at_unix_secs: 1_000,
+        });
+        assert_eq!(policy.classify_publisher(new, 100), TrustTier::Trusted);
+        assert_eq!(
+            policy.ingest_revocation(&Revocation {
+                revoked: old,
+                at_unix_secs: 2_000,
+            }),
+            KeyEventIngestResult::Applied
+        );
+
+        assert_eq!(policy.migrated_to(old), None);
+        assert_eq!(policy.classify_publisher(new, 100), TrustTier::Unknown);
+        assert_eq!(policy.explain_publisher(new, 100).direct_endorser_count, 0);
+        assert_eq!(
+            policy
+                .explain_publisher(endorsed, 100)
+                .direct_endorser_count,
+            1
+        );
+        let mut after = LocalWotPolicy::import_json(&before).expect("import");
+        after.ingest_revocation(&Revocation {
+            revoked: old,
+            at_unix_secs: 2_000,
+        });
+        assert_eq!(policy.summary().endorsements, after.summary().endorsements);
+    }
 }
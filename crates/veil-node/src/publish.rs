@@ -1,24 +1,29 @@
 use thiserror::Error;
 use veil_codec::error::CodecError;
 use veil_codec::object::{
-    decode_object_cbor, encode_object_cbor, object_signature_message_digest, ObjectV1, Signature,
-    OBJECT_FLAG_ACK_REQUESTED, OBJECT_FLAG_BATCHED, OBJECT_FLAG_SIGNED, OBJECT_V1_VERSION,
+    decode_object_any, encode_object_cbor, encode_object_v2_cbor, object_signature_message_digest,
+    object_v2_pow_stamp_digest, object_v2_signature_message_digest, ObjectExtension, ObjectV1,
+    ObjectV2, Signature, OBJECT_FLAG_ACK_REQUESTED, OBJECT_FLAG_BATCHED, OBJECT_FLAG_SIGNED,
+    OBJECT_V1_VERSION, OBJECT_V2_VERSION,
 };
-use veil_codec::shard::encode_shard_cbor;
 use veil_core::hash::blake3_32;
 use veil_core::types::{Epoch, Namespace};
 use veil_core::ObjectRoot;
 use veil_core::Tag;
 use veil_crypto::aead::{build_veil_aad, AeadCipher, AeadError};
+use veil_crypto::pow::mint_pow_stamp;
 use veil_crypto::signing::{Signer, SigningError};
-use veil_fec::sharder::{derive_object_root, object_to_shards_with_mode_and_padding, FecError};
+use veil_fec::pooled::{object_to_wire_shards, WireShardSet};
+use veil_fec::profile::{ErasureCodingMode, Profile};
+use veil_fec::sharder::{derive_object_root, FecError};
 use veil_transport::adapter::TransportAdapter;
 
-use crate::ack::register_pending_ack;
+use crate::ack::{register_pending_ack, register_pending_fountain_ack};
 use crate::batch::{FeedBatcher, DEFAULT_MAX_OBJECT_SIZE};
 use crate::config::NodeRuntimeConfig;
+use crate::publisher_log::next_log_entry;
 use crate::runtime::{pump_ack_timeouts, RuntimeStats};
-use crate::state::NodeState;
+use crate::state::{FountainRetrySource, NodeState};
 
 #[derive(Debug, Error)]
 pub enum PublishError {
@@ -180,6 +185,140 @@ pub fn build_encoded_object(
     Ok(encode_object_cbor(&object)?)
 }
 
+/// Builds an `ObjectV2` carrying `extensions` (for example an expiry time)
+/// under the object signature.
+#[allow(clippy::too_many_arguments)]
+pub fn build_encoded_object_v2(
+    payload: &[u8],
+    namespace: Namespace,
+    epoch: Epoch,
+    tag: Tag,
+    encrypt_key: &[u8; 32],
+    now_step: u64,
+    flags: u16,
+    extensions: Vec<ObjectExtension>,
+    cipher: &impl AeadCipher,
+    signer: Option<&impl Signer>,
+) -> Result<Vec<u8>, PublishError> {
+    encode_object_v2(
+        payload,
+        (namespace, epoch, tag),
+        encrypt_key,
+        now_step,
+        flags,
+        extensions,
+        None,
+        cipher,
+        signer,
+    )
+}
+
+/// Like [`build_encoded_object_v2`], but mints a proof-of-work stamp of
+/// `difficulty` bits over the finished object for namespaces that require one.
+#[allow(clippy::too_many_arguments)]
+pub fn build_stamped_object_v2(
+    payload: &[u8],
+    namespace: Namespace,
+    epoch: Epoch,
+    tag: Tag,
+    encrypt_key: &[u8; 32],
+    now_step: u64,
+    flags: u16,
+    extensions: Vec<ObjectExtension>,
+    difficulty: u8,
+    cipher: &impl AeadCipher,
+    signer: Option<&impl Signer>,
+) -> Result<Vec<u8>, PublishError> {
+    encode_object_v2(
+        payload,
+        (namespace, epoch, tag),
+        encrypt_key,
+        now_step,
+        flags,
+        extensions,
+        Some(difficulty),
+        cipher,
+        signer,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn encode_object_v2(
+    payload: &[u8],
+    (namespace, epoch, tag): (Namespace, Epoch, Tag),
+    encrypt_key: &[u8; 32],
+    now_step: u64,
+    flags: u16,
+    extensions: Vec<ObjectExtension>,
+    pow_difficulty: Option<u8>,
+    cipher: &impl AeadCipher,
+    signer: Option<&impl Signer>,
+) -> Result<Vec<u8>, PublishError> {
+    let signed = (flags & OBJECT_FLAG_SIGNED) != 0;
+    if signed && signer.is_none() {
+        return Err(PublishError::MissingSigner);
+    }
+
+    let nonce = derive_object_nonce(tag, namespace, epoch, now_step, payload);
+    let aad = build_veil_aad(tag, namespace, epoch);
+    let envelope = cipher.encrypt(encrypt_key, nonce, &aad, payload)?;
+
+    let mut object = ObjectV2 {
+        version: OBJECT_V2_VERSION,
+        namespace,
+        epoch,
+        flags,
+        tag,
+        object_root: derive_object_root(payload),
+        extensions: Vec::new(),
+        sender_pubkey: None,
+        signature: None,
+        nonce: envelope.nonce,
+        ciphertext: envelope.ciphertext,
+        padding: vec![0_u8; 8],
+    };
+    for extension in extensions {
+        object.set_extension(extension);
+    }
+    let signer = signer.filter(|_| signed);
+    if let Some(signer) = signer {
+        object.sender_pubkey = Some(signer.public_key());
+        object.signature = Some(Signature([0_u8; 64]));
+    }
+    // The stamp covers everything but itself and the signature, which in
+    // turn covers the stamp.
+    if let Some(difficulty) = pow_difficulty {
+        let subject = object_v2_pow_stamp_digest(&object)?;
+        object.set_extension(ObjectExtension::pow_stamp(mint_pow_stamp(
+            &subject, epoch, difficulty,
+        )));
+    }
+    if let Some(signer) = signer {
+        let digest = object_v2_signature_message_digest(&object)?;
+        object.signature = Some(Signature(signer.sign(&digest)?));
+    }
+
+    Ok(encode_object_v2_cbor(&object)?)
+}
+
+/// Chooses the FEC profile for a publish, escalating redundancy from the
+/// node's observed delivery rate when loss-adaptive selection is enabled.
+pub fn select_publish_profile(
+    node: &NodeState,
+    config: &NodeRuntimeConfig,
+    object_len: usize,
+) -> Result<Profile, FecError> {
+    let adaptive = config.loss_adaptive_fec;
+    let profile =
+        match node.fec_delivery_estimate {
+            Some(delivery_rate) if adaptive.enabled => config
+                .fec_profiles
+                .choose_for_delivery_rate(object_len, delivery_rate, adaptive.headroom),
+            _ => config.fec_profiles.choose(object_len),
+        };
+    profile.cloned().ok_or(FecError::ObjectTooLarge)
+}
+
 /// Publishes an encoded VEIL object over fast/fallback lanes and optionally
 /// registers ACK-timeout retry state when `ack_requested` is set.
 #[allow(clippy::too_many_arguments)]
@@ -199,24 +338,27 @@ pub fn publish_encoded_object_multi_lane<AFast: TransportAdapter, AFallback: Tra
             max: DEFAULT_MAX_OBJECT_SIZE,
         });
     }
-    let object = decode_object_cbor(encoded_object)?;
+    let object = decode_object_any(encoded_object)?;
     let wire_root = derive_object_root(encoded_object);
-    let erasure_mode = config.erasure_mode_for_namespace(object.namespace);
-    let shards = object_to_shards_with_mode_and_padding(
+    let erasure_mode = config.erasure_mode_for_namespace(object.namespace());
+    let profile = select_publish_profile(node, config, encoded_object.len())?;
+    let WireShardSet {
+        header,
+        shards: mut shard_bytes,
+    } = object_to_wire_shards(
         encoded_object,
-        object.namespace,
-        object.epoch,
-        object.tag,
+        object.namespace(),
+        object.epoch(),
+        object.tag(),
         wire_root,
         erasure_mode,
         config.bucket_jitter_extra_levels,
+        &profile,
+        &mut node.shard_buffers,
+        &config.sharding,
     )?;
-    let k = shards.first().map(|s| s.header.k as usize).unwrap_or(0);
-
-    let mut shard_bytes = Vec::with_capacity(shards.len());
-    for shard in &shards {
-        shard_bytes.push(encode_shard_cbor(shard)?);
-    }
+    let k = header.k as usize;
+    let shards_total = shard_bytes.len();
 
     let fast_count = shard_bytes.len().min(k.saturating_add(2));
     let fallback_start = fast_count;
@@ -250,15 +392,28 @@ pub fn publish_encoded_object_multi_lane<AFast: TransportAdapter, AFallback: Tra
     }
 
     let mut ack_tracked = false;
-    if (object.flags & OBJECT_FLAG_ACK_REQUESTED) != 0 {
-        let unsent = shard_bytes[fallback_end..].to_vec();
-        register_pending_ack(node, wire_root, unsent, now_step, config.ack_retry_policy());
+    if (object.flags() & OBJECT_FLAG_ACK_REQUESTED) != 0 {
+        if erasure_mode == ErasureCodingMode::Fountain {
+            let source =
+                FountainRetrySource::new(encoded_object.to_vec(), header, fallback_end as u16);
+            register_pending_fountain_ack(
+                node,
+                wire_root,
+                source,
+                now_step,
+                config.ack_retry_policy(),
+            );
+        } else {
+            let unsent = shard_bytes.split_off(fallback_end);
+            register_pending_ack(node, wire_root, unsent, now_step, config.ack_retry_policy());
+        }
         ack_tracked = true;
     }
+    node.shard_buffers.recycle_all(shard_bytes);
 
     Ok(PublishResult {
         object_root: wire_root,
-        shards_total: shard_bytes.len(),
+        shards_total,
         sent_fast,
         sent_fallback,
         failed_fast,
@@ -301,17 +456,47 @@ pub fn publish_queue_tick_multi_lane<
     if items.len() > 1 {
         flags |= OBJECT_FLAG_BATCHED;
     }
-    let encoded_object = build_encoded_object(
-        &payload,
-        params.namespace,
-        params.epoch,
-        params.tag,
-        params.encrypt_key,
-        params.now_step,
-        flags,
-        cipher,
-        signer,
-    )?;
+    let mut log_head = None;
+    if config.publisher_log && signer.is_some() && (flags & OBJECT_FLAG_SIGNED) != 0 {
+        let (entry, head) = next_log_entry(node.log_heads.get(&params.tag).copied(), &payload)
+            .map_err(PublishError::PayloadEncode)?;
+        payload = entry;
+        log_head = Some(head);
+    }
+    // Device keys carry their root delegation, which needs an ObjectV2, and
+    // date their objects so receivers check the certificate as of creation.
+    let delegation = node
+        .device_delegation
+        .as_ref()
+        .filter(|_| (flags & OBJECT_FLAG_SIGNED) != 0);
+    let encoded_object = match delegation {
+        Some(cert) => build_encoded_object_v2(
+            &payload,
+            params.namespace,
+            params.epoch,
+            params.tag,
+            params.encrypt_key,
+            params.now_step,
+            flags,
+            vec![
+                ObjectExtension::delegation(cert)?,
+                ObjectExtension::created_at(node.now_unix_secs()),
+            ],
+            cipher,
+            signer,
+        )?,
+        None => build_encoded_object(
+            &payload,
+            params.namespace,
+            params.epoch,
+            params.tag,
+            params.encrypt_key,
+            params.now_step,
+            flags,
+            cipher,
+            signer,
+        )?,
+    };
 
     let result = publish_encoded_object_multi_lane(
         node,
@@ -323,6 +508,9 @@ pub fn publish_queue_tick_multi_lane<
         params.now_step,
         config,
     )?;
+    if let Some(head) = log_head {
+        node.log_heads.insert(params.tag, head);
+    }
     Ok(Some(result))
 }
 
@@ -378,24 +566,34 @@ pub fn publish_service_tick_multi_lane<
 
 #[cfg(test)]
 mod tests {
+    use veil_codec::delegation::{CAP_LOG, CAP_PUBLISH};
+    use veil_codec::object::ObjectExtension;
     use veil_codec::object::{
         encode_object_cbor, object_signature_message_digest, ObjectV1, Signature,
         OBJECT_FLAG_ACK_REQUESTED, OBJECT_FLAG_SIGNED, OBJECT_V1_VERSION,
     };
+    use veil_codec::shard::decode_shard_cbor;
     use veil_core::{Epoch, Namespace};
     use veil_crypto::aead::{build_veil_aad, AeadCipher, XChaCha20Poly1305Cipher};
-    use veil_crypto::signing::{Ed25519Signer, Signer};
-    use veil_fec::sharder::derive_object_root;
+    use veil_crypto::signing::{
+        Ed25519Signer, Ed25519Verifier, NostrSigner, NostrVerifier, Signer,
+    };
+    use veil_fec::sharder::{derive_object_root, object_to_shards};
     use veil_transport::adapter::InMemoryAdapter;
 
+    use veil_fec::profile::{PROFILE_MICRO, PROFILE_MICRO_RESILIENT};
+
     use super::{
-        publish_encoded_object_multi_lane, publish_queue_tick_multi_lane,
-        publish_service_tick_multi_lane, PublishOptions, PublishQueueTickParams,
-        PublishServiceTickParams,
+        build_encoded_object_v2, publish_encoded_object_multi_lane, publish_queue_tick_multi_lane,
+        publish_service_tick_multi_lane, select_publish_profile, PublishOptions,
+        PublishQueueTickParams, PublishServiceTickParams,
     };
     use crate::ack::{register_pending_ack, AckRetryPolicy};
     use crate::batch::{BatchLimits, FeedBatcher};
-    use crate::config::NodeRuntimeConfig;
+    use crate::config::{LossAdaptiveFecConfig, NodeRuntimeConfig};
+    use crate::identity::{delegate_device, DelegationScope};
+    use crate::publisher_log::next_log_entry;
+    use crate::receive::{decode_batched_payload, receive_shard, ReceiveError, ReceiveEvent};
     use crate::state::NodeState;
 
     fn make_encoded_object(payload: &[u8], tag: [u8; 32], key: &[u8; 32], flags: u16) -> Vec<u8> {
@@ -463,6 +661,47 @@ mod tests {
         assert!(node.pending_acks.contains_key(&out.object_root));
     }
 
+    #[test]
+    fn loss_adaptive_publish_escalates_to_resilient_profile() {
+        let mut node = NodeState {
+            fec_delivery_estimate: Some(0.5),
+            ..NodeState::default()
+        };
+        let mut cfg = NodeRuntimeConfig::default();
+        assert_eq!(
+            select_publish_profile(&node, &cfg, 512).expect("profile"),
+            PROFILE_MICRO,
+            "estimate is ignored while disabled"
+        );
+
+        cfg.loss_adaptive_fec = LossAdaptiveFecConfig {
+            enabled: true,
+            ..LossAdaptiveFecConfig::default()
+        };
+        let mut fast = InMemoryAdapter::default();
+        let mut fallback = InMemoryAdapter::default();
+        let key = [0xAC_u8; 32];
+        let encoded = make_encoded_object(b"lossy lane", [0x12_u8; 32], &key, OBJECT_FLAG_SIGNED);
+        let peers = vec!["peer-a".to_string()];
+
+        let out = publish_encoded_object_multi_lane(
+            &mut node,
+            &mut fast,
+            &mut fallback,
+            &encoded,
+            &peers,
+            &peers,
+            10,
+            &cfg,
+        )
+        .expect("publish should succeed");
+
+        assert_eq!(out.shards_total, PROFILE_MICRO_RESILIENT.n as usize);
+        let (_, bytes) = fast.take_outbound().remove(0);
+        let shard = decode_shard_cbor(&bytes).expect("decode shard");
+        assert_eq!(shard.header.profile_id, PROFILE_MICRO_RESILIENT.id);
+    }
+
     #[test]
     fn publish_skips_ack_tracking_without_flag() {
         let mut node = NodeState::default();
@@ -532,6 +771,313 @@ mod tests {
         assert_eq!(batcher.len(), 0);
     }
 
+    #[test]
+    fn publisher_log_chains_objects_and_receivers_track_heads() {
+        let mut node = NodeState::default();
+        let mut receiver = NodeState::default();
+        let mut fast = InMemoryAdapter::default();
+        let mut fallback = InMemoryAdapter::default();
+        let cfg = NodeRuntimeConfig::builder().publisher_log(true).build();
+        let signer = Ed25519Signer::from_secret([0x66; 32]);
+        let tag = [0x44; 32];
+        let key = [0xAC; 32];
+        receiver.subscriptions.insert(tag);
+        let peers = vec!["peer-a".to_string()];
+
+        for (step, body) in [(1_u64, 1_u8), (2, 2)] {
+            let mut batcher = FeedBatcher::default();
+            batcher.enqueue(vec![body; 16]);
+            publish_queue_tick_multi_lane(
+                &mut node,
+                &mut fast,
+                &mut fallback,
+                &mut batcher,
+                PublishQueueTickParams {
+                    namespace: Namespace(3),
+                    epoch: Epoch(4),
+                    tag,
+                    encrypt_key: &key,
+                    now_step: step,
+                    flags: OBJECT_FLAG_SIGNED,
+                    interactive_flush: true,
+                    fast_peers: &peers,
+                    fallback_peers: &peers,
+                },
+                &cfg,
+                &XChaCha20Poly1305Cipher,
+                Some(&signer),
+            )
+            .expect("publish should succeed");
+
+            let mut delivered = None;
+            for (_, bytes) in fast.take_outbound() {
+                let shard = decode_shard_cbor(&bytes).expect("shard");
+                if let Ok(ReceiveEvent::Delivered { payload, .. }) = receive_shard(
+                    &mut receiver,
+                    &shard,
+                    step,
+                    100,
+                    &key,
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                ) {
+                    delivered = Some(payload);
+                }
+            }
+            let items = decode_batched_payload(&delivered.expect("delivered")).expect("batch");
+            assert_eq!(items, vec![vec![body; 16]]);
+        }
+
+        let head = node.log_heads[&tag];
+        assert_eq!(head.seq, 1);
+        assert_eq!(
+            receiver.publisher_logs.head(&signer.public_key(), &tag),
+            Some(head)
+        );
+    }
+
+    #[test]
+    fn unsigned_payloads_are_not_read_as_log_entries() {
+        let tag = [0x46; 32];
+        let key = [0xAE; 32];
+        let mut receiver = NodeState::default();
+        receiver.subscriptions.insert(tag);
+        let (entry, _) = next_log_entry(None, b"body").expect("entry");
+        let encoded = build_encoded_object_v2(
+            &entry,
+            Namespace(3),
+            Epoch(4),
+            tag,
+            &key,
+            1,
+            0,
+            Vec::new(),
+            &XChaCha20Poly1305Cipher,
+            None::<&Ed25519Signer>,
+        )
+        .expect("object should build");
+        let shards = object_to_shards(
+            &encoded,
+            Namespace(3),
+            Epoch(4),
+            tag,
+            derive_object_root(&encoded),
+        )
+        .expect("shard");
+        let delivered = shards
+            .iter()
+            .filter_map(|shard| {
+                match receive_shard(
+                    &mut receiver,
+                    shard,
+                    1,
+                    100,
+                    &key,
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                ) {
+                    Ok(ReceiveEvent::Delivered { payload, .. }) => Some(payload),
+                    _ => None,
+                }
+            })
+            .next();
+        assert_eq!(delivered, Some(entry));
+        assert_eq!(receiver.publisher_logs.publishers_len(), 0);
+    }
+
+    #[test]
+    fn device_signed_objects_are_attributed_to_the_root() {
+        let root_secret = [0x31; 32];
+        let root_pubkey = NostrSigner::from_secret(root_secret)
+            .expect("root")
+            .public_key();
+        let (device, cert) = delegate_device(
+            &root_secret,
+            0,
+            DelegationScope {
+                capabilities: CAP_PUBLISH | CAP_LOG,
+                namespaces: vec![Namespace(3)],
+                not_before: 1_000,
+                not_after: 2_000,
+            },
+        )
+        .expect("delegate");
+        let mut node = NodeState {
+            device_delegation: Some(cert),
+            ..NodeState::default()
+        };
+        let mut receiver = NodeState {
+            wall_clock_secs: Some(1_500),
+            ..NodeState::default()
+        };
+        let mut fast = InMemoryAdapter::default();
+        let mut fallback = InMemoryAdapter::default();
+        let cfg = NodeRuntimeConfig::builder().publisher_log(true).build();
+        let tag = [0x45; 32];
+        let key = [0xAD; 32];
+        receiver.subscriptions.insert(tag);
+        let peers = vec!["peer-a".to_string()];
+        let now_step = 1;
+
+        let mut publish = |node: &mut NodeState, namespace, step| {
+            let mut batcher = FeedBatcher::default();
+            batcher.enqueue(vec![7; 16]);
+            publish_queue_tick_multi_lane(
+                node,
+                &mut fast,
+                &mut fallback,
+                &mut batcher,
+                PublishQueueTickParams {
+                    namespace,
+                    epoch: Epoch(4),
+                    tag,
+                    encrypt_key: &key,
+                    now_step: step,
+                    flags: OBJECT_FLAG_SIGNED,
+                    interactive_flush: true,
+                    fast_peers: &peers,
+                    fallback_peers: &peers,
+                },
+                &cfg,
+                &XChaCha20Poly1305Cipher,
+                Some(&device),
+            )
+            .expect("publish should succeed");
+            fast.take_outbound()
+        };
+
+        let mut delivered = false;
+        for (_, bytes) in publish(&mut node, Namespace(3), now_step) {
+            let shard = decode_shard_cbor(&bytes).expect("shard");
+            let event = receive_shard(
+                &mut receiver,
+                &shard,
+                now_step,
+                100,
+                &key,
+                &XChaCha20Poly1305Cipher,
+                &NostrVerifier,
+            )
+            .expect("receive");
+            delivered |= matches!(event, ReceiveEvent::Delivered { .. });
+        }
+        assert!(delivered);
+        assert!(receiver.publisher_logs.head(&root_pubkey, &tag).is_some());
+        assert!(receiver
+            .publisher_logs
+            .head(&device.public_key(), &tag)
+            .is_none());
+
+        // Namespaces outside the certificate scope are rejected.
+        let mut rejected = false;
+        for (_, bytes) in publish(&mut node, Namespace(4), now_step + 1) {
+            let shard = decode_shard_cbor(&bytes).expect("shard");
+            rejected |= matches!(
+                receive_shard(
+                    &mut receiver,
+                    &shard,
+                    now_step + 1,
+                    100,
+                    &key,
+                    &XChaCha20Poly1305Cipher,
+                    &NostrVerifier,
+                ),
+                Err(ReceiveError::Delegation(_))
+            );
+        }
+        assert!(rejected);
+
+        // Once the certificate expires, objects created while it was valid
+        // (a mailbox drain, say) still verify; newer ones do not.
+        receiver.wall_clock_secs = Some(5_000);
+        let mut receive_all = |node: &mut NodeState, step| {
+            publish(node, Namespace(3), step)
+                .into_iter()
+                .map(|(_, bytes)| {
+                    let shard = decode_shard_cbor(&bytes).expect("shard");
+                    receive_shard(
+                        &mut receiver,
+                        &shard,
+                        step,
+                        100,
+                        &key,
+                        &XChaCha20Poly1305Cipher,
+                        &NostrVerifier,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        node.wall_clock_secs = Some(1_800);
+        let events = receive_all(&mut node, now_step + 2);
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, Ok(ReceiveEvent::Delivered { .. }))));
+        node.wall_clock_secs = Some(2_500);
+        let events = receive_all(&mut node, now_step + 3);
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, Err(ReceiveError::Delegation(_)))));
+    }
+
+    #[test]
+    fn expired_v2_objects_are_not_delivered() {
+        let tag = [0x45; 32];
+        let key = [0xAD; 32];
+        let signer = Ed25519Signer::from_secret([0x67; 32]);
+        let mut receiver = NodeState::default();
+        receiver.subscriptions.insert(tag);
+        receiver.wall_clock_secs = Some(1_000);
+
+        let mut deliver = |expires_at: u64, now_step: u64| {
+            let encoded = build_encoded_object_v2(
+                b"short-lived",
+                Namespace(3),
+                Epoch(4),
+                tag,
+                &key,
+                expires_at,
+                OBJECT_FLAG_SIGNED,
+                vec![ObjectExtension::expires_at(expires_at)],
+                &XChaCha20Poly1305Cipher,
+                Some(&signer),
+            )
+            .expect("object should build");
+            let shards = object_to_shards(
+                &encoded,
+                Namespace(3),
+                Epoch(4),
+                tag,
+                derive_object_root(&encoded),
+            )
+            .expect("shard");
+            shards
+                .iter()
+                .map(|shard| {
+                    receive_shard(
+                        &mut receiver,
+                        shard,
+                        now_step,
+                        100,
+                        &key,
+                        &XChaCha20Poly1305Cipher,
+                        &Ed25519Verifier,
+                    )
+                    .expect("receive")
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let fresh = deliver(2_000, 1_000);
+        assert!(fresh
+            .iter()
+            .any(|event| matches!(event, ReceiveEvent::Delivered { .. })));
+        let expired = deliver(500, 1_000);
+        assert!(expired.contains(&ReceiveEvent::IgnoredDeleted));
+        assert!(!expired
+            .iter()
+            .any(|event| matches!(event, ReceiveEvent::Delivered { .. })));
+    }
+
     #[test]
     fn publish_queue_tick_requires_signer_when_signed_flag_set() {
         let mut node = NodeState::default();
@@ -0,0 +1,455 @@
+//! Hash-chained per-publisher logs for gap and equivocation detection.
+//!
+//! A publisher that opts in wraps each signed payload in a log entry carrying
+//! a per-tag sequence number and the content root of its previous entry. The
+//! entry sits inside the encrypted payload, so the link is covered by the
+//! object signature. Receivers track per-publisher heads, report missing
+//! sequence numbers and roots so they can be repaired through root-addressed
+//! backfill, and keep evidence when a publisher signs two different entries
+//! at the same log position. Only signed objects carry log entries.
+//!
+//! Tracking is bounded: [`MAX_LOG_ENTRIES`] per log, [`MAX_LOG_TAGS_PER_PUBLISHER`]
+//! logs per publisher and [`MAX_LOGGED_PUBLISHERS`] publishers, forgetting the
+//! least recently updated log first.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::Hash;
+use std::io::Cursor;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+use veil_core::{ObjectRoot, Tag};
+use veil_fec::sharder::derive_object_root;
+
+use crate::backfill::BackfillRequest;
+
+const LOG_ENTRY_MAGIC: &[u8] = b"VEIL_LOG_V1";
+/// Entries retained per publisher log; older entries are forgotten.
+pub const MAX_LOG_ENTRIES: usize = 256;
+/// Tags whose logs are tracked per publisher.
+pub const MAX_LOG_TAGS_PER_PUBLISHER: usize = 4;
+/// Publishers whose logs are tracked.
+pub const MAX_LOGGED_PUBLISHERS: usize = 512;
+/// Equivocation evidence records retained until drained.
+pub const MAX_EQUIVOCATION_EVIDENCE: usize = 64;
+
+/// Position of an entry within its publisher's log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLink {
+    /// Zero-based sequence number within the publisher's log for a tag.
+    pub seq: u64,
+    /// Content root of entry `seq - 1`; `None` only for the first entry.
+    pub prev_root: Option<ObjectRoot>,
+}
+
+/// Wraps `body` in a log entry envelope.
+pub fn wrap_log_entry(link: LogLink, body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = LOG_ENTRY_MAGIC.to_vec();
+    ciborium::ser::into_writer(&link, &mut out).map_err(|e| e.to_string())?;
+    out.extend_from_slice(body);
+    Ok(out)
+}
+
+/// Splits a log entry into its link and body, returning `None` for payloads
+/// that are not log entries.
+pub fn unwrap_log_entry(payload: &[u8]) -> Option<(LogLink, &[u8])> {
+    let rest = payload.strip_prefix(LOG_ENTRY_MAGIC)?;
+    let mut cursor = Cursor::new(rest);
+    let link: LogLink = ciborium::de::from_reader(&mut cursor).ok()?;
+    Some((link, &rest[cursor.position() as usize..]))
+}
+
+/// Publisher-side head of one tag's log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogHead {
+    pub seq: u64,
+    pub root: ObjectRoot,
+}
+
+/// Builds the log entry following `head` and returns it with its new head.
+pub fn next_log_entry(head: Option<LogHead>, body: &[u8]) -> Result<(Vec<u8>, LogHead), String> {
+    let link = match head {
+        Some(head) => LogLink {
+            seq: head.seq + 1,
+            prev_root: Some(head.root),
+        },
+        None => LogLink {
+            seq: 0,
+            prev_root: None,
+        },
+    };
+    let entry = wrap_log_entry(link, body)?;
+    let root = derive_object_root(&entry);
+    Ok((
+        entry,
+        LogHead {
+            seq: link.seq,
+            root,
+        },
+    ))
+}
+
+/// Two distinct signed entries claiming the same log position.
+///
+/// Wire roots identify the conflicting signed objects so they can be fetched
+/// and re-verified by third parties.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquivocationEvidence {
+    pub publisher: [u8; 32],
+    pub tag: Tag,
+    pub seq: u64,
+    pub prev_root: Option<ObjectRoot>,
+    /// Content roots of the previously known and the conflicting entry.
+    pub roots: [ObjectRoot; 2],
+    pub wire_roots: [ObjectRoot; 2],
+}
+
+/// Result of observing one log entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogObservation {
+    /// Entry recorded.
+    Recorded,
+    /// Entry already known.
+    Duplicate,
+    /// Entry conflicts with a known entry; the evidence is also retained.
+    Equivocation(Box<EquivocationEvidence>),
+}
+
+/// Entries missing from a publisher's log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogGaps {
+    /// Runs of missing sequence numbers between the oldest and newest known
+    /// entry; at most one per retained entry however far apart seqs are.
+    pub seq_ranges: Vec<RangeInclusive<u64>>,
+    /// Content roots referenced as predecessors but not yet received.
+    pub roots: Vec<ObjectRoot>,
+}
+
+impl LogGaps {
+    pub fn is_empty(&self) -> bool {
+        self.seq_ranges.is_empty() && self.roots.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct LogRecord {
+    root: ObjectRoot,
+    wire_root: ObjectRoot,
+    prev_root: Option<ObjectRoot>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PublisherLog {
+    entries: BTreeMap<u64, LogRecord>,
+    /// Observation counter value when this log last changed.
+    #[serde(default)]
+    touched: u64,
+}
+
+impl PublisherLog {
+    fn conflict(&self, link: LogLink, root: ObjectRoot) -> Option<LogRecord> {
+        if let Some(existing) = self.entries.get(&link.seq) {
+            return (existing.root != root).then_some(*existing);
+        }
+        let prev = link.prev_root?;
+        self.entries
+            .values()
+            .find(|r| r.prev_root == Some(prev) && r.root != root)
+            .copied()
+    }
+
+    fn gaps(&self) -> LogGaps {
+        let mut gaps = LogGaps::default();
+        let Some(&first) = self.entries.keys().next() else {
+            return gaps;
+        };
+        let seqs = self.entries.keys().copied().collect::<Vec<_>>();
+        gaps.seq_ranges = seqs
+            .windows(2)
+            .filter(|pair| pair[1] > pair[0] + 1)
+            .map(|pair| pair[0] + 1..=pair[1] - 1)
+            .collect();
+        for (&seq, record) in &self.entries {
+            let Some(prev) = record.prev_root else {
+                continue;
+            };
+            let have_prev = seq
+                .checked_sub(1)
+                .and_then(|p| self.entries.get(&p))
+                .is_some_and(|p| p.root == prev);
+            if !have_prev && seq > first {
+                gaps.roots.push(prev);
+            }
+        }
+        gaps
+    }
+}
+
+/// Receiver-side view of observed publisher logs, keyed by publisher public
+/// key and tag.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PublisherLogs {
+    logs: HashMap<[u8; 32], HashMap<Tag, PublisherLog>>,
+    evidence: Vec<EquivocationEvidence>,
+    /// Observation counter used to find the least recently updated log.
+    #[serde(default)]
+    observed: u64,
+    /// Logs that gained entries with missing predecessors since the last
+    /// [`PublisherLogs::take_repair_requests`].
+    #[serde(skip)]
+    needs_repair: HashSet<([u8; 32], Tag)>,
+}
+
+fn least_recent<K: Copy + Eq + Hash, V>(
+    map: &HashMap<K, V>,
+    touched: impl Fn(&V) -> u64,
+) -> Option<K> {
+    map.iter()
+        .min_by_key(|(_, value)| touched(value))
+        .map(|(key, _)| *key)
+}
+
+impl PublisherLogs {
+    /// Records an entry signed by `publisher`.
+    ///
+    /// `root` is the entry's content root and `wire_root` the root of the
+    /// signed object that carried it.
+    pub fn observe(
+        &mut self,
+        publisher: [u8; 32],
+        tag: Tag,
+        link: LogLink,
+        root: ObjectRoot,
+        wire_root: ObjectRoot,
+    ) -> LogObservation {
+        if !self.logs.contains_key(&publisher) && self.logs.len() >= MAX_LOGGED_PUBLISHERS {
+            let newest = |tags: &HashMap<Tag, PublisherLog>| {
+                tags.values().map(|log| log.touched).max().unwrap_or(0)
+            };
+            if let Some(evicted) = least_recent(&self.logs, newest) {
+                self.logs.remove(&evicted);
+            }
+        }
+        let tags = self.logs.entry(publisher).or_default();
+        if !tags.contains_key(&tag) && tags.len() >= MAX_LOG_TAGS_PER_PUBLISHER {
+            if let Some(evicted) = least_recent(tags, |log| log.touched) {
+                tags.remove(&evicted);
+            }
+        }
+        let log = tags.entry(tag).or_default();
+        if log.entries.get(&link.seq).is_some_and(|r| r.root == root) {
+            return LogObservation::Duplicate;
+        }
+        if let Some(existing) = log.conflict(link, root) {
+            let evidence = EquivocationEvidence {
+                publisher,
+                tag,
+                seq: link.seq,
+                prev_root: link.prev_root,
+                roots: [existing.root, root],
+                wire_roots: [existing.wire_root, wire_root],
+            };
+            if self.evidence.len() < MAX_EQUIVOCATION_EVIDENCE {
+                self.evidence.push(evidence.clone());
+            }
+            return LogObservation::Equivocation(Box::new(evidence));
+        }
+        log.entries.insert(
+            link.seq,
+            LogRecord {
+                root,
+                wire_root,
+                prev_root: link.prev_root,
+            },
+        );
+        while log.entries.len() > MAX_LOG_ENTRIES {
+            log.entries.pop_first();
+        }
+        self.observed += 1;
+        log.touched = self.observed;
+        if !log.gaps().roots.is_empty() {
+            self.needs_repair.insert((publisher, tag));
+        }
+        LogObservation::Recorded
+    }
+
+    /// Highest sequence number reachable from the oldest known entry without
+    /// a gap, with its content root.
+    pub fn head(&self, publisher: &[u8; 32], tag: &Tag) -> Option<LogHead> {
+        let log = self.logs.get(publisher)?.get(tag)?;
+        let mut entries = log.entries.iter();
+        let (&first, record) = entries.next()?;
+        let mut head = LogHead {
+            seq: first,
+            root: record.root,
+        };
+        for (&seq, record) in entries {
+            if seq != head.seq + 1 || record.prev_root != Some(head.root) {
+                break;
+            }
+            head = LogHead {
+                seq,
+                root: record.root,
+            };
+        }
+        Some(head)
+    }
+
+    /// Entries missing between the oldest and newest known entry.
+    pub fn gaps(&self, publisher: &[u8; 32], tag: &Tag) -> LogGaps {
+        self.logs
+            .get(publisher)
+            .and_then(|logs| logs.get(tag))
+            .map(PublisherLog::gaps)
+            .unwrap_or_default()
+    }
+
+    /// Builds a root-addressed backfill request for the missing predecessors
+    /// of `publisher`'s log on `tag`, if any are known.
+    pub fn repair_request(
+        &self,
+        publisher: &[u8; 32],
+        tag: &Tag,
+        max_shards: u32,
+    ) -> Option<BackfillRequest> {
+        let gaps = self.gaps(publisher, tag);
+        (!gaps.roots.is_empty()).then(|| BackfillRequest::for_roots(*tag, gaps.roots, max_shards))
+    }
+
+    /// Drains the logs that gained entries with missing predecessors since
+    /// the last call, as root-addressed backfill requests.
+    pub fn take_repair_requests(&mut self, max_shards: u32) -> Vec<BackfillRequest> {
+        std::mem::take(&mut self.needs_repair)
+            .iter()
+            .filter_map(|(publisher, tag)| self.repair_request(publisher, tag, max_shards))
+            .collect()
+    }
+
+    /// Number of publishers whose logs are tracked.
+    pub fn publishers_len(&self) -> usize {
+        self.logs.len()
+    }
+
+    /// Drains retained equivocation evidence.
+    pub fn take_evidence(&mut self) -> Vec<EquivocationEvidence> {
+        std::mem::take(&mut self.evidence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use veil_fec::sharder::derive_object_root;
+
+    use super::{
+        next_log_entry, unwrap_log_entry, LogHead, LogLink, LogObservation, PublisherLogs,
+        MAX_LOGGED_PUBLISHERS, MAX_LOG_TAGS_PER_PUBLISHER,
+    };
+
+    const PUBLISHER: [u8; 32] = [1u8; 32];
+    const TAG: [u8; 32] = [2u8; 32];
+
+    fn chain(bodies: &[&[u8]]) -> Vec<(LogLink, [u8; 32])> {
+        let mut head: Option<LogHead> = None;
+        bodies
+            .iter()
+            .map(|body| {
+                let (entry, next) = next_log_entry(head, body).expect("entry");
+                let (link, unwrapped) = unwrap_log_entry(&entry).expect("unwrap");
+                assert_eq!(unwrapped, *body);
+                assert_eq!(derive_object_root(&entry), next.root);
+                head = Some(next);
+                (link, next.root)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gaps_report_missing_seqs_and_predecessor_roots() {
+        let entries = chain(&[b"a", b"b", b"c", b"d"]);
+        let mut logs = PublisherLogs::default();
+        for i in [0, 1, 3] {
+            let (link, root) = entries[i];
+            assert_eq!(
+                logs.observe(PUBLISHER, TAG, link, root, root),
+                LogObservation::Recorded
+            );
+        }
+        assert_eq!(
+            logs.observe(PUBLISHER, TAG, entries[3].0, entries[3].1, entries[3].1),
+            LogObservation::Duplicate
+        );
+        assert_eq!(logs.head(&PUBLISHER, &TAG).map(|h| h.seq), Some(1));
+
+        let gaps = logs.gaps(&PUBLISHER, &TAG);
+        assert_eq!(gaps.seq_ranges, vec![2..=2]);
+        assert_eq!(gaps.roots, vec![entries[2].1]);
+        let requests = logs.take_repair_requests(64);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].roots, vec![entries[2].1]);
+        assert!(logs.take_repair_requests(64).is_empty());
+
+        logs.observe(PUBLISHER, TAG, entries[2].0, entries[2].1, entries[2].1);
+        assert!(logs.gaps(&PUBLISHER, &TAG).is_empty());
+        assert_eq!(logs.head(&PUBLISHER, &TAG).map(|h| h.seq), Some(3));
+    }
+
+    #[test]
+    fn distant_seqs_report_one_gap_range() {
+        let mut logs = PublisherLogs::default();
+        for seq in [0, u64::MAX] {
+            let link = LogLink {
+                seq,
+                prev_root: None,
+            };
+            logs.observe(PUBLISHER, TAG, link, [seq as u8; 32], [0u8; 32]);
+        }
+        let gaps = logs.gaps(&PUBLISHER, &TAG);
+        assert_eq!(gaps.seq_ranges, vec![1..=u64::MAX - 1]);
+    }
+
+    #[test]
+    fn tracked_publishers_and_tags_are_bounded() {
+        let mut logs = PublisherLogs::default();
+        let first = LogLink {
+            seq: 0,
+            prev_root: None,
+        };
+        for i in 0..=MAX_LOGGED_PUBLISHERS as u32 {
+            let mut publisher = [0u8; 32];
+            publisher[..4].copy_from_slice(&i.to_be_bytes());
+            logs.observe(publisher, TAG, first, [3u8; 32], [3u8; 32]);
+        }
+        assert_eq!(logs.publishers_len(), MAX_LOGGED_PUBLISHERS);
+        // The least recently updated publisher was forgotten.
+        assert!(logs.head(&[0u8; 32], &TAG).is_none());
+
+        for i in 0..=MAX_LOG_TAGS_PER_PUBLISHER as u8 {
+            logs.observe(PUBLISHER, [i; 32], first, [3u8; 32], [3u8; 32]);
+        }
+        assert!(logs.head(&PUBLISHER, &[0u8; 32]).is_none());
+        assert!(logs
+            .head(&PUBLISHER, &[MAX_LOG_TAGS_PER_PUBLISHER as u8; 32])
+            .is_some());
+    }
+
+    #[test]
+    fn forks_are_surfaced_as_equivocation_evidence() {
+        let main = chain(&[b"a", b"b"]);
+        let fork = chain(&[b"a", b"other"]);
+        let mut logs = PublisherLogs::default();
+        for (link, root) in &main {
+            logs.observe(PUBLISHER, TAG, *link, *root, *root);
+        }
+        let LogObservation::Equivocation(evidence) =
+            logs.observe(PUBLISHER, TAG, fork[1].0, fork[1].1, [9u8; 32])
+        else {
+            panic!("expected equivocation");
+        };
+        assert_eq!(evidence.seq, 1);
+        assert_eq!(evidence.prev_root, Some(main[0].1));
+        assert_eq!(evidence.roots, [main[1].1, fork[1].1]);
+        assert_eq!(evidence.wire_roots[1], [9u8; 32]);
+        assert_eq!(logs.take_evidence(), vec![*evidence]);
+        assert!(logs.take_evidence().is_empty());
+    }
+}
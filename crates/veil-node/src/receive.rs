@@ -1,20 +1,36 @@
-use std::collections::HashSet;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 use thiserror::Error;
+use veil_codec::delegation::{CAP_LOG, CAP_PUBLISH};
 use veil_codec::error::CodecError;
+use veil_codec::multisig::SignerSet;
 use veil_codec::object::{
-    decode_object_cbor_prefix, object_signature_message_digest, OBJECT_FLAG_SIGNED,
+    decode_object_any_prefix, decode_object_any_prefix_strict, AnyObject,
+    OBJECT_FLAG_ACK_REQUESTED, OBJECT_FLAG_SIGNED,
 };
-use veil_codec::shard::{encode_shard_cbor, ShardErasureMode, ShardV1};
-use veil_core::{Epoch, Namespace, ObjectRoot, Tag};
+use veil_codec::shard::{encode_shard_cbor, ShardV1};
+use veil_core::{Epoch, Namespace, ObjectRoot, ShardId, Tag};
 use veil_crypto::aead::{build_veil_aad, AeadCipher, AeadError};
-use veil_crypto::signing::{SigningError, Verifier};
+use veil_crypto::pow::verify_pow_stamp;
+use veil_crypto::signing::{verify_batch_each, BatchItem, SigningError, Verifier};
 use veil_fec::profile::ErasureCodingMode;
-use veil_fec::sharder::{reconstruct_object_padded_with_mode, shard_id, FecError};
+use veil_fec::progressive::{DecodeProgress, ProgressiveDecoder};
+use veil_fec::sharder::{shard_id, FecError};
 
 use crate::cache::{cache_put, cache_put_with_policy};
 use crate::config::ProbabilisticForwardingConfig;
+use crate::identity::{verify_delegation, DelegationError};
+use crate::multisig::{verify_multisig, MultisigError};
 use crate::policy::{TrustTier, WotPolicy};
+use crate::publisher_log::unwrap_log_entry;
 use crate::state::NodeState;
+use crate::tombstone::{confirm_pending_tombstone, record_expiry, reject_expired};
+
+/// Objects whose shards may be held awaiting verification at once.
+pub const MAX_HELD_OBJECTS: usize = 1024;
+
+/// Partially received objects decoded at once.
+pub const MAX_INBOX_DECODERS: usize = 4096;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ReceiveEvent {
@@ -24,12 +40,17 @@ pub enum ReceiveEvent {
     IgnoredDuplicate,
     /// Tag not subscribed locally; ignored.
     IgnoredNotSubscribed,
+    /// Object deleted by a tombstone or past its expiry; ignored.
+    IgnoredDeleted,
     /// Shard buffered but object is not yet reconstructable.
     Buffered {
         object_root: ObjectRoot,
         have: usize,
         need: usize,
     },
+    /// Signed object reconstructed; delivery waits for its signature batch
+    /// (see [`flush_signature_batch`]).
+    PendingSignature { object_root: ObjectRoot },
     /// Object reconstructed, verified, decrypted, and delivered.
     Delivered {
         object_root: ObjectRoot,
@@ -57,6 +78,20 @@ pub enum ReceiveError {
     SignatureInvalid,
     #[error("namespace requires signed objects")]
     MissingRequiredSignature,
+    #[error("delegation error: {0}")]
+    Delegation(#[from] DelegationError),
+    #[error("multisig error: {0}")]
+    Multisig(#[from] MultisigError),
+    #[error("namespace requires objects signed by its signer set")]
+    MissingRequiredMultisig,
+    #[error("namespace requires a proof-of-work stamp from this publisher")]
+    MissingPowStamp,
+    #[error("proof-of-work stamp below required difficulty")]
+    InsufficientPowStamp,
+    #[error("proof-of-work stamp does not match object payload")]
+    PowStampRootMismatch,
+    #[error("publisher is blocked for the object's date")]
+    PublisherBlocked,
 }
 
 #[derive(Clone, Copy)]
@@ -73,10 +108,160 @@ pub struct ReceiveCachePolicy<'a> {
     pub bucket_jitter_extra_levels: usize,
     /// Namespaces that require signed objects at ingest.
     pub required_signed_namespaces: Option<&'a HashSet<u16>>,
+    /// Namespaces that require a threshold attestation from a signer set.
+    pub required_multisig_namespaces: Option<&'a HashMap<u16, SignerSet>>,
+    /// Proof-of-work difficulty (leading zero bits) required per namespace
+    /// from unsigned objects and `Unknown` publishers.
+    pub pow_stamp_difficulty: Option<&'a HashMap<u16, u8>>,
     /// Replica-estimate probabilistic forwarding controls.
     pub probabilistic_forwarding: ProbabilisticForwardingConfig,
     /// If true, bypass local tag subscription checks.
     pub accept_all_tags: bool,
+    /// If true, reject reconstructed objects not in deterministic CBOR form.
+    pub strict_cbor: bool,
+    /// If true, defer object signature checks to [`flush_signature_batch`].
+    pub batch_signatures: bool,
+}
+
+/// Reconstructed object awaiting verification and delivery.
+#[derive(Debug)]
+struct ReconstructedObject {
+    object: AnyObject,
+    root: ObjectRoot,
+    sid: ShardId,
+    encoded_shard: Vec<u8>,
+    // shards held back until the object verifies
+    held: Vec<(ShardId, Vec<u8>)>,
+    namespace: u16,
+    now_step: u64,
+    ttl_steps: u64,
+}
+
+#[derive(Debug)]
+struct PendingSignedObject {
+    reconstructed: ReconstructedObject,
+    tier: TrustTier,
+    pubkey: [u8; 32],
+    digest: [u8; 32],
+    sig: [u8; 64],
+}
+
+/// Signed objects awaiting batch verification, and the outcomes of
+/// flushed batches not yet handed to the caller.
+#[derive(Debug, Default)]
+pub struct SignatureBatch {
+    pending: Vec<PendingSignedObject>,
+    ready: VecDeque<Result<ReceiveEvent, ReceiveError>>,
+}
+
+impl SignatureBatch {
+    /// Objects waiting for the next flush.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Step the longest-waiting object was reconstructed at.
+    pub fn oldest_pending_step(&self) -> Option<u64> {
+        self.pending.iter().map(|p| p.reconstructed.now_step).min()
+    }
+
+    /// Takes the next outcome of a flushed batch.
+    pub fn pop_ready(&mut self) -> Option<Result<ReceiveEvent, ReceiveError>> {
+        self.ready.pop_front()
+    }
+
+    /// Whether shards of pending `root` are held until its signature checks.
+    pub fn holds(&self, root: &ObjectRoot) -> bool {
+        self.pending
+            .iter()
+            .any(|p| p.reconstructed.root == *root && !p.reconstructed.held.is_empty())
+    }
+}
+
+/// Ingest requirements `cache_policy` places on `namespace`.
+#[derive(Clone, Copy, Default)]
+struct NamespaceRequirements<'a> {
+    signed: bool,
+    signer_set: Option<&'a SignerSet>,
+    pow_difficulty: Option<u8>,
+}
+
+impl NamespaceRequirements<'_> {
+    /// Shards of namespaces with ingest requirements are cached and
+    /// forwarded only once their object verifies.
+    fn defer_cache(&self) -> bool {
+        self.signed || self.signer_set.is_some() || self.pow_difficulty.is_some()
+    }
+}
+
+fn namespace_requirements<'a>(
+    cache_policy: Option<ReceiveCachePolicy<'a>>,
+    namespace: u16,
+) -> NamespaceRequirements<'a> {
+    let Some(policy) = cache_policy else {
+        return NamespaceRequirements::default();
+    };
+    NamespaceRequirements {
+        signed: policy
+            .required_signed_namespaces
+            .is_some_and(|required| required.contains(&namespace)),
+        signer_set: policy
+            .required_multisig_namespaces
+            .and_then(|required| required.get(&namespace)),
+        pow_difficulty: policy
+            .pow_stamp_difficulty
+            .and_then(|required| required.get(&namespace).copied())
+            .filter(|difficulty| *difficulty > 0),
+    }
+}
+
+/// Date of `object` in UNIX seconds: its claimed creation time, never later
+/// than `received_secs`, or `received_secs` when it carries none.
+fn object_date(object: &AnyObject, received_secs: u64) -> u64 {
+    object
+        .created_at()
+        .map_or(received_secs, |at| at.min(received_secs))
+}
+
+/// Drops the inbox decoder with the fewest shards, and any shards held for it.
+fn evict_least_progressed_decoder(node: &mut NodeState) {
+    let Some(root) = node
+        .inbox
+        .iter()
+        .min_by_key(|(_, decoder)| decoder.have())
+        .map(|(root, _)| *root)
+    else {
+        return;
+    };
+    node.inbox.remove(&root);
+    node.held_shards.remove(&root);
+}
+
+/// Holds a shard of `root` until its object verifies, dropping lapsed holds
+/// and, at `MAX_HELD_OBJECTS`, the hold closest to lapsing.
+fn hold_shard(
+    node: &mut NodeState,
+    root: ObjectRoot,
+    shard: (ShardId, Vec<u8>),
+    now_step: u64,
+    ttl_steps: u64,
+) {
+    if !node.held_shards.contains_key(&root) && node.held_shards.len() >= MAX_HELD_OBJECTS {
+        node.held_shards.retain(|_, held| held.until > now_step);
+        if node.held_shards.len() >= MAX_HELD_OBJECTS {
+            if let Some(oldest) = node
+                .held_shards
+                .iter()
+                .min_by_key(|(_, held)| held.until)
+                .map(|(root, _)| *root)
+            {
+                node.held_shards.remove(&oldest);
+            }
+        }
+    }
+    let held = node.held_shards.entry(root).or_default();
+    held.until = held.until.max(now_step.saturating_add(ttl_steps));
+    held.shards.push(shard);
 }
 
 /// Decodes a queue-batched app payload into its original item list.
@@ -119,13 +304,17 @@ pub fn receive_shard_with_policy(
     cache_policy: Option<ReceiveCachePolicy<'_>>,
 ) -> Result<ReceiveEvent, ReceiveError> {
     let sid = shard_id(shard)?;
-    let require_signed_namespace = cache_policy
-        .and_then(|p| p.required_signed_namespaces)
-        .map(|required| required.contains(&shard.header.namespace.0))
-        .unwrap_or(false);
+    let defer_cache = namespace_requirements(cache_policy, shard.header.namespace.0).defer_cache();
     if node.is_shard_seen(&sid, now_step) {
         return Ok(ReceiveEvent::IgnoredDuplicate);
     }
+    if node
+        .tombstones
+        .suppresses(&shard.header.object_root, now_step)
+    {
+        node.mark_shard_seen(sid, now_step + ttl_steps);
+        return Ok(ReceiveEvent::IgnoredDeleted);
+    }
     let accept_all_tags = cache_policy.map(|p| p.accept_all_tags).unwrap_or(false);
     if !accept_all_tags && !node.subscriptions.contains(&shard.header.tag) {
         node.mark_shard_seen(sid, now_step + ttl_steps);
@@ -134,7 +323,7 @@ pub fn receive_shard_with_policy(
     node.mark_shard_seen(sid, now_step + ttl_steps);
 
     let encoded_shard = encode_shard_cbor(shard)?;
-    if !require_signed_namespace {
+    if !defer_cache {
         match cache_policy {
             Some(p) => cache_put_with_policy(
                 node,
@@ -151,62 +340,314 @@ pub fn receive_shard_with_policy(
     }
 
     let root = shard.header.object_root;
-    let root_inbox = node.inbox.entry(root).or_default();
-    root_inbox.insert(shard.header.index, shard.clone());
-
-    let have = root_inbox.len();
-    let need = shard.header.k as usize;
-    if have < need {
-        return Ok(ReceiveEvent::Buffered {
-            object_root: root,
-            have,
-            need,
-        });
+    if !node.inbox.contains_key(&root) && node.inbox.len() >= MAX_INBOX_DECODERS {
+        evict_least_progressed_decoder(node);
     }
-
-    let collected: Vec<_> = root_inbox.values().cloned().collect();
-    let erasure_mode = match shard.header.erasure_mode {
-        ShardErasureMode::Systematic => ErasureCodingMode::Systematic,
-        ShardErasureMode::HardenedNonSystematic => ErasureCodingMode::HardenedNonSystematic,
+    let decoder = match node.inbox.entry(root) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(ProgressiveDecoder::new(&shard.header)?),
+    };
+    let progress = match decoder.push(shard)? {
+        DecodeProgress::Complete(bytes) => Ok(bytes),
+        DecodeProgress::NeedMore { have, need } => Err((have, need)),
+        DecodeProgress::Redundant => Err((decoder.have(), decoder.need())),
+    };
+    let reconstructed = match progress {
+        Ok(bytes) => bytes,
+        Err((have, need)) => {
+            if defer_cache {
+                hold_shard(node, root, (sid, encoded_shard), now_step, ttl_steps);
+            }
+            return Ok(ReceiveEvent::Buffered {
+                object_root: root,
+                have,
+                need,
+            });
+        }
+    };
+    node.inbox.remove(&root);
+    let held = node
+        .held_shards
+        .remove(&root)
+        .map(|held| held.shards)
+        .unwrap_or_default();
+    let strict_cbor = cache_policy.map(|p| p.strict_cbor).unwrap_or(true);
+    let (object, _) = if strict_cbor {
+        decode_object_any_prefix_strict(&reconstructed)?
+    } else {
+        decode_object_any_prefix(&reconstructed)?
+    };
+    let mut reconstructed = ReconstructedObject {
+        object,
+        root,
+        sid,
+        encoded_shard,
+        held,
+        namespace: shard.header.namespace.0,
+        now_step,
+        ttl_steps,
     };
-    let reconstructed = reconstruct_object_padded_with_mode(&collected, root, erasure_mode)?;
-    let (object, _) = decode_object_cbor_prefix(&reconstructed)?;
 
-    if require_signed_namespace && (object.flags & OBJECT_FLAG_SIGNED) == 0 {
-        node.inbox.remove(&root);
+    // ACK-requested objects are verified inline so the ACK can still go
+    // back to the peer that completed them.
+    let flags = reconstructed.object.flags();
+    let deferrable = (flags & OBJECT_FLAG_SIGNED) != 0 && (flags & OBJECT_FLAG_ACK_REQUESTED) == 0;
+    if let Some(policy) = cache_policy.filter(|p| p.batch_signatures && deferrable) {
+        if defer_cache {
+            let completing = (sid, reconstructed.encoded_shard.clone());
+            reconstructed.held.push(completing);
+        }
+        let object = &reconstructed.object;
+        if let (Some(pubkey), Some(sig)) = (object.sender_pubkey(), object.signature()) {
+            let pending = PendingSignedObject {
+                tier: policy.tier,
+                pubkey,
+                digest: object.signature_message_digest()?,
+                sig: sig.0,
+                reconstructed,
+            };
+            node.signature_batch.pending.push(pending);
+            return Ok(ReceiveEvent::PendingSignature { object_root: root });
+        }
+    }
+    finish_object(
+        node,
+        reconstructed,
+        decrypt_key,
+        cipher,
+        verifier,
+        cache_policy,
+        false,
+    )
+}
+
+/// Verifies deferred signed objects as one batch and delivers the valid
+/// ones, falling back to individual checks to reject the forged ones.
+///
+/// Outcomes queue on `node.signature_batch` in arrival order; take them with
+/// [`SignatureBatch::pop_ready`]. `cache_policy` is applied with each
+/// object's original trust tier. Returns the number of objects verified.
+pub fn flush_signature_batch(
+    node: &mut NodeState,
+    decrypt_key: &[u8; 32],
+    cipher: &impl AeadCipher,
+    verifier: &impl Verifier,
+    cache_policy: Option<ReceiveCachePolicy<'_>>,
+) -> usize {
+    let pending = std::mem::take(&mut node.signature_batch.pending);
+    let items: Vec<BatchItem<'_>> = pending
+        .iter()
+        .map(|p| BatchItem {
+            pubkey: p.pubkey,
+            msg: &p.digest,
+            sig: p.sig,
+        })
+        .collect();
+    let verdicts = verify_batch_each(verifier, &items);
+    drop(items);
+    let flushed = pending.len();
+    for (entry, valid) in pending.into_iter().zip(verdicts) {
+        let result = if valid {
+            let policy = cache_policy.map(|p| ReceiveCachePolicy {
+                tier: entry.tier,
+                ..p
+            });
+            finish_object(
+                node,
+                entry.reconstructed,
+                decrypt_key,
+                cipher,
+                verifier,
+                policy,
+                true,
+            )
+        } else {
+            Err(ReceiveError::SignatureInvalid)
+        };
+        node.signature_batch.ready.push_back(result);
+    }
+    flushed
+}
+
+/// Checks signatures and policy, then decrypts and delivers an object.
+fn finish_object(
+    node: &mut NodeState,
+    reconstructed: ReconstructedObject,
+    decrypt_key: &[u8; 32],
+    cipher: &impl AeadCipher,
+    verifier: &impl Verifier,
+    cache_policy: Option<ReceiveCachePolicy<'_>>,
+    signature_checked: bool,
+) -> Result<ReceiveEvent, ReceiveError> {
+    let ReconstructedObject {
+        object,
+        root,
+        sid,
+        encoded_shard,
+        held,
+        namespace,
+        now_step,
+        ttl_steps,
+    } = reconstructed;
+    let requirements = namespace_requirements(cache_policy, namespace);
+    let required_signer_set = requirements.signer_set;
+    let flags = object.flags();
+
+    if requirements.signed && (flags & OBJECT_FLAG_SIGNED) == 0 {
         return Err(ReceiveError::MissingRequiredSignature);
     }
+    if required_signer_set.is_some() && (flags & OBJECT_FLAG_SIGNED) == 0 {
+        return Err(ReceiveError::MissingRequiredMultisig);
+    }
 
-    if (object.flags & OBJECT_FLAG_SIGNED) != 0 {
+    // Certificates and publishers are judged as of the object's date, so
+    // backfilled objects outlive the certificate or key that signed them.
+    let dated_secs = object_date(&object, node.now_unix_secs());
+    // Device-signed objects are attributed to the root their certificate
+    // chains to; the author may extend publisher logs only if delegated to.
+    let mut log_author = None;
+    let mut publisher = None;
+    let mut signer_author = None;
+    if (flags & OBJECT_FLAG_SIGNED) != 0 {
         let pubkey = object
-            .sender_pubkey
+            .sender_pubkey()
             .ok_or(ReceiveError::MissingSignatureFields)?;
         let sig = object
-            .signature
-            .as_ref()
+            .signature()
             .ok_or(ReceiveError::MissingSignatureFields)?
             .0;
-        let digest = object_signature_message_digest(&object)?;
-        let sig_ok = verifier.verify(pubkey, &digest, sig)?;
-        if !sig_ok {
-            return Err(ReceiveError::SignatureInvalid);
+        if !signature_checked {
+            let digest = object.signature_message_digest()?;
+            let sig_ok = verifier.verify(pubkey, &digest, sig)?;
+            if !sig_ok {
+                return Err(ReceiveError::SignatureInvalid);
+            }
+        }
+        let (author, may_log) = match object.delegation() {
+            Some(cert) => {
+                let root_key = verify_delegation(
+                    &cert,
+                    pubkey,
+                    object.namespace(),
+                    CAP_PUBLISH,
+                    dated_secs,
+                    verifier,
+                )?;
+                (root_key, cert.grants(CAP_LOG))
+            }
+            None => (pubkey, true),
+        };
+        signer_author = Some(author);
+        // Objects of a signer set are attributed to the set itself.
+        let author = match &object {
+            AnyObject::V2(v2) if v2.multisig().is_some() => {
+                let set = verify_multisig(v2, author, verifier)?;
+                if required_signer_set.is_some_and(|required| *required != set) {
+                    return Err(ReceiveError::MissingRequiredMultisig);
+                }
+                set.set_id()
+            }
+            _ if required_signer_set.is_some() => {
+                return Err(ReceiveError::MissingRequiredMultisig)
+            }
+            _ => author,
+        };
+        if node.tombstones.has_pending(&root)
+            && confirm_pending_tombstone(node, &root, author, object.namespace())
+        {
+            return Ok(ReceiveEvent::IgnoredDeleted);
+        }
+        publisher = Some(author);
+        log_author = Some(author).filter(|_| may_log);
+    }
+
+    // A revoked key keeps what it published before its revocation.
+    let publisher_tier = publisher
+        .zip(cache_policy)
+        .map(|(author, p)| p.wot_policy.classify_object(author, dated_secs, now_step));
+    if publisher_tier == Some(TrustTier::Blocked) {
+        return Err(ReceiveError::PublisherBlocked);
+    }
+    // Unsigned objects and unknown publishers pay for ingest with a stamp.
+    let stamp_difficulty = requirements
+        .pow_difficulty
+        .filter(|_| publisher.is_none() || publisher_tier == Some(TrustTier::Unknown));
+    if let Some(difficulty) = stamp_difficulty {
+        let stamp = object.pow_stamp().ok_or(ReceiveError::MissingPowStamp)?;
+        let subject = object
+            .pow_stamp_digest()?
+            .ok_or(ReceiveError::MissingPowStamp)?;
+        if !verify_pow_stamp(&subject, object.epoch(), stamp, difficulty) {
+            return Err(ReceiveError::InsufficientPowStamp);
+        }
+    }
+    let expires_at = object.expires_at();
+    if expires_at.is_some_and(|at| reject_expired(node, root, at, now_step, ttl_steps)) {
+        return Ok(ReceiveEvent::IgnoredDeleted);
+    }
+
+    let (tag, namespace, epoch) = (object.tag(), object.namespace(), object.epoch());
+    // Verified objects are cached and forwarded before decryption, so relays
+    // that cannot read them still carry them.
+    if requirements.defer_cache() {
+        let held_shards = held.into_iter().filter(|(held_sid, _)| *held_sid != sid);
+        for (shard_sid, bytes) in held_shards.chain([(sid, encoded_shard)]) {
+            match cache_policy {
+                Some(p) => cache_put_with_policy(
+                    node,
+                    shard_sid,
+                    bytes.clone(),
+                    now_step,
+                    ttl_steps,
+                    p.tier,
+                    p.max_cache_shards,
+                    p.wot_policy,
+                ),
+                None => cache_put(node, shard_sid, bytes.clone(), now_step, ttl_steps),
+            }
+            node.released_shards.push((tag, bytes));
         }
     }
+    if let Some(author) = signer_author.filter(|_| node.shard_index.contains_key(&root)) {
+        node.tombstones.record_author(root, author, namespace);
+    }
 
-    let aad = build_veil_aad(object.tag, object.namespace, object.epoch);
-    let payload = match cipher.decrypt(decrypt_key, object.nonce, &aad, &object.ciphertext) {
+    let (nonce, ciphertext) = (object.nonce(), object.ciphertext());
+    let aad = build_veil_aad(tag, namespace, epoch);
+    let payload = match cipher.decrypt(decrypt_key, nonce, &aad, ciphertext) {
         Ok(p) => p,
-        Err(e) if (object.flags & veil_codec::object::OBJECT_FLAG_PUBLIC) != 0 => {
+        Err(e) if (flags & veil_codec::object::OBJECT_FLAG_PUBLIC) != 0 => {
             cipher
-                .decrypt(&[0u8; 32], object.nonce, &aad, &object.ciphertext)
+                .decrypt(&[0u8; 32], nonce, &aad, ciphertext)
                 .map_err(|_| e)?
         }
         Err(e) => return Err(e.into()),
     };
+    let object_root = object.object_root();
+    // A stamp only binds the content its object root commits to; relays
+    // cannot see the content, so only delivering nodes check this.
+    if stamp_difficulty.is_some() && veil_fec::sharder::derive_object_root(&payload) != object_root
+    {
+        return Err(ReceiveError::PowStampRootMismatch);
+    }
+
+    // Log entries of signed objects are tracked per signer and delivered
+    // without the envelope; unsigned payloads are delivered as sent.
+    let log_entry = publisher.and_then(|_| unwrap_log_entry(&payload));
+    let payload = match log_entry {
+        Some((link, body)) => {
+            if let Some(pubkey) = log_author {
+                node.publisher_logs
+                    .observe(pubkey, tag, link, object_root, root);
+            }
+            body.to_vec()
+        }
+        None => payload,
+    };
 
     // Index content roots for faster lookup
-    if object.object_root != root {
-        node.content_index.insert(object.object_root, root);
+    if object_root != root {
+        node.content_index.insert(object_root, root);
     }
     if let Ok(batch) = decode_batched_payload(&payload) {
         for item in batch {
@@ -216,32 +657,17 @@ pub fn receive_shard_with_policy(
             }
         }
     }
-
-    if require_signed_namespace {
-        match cache_policy {
-            Some(p) => cache_put_with_policy(
-                node,
-                sid,
-                encoded_shard,
-                now_step,
-                ttl_steps,
-                p.tier,
-                p.max_cache_shards,
-                p.wot_policy,
-            ),
-            None => cache_put(node, sid, encoded_shard, now_step, ttl_steps),
-        }
+    if let Some(expires_at) = expires_at {
+        record_expiry(node, root, expires_at);
     }
 
-    node.inbox.remove(&root);
-
     Ok(ReceiveEvent::Delivered {
         object_root: root,
         payload,
-        namespace: object.namespace,
-        epoch: object.epoch,
-        tag: object.tag,
-        flags: object.flags,
+        namespace,
+        epoch,
+        tag,
+        flags,
     })
 }
 
@@ -251,6 +677,7 @@ mod tests {
         encode_object_cbor, object_signature_message_digest, ObjectV1, Signature,
         OBJECT_FLAG_SIGNED, OBJECT_V1_VERSION,
     };
+    use veil_codec::shard::ShardV1;
     use veil_core::{Epoch, Namespace};
     use veil_crypto::aead::{build_veil_aad, AeadCipher, XChaCha20Poly1305Cipher};
     use veil_crypto::signing::{Ed25519Signer, Ed25519Verifier, Signer};
@@ -258,8 +685,8 @@ mod tests {
     use veil_fec::sharder::{derive_object_root, object_to_shards};
 
     use super::{
-        decode_batched_payload, receive_shard, receive_shard_with_policy, ReceiveCachePolicy,
-        ReceiveEvent,
+        decode_batched_payload, hold_shard, receive_shard, receive_shard_with_policy,
+        ReceiveCachePolicy, ReceiveEvent, MAX_HELD_OBJECTS, MAX_INBOX_DECODERS,
     };
     use crate::policy::{LocalWotPolicy, TrustTier, WotConfig};
     use crate::state::NodeState;
@@ -371,6 +798,46 @@ mod tests {
         assert!(!node.inbox.contains_key(&wire_root));
     }
 
+    #[test]
+    fn buffers_progressively_from_parity_shards() {
+        let mut node = NodeState::default();
+        let tag = [0x11_u8; 32];
+        node.subscriptions.insert(tag);
+
+        let (namespace, epoch) = (Namespace(7), Epoch(9));
+        let decrypt_key = [0xAB_u8; 32];
+        let payload = b"decoded shard by shard".to_vec();
+        let encoded_object =
+            make_signed_encrypted_object(&payload, tag, namespace, epoch, &decrypt_key);
+        let wire_root = derive_object_root(&encoded_object);
+        let shards = object_to_shards(&encoded_object, namespace, epoch, tag, wire_root)
+            .expect("object should shard");
+        let k = shards[0].header.k as usize;
+
+        let cipher = XChaCha20Poly1305Cipher;
+        let verifier = Ed25519Verifier;
+        for (i, shard) in shards.iter().rev().take(k).enumerate() {
+            let event = receive_shard(&mut node, shard, 10, 30, &decrypt_key, &cipher, &verifier)
+                .expect("receive should work");
+            if i + 1 < k {
+                assert_eq!(
+                    event,
+                    ReceiveEvent::Buffered {
+                        object_root: wire_root,
+                        have: i + 1,
+                        need: k,
+                    }
+                );
+                assert_eq!(node.inbox[&wire_root].have(), i + 1);
+            } else {
+                assert!(
+                    matches!(event, ReceiveEvent::Delivered { payload: ref p, .. } if *p == payload)
+                );
+            }
+        }
+        assert!(node.inbox.is_empty());
+    }
+
     #[test]
     fn ignores_when_not_subscribed() {
         let mut node = NodeState::default();
@@ -459,8 +926,12 @@ mod tests {
             erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
             bucket_jitter_extra_levels: 0,
             required_signed_namespaces: None,
+            required_multisig_namespaces: None,
+            pow_stamp_difficulty: None,
+            batch_signatures: false,
             probabilistic_forwarding: crate::config::ProbabilisticForwardingConfig::default(),
             accept_all_tags: false,
+            strict_cbor: true,
         };
 
         let first_obj =
@@ -492,8 +963,12 @@ mod tests {
                 erasure_coding_mode: cache_policy.erasure_coding_mode,
                 bucket_jitter_extra_levels: cache_policy.bucket_jitter_extra_levels,
                 required_signed_namespaces: cache_policy.required_signed_namespaces,
+                required_multisig_namespaces: cache_policy.required_multisig_namespaces,
+                pow_stamp_difficulty: cache_policy.pow_stamp_difficulty,
+                batch_signatures: cache_policy.batch_signatures,
                 probabilistic_forwarding: cache_policy.probabilistic_forwarding,
                 accept_all_tags: cache_policy.accept_all_tags,
+                strict_cbor: cache_policy.strict_cbor,
             }),
         )
         .expect("receive should work");
@@ -546,8 +1021,12 @@ mod tests {
             erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
             bucket_jitter_extra_levels: 0,
             required_signed_namespaces: Some(&required),
+            required_multisig_namespaces: None,
+            pow_stamp_difficulty: None,
+            batch_signatures: false,
             probabilistic_forwarding: crate::config::ProbabilisticForwardingConfig::default(),
             accept_all_tags: false,
+            strict_cbor: true,
         };
 
         let mut got_required_sig_err = false;
@@ -572,6 +1051,305 @@ mod tests {
         assert!(!node.inbox.contains_key(&wire_root));
     }
 
+    #[test]
+    fn multisig_namespace_policy_requires_signer_threshold() {
+        use crate::multisig::{cosign, finalize_multisig_object, MultisigError};
+        use crate::publish::build_encoded_object_v2;
+        use veil_codec::multisig::{MultisigAttestation, SignerSet};
+        use veil_codec::object::{decode_object_v2_cbor, ObjectExtension};
+
+        let tag = [0x53_u8; 32];
+        let namespace = Namespace(53);
+        let epoch = Epoch(8);
+        let key = [0xAB_u8; 32];
+        let editors: Vec<Ed25519Signer> = (1..=3)
+            .map(|i| Ed25519Signer::from_secret([i; 32]))
+            .collect();
+        let set = SignerSet::new(2, editors.iter().map(|e| e.public_key()).collect())
+            .expect("signer set should build");
+        let extension = ObjectExtension::multisig(&MultisigAttestation::unsigned(set.clone()))
+            .expect("extension should encode");
+        let draft = build_encoded_object_v2(
+            b"board statement",
+            namespace,
+            epoch,
+            tag,
+            &key,
+            1,
+            OBJECT_FLAG_SIGNED,
+            vec![extension],
+            &XChaCha20Poly1305Cipher,
+            Some(&editors[0]),
+        )
+        .expect("draft should build");
+        let draft = decode_object_v2_cbor(&draft).expect("draft should decode");
+        let cosignature = cosign(&editors[2], &draft).expect("cosign should work");
+        let two_of_three = finalize_multisig_object(draft.clone(), [cosignature], &editors[0])
+            .expect("object should finalize");
+        let one_of_three =
+            finalize_multisig_object(draft, [], &editors[0]).expect("object should finalize");
+
+        let mut required = std::collections::HashMap::new();
+        required.insert(namespace.0, set);
+        let wot_policy = LocalWotPolicy::default();
+        let policy = ReceiveCachePolicy {
+            tier: TrustTier::Unknown,
+            max_cache_shards: 100,
+            wot_policy: &wot_policy,
+            erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
+            bucket_jitter_extra_levels: 0,
+            required_signed_namespaces: None,
+            required_multisig_namespaces: Some(&required),
+            pow_stamp_difficulty: None,
+            batch_signatures: false,
+            probabilistic_forwarding: crate::config::ProbabilisticForwardingConfig::default(),
+            accept_all_tags: false,
+            strict_cbor: true,
+        };
+        let receive_all = |node: &mut NodeState, encoded_object: &[u8]| {
+            let root = derive_object_root(encoded_object);
+            let shards = object_to_shards(encoded_object, namespace, epoch, tag, root)
+                .expect("object should shard");
+            let mut last = None;
+            for shard in &shards {
+                last = Some(receive_shard_with_policy(
+                    node,
+                    shard,
+                    1,
+                    100,
+                    &key,
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                    Some(policy),
+                ));
+                if !matches!(last, Some(Ok(ReceiveEvent::Buffered { .. }))) {
+                    break;
+                }
+            }
+            last.expect("object should have shards")
+        };
+
+        let mut node = NodeState::default();
+        node.subscriptions.insert(tag);
+        assert!(matches!(
+            receive_all(&mut node, &one_of_three),
+            Err(super::ReceiveError::Multisig(
+                MultisigError::BelowThreshold { have: 1, need: 2 }
+            ))
+        ));
+        assert!(node.cache.is_empty());
+
+        let mut node = NodeState::default();
+        node.subscriptions.insert(tag);
+        let event = receive_all(&mut node, &two_of_three).expect("receive should succeed");
+        assert!(
+            matches!(event, ReceiveEvent::Delivered { ref payload, .. } if payload == b"board statement")
+        );
+        assert!(!node.cache.is_empty());
+
+        let mut node = NodeState::default();
+        node.subscriptions.insert(tag);
+        let unsigned = make_unsigned_encrypted_object(b"payload", tag, namespace, epoch, &key);
+        assert!(matches!(
+            receive_all(&mut node, &unsigned),
+            Err(super::ReceiveError::MissingRequiredMultisig)
+        ));
+    }
+
+    #[test]
+    fn pow_stamp_policy_gates_unknown_publishers() {
+        use crate::publish::{build_encoded_object_v2, build_stamped_object_v2};
+        use veil_codec::object::{decode_object_any, ObjectExtension};
+
+        let tag = [0x54_u8; 32];
+        let namespace = Namespace(54);
+        let epoch = Epoch(9);
+        let key = [0xAC_u8; 32];
+        let publisher = Ed25519Signer::from_secret([0x21; 32]);
+        let build = |now_step, extensions| {
+            build_encoded_object_v2(
+                b"fresh key post",
+                namespace,
+                epoch,
+                tag,
+                &key,
+                now_step,
+                OBJECT_FLAG_SIGNED,
+                extensions,
+                &XChaCha20Poly1305Cipher,
+                Some(&publisher),
+            )
+            .expect("object should build")
+        };
+        let unstamped = build(1, Vec::new());
+        let stamped = build_stamped_object_v2(
+            b"fresh key post",
+            namespace,
+            epoch,
+            tag,
+            &key,
+            1,
+            OBJECT_FLAG_SIGNED,
+            Vec::new(),
+            4,
+            &XChaCha20Poly1305Cipher,
+            Some(&publisher),
+        )
+        .expect("object should build");
+        // The same payload re-encrypted under another nonce cannot reuse the
+        // stamp (it carries over by chance 1 in 16; these inputs do not).
+        let nonce = decode_object_any(&stamped)
+            .expect("decode")
+            .pow_stamp()
+            .expect("stamp");
+        let replayed = build(2, vec![ObjectExtension::pow_stamp(nonce)]);
+
+        let mut difficulty = std::collections::HashMap::new();
+        difficulty.insert(namespace.0, 4);
+        let receive_all = |wot_policy: &LocalWotPolicy, encoded_object: &[u8]| {
+            let policy = ReceiveCachePolicy {
+                tier: TrustTier::Unknown,
+                max_cache_shards: 100,
+                wot_policy,
+                erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
+                bucket_jitter_extra_levels: 0,
+                required_signed_namespaces: None,
+                required_multisig_namespaces: None,
+                pow_stamp_difficulty: Some(&difficulty),
+                batch_signatures: false,
+                probabilistic_forwarding: crate::config::ProbabilisticForwardingConfig::default(),
+                accept_all_tags: false,
+                strict_cbor: true,
+            };
+            let mut node = NodeState::default();
+            node.subscriptions.insert(tag);
+            let root = derive_object_root(encoded_object);
+            let shards = object_to_shards(encoded_object, namespace, epoch, tag, root)
+                .expect("object should shard");
+            let mut last = None;
+            for shard in &shards {
+                last = Some(receive_shard_with_policy(
+                    &mut node,
+                    shard,
+                    1,
+                    100,
+                    &key,
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                    Some(policy),
+                ));
+                if !matches!(last, Some(Ok(ReceiveEvent::Buffered { .. }))) {
+                    break;
+                }
+            }
+            (last.expect("object should have shards"), node.cache.len())
+        };
+
+        let unknown = LocalWotPolicy::default();
+        let (result, cached) = receive_all(&unknown, &unstamped);
+        assert!(matches!(result, Err(super::ReceiveError::MissingPowStamp)));
+        assert_eq!(cached, 0);
+        let (result, cached) = receive_all(&unknown, &stamped);
+        assert!(matches!(result, Ok(ReceiveEvent::Delivered { .. })));
+        assert!(cached > 0);
+        let (result, cached) = receive_all(&unknown, &replayed);
+        assert!(matches!(
+            result,
+            Err(super::ReceiveError::InsufficientPowStamp)
+        ));
+        assert_eq!(cached, 0);
+
+        let mut trusting = LocalWotPolicy::default();
+        trusting.trust(publisher.public_key());
+        let (result, _) = receive_all(&trusting, &unstamped);
+        assert!(matches!(result, Ok(ReceiveEvent::Delivered { .. })));
+    }
+
+    #[test]
+    fn revoked_publishers_are_classified_as_of_the_object_date() {
+        use crate::publish::build_encoded_object_v2;
+        use crate::revocation::Revocation;
+        use veil_codec::object::ObjectExtension;
+
+        let tag = [0x55_u8; 32];
+        let namespace = Namespace(55);
+        let epoch = Epoch(9);
+        let key = [0xAD_u8; 32];
+        let publisher = Ed25519Signer::from_secret([0x22; 32]);
+        let mut wot_policy = LocalWotPolicy::default();
+        wot_policy.set_wall_clock_secs(2_000);
+        wot_policy.ingest_revocation(&Revocation {
+            revoked: publisher.public_key(),
+            at_unix_secs: 1_000,
+        });
+        let receive_all = |extensions| {
+            let encoded_object = build_encoded_object_v2(
+                b"dated post",
+                namespace,
+                epoch,
+                tag,
+                &key,
+                1,
+                OBJECT_FLAG_SIGNED,
+                extensions,
+                &XChaCha20Poly1305Cipher,
+                Some(&publisher),
+            )
+            .expect("object should build");
+            let policy = ReceiveCachePolicy {
+                tier: TrustTier::Unknown,
+                max_cache_shards: 100,
+                wot_policy: &wot_policy,
+                erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
+                bucket_jitter_extra_levels: 0,
+                required_signed_namespaces: None,
+                required_multisig_namespaces: None,
+                pow_stamp_difficulty: None,
+                batch_signatures: false,
+                probabilistic_forwarding: crate::config::ProbabilisticForwardingConfig::default(),
+                accept_all_tags: false,
+                strict_cbor: true,
+            };
+            let mut node = NodeState {
+                wall_clock_secs: Some(2_000),
+                ..NodeState::default()
+            };
+            node.subscriptions.insert(tag);
+            let root = derive_object_root(&encoded_object);
+            let shards = object_to_shards(&encoded_object, namespace, epoch, tag, root)
+                .expect("object should shard");
+            let mut last = None;
+            for shard in &shards {
+                last = Some(receive_shard_with_policy(
+                    &mut node,
+                    shard,
+                    1,
+                    100,
+                    &key,
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                    Some(policy),
+                ));
+                if !matches!(last, Some(Ok(ReceiveEvent::Buffered { .. }))) {
+                    break;
+                }
+            }
+            last.expect("object should have shards")
+        };
+
+        // Created before the revocation: still attributable to the key.
+        let result = receive_all(vec![ObjectExtension::created_at(500)]);
+        assert!(matches!(result, Ok(ReceiveEvent::Delivered { .. })));
+        // Created after it, or undated and so dated at receipt.
+        let result = receive_all(vec![ObjectExtension::created_at(1_500)]);
+        assert!(matches!(result, Err(super::ReceiveError::PublisherBlocked)));
+        let result = receive_all(Vec::new());
+        assert!(matches!(result, Err(super::ReceiveError::PublisherBlocked)));
+        // A claimed date past the receive time is capped at the receive time.
+        let result = receive_all(vec![ObjectExtension::created_at(5_000)]);
+        assert!(matches!(result, Err(super::ReceiveError::PublisherBlocked)));
+    }
+
     #[test]
     fn receive_with_policy_can_bypass_subscription_gate() {
         let mut node = NodeState::default();
@@ -593,8 +1371,12 @@ mod tests {
             erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
             bucket_jitter_extra_levels: 0,
             required_signed_namespaces: None,
+            required_multisig_namespaces: None,
+            pow_stamp_difficulty: None,
+            batch_signatures: false,
             probabilistic_forwarding: crate::config::ProbabilisticForwardingConfig::default(),
             accept_all_tags: true,
+            strict_cbor: true,
         };
 
         let event = receive_shard_with_policy(
@@ -611,4 +1393,63 @@ mod tests {
 
         assert_ne!(event, ReceiveEvent::IgnoredNotSubscribed);
     }
+
+    #[test]
+    fn held_shards_are_capped_and_lapse() {
+        let mut node = NodeState::default();
+        for i in 0..MAX_HELD_OBJECTS as u64 {
+            let mut root = [0u8; 32];
+            root[..8].copy_from_slice(&i.to_le_bytes());
+            hold_shard(&mut node, root, ([1u8; 32], vec![1]), i, 10_000);
+        }
+        assert_eq!(node.held_shards.len(), MAX_HELD_OBJECTS);
+
+        // At the cap, the hold closest to lapsing makes room.
+        hold_shard(&mut node, [0xFF; 32], ([2u8; 32], vec![2]), 5_000, 10_000);
+        assert_eq!(node.held_shards.len(), MAX_HELD_OBJECTS);
+        assert!(!node.held_shards.contains_key(&[0u8; 32]));
+
+        // Once holds lapse they are all dropped.
+        hold_shard(&mut node, [0xFE; 32], ([3u8; 32], vec![3]), 20_000, 10);
+        assert_eq!(node.held_shards.len(), 1);
+    }
+
+    #[test]
+    fn inbox_decoders_are_capped() {
+        let tag = [0x42; 32];
+        let key = [0x24; 32];
+        let mut node = NodeState::default();
+        node.subscriptions.insert(tag);
+        let encoded =
+            make_unsigned_encrypted_object(&[7; 20_000], tag, Namespace(1), Epoch(1), &key);
+        let root = derive_object_root(&encoded);
+        let shards = object_to_shards(&encoded, Namespace(1), Epoch(1), tag, root).expect("shards");
+        assert!(shards[0].header.k > 2);
+        let receive = |node: &mut NodeState, shard: &ShardV1| {
+            receive_shard(
+                node,
+                shard,
+                1,
+                100,
+                &key,
+                &XChaCha20Poly1305Cipher,
+                &Ed25519Verifier,
+            )
+            .expect("receive")
+        };
+        receive(&mut node, &shards[0]);
+        receive(&mut node, &shards[1]);
+
+        let micro = make_unsigned_encrypted_object(b"micro", tag, Namespace(1), Epoch(1), &key);
+        let micro_shards =
+            object_to_shards(&micro, Namespace(1), Epoch(1), tag, [0; 32]).expect("shards");
+        for i in 0..MAX_INBOX_DECODERS as u64 + 8 {
+            let mut shard = micro_shards[0].clone();
+            shard.header.object_root[..8].copy_from_slice(&i.to_be_bytes());
+            receive(&mut node, &shard);
+        }
+        assert_eq!(node.inbox.len(), MAX_INBOX_DECODERS);
+        // The object that made progress survives the churn.
+        assert_eq!(node.inbox[&root].have(), 2);
+    }
 }
@@ -0,0 +1,227 @@
+//! Signed key revocation and identity migration notices.
+//!
+//! Both travel as payloads on the WoT namespace (3) next to endorsements and
+//! are applied by [`crate::policy::LocalWotPolicy`]:
+//! - a revocation is signed by the compromised key itself and blocks objects
+//!   dated at or after `at_unix_secs`;
+//! - a migration is signed by both the old and the new key and moves trust
+//!   tier and endorsements from the old key to the new one, until the old key
+//!   is revoked.
+//!
+//! Both are dated in wall-clock unix seconds, since step counters are local
+//! to each node.
+//!
+//! Payloads are JSON or CBOR maps with hex-encoded keys and signatures, like
+//! endorsement payloads.
+
+use serde::{Deserialize, Serialize};
+use veil_core::hash::blake3_32;
+use veil_crypto::signing::{Signer, SigningError, Verifier};
+
+/// `kind` value of a revocation payload.
+pub const REVOCATION_KIND: &str = "revocation";
+/// `kind` value of a migration payload.
+pub const MIGRATION_KIND: &str = "migration";
+
+/// Verified revocation of `revoked` effective from `at_unix_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Revocation {
+    pub revoked: [u8; 32],
+    pub at_unix_secs: u64,
+}
+
+/// Verified move of a publisher from `old` to `new` at `at_unix_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub old: [u8; 32],
+    pub new: [u8; 32],
+    pub at_unix_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RevocationPayloadSerde {
+    kind: String,
+    revoked_pubkey_hex: String,
+    at_unix_secs: u64,
+    signature_hex: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MigrationPayloadSerde {
+    kind: String,
+    old_pubkey_hex: String,
+    new_pubkey_hex: String,
+    at_unix_secs: u64,
+    old_signature_hex: String,
+    new_signature_hex: String,
+}
+
+/// Digest the revoked key signs.
+pub fn revocation_digest(revoked: &[u8; 32], at_unix_secs: u64) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(18 + 32 + 8);
+    preimage.extend_from_slice(b"veil/revocation/v1");
+    preimage.extend_from_slice(revoked);
+    preimage.extend_from_slice(&at_unix_secs.to_be_bytes());
+    blake3_32(&preimage)
+}
+
+/// Digest both the old and the new key sign.
+pub fn migration_digest(old: &[u8; 32], new: &[u8; 32], at_unix_secs: u64) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(17 + 64 + 8);
+    preimage.extend_from_slice(b"veil/migration/v1");
+    preimage.extend_from_slice(old);
+    preimage.extend_from_slice(new);
+    preimage.extend_from_slice(&at_unix_secs.to_be_bytes());
+    blake3_32(&preimage)
+}
+
+/// Builds a CBOR revocation payload for `revoked`'s own key.
+pub fn build_revocation_payload(
+    revoked: &impl Signer,
+    at_unix_secs: u64,
+) -> Result<Vec<u8>, SigningError> {
+    let pubkey = revoked.public_key();
+    let signature = revoked.sign(&revocation_digest(&pubkey, at_unix_secs))?;
+    Ok(to_cbor(&RevocationPayloadSerde {
+        kind: REVOCATION_KIND.to_string(),
+        revoked_pubkey_hex: encode_hex(&pubkey),
+        at_unix_secs,
+        signature_hex: encode_hex(&signature),
+    }))
+}
+
+/// Builds a CBOR migration payload signed by both keys.
+pub fn build_migration_payload(
+    old: &impl Signer,
+    new: &impl Signer,
+    at_unix_secs: u64,
+) -> Result<Vec<u8>, SigningError> {
+    let (old_pubkey, new_pubkey) = (old.public_key(), new.public_key());
+    let digest = migration_digest(&old_pubkey, &new_pubkey, at_unix_secs);
+    Ok(to_cbor(&MigrationPayloadSerde {
+        kind: MIGRATION_KIND.to_string(),
+        old_pubkey_hex: encode_hex(&old_pubkey),
+        new_pubkey_hex: encode_hex(&new_pubkey),
+        at_unix_secs,
+        old_signature_hex: encode_hex(&old.sign(&digest)?),
+        new_signature_hex: encode_hex(&new.sign(&digest)?),
+    }))
+}
+
+/// Parses and verifies a revocation payload (JSON or CBOR).
+pub fn parse_revocation_payload(payload: &[u8], verifier: &impl Verifier) -> Option<Revocation> {
+    let p: RevocationPayloadSerde = decode_json_or_cbor(payload)?;
+    if p.kind != REVOCATION_KIND {
+        return None;
+    }
+    let revoked = decode_hex(&p.revoked_pubkey_hex)?;
+    let signature = decode_hex(&p.signature_hex)?;
+    verifier
+        .verify(
+            revoked,
+            &revocation_digest(&revoked, p.at_unix_secs),
+            signature,
+        )
+        .ok()?
+        .then_some(Revocation {
+            revoked,
+            at_unix_secs: p.at_unix_secs,
+        })
+}
+
+/// Parses and verifies a migration payload (JSON or CBOR).
+pub fn parse_migration_payload(payload: &[u8], verifier: &impl Verifier) -> Option<Migration> {
+    let p: MigrationPayloadSerde = decode_json_or_cbor(payload)?;
+    if p.kind != MIGRATION_KIND {
+        return None;
+    }
+    let old = decode_hex(&p.old_pubkey_hex)?;
+    let new = decode_hex(&p.new_pubkey_hex)?;
+    if old == new {
+        return None;
+    }
+    let digest = migration_digest(&old, &new, p.at_unix_secs);
+    let old_ok = verifier
+        .verify(old, &digest, decode_hex(&p.old_signature_hex)?)
+        .ok()?;
+    let new_ok = verifier
+        .verify(new, &digest, decode_hex(&p.new_signature_hex)?)
+        .ok()?;
+    (old_ok && new_ok).then_some(Migration {
+        old,
+        new,
+        at_unix_secs: p.at_unix_secs,
+    })
+}
+
+fn decode_json_or_cbor<T: for<'de> Deserialize<'de>>(payload: &[u8]) -> Option<T> {
+    serde_json::from_slice(payload)
+        .ok()
+        .or_else(|| ciborium::de::from_reader(payload).ok())
+}
+
+fn to_cbor(value: &impl Serialize) -> Vec<u8> {
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(value, &mut out).expect("in-memory CBOR encoding cannot fail");
+    out
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex<const N: usize>(value: &str) -> Option<[u8; N]> {
+    if value.len() != N * 2 {
+        return None;
+    }
+    let mut out = [0_u8; N];
+    for (byte, chunk) in out.iter_mut().zip(value.as_bytes().chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_migration_payload, build_revocation_payload, encode_hex, parse_migration_payload,
+        parse_revocation_payload, to_cbor, MigrationPayloadSerde, RevocationPayloadSerde,
+    };
+    use veil_crypto::signing::{NostrSigner, NostrVerifier, Signer};
+
+    #[test]
+    fn revocation_round_trips_and_rejects_tampering() {
+        let key = NostrSigner::from_secret([0x21; 32]).expect("key");
+        let payload = build_revocation_payload(&key, 40).expect("build");
+        let parsed = parse_revocation_payload(&payload, &NostrVerifier).expect("parse");
+        assert_eq!(parsed.revoked, key.public_key());
+        assert_eq!(parsed.at_unix_secs, 40);
+
+        // Backdating the revocation invalidates the signature.
+        let mut tampered: RevocationPayloadSerde =
+            ciborium::de::from_reader(payload.as_slice()).expect("decode");
+        tampered.at_unix_secs = 10;
+        assert!(parse_revocation_payload(&to_cbor(&tampered), &NostrVerifier).is_none());
+    }
+
+    #[test]
+    fn migration_requires_both_signatures() {
+        let old = NostrSigner::from_secret([0x22; 32]).expect("old");
+        let new = NostrSigner::from_secret([0x23; 32]).expect("new");
+        let payload = build_migration_payload(&old, &new, 7).expect("build");
+        let parsed = parse_migration_payload(&payload, &NostrVerifier).expect("parse");
+        assert_eq!(
+            (parsed.old, parsed.new),
+            (old.public_key(), new.public_key())
+        );
+
+        // Claiming `new` without its co-signature is rejected (JSON form).
+        let other = NostrSigner::from_secret([0x24; 32]).expect("other");
+        let forged = build_migration_payload(&old, &other, 7).expect("build");
+        let mut forged: MigrationPayloadSerde =
+            ciborium::de::from_reader(forged.as_slice()).expect("decode");
+        forged.new_pubkey_hex = encode_hex(&new.public_key());
+        let json = serde_json::to_vec(&forged).expect("json");
+        assert!(parse_migration_payload(&json, &NostrVerifier).is_none());
+    }
+}
@@ -1,21 +1,34 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use veil_codec::multisig::SignerSet;
 use veil_codec::object::OBJECT_FLAG_ACK_REQUESTED;
-use veil_codec::shard::decode_shard_cbor;
+use veil_codec::shard::{decode_shard_cbor, decode_shard_cbor_strict};
 use veil_core::hash::blake3_32;
 use veil_crypto::aead::AeadCipher;
 use veil_crypto::signing::Verifier;
 use veil_fec::profile::ErasureCodingMode;
+use veil_fec::sharder::shard_id;
 use veil_transport::adapter::TransportAdapter;
 
 use crate::ack::{
     ack_received, build_ack_shard_bytes_with_mode_and_padding, decode_ack_payload,
     next_ack_escalation_batch,
 };
-use crate::bloom::decode_bloom_exchange_packet;
-use crate::config::{NodeRuntimeConfig, ProbabilisticForwardingConfig};
+use crate::backfill::{collect_backfill_shards, decode_backfill_request};
+use crate::bloom::{decode_bloom_exchange_packet, decode_interest_packet};
+use crate::config::{
+    BackfillConfig, InterestForwardingConfig, NodeRuntimeConfig, ProbabilisticForwardingConfig,
+    TombstoneConfig,
+};
+use crate::forwarding::{select_interest_targets, shuffle_key};
 use crate::policy::{TrustTier, WotPolicy};
-use crate::receive::{receive_shard_with_policy, ReceiveCachePolicy, ReceiveError, ReceiveEvent};
+use crate::receive::{
+    flush_signature_batch, receive_shard_with_policy, ReceiveCachePolicy, ReceiveError,
+    ReceiveEvent,
+};
+use crate::shaping::is_cover_shard;
 use crate::state::NodeState;
+use crate::tombstone::{apply_tombstone, decode_tombstone_packet, purge_expired, TombstoneOutcome};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct TierCounters {
@@ -58,6 +71,16 @@ pub struct RuntimeStats {
     pub malformed_messages: usize,
     /// Inbound control-plane Bloom exchange packets.
     pub bloom_messages: usize,
+    /// Inbound control-plane subscription-interest advertisements.
+    pub interest_messages: usize,
+    /// Inbound control-plane backfill requests.
+    pub backfill_requests: usize,
+    /// Cached shards sent in answer to backfill requests.
+    pub backfill_shards_sent: usize,
+    /// Inbound control-plane tombstone packets.
+    pub tombstone_messages: usize,
+    /// Cached shards dropped by tombstones or object expiry.
+    pub purged_shards: usize,
     /// Outbound send attempts that failed at transport level.
     pub send_failures: usize,
     /// Inbound message counts grouped by source trust tier.
@@ -66,6 +89,22 @@ pub struct RuntimeStats {
     pub forwarded_by_tier: TierCounters,
     /// Forward opportunities not used due fanout/quota limits.
     pub dropped_by_tier: TierCounters,
+    /// Real (non-cover) sends released by the traffic shaper.
+    pub shaped_real_messages: usize,
+    /// Bytes of real sends released by the traffic shaper.
+    pub shaped_real_bytes: usize,
+    /// Cover shards released by the traffic shaper.
+    pub cover_messages: usize,
+    /// Bytes of cover shards released by the traffic shaper.
+    pub cover_bytes: usize,
+    /// Cover shards received from peers and dropped at this hop.
+    pub cover_received_messages: usize,
+    /// Sends rejected because a shaping queue was full.
+    pub shaping_dropped_messages: usize,
+    /// Signature batches flushed at ingest.
+    pub signature_batches: usize,
+    /// Signed objects verified through signature batches.
+    pub batched_signatures: usize,
 }
 
 /// Parameters for a single-lane `pump_once` call.
@@ -133,8 +172,15 @@ pub struct RuntimePolicyHooks<'a, P> {
     pub erasure_coding_mode: ErasureCodingMode,
     pub bucket_jitter_extra_levels: usize,
     pub required_signed_namespaces: Option<&'a HashSet<u16>>,
+    pub required_multisig_namespaces: Option<&'a HashMap<u16, SignerSet>>,
+    pub pow_stamp_difficulty: Option<&'a HashMap<u16, u8>>,
+    pub batch_signatures: bool,
     pub probabilistic_forwarding: ProbabilisticForwardingConfig,
     pub accept_all_tags: bool,
+    pub strict_cbor: bool,
+    pub interest_forwarding: InterestForwardingConfig,
+    pub backfill: BackfillConfig,
+    pub tombstones: TombstoneConfig,
 }
 
 impl<'a, P> Default for RuntimePolicyHooks<'a, P> {
@@ -147,8 +193,15 @@ impl<'a, P> Default for RuntimePolicyHooks<'a, P> {
             erasure_coding_mode: ErasureCodingMode::HardenedNonSystematic,
             bucket_jitter_extra_levels: 0,
             required_signed_namespaces: None,
+            required_multisig_namespaces: None,
+            pow_stamp_difficulty: None,
+            batch_signatures: false,
             probabilistic_forwarding: ProbabilisticForwardingConfig::default(),
             accept_all_tags: false,
+            strict_cbor: true,
+            interest_forwarding: InterestForwardingConfig::default(),
+            backfill: BackfillConfig::default(),
+            tombstones: TombstoneConfig::default(),
         }
     }
 }
@@ -165,14 +218,81 @@ struct InboundProcessParams<'a, P> {
     decrypt_key: &'a [u8; 32],
     cache_policy: Option<ReceiveCachePolicy<'a>>,
     probabilistic_forwarding: ProbabilisticForwardingConfig,
+    interest_forwarding: InterestForwardingConfig,
+    backfill: BackfillConfig,
+    tombstones: TombstoneConfig,
+    strict_cbor: bool,
     stats: &'a mut RuntimeStats,
 }
 
-fn is_forwardable(event: &ReceiveEvent) -> bool {
-    matches!(
-        event,
-        ReceiveEvent::Buffered { .. } | ReceiveEvent::Delivered { .. }
-    )
+fn is_forwardable(node: &NodeState, event: &ReceiveEvent) -> bool {
+    match event {
+        ReceiveEvent::Buffered { object_root, .. } => !node.held_shards.contains_key(object_root),
+        ReceiveEvent::PendingSignature { object_root } => !node.signature_batch.holds(object_root),
+        ReceiveEvent::Delivered { .. } => true,
+        _ => false,
+    }
+}
+
+/// Flushes the signature batch once it is full, its oldest object has
+/// waited `max_wait_steps`, or the lanes were idle, and hands back a flushed
+/// outcome in place of an idle or pending `event`.
+#[allow(clippy::too_many_arguments)]
+fn settle_signature_batch(
+    node: &mut NodeState,
+    event: Option<ReceiveEvent>,
+    now_step: u64,
+    decrypt_key: &[u8; 32],
+    config: &NodeRuntimeConfig,
+    cipher: &impl AeadCipher,
+    verifier: &impl Verifier,
+    stats: &mut RuntimeStats,
+) -> Result<Option<ReceiveEvent>, ReceiveError> {
+    let batch_config = config.signature_batch;
+    let pending = node.signature_batch.pending_len();
+    let due = event.is_none()
+        || pending >= batch_config.max_batch
+        || node
+            .signature_batch
+            .oldest_pending_step()
+            .is_some_and(|step| now_step.saturating_sub(step) >= batch_config.max_wait_steps);
+    if pending > 0 && due {
+        let cache_policy = ReceiveCachePolicy {
+            tier: TrustTier::Unknown,
+            max_cache_shards: config.max_cache_shards,
+            wot_policy: &config.wot_policy,
+            erasure_coding_mode: config.erasure_coding_mode,
+            bucket_jitter_extra_levels: config.bucket_jitter_extra_levels,
+            required_signed_namespaces: Some(&config.required_signed_namespaces),
+            required_multisig_namespaces: Some(&config.required_multisig_namespaces),
+            pow_stamp_difficulty: Some(&config.pow_stamp_difficulty),
+            batch_signatures: batch_config.enabled,
+            probabilistic_forwarding: config.probabilistic_forwarding,
+            accept_all_tags: config.accept_all_tags,
+            strict_cbor: config.strict_cbor,
+        };
+        stats.signature_batches += 1;
+        stats.batched_signatures +=
+            flush_signature_batch(node, decrypt_key, cipher, verifier, Some(cache_policy));
+    }
+    if !matches!(event, None | Some(ReceiveEvent::PendingSignature { .. })) {
+        return Ok(event);
+    }
+    match node.signature_batch.pop_ready() {
+        Some(outcome) => {
+            let event = outcome?;
+            if let ReceiveEvent::Delivered { payload, .. } = &event {
+                stats.delivered_messages += 1;
+                if let Some(acked_root) = decode_ack_payload(payload) {
+                    if ack_received(node, acked_root) {
+                        stats.ack_messages += 1;
+                    }
+                }
+            }
+            Ok(Some(event))
+        }
+        None => Ok(event),
+    }
 }
 
 fn forwarding_probability(replica_estimate: u64, cfg: ProbabilisticForwardingConfig) -> f64 {
@@ -206,6 +326,15 @@ fn probabilistic_allow(
     draw <= probability
 }
 
+/// Whether `bytes` is a shard answering one of this node's open backfill requests.
+fn answers_backfill<P: Hash>(node: &NodeState, from_peer: &P, bytes: &[u8], now_step: u64) -> bool {
+    node.backfill.pending_len() > 0
+        && decode_shard_cbor(bytes).is_ok_and(|shard| {
+            node.backfill
+                .is_response(from_peer, &shard.header.tag, now_step)
+        })
+}
+
 fn process_inbound<A: TransportAdapter>(
     node: &mut NodeState,
     adapter: &mut A,
@@ -225,9 +354,12 @@ fn process_inbound<A: TransportAdapter>(
         decrypt_key,
         cache_policy,
         probabilistic_forwarding,
+        interest_forwarding,
+        backfill,
+        tombstones,
+        strict_cbor,
         stats,
     } = params;
-    let sid = blake3_32(bytes);
 
     stats.inbound_messages += 1;
     stats.inbound_by_tier.incr(inbound_tier, 1);
@@ -237,8 +369,62 @@ fn process_inbound<A: TransportAdapter>(
         stats.ignored_messages += 1;
         return Ok(ReceiveEvent::IgnoredMalformed);
     }
+    if let Some(advert) = decode_interest_packet(bytes) {
+        node.peer_interests.record(from_peer, advert, now_step);
+        stats.interest_messages += 1;
+        stats.ignored_messages += 1;
+        return Ok(ReceiveEvent::IgnoredMalformed);
+    }
 
-    let shard = match decode_shard_cbor(bytes) {
+    if let Some(request) = decode_backfill_request(bytes) {
+        stats.backfill_requests += 1;
+        stats.ignored_messages += 1;
+        if backfill.enabled && node.backfill.allow_serve(from_peer, now_step, backfill) {
+            for shard_bytes in collect_backfill_shards(node, &request, now_step, backfill) {
+                if adapter.send(from_peer, &shard_bytes).is_ok() {
+                    stats.backfill_shards_sent += 1;
+                    stats.forwarded_messages += 1;
+                } else {
+                    stats.send_failures += 1;
+                }
+            }
+        }
+        return Ok(ReceiveEvent::IgnoredMalformed);
+    }
+
+    if let Some(tombstone) = decode_tombstone_packet(bytes) {
+        stats.tombstone_messages += 1;
+        stats.ignored_messages += 1;
+        if tombstones.enabled {
+            let outcome = apply_tombstone(
+                node,
+                &tombstone,
+                verifier,
+                now_step,
+                tombstones.retention_steps,
+            );
+            if let TombstoneOutcome::Applied { purged } = outcome {
+                stats.purged_shards += purged;
+            }
+            if outcome.should_forward() {
+                for peer in peers.iter().filter(|p| **p != *from_peer).take(fanout) {
+                    if adapter.send(peer, bytes).is_ok() {
+                        stats.forwarded_messages += 1;
+                    } else {
+                        stats.send_failures += 1;
+                    }
+                }
+            }
+        }
+        return Ok(ReceiveEvent::IgnoredMalformed);
+    }
+
+    let decoded = if strict_cbor {
+        decode_shard_cbor_strict(bytes)
+    } else {
+        decode_shard_cbor(bytes)
+    };
+    let shard = match decoded {
         Ok(shard) => shard,
         Err(_) => {
             stats.ignored_messages += 1;
@@ -247,8 +433,13 @@ fn process_inbound<A: TransportAdapter>(
         }
     };
     stats.parsed_shards += 1;
+    if is_cover_shard(&shard.header) {
+        stats.cover_received_messages += 1;
+        stats.ignored_messages += 1;
+        return Ok(ReceiveEvent::IgnoredMalformed);
+    }
 
-    let event = receive_shard_with_policy(
+    let received = receive_shard_with_policy(
         node,
         &shard,
         now_step,
@@ -257,13 +448,34 @@ fn process_inbound<A: TransportAdapter>(
         cipher,
         verifier,
         cache_policy,
-    )?;
+    );
 
-    if is_forwardable(&event) {
+    let backfill_response = node
+        .backfill
+        .is_response(from_peer, &shard.header.tag, now_step);
+    // Shards held until their object verified go out alongside this one,
+    // even if this node then fails to decrypt the object.
+    let released = std::mem::take(&mut node.released_shards);
+    let released_current = !released.is_empty()
+        && shard_id(&shard)
+            .is_ok_and(|sid| released.iter().any(|(_, held)| blake3_32(held) == sid));
+    let current = received
+        .as_ref()
+        .is_ok_and(|event| is_forwardable(node, event) && !released_current)
+        .then_some((shard.header.tag, bytes));
+    let outgoing = current
+        .into_iter()
+        .chain(released.iter().map(|(tag, held)| (*tag, held.as_slice())))
+        .filter(|_| !backfill_response);
+    for (tag, bytes) in outgoing {
+        let sid = blake3_32(bytes);
         let mut candidates = peers
             .iter()
             .filter(|peer| **peer != *from_peer)
             .collect::<Vec<_>>();
+        if interest_forwarding.enabled {
+            candidates.sort_by_cached_key(|peer| shuffle_key(&sid, *peer));
+        }
         if let Some(classify) = classify_peer_tier {
             candidates.sort_by_key(|peer| match classify(peer, now_step) {
                 TrustTier::Trusted => 0_u8,
@@ -273,16 +485,29 @@ fn process_inbound<A: TransportAdapter>(
                 TrustTier::Blocked => 4_u8,
             });
         }
+        let available = candidates.len();
+        let targets = if interest_forwarding.enabled {
+            select_interest_targets(
+                &node.peer_interests,
+                candidates,
+                &tag,
+                fanout,
+                now_step,
+                interest_forwarding,
+            )
+        } else {
+            candidates.into_iter().take(fanout).collect()
+        };
         stats
             .dropped_by_tier
-            .incr(inbound_tier, candidates.len().saturating_sub(fanout));
-        for (ordinal, peer) in candidates.into_iter().take(fanout).enumerate() {
+            .incr(inbound_tier, available.saturating_sub(targets.len()));
+        for (ordinal, peer) in targets.into_iter().enumerate() {
             let replica = *node.replica_estimate.get(&sid).unwrap_or(&0);
             let p = forwarding_probability(replica, probabilistic_forwarding);
             if !probabilistic_allow(sid, ordinal, now_step, p) {
                 continue;
             }
-            if adapter.send(peer, bytes).is_ok() {
+            if adapter.relay(peer, bytes).is_ok() {
                 stats.forwarded_messages += 1;
                 stats.forwarded_by_tier.incr(inbound_tier, 1);
             } else {
@@ -291,6 +516,7 @@ fn process_inbound<A: TransportAdapter>(
         }
     }
 
+    let event = received?;
     if let ReceiveEvent::Delivered {
         object_root,
         payload,
@@ -339,7 +565,10 @@ fn process_inbound<A: TransportAdapter>(
         stats.duplicate_messages += 1;
         stats.ignored_messages += 1;
     }
-    if matches!(event, ReceiveEvent::IgnoredNotSubscribed) {
+    if matches!(
+        event,
+        ReceiveEvent::IgnoredNotSubscribed | ReceiveEvent::IgnoredDeleted
+    ) {
         stats.ignored_messages += 1;
     }
 
@@ -383,8 +612,12 @@ pub fn pump_once<A: TransportAdapter>(
             erasure_coding_mode: policy_hooks.erasure_coding_mode,
             bucket_jitter_extra_levels: policy_hooks.bucket_jitter_extra_levels,
             required_signed_namespaces: policy_hooks.required_signed_namespaces,
+            required_multisig_namespaces: policy_hooks.required_multisig_namespaces,
+            pow_stamp_difficulty: policy_hooks.pow_stamp_difficulty,
+            batch_signatures: policy_hooks.batch_signatures,
             probabilistic_forwarding: policy_hooks.probabilistic_forwarding,
             accept_all_tags: policy_hooks.accept_all_tags,
+            strict_cbor: policy_hooks.strict_cbor,
         });
     let event = process_inbound(
         node,
@@ -401,6 +634,10 @@ pub fn pump_once<A: TransportAdapter>(
             decrypt_key,
             cache_policy,
             probabilistic_forwarding: policy_hooks.probabilistic_forwarding,
+            interest_forwarding: policy_hooks.interest_forwarding,
+            backfill: policy_hooks.backfill,
+            tombstones: policy_hooks.tombstones,
+            strict_cbor: policy_hooks.strict_cbor,
             stats,
         },
         cipher,
@@ -469,7 +706,7 @@ pub fn pump_once_with_config_resolver<A: TransportAdapter>(
     };
     let tier_fn = |peer: &A::Peer, step: u64| config.classify_publisher_tier(resolver(peer), step);
 
-    pump_once(
+    let event = pump_once(
         node,
         adapter,
         PumpParams {
@@ -485,14 +722,31 @@ pub fn pump_once_with_config_resolver<A: TransportAdapter>(
                 erasure_coding_mode: config.erasure_coding_mode,
                 bucket_jitter_extra_levels: config.bucket_jitter_extra_levels,
                 required_signed_namespaces: Some(&config.required_signed_namespaces),
+                required_multisig_namespaces: Some(&config.required_multisig_namespaces),
+                pow_stamp_difficulty: Some(&config.pow_stamp_difficulty),
+                batch_signatures: config.signature_batch.enabled,
                 probabilistic_forwarding: config.probabilistic_forwarding,
                 accept_all_tags: config.accept_all_tags,
+                strict_cbor: config.strict_cbor,
+                interest_forwarding: config.interest_forwarding,
+                backfill: config.backfill,
+                tombstones: config.tombstones,
             },
             decrypt_key,
             stats,
         },
         cipher,
         verifier,
+    )?;
+    settle_signature_batch(
+        node,
+        event,
+        now_step,
+        decrypt_key,
+        config,
+        cipher,
+        verifier,
+        stats,
     )
 }
 
@@ -553,8 +807,12 @@ pub fn pump_multi_lane_once_split<AFast: TransportAdapter, AFallback: TransportA
                 erasure_coding_mode: fast_policy_hooks.erasure_coding_mode,
                 bucket_jitter_extra_levels: fast_policy_hooks.bucket_jitter_extra_levels,
                 required_signed_namespaces: fast_policy_hooks.required_signed_namespaces,
+                required_multisig_namespaces: fast_policy_hooks.required_multisig_namespaces,
+                pow_stamp_difficulty: fast_policy_hooks.pow_stamp_difficulty,
+                batch_signatures: fast_policy_hooks.batch_signatures,
                 probabilistic_forwarding: fast_policy_hooks.probabilistic_forwarding,
                 accept_all_tags: fast_policy_hooks.accept_all_tags,
+                strict_cbor: fast_policy_hooks.strict_cbor,
             }),
             _ => None,
         };
@@ -577,6 +835,10 @@ pub fn pump_multi_lane_once_split<AFast: TransportAdapter, AFallback: TransportA
                 decrypt_key,
                 cache_policy,
                 probabilistic_forwarding: fast_policy_hooks.probabilistic_forwarding,
+                interest_forwarding: fast_policy_hooks.interest_forwarding,
+                backfill: fast_policy_hooks.backfill,
+                tombstones: fast_policy_hooks.tombstones,
+                strict_cbor: fast_policy_hooks.strict_cbor,
                 stats,
             },
             cipher,
@@ -588,7 +850,10 @@ pub fn pump_multi_lane_once_split<AFast: TransportAdapter, AFallback: TransportA
             .map(|f| f(&from_peer, now_step, fallback_redundancy_fanout))
             .unwrap_or(fallback_redundancy_fanout);
 
-        if is_forwardable(&event) && effective_redundancy > 0 {
+        if is_forwardable(node, &event)
+            && effective_redundancy > 0
+            && !answers_backfill(node, &from_peer, &bytes, now_step)
+        {
             stats.dropped_by_tier.incr(
                 inbound_tier,
                 fallback_lane
@@ -624,8 +889,12 @@ pub fn pump_multi_lane_once_split<AFast: TransportAdapter, AFallback: TransportA
                 erasure_coding_mode: fallback_policy_hooks.erasure_coding_mode,
                 bucket_jitter_extra_levels: fallback_policy_hooks.bucket_jitter_extra_levels,
                 required_signed_namespaces: fallback_policy_hooks.required_signed_namespaces,
+                required_multisig_namespaces: fallback_policy_hooks.required_multisig_namespaces,
+                pow_stamp_difficulty: fallback_policy_hooks.pow_stamp_difficulty,
+                batch_signatures: fallback_policy_hooks.batch_signatures,
                 probabilistic_forwarding: fallback_policy_hooks.probabilistic_forwarding,
                 accept_all_tags: fallback_policy_hooks.accept_all_tags,
+                strict_cbor: fallback_policy_hooks.strict_cbor,
             }),
             _ => None,
         };
@@ -648,6 +917,10 @@ pub fn pump_multi_lane_once_split<AFast: TransportAdapter, AFallback: TransportA
                 decrypt_key,
                 cache_policy,
                 probabilistic_forwarding: fallback_policy_hooks.probabilistic_forwarding,
+                interest_forwarding: fallback_policy_hooks.interest_forwarding,
+                backfill: fallback_policy_hooks.backfill,
+                tombstones: fallback_policy_hooks.tombstones,
+                strict_cbor: fallback_policy_hooks.strict_cbor,
                 stats,
             },
             cipher,
@@ -740,7 +1013,7 @@ where
         config.classify_publisher_tier(fallback_resolver(peer), step)
     };
 
-    pump_multi_lane_once_split(
+    let event = pump_multi_lane_once_split(
         node,
         fast_adapter,
         fallback_adapter,
@@ -764,8 +1037,15 @@ where
                 erasure_coding_mode: config.erasure_coding_mode,
                 bucket_jitter_extra_levels: config.bucket_jitter_extra_levels,
                 required_signed_namespaces: Some(&config.required_signed_namespaces),
+                required_multisig_namespaces: Some(&config.required_multisig_namespaces),
+                pow_stamp_difficulty: Some(&config.pow_stamp_difficulty),
+                batch_signatures: config.signature_batch.enabled,
                 probabilistic_forwarding: config.probabilistic_forwarding,
                 accept_all_tags: config.accept_all_tags,
+                strict_cbor: config.strict_cbor,
+                interest_forwarding: config.interest_forwarding,
+                backfill: config.backfill,
+                tombstones: config.tombstones,
             },
             fallback_policy_hooks: RuntimePolicyHooks {
                 fanout_for_peer: Some(&fallback_fanout_fn),
@@ -775,14 +1055,31 @@ where
                 erasure_coding_mode: config.erasure_coding_mode,
                 bucket_jitter_extra_levels: config.bucket_jitter_extra_levels,
                 required_signed_namespaces: Some(&config.required_signed_namespaces),
+                required_multisig_namespaces: Some(&config.required_multisig_namespaces),
+                pow_stamp_difficulty: Some(&config.pow_stamp_difficulty),
+                batch_signatures: config.signature_batch.enabled,
                 probabilistic_forwarding: config.probabilistic_forwarding,
                 accept_all_tags: config.accept_all_tags,
+                strict_cbor: config.strict_cbor,
+                interest_forwarding: config.interest_forwarding,
+                backfill: config.backfill,
+                tombstones: config.tombstones,
             },
             decrypt_key,
             stats,
         },
         cipher,
         verifier,
+    )?;
+    settle_signature_batch(
+        node,
+        event,
+        now_step,
+        decrypt_key,
+        config,
+        cipher,
+        verifier,
+        stats,
     )
 }
 
@@ -929,6 +1226,7 @@ where
         config.base_fallback_fanout.max(1),
         stats,
     );
+    stats.purged_shards += purge_expired(node, now_step, config.ttl_steps);
 
     Ok(event)
 }
@@ -1026,8 +1324,15 @@ mod tests {
         MultiLanePumpParams, PumpParams, RuntimePolicyHooks, RuntimeStats,
     };
     use crate::ack::{encode_ack_payload, register_pending_ack, AckRetryPolicy};
-    use crate::config::{NodeRuntimeConfig, ProbabilisticForwardingConfig};
+    use crate::backfill::{request_backfill, BackfillRequest};
+    use crate::bloom::{encode_interest_packet, interest_filter_for_tags};
+    use crate::cache::cache_put;
+    use crate::config::{
+        BackfillConfig, InterestForwardingConfig, NodeRuntimeConfig, ProbabilisticForwardingConfig,
+    };
+    use crate::receive::ReceiveEvent;
     use crate::state::NodeState;
+    use crate::tombstone::{encode_tombstone_packet, Tombstone};
 
     fn make_encoded_object_with_flags(
         payload: &[u8],
@@ -1340,6 +1645,268 @@ mod tests {
         assert_eq!(stats.dropped_by_tier.blocked, 2);
     }
 
+    #[test]
+    fn interest_forwarding_targets_peers_advertising_the_tag() {
+        let mut node = NodeState::default();
+        let tag = [0x43_u8; 32];
+        node.subscriptions.insert(tag);
+        let key = [0xD6_u8; 32];
+
+        let encoded_object = make_encoded_object(b"interest fanout", tag, &key);
+        let root = blake3_32(&encoded_object);
+        let shards = object_to_shards(&encoded_object, Namespace(10), Epoch(45), tag, root)
+            .expect("sharding should succeed");
+        let bytes = encode_shard_cbor(&shards[0]).expect("shard should encode");
+
+        let interested = encode_interest_packet(0, interest_filter_for_tags([&tag], 0.01, [1; 16]))
+            .expect("encode interest");
+        let uninterested =
+            encode_interest_packet(0, interest_filter_for_tags([&[0x44_u8; 32]], 0.01, [1; 16]))
+                .expect("encode interest");
+        let mut adapter = InMemoryAdapter::default();
+        adapter.enqueue_inbound("peer-d", interested);
+        for peer in ["peer-a", "peer-b", "peer-c"] {
+            adapter.enqueue_inbound(peer, uninterested.clone());
+        }
+        adapter.enqueue_inbound("sender", bytes);
+
+        let peers = ["sender", "peer-a", "peer-b", "peer-c", "peer-d"]
+            .map(String::from)
+            .to_vec();
+        let mut stats = RuntimeStats::default();
+        for step in 0..5 {
+            pump_once(
+                &mut node,
+                &mut adapter,
+                PumpParams {
+                    peers: &peers,
+                    now_step: step,
+                    ttl_steps: 100,
+                    fanout: 2,
+                    policy_hooks: RuntimePolicyHooks {
+                        interest_forwarding: InterestForwardingConfig {
+                            enabled: true,
+                            ..InterestForwardingConfig::default()
+                        },
+                        ..RuntimePolicyHooks::default()
+                    },
+                    decrypt_key: &key,
+                    stats: &mut stats,
+                },
+                &XChaCha20Poly1305Cipher,
+                &Ed25519Verifier,
+            )
+            .expect("pump should succeed");
+        }
+
+        assert_eq!(stats.interest_messages, 4);
+        let outbound = adapter.take_outbound();
+        let targets = outbound.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>();
+        assert_eq!(targets, vec!["peer-d"]);
+    }
+
+    #[test]
+    fn backfill_request_is_served_from_cache_and_answers_are_not_reforwarded() {
+        let tag = [0x52_u8; 32];
+        let key = [0xA1_u8; 32];
+        let encoded_object = make_encoded_object(b"history post", tag, &key);
+        let root = blake3_32(&encoded_object);
+        let shards = object_to_shards(&encoded_object, Namespace(10), Epoch(45), tag, root)
+            .expect("sharding should succeed");
+
+        let mut server = NodeState::default();
+        for shard in &shards {
+            let bytes = encode_shard_cbor(shard).expect("shard should encode");
+            cache_put(&mut server, blake3_32(&bytes), bytes, 0, 1_000);
+        }
+
+        let mut subscriber = NodeState::default();
+        subscriber.subscriptions.insert(tag);
+        let mut subscriber_adapter = InMemoryAdapter::default();
+        let request = BackfillRequest::new(tag, Epoch(40), Epoch(50), 256);
+        let sent = request_backfill(
+            &mut subscriber,
+            &mut subscriber_adapter,
+            &["server".to_string()],
+            &request,
+            1,
+            BackfillConfig::default(),
+        );
+        assert_eq!(sent, 1);
+
+        let mut server_adapter = InMemoryAdapter::default();
+        for (_, packet) in subscriber_adapter.take_outbound() {
+            server_adapter.enqueue_inbound("subscriber", packet);
+        }
+        let server_peers = ["subscriber", "other"].map(String::from).to_vec();
+        let mut server_stats = RuntimeStats::default();
+        pump_once(
+            &mut server,
+            &mut server_adapter,
+            PumpParams {
+                peers: &server_peers,
+                now_step: 2,
+                ttl_steps: 100,
+                fanout: 2,
+                policy_hooks: RuntimePolicyHooks {
+                    backfill: BackfillConfig {
+                        enabled: true,
+                        ..BackfillConfig::default()
+                    },
+                    ..RuntimePolicyHooks::default()
+                },
+                decrypt_key: &key,
+                stats: &mut server_stats,
+            },
+            &XChaCha20Poly1305Cipher,
+            &Ed25519Verifier,
+        )
+        .expect("pump should succeed");
+        assert_eq!(server_stats.backfill_requests, 1);
+        assert_eq!(server_stats.backfill_shards_sent, shards.len());
+
+        for (peer, packet) in server_adapter.take_outbound() {
+            assert_eq!(peer, "subscriber");
+            subscriber_adapter.enqueue_inbound("server", packet);
+        }
+        let subscriber_peers = ["server", "other"].map(String::from).to_vec();
+        let mut stats = RuntimeStats::default();
+        for step in 3..3 + shards.len() as u64 {
+            pump_once(
+                &mut subscriber,
+                &mut subscriber_adapter,
+                PumpParams {
+                    peers: &subscriber_peers,
+                    now_step: step,
+                    ttl_steps: 100,
+                    fanout: 2,
+                    policy_hooks: RuntimePolicyHooks::default(),
+                    decrypt_key: &key,
+                    stats: &mut stats,
+                },
+                &XChaCha20Poly1305Cipher,
+                &Ed25519Verifier,
+            )
+            .expect("pump should succeed");
+        }
+        assert_eq!(stats.delivered_messages, 1);
+        assert!(subscriber_adapter.take_outbound().is_empty());
+    }
+
+    #[test]
+    fn tombstone_purges_cached_object_and_blocks_reingest() {
+        let tag = [0x53_u8; 32];
+        let key = [0xA2_u8; 32];
+        let encoded_object = make_encoded_object(b"regretted post", tag, &key);
+        let root = derive_object_root(&encoded_object);
+        let shards = object_to_shards(&encoded_object, Namespace(7), Epoch(42), tag, root)
+            .expect("sharding should succeed");
+
+        let mut relay = NodeState::default();
+        relay.subscriptions.insert(tag);
+        for shard in &shards {
+            let bytes = encode_shard_cbor(shard).expect("shard should encode");
+            cache_put(&mut relay, blake3_32(&bytes), bytes, 0, 1_000);
+        }
+
+        let author = Ed25519Signer::from_secret([0x42_u8; 32]);
+        // Receive indexes this once the cached object verifies.
+        relay
+            .tombstones
+            .record_author(root, author.public_key(), Namespace(7));
+        let tombstone = Tombstone::sign(&author, root, 1_000).expect("sign");
+        let mut adapter = InMemoryAdapter::default();
+        adapter.enqueue_inbound(
+            "publisher",
+            encode_tombstone_packet(&tombstone).expect("encode"),
+        );
+        adapter.enqueue_inbound(
+            "publisher",
+            encode_shard_cbor(&shards[0]).expect("shard should encode"),
+        );
+        let peers = ["publisher", "other"].map(String::from).to_vec();
+        let mut stats = RuntimeStats::default();
+        let mut events = Vec::new();
+        for step in 1..=2 {
+            events.push(
+                pump_once(
+                    &mut relay,
+                    &mut adapter,
+                    PumpParams {
+                        peers: &peers,
+                        now_step: step,
+                        ttl_steps: 100,
+                        fanout: 2,
+                        policy_hooks: RuntimePolicyHooks::default(),
+                        decrypt_key: &key,
+                        stats: &mut stats,
+                    },
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                )
+                .expect("pump should succeed"),
+            );
+        }
+
+        assert_eq!(stats.tombstone_messages, 1);
+        assert_eq!(stats.purged_shards, shards.len());
+        assert!(relay.cache.is_empty());
+        assert_eq!(events[1], Some(ReceiveEvent::IgnoredDeleted));
+        let outbound = adapter.take_outbound();
+        assert_eq!(outbound.len(), 1);
+        assert_eq!(outbound[0].0, "other");
+    }
+
+    #[test]
+    fn strict_cbor_rejects_non_canonical_shard_reencodings() {
+        let tag = [0x54_u8; 32];
+        let key = [0xA3_u8; 32];
+        let encoded_object = make_encoded_object(b"one shard one id", tag, &key);
+        let root = derive_object_root(&encoded_object);
+        let shards = object_to_shards(&encoded_object, Namespace(7), Epoch(42), tag, root)
+            .expect("sharding should succeed");
+        let canonical = encode_shard_cbor(&shards[0]).expect("shard should encode");
+        let at = canonical
+            .windows(3)
+            .position(|w| w == [0x61, b'k', shards[0].header.k as u8])
+            .expect("k field");
+        let mut variant = canonical.clone();
+        variant.splice(at + 2..at + 3, [0x18, shards[0].header.k as u8]);
+
+        for (strict_cbor, expect_parsed) in [(true, 1), (false, 2)] {
+            let mut node = NodeState::default();
+            node.subscriptions.insert(tag);
+            let mut adapter = InMemoryAdapter::default();
+            adapter.enqueue_inbound("peer", canonical.clone());
+            adapter.enqueue_inbound("peer", variant.clone());
+            let peers = vec!["peer".to_string()];
+            let mut stats = RuntimeStats::default();
+            for step in 1..=2 {
+                pump_once(
+                    &mut node,
+                    &mut adapter,
+                    PumpParams {
+                        peers: &peers,
+                        now_step: step,
+                        ttl_steps: 100,
+                        fanout: 1,
+                        policy_hooks: RuntimePolicyHooks {
+                            strict_cbor,
+                            ..RuntimePolicyHooks::default()
+                        },
+                        decrypt_key: &key,
+                        stats: &mut stats,
+                    },
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                )
+                .expect("pump should succeed");
+            }
+            assert_eq!(stats.parsed_shards, expect_parsed);
+            assert_eq!(stats.malformed_messages, 2 - expect_parsed);
+        }
+    }
+
     #[test]
     fn probabilistic_forwarding_probability_drops_as_replica_estimate_rises() {
         let cfg = ProbabilisticForwardingConfig {
@@ -1540,6 +2107,141 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn signature_batch_defers_and_locates_forged_object() {
+        let mut node = NodeState::default();
+        let tag = [0x53_u8; 32];
+        node.subscriptions.insert(tag);
+        let key = [0xE7_u8; 32];
+        let mut objects: Vec<Vec<u8>> = [&b"batched one"[..], b"batched two", b"forged"]
+            .iter()
+            .map(|payload| make_encoded_object(payload, tag, &key))
+            .collect();
+        let mut forged = veil_codec::object::decode_object_cbor(&objects[2]).expect("decode");
+        forged.signature.as_mut().expect("signed").0[63] ^= 0x01;
+        objects[2] = encode_object_cbor(&forged).expect("encoding should succeed");
+
+        let mut adapter = InMemoryAdapter::default();
+        for encoded_object in &objects {
+            let root = blake3_32(encoded_object);
+            let shards = object_to_shards(encoded_object, Namespace(7), Epoch(42), tag, root)
+                .expect("sharding should succeed");
+            for shard in &shards {
+                adapter.enqueue_inbound(
+                    "sender",
+                    encode_shard_cbor(shard).expect("shard should encode"),
+                );
+            }
+        }
+        let peers = vec!["sender".to_string()];
+        let mut stats = RuntimeStats::default();
+        let mut cfg = NodeRuntimeConfig::default();
+        cfg.signature_batch = crate::config::SignatureBatchConfig {
+            enabled: true,
+            max_batch: 8,
+            max_wait_steps: 1_000,
+        };
+
+        let mut delivered = Vec::new();
+        let mut rejected = 0;
+        for step in 0..64 {
+            let event = pump_once_with_config(
+                &mut node,
+                &mut adapter,
+                ConfigPumpParams {
+                    peers: &peers,
+                    now_step: step,
+                    decrypt_key: &key,
+                    config: &cfg,
+                    stats: &mut stats,
+                },
+                &XChaCha20Poly1305Cipher,
+                &Ed25519Verifier,
+            );
+            match event {
+                Ok(Some(ReceiveEvent::Delivered { payload, .. })) => delivered.push(payload),
+                Err(crate::receive::ReceiveError::SignatureInvalid) => rejected += 1,
+                Ok(_) => {}
+                Err(err) => panic!("unexpected error: {err}"),
+            }
+        }
+
+        assert_eq!(
+            delivered,
+            vec![b"batched one".to_vec(), b"batched two".to_vec()]
+        );
+        assert_eq!(rejected, 1);
+        assert_eq!(stats.signature_batches, 1);
+        assert_eq!(stats.batched_signatures, 3);
+        assert_eq!(stats.delivered_messages, 2);
+    }
+
+    #[test]
+    fn signed_namespace_shards_are_forwarded_only_once_the_object_verifies() {
+        let tag = [0x54_u8; 32];
+        let key = [0xE8_u8; 32];
+        let mut cfg = NodeRuntimeConfig::default();
+        cfg.required_signed_namespaces.insert(7);
+        let peers = vec!["sender".to_string(), "peer-a".to_string()];
+
+        let genuine = make_encoded_object(b"verified relay", tag, &key);
+        let mut forged = veil_codec::object::decode_object_cbor(&genuine).expect("decode");
+        forged.signature.as_mut().expect("signed").0[63] ^= 0x01;
+        let forged = encode_object_cbor(&forged).expect("encoding should succeed");
+
+        // A relay that cannot decrypt still forwards verified objects.
+        let relay_key = [0x11_u8; 32];
+        for (encoded_object, valid, decrypt_key) in [
+            (genuine.clone(), true, &key),
+            (genuine, true, &relay_key),
+            (forged, false, &key),
+        ] {
+            let mut node = NodeState::default();
+            node.subscriptions.insert(tag);
+            let root = blake3_32(&encoded_object);
+            let shards = object_to_shards(&encoded_object, Namespace(7), Epoch(42), tag, root)
+                .expect("sharding should succeed");
+            let k = shards[0].header.k as usize;
+            let mut adapter = InMemoryAdapter::default();
+            for shard in shards.iter().take(k) {
+                adapter.enqueue_inbound(
+                    "sender",
+                    encode_shard_cbor(shard).expect("shard should encode"),
+                );
+            }
+
+            let mut stats = RuntimeStats::default();
+            for step in 0..k {
+                let _ = pump_once_with_config(
+                    &mut node,
+                    &mut adapter,
+                    ConfigPumpParams {
+                        peers: &peers,
+                        now_step: step as u64,
+                        decrypt_key,
+                        config: &cfg,
+                        stats: &mut stats,
+                    },
+                    &XChaCha20Poly1305Cipher,
+                    &Ed25519Verifier,
+                );
+                if step + 1 < k {
+                    assert!(adapter.take_outbound().is_empty());
+                }
+            }
+
+            let forwarded = adapter.take_outbound();
+            if valid {
+                assert_eq!(forwarded.len(), k);
+                assert_eq!(node.cache.len(), k);
+            } else {
+                assert!(forwarded.is_empty());
+                assert!(node.cache.is_empty());
+            }
+            assert!(node.held_shards.is_empty());
+        }
+    }
+
     #[test]
     fn multi_lane_config_wrapper_runs() {
         let mut node = NodeState::default();
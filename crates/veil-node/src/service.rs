@@ -1,22 +1,31 @@
 use std::time::Duration;
+use veil_core::hash::blake3_32;
 use veil_core::{Epoch, Namespace, Tag};
 use veil_crypto::aead::AeadCipher;
 use veil_crypto::signing::{Signer, Verifier};
 use veil_transport::adapter::{TransportAdapter, TransportHealthSnapshot};
 
+use crate::backfill::{request_backfill, BackfillRequest};
 use crate::batch::FeedBatcher;
-use crate::bloom::{encode_bloom_exchange_packet, BloomFilter};
+use crate::bloom::{
+    encode_bloom_exchange_packet, encode_interest_packet, interest_filter_for_tags, BloomFilter,
+};
+use crate::clock::{clock_step, Clock, SystemClock};
 use crate::config::{AdaptiveLaneScoringConfig, NodeRuntimeConfig};
 use crate::policy::EndorsementIngestResult;
 use crate::publish::{
     publish_service_tick_multi_lane, PublishError, PublishOptions, PublishQueueTickParams,
     PublishServiceTickParams, PublishServiceTickResult,
 };
+use crate::publisher_log::EquivocationEvidence;
 use crate::receive::{ReceiveError, ReceiveEvent};
 use crate::runtime::{
     pump_multi_lane_tick_with_config_split, ConfigMultiLanePumpParams, RuntimeStats,
 };
+use crate::shaping::{ShapedLane, TrafficShaper};
 use crate::state::NodeState;
+use crate::subscriptions::RvSubscriptionManager;
+use crate::tombstone::{apply_tombstone, encode_tombstone_packet, Tombstone, TombstoneOutcome};
 
 /// Inputs used by one publisher runtime tick.
 #[derive(Debug, Clone, Copy)]
@@ -47,6 +56,7 @@ pub struct PublisherTickOptionsInput<'a, PFast, PFallback> {
 /// Optional callbacks fired after one node runtime tick.
 pub type DeliveredCallback<'a> = dyn FnMut(veil_core::ObjectRoot, &[u8]) + 'a;
 pub type CountCallback<'a> = dyn FnMut(usize) + 'a;
+pub type EvidenceCallback<'a> = dyn FnMut(&EquivocationEvidence) + 'a;
 
 #[derive(Default)]
 pub struct NodeRuntimeCallbacks<'a> {
@@ -54,6 +64,8 @@ pub struct NodeRuntimeCallbacks<'a> {
     pub on_ack_cleared: Option<&'a mut CountCallback<'a>>,
     pub on_send_failure: Option<&'a mut CountCallback<'a>>,
     pub on_endorsement_ingested: Option<&'a mut CountCallback<'a>>,
+    /// Receives publisher-log equivocation evidence as it is found.
+    pub on_equivocation: Option<&'a mut EvidenceCallback<'a>>,
 }
 
 /// Aggregated per-lane transport health snapshots for a node runtime.
@@ -102,6 +114,15 @@ impl AdaptiveLaneScoringState {
     }
 }
 
+/// Observed per-lane shard delivery feeding loss-adaptive FEC selection.
+#[derive(Debug, Clone, Default)]
+struct LaneDeliveryState {
+    fast_ewma: Option<f64>,
+    fallback_ewma: Option<f64>,
+    last_fast_snapshot: TransportHealthSnapshot,
+    last_fallback_snapshot: TransportHealthSnapshot,
+}
+
 /// Runtime loop configuration for `NodeRuntime` orchestration.
 #[derive(Debug, Clone, Copy)]
 pub struct NodeRuntimeRunnerConfig {
@@ -113,6 +134,9 @@ pub struct NodeRuntimeRunnerConfig {
     pub error_backoff: Duration,
     /// If set, exits loop after this many consecutive tick errors.
     pub max_consecutive_errors: Option<u32>,
+    /// Derive each tick's step from the runtime clock instead of counting
+    /// up from `start_step`.
+    pub use_clock: bool,
 }
 
 impl Default for NodeRuntimeRunnerConfig {
@@ -122,6 +146,7 @@ impl Default for NodeRuntimeRunnerConfig {
             tick_interval: Duration::from_millis(50),
             error_backoff: Duration::from_millis(250),
             max_consecutive_errors: Some(32),
+            use_clock: false,
         }
     }
 }
@@ -143,6 +168,16 @@ fn ratio_or_neutral(ok: u64, total: u64, neutral: f64) -> f64 {
     }
 }
 
+/// Combines send success and transport ACK success into one delivery sample;
+/// `None` when the lane had no observable traffic.
+fn delivery_sample((ok, total): (u64, u64), ack_success: Option<f64>) -> Option<f64> {
+    let send = (total > 0).then(|| ok as f64 / total as f64);
+    if send.is_none() && ack_success.is_none() {
+        return None;
+    }
+    Some(clamp01(send.unwrap_or(1.0) * ack_success.unwrap_or(1.0)))
+}
+
 fn latency_to_score(p95_latency_ms: Option<u64>, scale_ms: u64) -> f64 {
     let Some(p95) = p95_latency_ms else {
         return 0.5;
@@ -212,8 +247,14 @@ where
     pub config: NodeRuntimeConfig,
     pub decrypt_key: [u8; 32],
     pub stats: RuntimeStats,
+    /// Identity rv subscriptions rotated against wall-clock epochs each tick.
+    pub rv_subscriptions: RvSubscriptionManager,
+    clock: Box<dyn Clock + Send>,
     adaptive_lane_state: AdaptiveLaneScoringState,
+    lane_delivery: LaneDeliveryState,
     last_bloom_exchange_step: Option<u64>,
+    last_interest_advert: Option<(u64, [u8; 32])>,
+    traffic_shaper: TrafficShaper<AFast::Peer, AFallback::Peer>,
     cipher: C,
     verifier: V,
 }
@@ -234,6 +275,8 @@ where
         cipher: C,
         verifier: V,
     ) -> Self {
+        let mut state = state;
+        state.align_step_duration(config.step_duration);
         let adaptive_lane_state =
             AdaptiveLaneScoringState::new(config.base_fast_fanout, config.base_fallback_fanout);
         Self {
@@ -243,8 +286,13 @@ where
             config,
             decrypt_key,
             stats: RuntimeStats::default(),
+            rv_subscriptions: RvSubscriptionManager::default(),
+            clock: Box::new(SystemClock),
             adaptive_lane_state,
+            lane_delivery: LaneDeliveryState::default(),
             last_bloom_exchange_step: None,
+            last_interest_advert: None,
+            traffic_shaper: TrafficShaper::default(),
             cipher,
             verifier,
         }
@@ -258,6 +306,35 @@ where
         }
     }
 
+    /// Overrides the wall-clock source (e.g. with a `MockClock` in tests).
+    pub fn set_clock(&mut self, clock: impl Clock + Send + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Current step derived from the runtime clock and `config.step_duration`.
+    pub fn now_step(&self) -> u64 {
+        clock_step(&self.clock, self.config.step_duration)
+    }
+
+    /// Moves step values a tick-counting runtime left in `state` and the WoT
+    /// policy onto the clock step scale. Clock-driven ticks call this first;
+    /// it is a no-op once both have been moved.
+    pub fn adopt_clock_steps(&mut self) {
+        let now_step = self.now_step();
+        self.state.adopt_clock_steps(now_step);
+        self.config.wot_policy.adopt_clock_steps(now_step);
+    }
+
+    /// Returns cover/delay queue state for both lanes.
+    pub fn traffic_shaper(&self) -> &TrafficShaper<AFast::Peer, AFallback::Peer> {
+        &self.traffic_shaper
+    }
+
+    /// Replaces traffic shaper state (e.g. with seeded lanes for simulation).
+    pub fn set_traffic_shaper(&mut self, shaper: TrafficShaper<AFast::Peer, AFallback::Peer>) {
+        self.traffic_shaper = shaper;
+    }
+
     pub fn adaptive_lane_scores(&self) -> Option<AdaptiveLaneScoreSnapshot> {
         if !self.config.adaptive_lane_scoring.enabled {
             return None;
@@ -351,6 +428,45 @@ where
         self.adaptive_lane_state.last_fallback_snapshot = fallback;
     }
 
+    /// Refreshes `state.fec_delivery_estimate` from lane send/ACK outcomes.
+    ///
+    /// The estimate is the worse of the two lanes, so publishes pick enough
+    /// redundancy for whichever lane is currently losing shards.
+    fn update_fec_delivery_estimate(&mut self) {
+        if !self.config.loss_adaptive_fec.enabled {
+            return;
+        }
+        let alpha = self.config.adaptive_lane_scoring.ewma_alpha;
+        let fast = self.fast_adapter.health_snapshot();
+        let fallback = self.fallback_adapter.health_snapshot();
+        let fast_sample = delivery_sample(
+            send_delta(&self.lane_delivery.last_fast_snapshot, &fast),
+            self.fast_adapter.ack_success_rate(),
+        );
+        let fallback_sample = delivery_sample(
+            send_delta(&self.lane_delivery.last_fallback_snapshot, &fallback),
+            self.fallback_adapter.ack_success_rate(),
+        );
+
+        let blend = |previous: Option<f64>, sample: Option<f64>| match (previous, sample) {
+            (Some(prev), Some(next)) => Some(ewma_update(prev, next, alpha)),
+            (None, sample) => sample,
+            (prev, None) => prev,
+        };
+        self.lane_delivery.fast_ewma = blend(self.lane_delivery.fast_ewma, fast_sample);
+        self.lane_delivery.fallback_ewma = blend(self.lane_delivery.fallback_ewma, fallback_sample);
+        self.lane_delivery.last_fast_snapshot = fast;
+        self.lane_delivery.last_fallback_snapshot = fallback;
+
+        self.state.fec_delivery_estimate = match (
+            self.lane_delivery.fast_ewma,
+            self.lane_delivery.fallback_ewma,
+        ) {
+            (Some(fast), Some(fallback)) => Some(fast.min(fallback)),
+            (fast, fallback) => fast.or(fallback),
+        };
+    }
+
     fn maybe_broadcast_bloom_filters(
         &mut self,
         now_step: u64,
@@ -396,6 +512,105 @@ where
         self.last_bloom_exchange_step = Some(now_step);
     }
 
+    /// Advertises a filter of local subscriptions to every peer, on the
+    /// configured interval or as soon as the subscription set changes.
+    fn maybe_advertise_interest(
+        &mut self,
+        now_step: u64,
+        fast_peers: &[AFast::Peer],
+        fallback_peers: &[AFallback::Peer],
+    ) {
+        let cfg = self.config.interest_forwarding;
+        if !cfg.enabled {
+            return;
+        }
+        self.state
+            .peer_interests
+            .prune(now_step, cfg.advert_ttl_steps);
+
+        let mut tags = self.state.subscriptions.iter().collect::<Vec<_>>();
+        tags.sort_unstable();
+        let digest = blake3_32(
+            &tags
+                .iter()
+                .flat_map(|t| t.iter().copied())
+                .collect::<Vec<_>>(),
+        );
+        if self
+            .last_interest_advert
+            .is_some_and(|(prev, prev_digest)| {
+                prev_digest == digest
+                    && (cfg.advertise_interval_steps == 0
+                        || now_step.saturating_sub(prev) < cfg.advertise_interval_steps)
+            })
+        {
+            return;
+        }
+
+        let mut salt = [0_u8; 16];
+        salt[..8].copy_from_slice(&now_step.to_be_bytes());
+        let bf = interest_filter_for_tags(tags, cfg.false_positive_rate, salt);
+        let Ok(packet) = encode_interest_packet(now_step, bf) else {
+            return;
+        };
+
+        for peer in fast_peers {
+            if self.fast_adapter.send(peer, &packet).is_ok() {
+                self.stats.forwarded_messages += 1;
+            } else {
+                self.stats.send_failures += 1;
+            }
+        }
+        for peer in fallback_peers {
+            if self.fallback_adapter.send(peer, &packet).is_ok() {
+                self.stats.forwarded_messages += 1;
+            } else {
+                self.stats.send_failures += 1;
+            }
+        }
+        self.last_interest_advert = Some((now_step, digest));
+    }
+
+    /// Emits cover shards and releases due shaped sends on both lanes.
+    fn run_traffic_shaping(
+        &mut self,
+        now_step: u64,
+        fast_peers: &[AFast::Peer],
+        fallback_peers: &[AFallback::Peer],
+    ) {
+        if !self.config.traffic_shaping.enabled {
+            return;
+        }
+        let fast_lane = self.config.traffic_shaping.fast_lane;
+        let fallback_lane = self.config.traffic_shaping.fallback_lane;
+        self.traffic_shaper.fast_lane.emit_cover(
+            fast_peers,
+            now_step,
+            &self.config,
+            &fast_lane,
+            self.fast_adapter.max_payload_hint(),
+        );
+        self.traffic_shaper.fallback_lane.emit_cover(
+            fallback_peers,
+            now_step,
+            &self.config,
+            &fallback_lane,
+            self.fallback_adapter.max_payload_hint(),
+        );
+        self.traffic_shaper.fast_lane.flush(
+            &mut self.fast_adapter,
+            now_step,
+            &fast_lane,
+            &mut self.stats,
+        );
+        self.traffic_shaper.fallback_lane.flush(
+            &mut self.fallback_adapter,
+            now_step,
+            &fallback_lane,
+            &mut self.stats,
+        );
+    }
+
     pub fn tick(
         &mut self,
         now_step: u64,
@@ -406,35 +621,180 @@ where
         AFast::Peer: ToString,
         AFallback::Peer: ToString,
     {
+        let now_seconds = self.clock.now_seconds();
+        self.state.wall_clock_secs = Some(now_seconds);
+        self.config.wot_policy.set_wall_clock_secs(now_seconds);
+        // Rotate even with no identities left so the tags of the last removed
+        // identity are retired.
+        self.rv_subscriptions.rotate(&mut self.state, now_seconds);
+
         let (effective_fast_fanout, effective_fallback_fanout) = self.effective_lane_fanouts();
         let mut cfg = self.config.clone();
         cfg.base_fast_fanout = effective_fast_fanout;
         cfg.base_fallback_fanout = effective_fallback_fanout;
 
         let prev_ack = self.stats.ack_messages;
-        let result = pump_multi_lane_tick_with_config_split(
-            &mut self.state,
-            &mut self.fast_adapter,
-            &mut self.fallback_adapter,
-            ConfigMultiLanePumpParams {
-                fast_peers,
-                fallback_peers,
+        let params = ConfigMultiLanePumpParams {
+            fast_peers,
+            fallback_peers,
+            now_step,
+            decrypt_key: &self.decrypt_key,
+            config: &cfg,
+            stats: &mut self.stats,
+        };
+        let result = if self.config.traffic_shaping.enabled {
+            let shaping = &self.config.traffic_shaping;
+            let mut fast = ShapedLane::new(
+                &mut self.fast_adapter,
+                &mut self.traffic_shaper.fast_lane,
+                shaping,
+                shaping.fast_lane,
                 now_step,
-                decrypt_key: &self.decrypt_key,
-                config: &cfg,
-                stats: &mut self.stats,
-            },
-            &self.cipher,
-            &self.verifier,
-        );
+            );
+            let mut fallback = ShapedLane::new(
+                &mut self.fallback_adapter,
+                &mut self.traffic_shaper.fallback_lane,
+                shaping,
+                shaping.fallback_lane,
+                now_step,
+            );
+            let result = pump_multi_lane_tick_with_config_split(
+                &mut self.state,
+                &mut fast,
+                &mut fallback,
+                params,
+                &self.cipher,
+                &self.verifier,
+            );
+            self.stats.shaping_dropped_messages += fast.dropped() + fallback.dropped();
+            result
+        } else {
+            pump_multi_lane_tick_with_config_split(
+                &mut self.state,
+                &mut self.fast_adapter,
+                &mut self.fallback_adapter,
+                params,
+                &self.cipher,
+                &self.verifier,
+            )
+        };
+        self.run_traffic_shaping(now_step, fast_peers, fallback_peers);
         if result.is_ok() {
             let ack_delta = self.stats.ack_messages.saturating_sub(prev_ack);
             self.update_adaptive_lane_scoring(ack_delta);
+            self.update_fec_delivery_estimate();
             self.maybe_broadcast_bloom_filters(now_step, fast_peers, fallback_peers);
+            self.maybe_advertise_interest(now_step, fast_peers, fallback_peers);
+            self.request_log_repairs(now_step, fast_peers, fallback_peers);
         }
         result
     }
 
+    /// Runs one tick at the clock-derived step (see [`Self::now_step`]).
+    pub fn tick_now(
+        &mut self,
+        fast_peers: &[AFast::Peer],
+        fallback_peers: &[AFallback::Peer],
+    ) -> Result<Option<ReceiveEvent>, ReceiveError>
+    where
+        AFast::Peer: ToString,
+        AFallback::Peer: ToString,
+    {
+        self.adopt_clock_steps();
+        let now_step = self.now_step();
+        self.tick(now_step, fast_peers, fallback_peers)
+    }
+
+    /// Asks peers on both lanes for cached history matching `request`.
+    ///
+    /// Answers arrive as ordinary shards on later ticks. Returns the number of
+    /// peers the request reached.
+    pub fn request_backfill(
+        &mut self,
+        request: &BackfillRequest,
+        now_step: u64,
+        fast_peers: &[AFast::Peer],
+        fallback_peers: &[AFallback::Peer],
+    ) -> usize {
+        let cfg = self.config.backfill;
+        let sent = request_backfill(
+            &mut self.state,
+            &mut self.fast_adapter,
+            fast_peers,
+            request,
+            now_step,
+            cfg,
+        ) + request_backfill(
+            &mut self.state,
+            &mut self.fallback_adapter,
+            fallback_peers,
+            request,
+            now_step,
+            cfg,
+        );
+        self.stats.forwarded_messages += sent;
+        sent
+    }
+
+    /// Asks peers for the missing predecessors of publisher logs that gained
+    /// gaps. Repairs ride on backfill, so they follow its opt-in and budgets.
+    fn request_log_repairs(
+        &mut self,
+        now_step: u64,
+        fast_peers: &[AFast::Peer],
+        fallback_peers: &[AFallback::Peer],
+    ) {
+        if !self.config.backfill.enabled {
+            return;
+        }
+        let max_shards = self.config.backfill.max_shards_per_response as u32;
+        for request in self.state.publisher_logs.take_repair_requests(max_shards) {
+            self.request_backfill(&request, now_step, fast_peers, fallback_peers);
+        }
+    }
+
+    /// Applies `tombstone` locally and sends it to peers on both lanes.
+    ///
+    /// Returns the number of peers the tombstone reached.
+    pub fn publish_tombstone(
+        &mut self,
+        tombstone: &Tombstone,
+        now_step: u64,
+        fast_peers: &[AFast::Peer],
+        fallback_peers: &[AFallback::Peer],
+    ) -> usize {
+        let outcome = apply_tombstone(
+            &mut self.state,
+            tombstone,
+            &self.verifier,
+            now_step,
+            self.config.tombstones.retention_steps,
+        );
+        if let TombstoneOutcome::Applied { purged } = outcome {
+            self.stats.purged_shards += purged;
+        }
+        let Ok(packet) = encode_tombstone_packet(tombstone) else {
+            return 0;
+        };
+        let mut sent = 0;
+        for peer in fast_peers {
+            if self.fast_adapter.send(peer, &packet).is_ok() {
+                sent += 1;
+            } else {
+                self.stats.send_failures += 1;
+            }
+        }
+        for peer in fallback_peers {
+            if self.fallback_adapter.send(peer, &packet).is_ok() {
+                sent += 1;
+            } else {
+                self.stats.send_failures += 1;
+            }
+        }
+        self.stats.forwarded_messages += sent;
+        sent
+    }
+
     pub fn tick_with_callbacks(
         &mut self,
         now_step: u64,
@@ -506,6 +866,11 @@ where
                 (*cb)(endorsement_delta);
             }
         }
+        if let Some(cb) = callbacks.on_equivocation.as_mut() {
+            for evidence in self.state.publisher_logs.take_evidence() {
+                (*cb)(&evidence);
+            }
+        }
 
         Ok(event)
     }
@@ -533,10 +898,16 @@ where
                 return NodeRuntimeRunnerExit::Cancelled { steps };
             }
 
+            let now_step = if config.use_clock {
+                self.adopt_clock_steps();
+                self.now_step()
+            } else {
+                step
+            };
             let tick_result = if let Some(cb) = callbacks.as_deref_mut() {
-                self.tick_with_callbacks_ref(step, fast_peers, fallback_peers, cb)
+                self.tick_with_callbacks_ref(now_step, fast_peers, fallback_peers, cb)
             } else {
-                self.tick(step, fast_peers, fallback_peers)
+                self.tick(now_step, fast_peers, fallback_peers)
             };
 
             match tick_result {
@@ -585,10 +956,16 @@ where
         let mut consecutive_errors = 0_u32;
 
         while ran < steps {
+            let now_step = if config.use_clock {
+                self.adopt_clock_steps();
+                self.now_step()
+            } else {
+                step
+            };
             let tick_result = if let Some(cb) = callbacks.as_deref_mut() {
-                self.tick_with_callbacks_ref(step, fast_peers, fallback_peers, cb)
+                self.tick_with_callbacks_ref(now_step, fast_peers, fallback_peers, cb)
             } else {
-                self.tick(step, fast_peers, fallback_peers)
+                self.tick(now_step, fast_peers, fallback_peers)
             };
 
             match tick_result {
@@ -661,6 +1038,8 @@ where
         signer: Option<S>,
         cipher: C,
     ) -> Self {
+        let mut state = state;
+        state.align_step_duration(config.step_duration);
         Self {
             state,
             batcher,
@@ -726,8 +1105,9 @@ where
 mod tests {
     use std::time::Duration;
 
-    use crate::bloom::decode_bloom_exchange_packet;
-    use crate::config::BloomExchangeConfig;
+    use crate::bloom::{decode_bloom_exchange_packet, decode_interest_packet};
+    use crate::clock::MockClock;
+    use crate::config::{BloomExchangeConfig, InterestForwardingConfig};
     use veil_codec::object::OBJECT_FLAG_SIGNED;
     use veil_codec::shard::encode_shard_cbor;
     use veil_core::{Epoch, Namespace};
@@ -818,6 +1198,48 @@ mod tests {
         assert!(decode_bloom_exchange_packet(&fallback[0].1).is_some());
     }
 
+    #[test]
+    fn node_runtime_advertises_interest_on_interval_and_subscription_change() {
+        let mut rt = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            crate::config::NodeRuntimeConfig::builder()
+                .interest_forwarding(InterestForwardingConfig {
+                    enabled: true,
+                    advertise_interval_steps: 100,
+                    ..InterestForwardingConfig::default()
+                })
+                .build(),
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        let peers = vec!["peer-a".to_string()];
+        let tag = [0x61_u8; 32];
+        rt.state.subscriptions.insert(tag);
+
+        let _ = rt.tick(1, &peers, &peers).expect("tick should succeed");
+        let fast = rt.fast_adapter.take_outbound();
+        assert_eq!(fast.len(), 1);
+        let advert = decode_interest_packet(&fast[0].1).expect("interest packet");
+        assert!(advert.filter.might_contain(&tag));
+        assert_eq!(rt.fallback_adapter.take_outbound().len(), 1);
+
+        let _ = rt.tick(2, &peers, &peers).expect("tick should succeed");
+        assert!(rt.fast_adapter.take_outbound().is_empty());
+
+        let added = [0x62_u8; 32];
+        rt.state.subscriptions.insert(added);
+        let _ = rt.tick(3, &peers, &peers).expect("tick should succeed");
+        let fast = rt.fast_adapter.take_outbound();
+        let advert = decode_interest_packet(&fast[0].1).expect("interest packet");
+        assert!(advert.filter.might_contain(&added));
+
+        let _ = rt.tick(103, &peers, &peers).expect("tick should succeed");
+        assert_eq!(rt.fast_adapter.take_outbound().len(), 1);
+    }
+
     #[test]
     fn bloom_exchange_interval_estimates_control_plane_traffic_overhead() {
         let peers = vec!["peer-a".to_string(), "peer-b".to_string()];
@@ -954,6 +1376,277 @@ mod tests {
         assert_eq!(send_failure_count, 0);
     }
 
+    #[test]
+    fn node_runtime_traffic_shaping_emits_cover_when_idle() {
+        let mut cfg = crate::config::NodeRuntimeConfig::default();
+        cfg.traffic_shaping.enabled = true;
+        cfg.traffic_shaping.fast_lane.cover_rate_per_step = 2.0;
+        let mut rt = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            cfg,
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        rt.set_traffic_shaper(crate::shaping::TrafficShaper::with_seed(42));
+        let peers = vec!["peer-a".to_string()];
+
+        for step in 0..40 {
+            rt.tick(step, &peers, &peers).expect("tick should succeed");
+        }
+
+        let fast_out = rt.fast_adapter.take_outbound();
+        assert!(!fast_out.is_empty());
+        assert!(rt.fallback_adapter.take_outbound().is_empty());
+        for (_, bytes) in &fast_out {
+            veil_codec::shard::decode_shard_cbor(bytes).expect("cover is shard-shaped");
+        }
+        assert_eq!(rt.stats.cover_messages, fast_out.len());
+        assert_eq!(rt.stats.shaped_real_messages, 0);
+        assert_eq!(rt.stats.forwarded_messages, 0);
+
+        // A relay accepting every tag drops cover at the first hop.
+        let mut relay = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            crate::config::NodeRuntimeConfig::builder()
+                .accept_all_tags(true)
+                .build(),
+            [0xBB; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        let relay_peers = vec!["peer-a".to_string(), "peer-b".to_string()];
+        for (_, bytes) in &fast_out {
+            relay.fast_adapter.enqueue_inbound("peer-a", bytes.clone());
+        }
+        for step in 0..fast_out.len() as u64 {
+            relay
+                .tick(step, &relay_peers, &[])
+                .expect("tick should succeed");
+        }
+        assert_eq!(relay.stats.cover_received_messages, fast_out.len());
+        assert!(relay.fast_adapter.take_outbound().is_empty());
+        assert!(relay.state.cache.is_empty());
+        assert!(relay.state.inbox.is_empty());
+    }
+
+    #[test]
+    fn node_runtime_traffic_shaping_delays_forwarded_shards() {
+        let mut cfg = crate::config::NodeRuntimeConfig::builder()
+            .accept_all_tags(true)
+            .build();
+        cfg.traffic_shaping.enabled = true;
+        cfg.traffic_shaping.default_delay = crate::config::DelayDistribution::Uniform {
+            min_steps: 3,
+            max_steps: 3,
+        };
+        let mut rt = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            cfg,
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        let object = vec![0x5A; 600];
+        let shards = object_to_shards(
+            &object,
+            Namespace(1),
+            Epoch(1),
+            [0x44; 32],
+            derive_object_root(&object),
+        )
+        .expect("shards");
+        rt.fast_adapter
+            .enqueue_inbound("peer-a", encode_shard_cbor(&shards[0]).expect("encode"));
+        let peers = vec!["peer-a".to_string(), "peer-b".to_string()];
+
+        rt.tick(1, &peers, &[]).expect("tick should succeed");
+        assert!(rt.fast_adapter.take_outbound().is_empty());
+        assert_eq!(rt.traffic_shaper().fast_lane.pending_len(), 1);
+
+        rt.tick(3, &peers, &[]).expect("tick should succeed");
+        assert!(rt.fast_adapter.take_outbound().is_empty());
+
+        rt.tick(4, &peers, &[]).expect("tick should succeed");
+        let out = rt.fast_adapter.take_outbound();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].0, "peer-b");
+        assert_eq!(rt.stats.shaped_real_messages, 1);
+        assert_eq!(rt.stats.cover_messages, 0);
+    }
+
+    #[test]
+    fn node_runtime_tick_rotates_identity_rv_subscriptions_by_wall_clock() {
+        let mut rt = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            crate::config::NodeRuntimeConfig::default(),
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        let clock = MockClock::new(Duration::from_secs(86_400 + 100));
+        rt.set_clock(clock.clone());
+        let identity =
+            crate::subscriptions::IdentitySubscription::recipient([0x77; 32], Namespace(2));
+        rt.rv_subscriptions.add_identity(identity.clone());
+        let peers = vec!["peer-a".to_string()];
+
+        rt.tick(1, &peers, &peers).expect("tick should succeed");
+        assert!(rt
+            .state
+            .subscriptions
+            .contains(&identity.tag_for_epoch(Epoch(1))));
+
+        // Step counter barely moves but two days of wall-clock time pass.
+        clock.advance(Duration::from_secs(2 * 86_400));
+        rt.tick(2, &peers, &peers).expect("tick should succeed");
+        assert!(!rt
+            .state
+            .subscriptions
+            .contains(&identity.tag_for_epoch(Epoch(1))));
+        assert!(rt
+            .state
+            .subscriptions
+            .contains(&identity.tag_for_epoch(Epoch(3))));
+
+        rt.rv_subscriptions.remove_identity(&identity);
+        rt.tick(3, &peers, &peers).expect("tick should succeed");
+        assert!(!rt
+            .state
+            .subscriptions
+            .contains(&identity.tag_for_epoch(Epoch(3))));
+        assert_eq!(rt.rv_subscriptions.installed_len(), 0);
+    }
+
+    #[test]
+    fn node_runtime_repairs_publisher_log_gaps_and_reports_equivocation() {
+        use crate::publisher_log::{EquivocationEvidence, LogLink};
+
+        let cfg = crate::config::NodeRuntimeConfig::builder()
+            .backfill(crate::config::BackfillConfig {
+                enabled: true,
+                ..crate::config::BackfillConfig::default()
+            })
+            .build();
+        let mut rt = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            cfg,
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        let (publisher, tag) = ([0x51; 32], [0x52; 32]);
+        let logs = &mut rt.state.publisher_logs;
+        for (seq, prev_root, root) in [(0, None, [1; 32]), (2, Some([2; 32]), [3; 32])] {
+            logs.observe(publisher, tag, LogLink { seq, prev_root }, root, root);
+        }
+        logs.observe(
+            publisher,
+            tag,
+            LogLink {
+                seq: 0,
+                prev_root: None,
+            },
+            [9; 32],
+            [9; 32],
+        );
+
+        let peers = vec!["peer-a".to_string()];
+        let mut evidence: Vec<EquivocationEvidence> = Vec::new();
+        let mut on_equivocation = |e: &EquivocationEvidence| evidence.push(e.clone());
+        rt.tick_with_callbacks(
+            1,
+            &peers,
+            &[],
+            NodeRuntimeCallbacks {
+                on_equivocation: Some(&mut on_equivocation),
+                ..NodeRuntimeCallbacks::default()
+            },
+        )
+        .expect("tick should succeed");
+
+        let requests = rt
+            .fast_adapter
+            .take_outbound()
+            .into_iter()
+            .filter_map(|(_, bytes)| crate::backfill::decode_backfill_request(&bytes))
+            .collect::<Vec<_>>();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].roots, vec![[2; 32]]);
+        assert_eq!(evidence.len(), 1);
+        assert_eq!(evidence[0].roots, [[1; 32], [9; 32]]);
+    }
+
+    #[test]
+    fn node_runtime_clock_steps_keep_cache_valid_across_restart() {
+        let clock = MockClock::new(Duration::from_secs(1_000));
+        let cfg = crate::config::NodeRuntimeConfig::builder()
+            .accept_all_tags(true)
+            .ttl(Duration::from_secs(60))
+            .build();
+        let mut rt = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            cfg.clone(),
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        rt.set_clock(clock.clone());
+        let object = vec![0x5A; 600];
+        let shards = object_to_shards(
+            &object,
+            Namespace(1),
+            Epoch(1),
+            [0x44; 32],
+            derive_object_root(&object),
+        )
+        .expect("shards");
+        rt.fast_adapter
+            .enqueue_inbound("peer-a", encode_shard_cbor(&shards[0]).expect("encode"));
+        rt.tick_now(&[], &[]).expect("tick should succeed");
+        assert_eq!(rt.state.cache.len(), 1);
+
+        // Restart with a coarser step resolution 30s later: entry still live.
+        let state_bytes = crate::persistence::encode_state_cbor(&mut rt.state).expect("encode");
+        let state = crate::persistence::decode_state_cbor(&state_bytes).expect("decode");
+        clock.advance(Duration::from_secs(30));
+        let mut coarse = cfg.clone();
+        coarse.step_duration = Duration::from_millis(500);
+        coarse.ttl_steps = coarse.steps_for(Duration::from_secs(60));
+        let mut rt = NodeRuntime::new(
+            state,
+            InMemoryAdapter::default(),
+            InMemoryAdapter::default(),
+            coarse,
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        rt.set_clock(clock.clone());
+        let now_step = rt.now_step();
+        let expiry = rt.state.cache.values().next().expect("cached").expiry_step;
+        assert!(expiry > now_step);
+        assert_eq!(
+            rt.config.duration_of(expiry - now_step),
+            Duration::from_secs(30)
+        );
+
+        clock.advance(Duration::from_secs(31));
+        assert!(expiry <= rt.now_step());
+    }
+
     #[test]
     fn node_runtime_run_steps_completes_requested_budget() {
         let mut rt = NodeRuntime::new(
@@ -975,6 +1668,7 @@ mod tests {
                 tick_interval: Duration::ZERO,
                 error_backoff: Duration::ZERO,
                 max_consecutive_errors: Some(4),
+                use_clock: false,
             },
             None,
         );
@@ -1003,6 +1697,7 @@ mod tests {
                 tick_interval: Duration::ZERO,
                 error_backoff: Duration::ZERO,
                 max_consecutive_errors: Some(4),
+                use_clock: false,
             },
             || {
                 polls += 1;
@@ -1067,6 +1762,56 @@ mod tests {
         assert!(adaptive.effective_fast_fanout <= 2);
     }
 
+    #[test]
+    fn loss_adaptive_fec_tracks_lossy_lane_and_escalates_profile() {
+        let mut fast = CappedInMemoryAdapter::with_max_send_bytes(16 * 1024);
+        fast.set_allow_send(false);
+        let fallback = CappedInMemoryAdapter::with_max_send_bytes(16 * 1024);
+
+        let config = crate::config::NodeRuntimeConfig::builder()
+            .loss_adaptive_fec(crate::config::LossAdaptiveFecConfig {
+                enabled: true,
+                ..crate::config::LossAdaptiveFecConfig::default()
+            })
+            .build();
+        let mut rt = NodeRuntime::new(
+            crate::state::NodeState::default(),
+            fast,
+            fallback,
+            config,
+            [0xAA; 32],
+            XChaCha20Poly1305Cipher,
+            Ed25519Verifier,
+        );
+        let tag = [0x45; 32];
+        rt.state.subscriptions.insert(tag);
+        let encoded = b"loss adaptive fec probe".to_vec();
+        let root = derive_object_root(&encoded);
+        let shard = object_to_shards(&encoded, Namespace(1), Epoch(1), tag, root)
+            .expect("shard build should work")
+            .remove(0);
+        let shard_bytes = encode_shard_cbor(&shard).expect("shard should encode");
+        let peers = vec!["peer-a".to_string(), "peer-b".to_string()];
+
+        let _ = rt.tick(1, &peers, &peers);
+        assert_eq!(rt.state.fec_delivery_estimate, None, "no traffic observed");
+
+        rt.fast_adapter.enqueue_inbound("origin", shard_bytes);
+        let _ = rt.tick(2, &peers, &peers);
+        let estimate = rt
+            .state
+            .fec_delivery_estimate
+            .expect("forwarding attempts observed");
+        assert!(
+            estimate < 0.5,
+            "fast-lane failures lower delivery: {estimate}"
+        );
+
+        let profile =
+            crate::publish::select_publish_profile(&rt.state, &rt.config, 512).expect("profile");
+        assert_eq!(profile, veil_fec::profile::PROFILE_MICRO_RESILIENT);
+    }
+
     #[test]
     fn normal_social_usage_estimates_total_network_traffic() {
         let tag = [0x55; 32];
@@ -0,0 +1,654 @@
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use thiserror::Error;
+use veil_codec::shard::{
+    decode_shard_cbor, encode_shard_cbor, ShardErasureMode, ShardHeaderV1, ShardV1,
+    SHARD_HEADER_LEN, SHARD_V1_VERSION,
+};
+use veil_core::hash::blake3_32;
+use veil_core::{Epoch, Namespace, ObjectRoot, Tag};
+use veil_fec::profile::PROFILE_MICRO;
+use veil_fec::sharder::erasure_mode_to_wire;
+use veil_transport::adapter::{TransportAdapter, TransportHealthSnapshot};
+
+use crate::config::{DelayDistribution, LaneShapingConfig, NodeRuntimeConfig};
+use crate::runtime::RuntimeStats;
+
+/// Upper bound on cover shards emitted per lane in one step.
+const MAX_COVER_PER_STEP: usize = 64;
+/// Number of recently observed real shard shapes cover traffic mimics.
+const OBSERVED_SHAPE_WINDOW: usize = 32;
+/// Domain separating cover shard roots from real object roots.
+const COVER_ROOT_DOMAIN: &[u8] = b"veil/cover-root/v1";
+
+/// Errors returned by shaped lane sends.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ShapingError {
+    #[error("traffic shaping queue is full")]
+    QueueFull,
+    #[error("transport send failed")]
+    Transport,
+}
+
+/// Header shape of a real shard, reused when synthesizing cover shards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ShardShape {
+    namespace: u16,
+    epoch: u32,
+    profile_id: u16,
+    erasure_mode: ShardErasureMode,
+    bucket_size: u32,
+    k: u16,
+    n: u16,
+}
+
+#[derive(Debug, Clone)]
+struct ShapedSend<P> {
+    release_step: u64,
+    seq: u64,
+    peer: P,
+    bytes: Vec<u8>,
+    cover: bool,
+}
+
+/// Per-lane delay queue, bandwidth cap, and cover generator.
+#[derive(Debug, Clone)]
+pub struct LaneShaper<P> {
+    pending: Vec<ShapedSend<P>>,
+    observed: Vec<ShardShape>,
+    rng: StdRng,
+    next_seq: u64,
+}
+
+impl<P> Default for LaneShaper<P> {
+    fn default() -> Self {
+        Self::from_rng(StdRng::from_entropy())
+    }
+}
+
+impl<P> LaneShaper<P> {
+    /// Creates a lane shaper with a deterministic RNG seed (tests/simulation).
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(rng: StdRng) -> Self {
+        Self {
+            pending: Vec::new(),
+            observed: Vec::new(),
+            rng,
+            next_seq: 0,
+        }
+    }
+
+    /// Number of sends waiting for release.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Total bytes waiting for release.
+    pub fn pending_bytes(&self) -> usize {
+        self.pending.iter().map(|s| s.bytes.len()).sum()
+    }
+
+    fn observe(&mut self, shard: &ShardV1) {
+        let h = &shard.header;
+        let shape = ShardShape {
+            namespace: h.namespace.0,
+            epoch: h.epoch.0,
+            profile_id: h.profile_id,
+            erasure_mode: h.erasure_mode,
+            bucket_size: h.bucket_size,
+            k: h.k,
+            n: h.n,
+        };
+        if self.observed.len() >= OBSERVED_SHAPE_WINDOW {
+            self.observed.remove(0);
+        }
+        self.observed.push(shape);
+    }
+
+    fn push(
+        &mut self,
+        peer: P,
+        bytes: Vec<u8>,
+        release_step: u64,
+        cover: bool,
+        max_queued: usize,
+    ) -> Result<(), ShapingError> {
+        if self.pending.len() >= max_queued {
+            return Err(ShapingError::QueueFull);
+        }
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        self.pending.push(ShapedSend {
+            release_step,
+            seq,
+            peer,
+            bytes,
+            cover,
+        });
+        Ok(())
+    }
+
+    /// Queues a relayed shard, delaying it by the namespace's distribution.
+    pub fn enqueue(
+        &mut self,
+        peer: P,
+        bytes: Vec<u8>,
+        now_step: u64,
+        config: &crate::config::TrafficShapingConfig,
+        lane: &LaneShapingConfig,
+    ) -> Result<(), ShapingError> {
+        let delay = match decode_shard_cbor(&bytes) {
+            Ok(shard) => {
+                self.observe(&shard);
+                config.delay_for_namespace(shard.header.namespace.0)
+            }
+            Err(_) => config.default_delay,
+        };
+        let release_step = now_step.saturating_add(sample_delay(&mut self.rng, delay));
+        self.push(peer, bytes, release_step, false, lane.max_queued_sends)
+    }
+
+    /// Queues a Poisson-distributed number of cover shards to random peers.
+    ///
+    /// Cover shards reuse recently observed real header shapes (namespace,
+    /// epoch, profile, bucket) with random tag, index, and payload. Their
+    /// root is derived from the tag (see [`is_cover_shard`]) so the first
+    /// hop drops them instead of caching and forwarding them.
+    pub fn emit_cover(
+        &mut self,
+        peers: &[P],
+        now_step: u64,
+        config: &NodeRuntimeConfig,
+        lane: &LaneShapingConfig,
+        max_payload_hint: Option<usize>,
+    ) -> usize
+    where
+        P: Clone,
+    {
+        if peers.is_empty() || lane.cover_rate_per_step <= 0.0 {
+            return 0;
+        }
+        let count = sample_poisson(&mut self.rng, lane.cover_rate_per_step).min(MAX_COVER_PER_STEP);
+        let mut queued = 0;
+        for _ in 0..count {
+            let shape = self.pick_cover_shape(config);
+            let Some(bytes) = build_cover_shard(&mut self.rng, shape) else {
+                continue;
+            };
+            if max_payload_hint.is_some_and(|hint| bytes.len() > hint) {
+                continue;
+            }
+            let peer = peers[self.rng.gen_range(0..peers.len())].clone();
+            let delay = config.traffic_shaping.delay_for_namespace(shape.namespace);
+            let release_step = now_step.saturating_add(sample_delay(&mut self.rng, delay));
+            if self
+                .push(peer, bytes, release_step, true, lane.max_queued_sends)
+                .is_err()
+            {
+                break;
+            }
+            queued += 1;
+        }
+        queued
+    }
+
+    fn pick_cover_shape(&mut self, config: &NodeRuntimeConfig) -> ShardShape {
+        if !self.observed.is_empty() {
+            let idx = self.rng.gen_range(0..self.observed.len());
+            return self.observed[idx];
+        }
+        let namespaces = &config.traffic_shaping.cover_namespaces;
+        let namespace = if namespaces.is_empty() {
+            veil_core::types::NAMESPACE_PUBLIC_FEED.0
+        } else {
+            namespaces[self.rng.gen_range(0..namespaces.len())]
+        };
+        let erasure_mode =
+            erasure_mode_to_wire(config.erasure_mode_for_namespace(Namespace(namespace)));
+        ShardShape {
+            namespace,
+            epoch: 0,
+            profile_id: PROFILE_MICRO.id,
+            erasure_mode,
+            bucket_size: PROFILE_MICRO.buckets[0] as u32,
+            k: PROFILE_MICRO.k,
+            n: PROFILE_MICRO.n,
+        }
+    }
+
+    /// Releases due sends through `adapter`, honoring the lane byte cap.
+    ///
+    /// Sends are released in due order; the first send that does not fit the
+    /// remaining step budget stops the flush so ordering is preserved.
+    /// Returns the number of sends released this step.
+    pub fn flush<A>(
+        &mut self,
+        adapter: &mut A,
+        now_step: u64,
+        lane: &LaneShapingConfig,
+        stats: &mut RuntimeStats,
+    ) -> usize
+    where
+        A: TransportAdapter<Peer = P>,
+    {
+        let (mut due, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|s| s.release_step <= now_step);
+        self.pending = rest;
+        due.sort_by_key(|s| (s.release_step, s.seq));
+
+        let mut budget = lane.max_bytes_per_step.unwrap_or(usize::MAX);
+        let mut released = 0;
+        let mut due = due.into_iter();
+        for send in due.by_ref() {
+            // An oversized send still goes out alone so it cannot stall the lane.
+            if send.bytes.len() > budget && released > 0 {
+                self.pending.push(send);
+                break;
+            }
+            budget = budget.saturating_sub(send.bytes.len());
+            released += 1;
+            if adapter.send(&send.peer, &send.bytes).is_err() {
+                stats.send_failures += 1;
+                continue;
+            }
+            if send.cover {
+                stats.cover_messages += 1;
+                stats.cover_bytes += send.bytes.len();
+            } else {
+                stats.shaped_real_messages += 1;
+                stats.shaped_real_bytes += send.bytes.len();
+            }
+        }
+        self.pending.extend(due);
+        released
+    }
+}
+
+/// Cover/delay state for both runtime lanes.
+#[derive(Debug, Clone)]
+pub struct TrafficShaper<PFast, PFallback> {
+    pub fast_lane: LaneShaper<PFast>,
+    pub fallback_lane: LaneShaper<PFallback>,
+}
+
+impl<PFast, PFallback> Default for TrafficShaper<PFast, PFallback> {
+    fn default() -> Self {
+        Self {
+            fast_lane: LaneShaper::default(),
+            fallback_lane: LaneShaper::default(),
+        }
+    }
+}
+
+impl<PFast, PFallback> TrafficShaper<PFast, PFallback> {
+    /// Creates lane shapers with deterministic RNG seeds.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            fast_lane: LaneShaper::with_seed(seed),
+            fallback_lane: LaneShaper::with_seed(seed.wrapping_add(1)),
+        }
+    }
+}
+
+/// Transport adapter view that routes relayed shards through a lane shaper
+/// queue.
+///
+/// Direct sends (ACKs, backfill responses, control packets), receives, and
+/// health queries pass through to the wrapped adapter.
+pub struct ShapedLane<'a, A: TransportAdapter> {
+    adapter: &'a mut A,
+    shaper: &'a mut LaneShaper<A::Peer>,
+    config: &'a crate::config::TrafficShapingConfig,
+    lane: LaneShapingConfig,
+    now_step: u64,
+    stats_dropped: usize,
+}
+
+impl<'a, A: TransportAdapter> ShapedLane<'a, A> {
+    pub fn new(
+        adapter: &'a mut A,
+        shaper: &'a mut LaneShaper<A::Peer>,
+        config: &'a crate::config::TrafficShapingConfig,
+        lane: LaneShapingConfig,
+        now_step: u64,
+    ) -> Self {
+        Self {
+            adapter,
+            shaper,
+            config,
+            lane,
+            now_step,
+            stats_dropped: 0,
+        }
+    }
+
+    /// Number of sends rejected because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.stats_dropped
+    }
+}
+
+impl<A: TransportAdapter> TransportAdapter for ShapedLane<'_, A> {
+    type Peer = A::Peer;
+    type Error = ShapingError;
+
+    fn send(&mut self, peer: &Self::Peer, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.adapter
+            .send(peer, bytes)
+            .map_err(|_| ShapingError::Transport)
+    }
+
+    fn relay(&mut self, peer: &Self::Peer, bytes: &[u8]) -> Result<(), Self::Error> {
+        let result = self.shaper.enqueue(
+            peer.clone(),
+            bytes.to_vec(),
+            self.now_step,
+            self.config,
+            &self.lane,
+        );
+        if result.is_err() {
+            self.stats_dropped += 1;
+        }
+        result
+    }
+
+    fn recv(&mut self) -> Option<(Self::Peer, Vec<u8>)> {
+        self.adapter.recv()
+    }
+
+    fn max_payload_hint(&self) -> Option<usize> {
+        self.adapter.max_payload_hint()
+    }
+
+    fn can_send(&self) -> bool {
+        self.adapter.can_send()
+    }
+
+    fn can_recv(&self) -> bool {
+        self.adapter.can_recv()
+    }
+
+    fn health_snapshot(&self) -> TransportHealthSnapshot {
+        self.adapter.health_snapshot()
+    }
+
+    fn p95_latency_ms(&self) -> Option<u64> {
+        self.adapter.p95_latency_ms()
+    }
+
+    fn ack_success_rate(&self) -> Option<f64> {
+        self.adapter.ack_success_rate()
+    }
+}
+
+/// Samples a delay in steps from `dist`.
+pub fn sample_delay<R: Rng>(rng: &mut R, dist: DelayDistribution) -> u64 {
+    match dist {
+        DelayDistribution::None => 0,
+        DelayDistribution::Uniform {
+            min_steps,
+            max_steps,
+        } => {
+            if max_steps <= min_steps {
+                min_steps
+            } else {
+                rng.gen_range(min_steps..=max_steps)
+            }
+        }
+        DelayDistribution::Exponential {
+            mean_steps,
+            max_steps,
+        } => {
+            if mean_steps <= 0.0 {
+                return 0;
+            }
+            // 1 - U lies in (0, 1], so ln() stays finite.
+            let u: f64 = 1.0 - rng.gen::<f64>();
+            let delay = (-mean_steps * u.ln()).round();
+            (delay as u64).min(max_steps)
+        }
+    }
+}
+
+/// Samples a Poisson-distributed count with mean `rate` (Knuth).
+fn sample_poisson<R: Rng>(rng: &mut R, rate: f64) -> usize {
+    if rate <= 0.0 {
+        return 0;
+    }
+    let limit = (-rate).exp();
+    let mut product: f64 = rng.gen();
+    let mut count = 0;
+    while product > limit && count < MAX_COVER_PER_STEP {
+        product *= rng.gen::<f64>();
+        count += 1;
+    }
+    count
+}
+
+fn cover_root(tag: &Tag) -> ObjectRoot {
+    blake3_32(&[COVER_ROOT_DOMAIN, tag.as_slice()].concat())
+}
+
+/// Whether `header` belongs to a cover shard, which receivers drop without
+/// caching or forwarding.
+pub fn is_cover_shard(header: &ShardHeaderV1) -> bool {
+    header.object_root == cover_root(&header.tag)
+}
+
+fn build_cover_shard<R: RngCore>(rng: &mut R, shape: ShardShape) -> Option<Vec<u8>> {
+    let mut tag = [0_u8; 32];
+    rng.fill_bytes(&mut tag);
+    let object_root = cover_root(&tag);
+    let mut payload = vec![0_u8; (shape.bucket_size as usize).checked_sub(SHARD_HEADER_LEN)?];
+    rng.fill_bytes(&mut payload);
+    let index = (rng.next_u32() % u32::from(shape.n.max(1))) as u16;
+    let shard = ShardV1 {
+        header: ShardHeaderV1 {
+            version: SHARD_V1_VERSION,
+            namespace: Namespace(shape.namespace),
+            epoch: Epoch(shape.epoch),
+            tag,
+            object_root,
+            profile_id: shape.profile_id,
+            erasure_mode: shape.erasure_mode,
+            bucket_size: shape.bucket_size,
+            k: shape.k,
+            n: shape.n,
+            index,
+        },
+        payload,
+    };
+    encode_shard_cbor(&shard).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use veil_codec::shard::decode_shard_cbor;
+    use veil_core::{Epoch, Namespace};
+    use veil_fec::sharder::object_to_shards;
+    use veil_transport::adapter::{InMemoryAdapter, TransportAdapter};
+
+    use super::{is_cover_shard, sample_delay, sample_poisson, LaneShaper, ShapedLane};
+    use crate::config::{
+        DelayDistribution, LaneShapingConfig, NodeRuntimeConfig, TrafficShapingConfig,
+    };
+    use crate::runtime::RuntimeStats;
+
+    fn real_shard_bytes(namespace: u16) -> Vec<u8> {
+        let shards = object_to_shards(
+            &[0x5A; 600],
+            Namespace(namespace),
+            Epoch(9),
+            [0x44; 32],
+            [0x55; 32],
+        )
+        .expect("shards");
+        veil_codec::shard::encode_shard_cbor(&shards[0]).expect("encode")
+    }
+
+    #[test]
+    fn delay_samples_stay_within_bounds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            let d = sample_delay(
+                &mut rng,
+                DelayDistribution::Uniform {
+                    min_steps: 2,
+                    max_steps: 5,
+                },
+            );
+            assert!((2..=5).contains(&d));
+            let e = sample_delay(
+                &mut rng,
+                DelayDistribution::Exponential {
+                    mean_steps: 3.0,
+                    max_steps: 10,
+                },
+            );
+            assert!(e <= 10);
+        }
+        assert_eq!(sample_delay(&mut rng, DelayDistribution::None), 0);
+    }
+
+    #[test]
+    fn poisson_sample_mean_tracks_rate() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let total: usize = (0..2_000).map(|_| sample_poisson(&mut rng, 2.0)).sum();
+        let mean = total as f64 / 2_000.0;
+        assert!((1.7..2.3).contains(&mean), "mean={mean}");
+    }
+
+    #[test]
+    fn shaped_lane_delays_sends_by_namespace() {
+        let mut adapter = InMemoryAdapter::default();
+        let mut shaper = LaneShaper::with_seed(3);
+        let mut cfg = TrafficShapingConfig {
+            enabled: true,
+            default_delay: DelayDistribution::None,
+            ..TrafficShapingConfig::default()
+        };
+        cfg.namespace_delays.insert(
+            2,
+            DelayDistribution::Uniform {
+                min_steps: 4,
+                max_steps: 4,
+            },
+        );
+        let lane = LaneShapingConfig::default();
+        let mut stats = RuntimeStats::default();
+        {
+            let mut shaped = ShapedLane::new(&mut adapter, &mut shaper, &cfg, lane, 10);
+            shaped
+                .relay(&"peer-a".to_string(), &real_shard_bytes(1))
+                .expect("queue");
+            shaped
+                .relay(&"peer-a".to_string(), &real_shard_bytes(2))
+                .expect("queue");
+            // Direct sends skip the delay queue.
+            shaped
+                .send(&"peer-b".to_string(), &real_shard_bytes(2))
+                .expect("send");
+        }
+        assert_eq!(adapter.take_outbound().len(), 1);
+
+        assert_eq!(shaper.flush(&mut adapter, 10, &lane, &mut stats), 1);
+        assert_eq!(adapter.take_outbound().len(), 1);
+        assert_eq!(shaper.flush(&mut adapter, 13, &lane, &mut stats), 0);
+        assert_eq!(shaper.flush(&mut adapter, 14, &lane, &mut stats), 1);
+        assert_eq!(stats.shaped_real_messages, 2);
+        assert_eq!(stats.cover_messages, 0);
+        assert_eq!(shaper.pending_len(), 0);
+    }
+
+    #[test]
+    fn flush_honors_lane_byte_cap() {
+        let mut adapter = InMemoryAdapter::default();
+        let mut shaper = LaneShaper::with_seed(5);
+        let cfg = TrafficShapingConfig {
+            enabled: true,
+            default_delay: DelayDistribution::None,
+            ..TrafficShapingConfig::default()
+        };
+        let bytes = real_shard_bytes(1);
+        let lane = LaneShapingConfig {
+            max_bytes_per_step: Some(bytes.len() * 2),
+            ..LaneShapingConfig::default()
+        };
+        for _ in 0..5 {
+            shaper
+                .enqueue("peer-a".to_string(), bytes.clone(), 0, &cfg, &lane)
+                .expect("queue");
+        }
+        let mut stats = RuntimeStats::default();
+        assert_eq!(shaper.flush(&mut adapter, 0, &lane, &mut stats), 2);
+        assert_eq!(shaper.flush(&mut adapter, 1, &lane, &mut stats), 2);
+        assert_eq!(shaper.flush(&mut adapter, 2, &lane, &mut stats), 1);
+        assert_eq!(stats.shaped_real_bytes, bytes.len() * 5);
+    }
+
+    #[test]
+    fn full_queue_rejects_sends() {
+        let mut adapter = InMemoryAdapter::default();
+        let mut shaper = LaneShaper::with_seed(1);
+        let cfg = TrafficShapingConfig::default();
+        let lane = LaneShapingConfig {
+            max_queued_sends: 1,
+            ..LaneShapingConfig::default()
+        };
+        let mut shaped = ShapedLane::new(&mut adapter, &mut shaper, &cfg, lane, 0);
+        assert!(shaped.relay(&"p".to_string(), b"a").is_ok());
+        assert!(shaped.relay(&"p".to_string(), b"b").is_err());
+        assert_eq!(shaped.dropped(), 1);
+    }
+
+    #[test]
+    fn cover_shards_decode_and_mimic_observed_shapes() {
+        let mut adapter = InMemoryAdapter::default();
+        let mut shaper = LaneShaper::with_seed(9);
+        let mut cfg = NodeRuntimeConfig::default();
+        cfg.traffic_shaping.enabled = true;
+        cfg.traffic_shaping.default_delay = DelayDistribution::None;
+        let lane = LaneShapingConfig {
+            cover_rate_per_step: 4.0,
+            ..LaneShapingConfig::default()
+        };
+        let real = decode_shard_cbor(&real_shard_bytes(5)).expect("real");
+        shaper
+            .enqueue(
+                "peer-a".to_string(),
+                real_shard_bytes(5),
+                0,
+                &cfg.traffic_shaping,
+                &lane,
+            )
+            .expect("queue");
+
+        let peers = vec!["peer-a".to_string(), "peer-b".to_string()];
+        let mut queued = 0;
+        for step in 0..10 {
+            queued += shaper.emit_cover(&peers, step, &cfg, &lane, None);
+        }
+        assert!(queued > 0);
+
+        let mut stats = RuntimeStats::default();
+        shaper.flush(&mut adapter, 10, &lane, &mut stats);
+        assert_eq!(stats.cover_messages, queued);
+        assert_eq!(stats.shaped_real_messages, 1);
+
+        let out = adapter.take_outbound();
+        assert_eq!(out.len(), queued + 1);
+        for (_, bytes) in out {
+            let shard = decode_shard_cbor(&bytes).expect("cover decodes as shard");
+            assert_eq!(shard.header.namespace, real.header.namespace);
+            assert_eq!(shard.header.epoch, real.header.epoch);
+            assert_eq!(shard.header.bucket_size, real.header.bucket_size);
+            assert_eq!(is_cover_shard(&shard.header), shard != real);
+        }
+    }
+}
@@ -1,10 +1,21 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use veil_codec::shard::ShardV1;
+use std::time::Duration;
+use veil_codec::delegation::DelegationCertificate;
+use veil_codec::shard::ShardHeaderV1;
 use veil_core::ObjectRoot;
 use veil_core::{ShardId, Tag};
+use veil_fec::pooled::ShardBufferPool;
+use veil_fec::progressive::ProgressiveDecoder;
+use veil_fec::sharder::FountainEncoder;
 
+use crate::backfill::BackfillState;
+use crate::clock::{rescale_step, Clock, SystemClock, DEFAULT_STEP_DURATION};
+use crate::forwarding::PeerInterestTable;
 use crate::policy::TrustTier;
+use crate::publisher_log::{LogHead, PublisherLogs};
+use crate::receive::SignatureBatch;
+use crate::tombstone::TombstoneState;
 
 /// Cached shard bytes and eviction metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +28,15 @@ pub struct CachedShard {
     pub last_seen_step: u64,
 }
 
+/// Shards of one object held back until it verifies.
+#[derive(Debug, Clone, Default)]
+pub struct HeldShards {
+    /// Step after which the held shards are dropped.
+    pub until: u64,
+    /// Held `(shard id, encoded shard)` pairs.
+    pub shards: Vec<(ShardId, Vec<u8>)>,
+}
+
 /// Pending ACK timeout/escalation state for an outbound object.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PendingAck {
@@ -32,6 +52,35 @@ pub struct PendingAck {
     pub retry_batch_size: usize,
     /// Delay between retries.
     pub backoff_step: u64,
+    /// Fountain-mode object source; when set, retries generate fresh repair
+    /// shards instead of draining `unsent_shards`.
+    #[serde(default)]
+    pub fountain: Option<FountainRetrySource>,
+}
+
+/// Inputs needed to regenerate fountain-mode shards for ACK retries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FountainRetrySource {
+    /// Encoded object bytes the shard set was built from.
+    pub encoded_object: Vec<u8>,
+    /// Shard-set header template (index ignored).
+    pub header: ShardHeaderV1,
+    /// Next never-sent shard index.
+    pub next_index: u16,
+    /// Encoder kept across retries; rebuilt on demand after a restore.
+    #[serde(skip)]
+    pub(crate) encoder: Option<FountainEncoder>,
+}
+
+impl FountainRetrySource {
+    pub fn new(encoded_object: Vec<u8>, header: ShardHeaderV1, next_index: u16) -> Self {
+        Self {
+            encoded_object,
+            header,
+            next_index,
+            encoder: None,
+        }
+    }
 }
 
 /// Mutable node-local state used by receive/runtime/cache pipelines.
@@ -47,8 +96,19 @@ pub struct NodeState {
     pub shard_tier: HashMap<ShardId, TrustTier>,
     /// Request count signal associated with each cached shard.
     pub shard_requested: HashMap<ShardId, u64>,
-    /// Reconstruction inbox grouped by object root and shard index.
-    pub inbox: HashMap<ObjectRoot, HashMap<u16, ShardV1>>,
+    /// Progressive reconstruction state per object root (not persisted;
+    /// partially received objects restart decoding after a reload).
+    #[serde(skip)]
+    pub inbox: HashMap<ObjectRoot, ProgressiveDecoder>,
+    /// Shards of objects in namespaces with ingest requirements, held back
+    /// from cache and forwarding until their object verifies. Bounded by
+    /// `MAX_HELD_OBJECTS`; entries lapse with their shards' TTL.
+    #[serde(skip)]
+    pub held_shards: HashMap<ObjectRoot, HeldShards>,
+    /// Held shards whose object has verified, as `(tag, shard bytes)`; the
+    /// runtime forwards them with the next inbound shard it processes.
+    #[serde(skip)]
+    pub released_shards: Vec<(Tag, Vec<u8>)>,
     /// Recently seen shard ids used for duplicate suppression independent of cache policy.
     #[serde(skip)]
     pub seen_shards_lru: Option<lru::LruCache<ShardId, u64>>,
@@ -65,6 +125,46 @@ pub struct NodeState {
     /// Index of content roots (payload or batch items) to their wire roots.
     #[serde(default)]
     pub content_index: HashMap<ObjectRoot, ObjectRoot>,
+    /// Subscription-interest filters advertised by peers (not persisted).
+    #[serde(skip)]
+    pub peer_interests: PeerInterestTable,
+    /// Open backfill requests and per-peer serving budgets (not persisted).
+    #[serde(skip)]
+    pub backfill: BackfillState,
+    /// Heads of this node's own publisher logs, per tag.
+    #[serde(default)]
+    pub log_heads: HashMap<Tag, LogHead>,
+    /// Observed publisher logs and equivocation evidence.
+    #[serde(default)]
+    pub publisher_logs: PublisherLogs,
+    /// Verified deletions and object expiries suppressing re-ingest.
+    #[serde(default)]
+    pub tombstones: TombstoneState,
+    /// Step resolution (ms) stored step values were recorded at; 0 if unknown.
+    #[serde(default)]
+    pub step_duration_ms: u64,
+    /// Whether stored step values are clock-derived; `false` for state
+    /// written by a runtime counting ticks from zero.
+    #[serde(default)]
+    pub clock_steps: bool,
+    /// UNIX seconds of the current tick, set by runtimes that own a clock;
+    /// `None` reads the system clock. Used for signed wall-clock timestamps.
+    #[serde(skip)]
+    pub wall_clock_secs: Option<u64>,
+    /// Observed shard delivery rate in `[0, 1]` used by loss-adaptive FEC
+    /// profile selection; `None` until lanes have been observed.
+    #[serde(skip)]
+    pub fec_delivery_estimate: Option<f64>,
+    /// Reusable shard buffers for publish-time sharding.
+    #[serde(skip)]
+    pub shard_buffers: ShardBufferPool,
+    /// Certificate attached to objects signed with a delegated device key.
+    #[serde(skip)]
+    pub device_delegation: Option<DelegationCertificate>,
+    /// Signed objects awaiting batch verification (not persisted; pending
+    /// objects are dropped and re-fetched after a reload).
+    #[serde(skip)]
+    pub signature_batch: SignatureBatch,
 }
 
 impl NodeState {
@@ -94,6 +194,72 @@ impl NodeState {
         self.seen_shards_lru.as_mut().unwrap()
     }
 
+    /// Current UNIX seconds for checks against signed timestamps.
+    pub fn now_unix_secs(&self) -> u64 {
+        self.wall_clock_secs
+            .unwrap_or_else(|| SystemClock.now_seconds())
+    }
+
+    /// Records `step_duration` as this state's step resolution, rescaling any
+    /// stored step values if they were recorded at a different resolution.
+    ///
+    /// State without a recorded resolution predates clock-derived steps; its
+    /// tick counter advanced once per default tick, so it is treated as
+    /// [`DEFAULT_STEP_DURATION`].
+    pub fn align_step_duration(&mut self, step_duration: Duration) {
+        let target_ms = step_duration.as_millis().max(1) as u64;
+        let stored = match self.step_duration_ms {
+            0 => DEFAULT_STEP_DURATION,
+            ms => Duration::from_millis(ms),
+        };
+        if stored != Duration::from_millis(target_ms) {
+            self.rebase_steps(stored, step_duration);
+        }
+        self.step_duration_ms = target_ms;
+    }
+
+    /// Rescales every stored step value from `from` to `to` resolution.
+    pub fn rebase_steps(&mut self, from: Duration, to: Duration) {
+        let rescale = |step: u64| rescale_step(step, from, to);
+        self.map_steps(rescale);
+        for pending in self.pending_acks.values_mut() {
+            pending.backoff_step = rescale(pending.backoff_step).max(1);
+        }
+    }
+
+    /// Moves step values written by a tick-counting runtime onto the clock
+    /// step scale, shifting them so the latest recorded step lands on
+    /// `now_step`. Does nothing once the state is clock-derived.
+    pub fn adopt_clock_steps(&mut self, now_step: u64) {
+        if self.clock_steps {
+            return;
+        }
+        let latest = self
+            .cache
+            .values()
+            .map(|entry| entry.last_seen_step)
+            .max()
+            .unwrap_or(0);
+        let offset = now_step.saturating_sub(latest);
+        self.map_steps(|step| step.saturating_add(offset));
+        self.clock_steps = true;
+    }
+
+    fn map_steps(&mut self, f: impl Fn(u64) -> u64) {
+        for entry in self.cache.values_mut() {
+            entry.expiry_step = f(entry.expiry_step);
+            entry.last_seen_step = f(entry.last_seen_step);
+        }
+        self.prepare_for_persist();
+        self.seen_shards_lru = None;
+        for expiry in self.seen_shards.values_mut() {
+            *expiry = f(*expiry);
+        }
+        for pending in self.pending_acks.values_mut() {
+            pending.next_retry_step = f(pending.next_retry_step);
+        }
+    }
+
     pub fn prepare_for_persist(&mut self) {
         if let Some(lru) = &self.seen_shards_lru {
             self.seen_shards.clear();
@@ -106,7 +272,9 @@ impl NodeState {
 
 #[cfg(test)]
 mod tests {
-    use super::NodeState;
+    use std::time::Duration;
+
+    use super::{CachedShard, NodeState};
 
     #[test]
     fn node_state_default_is_empty() {
@@ -154,6 +322,59 @@ mod tests {
         assert_eq!(state.seen_shards.get(&[0x22; 32]), Some(&200));
     }
 
+    #[test]
+    fn align_step_duration_rescales_stored_steps() {
+        let mut state = NodeState::default();
+        state.align_step_duration(Duration::from_millis(50));
+        state.cache.insert(
+            [0x44; 32],
+            CachedShard {
+                bytes: vec![1],
+                expiry_step: 2_000,
+                last_seen_step: 1_000,
+            },
+        );
+        state.mark_shard_seen([0x55; 32], 4_000);
+
+        state.align_step_duration(Duration::from_millis(100));
+        assert_eq!(state.step_duration_ms, 100);
+        let entry = &state.cache[&[0x44; 32]];
+        assert_eq!(entry.expiry_step, 1_000);
+        assert_eq!(entry.last_seen_step, 500);
+        assert!(state.is_shard_seen(&[0x55; 32], 1_999));
+        assert!(!state.is_shard_seen(&[0x55; 32], 2_000));
+    }
+
+    #[test]
+    fn legacy_counter_state_is_rescaled_and_moved_onto_clock_steps() {
+        let mut state = NodeState::default();
+        state.cache.insert(
+            [0x44; 32],
+            CachedShard {
+                bytes: vec![1],
+                expiry_step: 3_000,
+                last_seen_step: 1_000,
+            },
+        );
+
+        // No recorded resolution: counted at the default 50ms tick.
+        state.align_step_duration(Duration::from_millis(100));
+        let entry = &state.cache[&[0x44; 32]];
+        assert_eq!((entry.last_seen_step, entry.expiry_step), (500, 1_500));
+
+        state.adopt_clock_steps(2_000_000);
+        assert!(state.clock_steps);
+        let entry = &state.cache[&[0x44; 32]];
+        assert_eq!(
+            (entry.last_seen_step, entry.expiry_step),
+            (2_000_000, 2_001_000)
+        );
+
+        // Already clock-derived: later calls leave steps alone.
+        state.adopt_clock_steps(9_000_000);
+        assert_eq!(state.cache[&[0x44; 32]].expiry_step, 2_001_000);
+    }
+
     #[test]
     fn lazy_lru_restores_from_seen_shards() {
         let mut state = NodeState::default();
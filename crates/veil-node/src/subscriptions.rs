@@ -1,6 +1,13 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
 use crate::state::NodeState;
-use veil_core::tags::derive_rv_tag_window;
-use veil_core::{Namespace, Tag};
+use veil_core::tags::{
+    current_epoch, derive_channel_namespace, derive_pairwise_rv_tag, derive_pairwise_rv_tag_window,
+    derive_rv_tag, derive_rv_tag_window, in_next_epoch_overlap,
+};
+use veil_core::{Epoch, Namespace, Tag};
 
 /// Subscribes to one explicit tag. Returns true when newly added.
 pub fn subscribe_tag(node: &mut NodeState, tag: Tag) -> bool {
@@ -25,6 +32,222 @@ pub fn subscribe_rv_tag_window(
         overlap_seconds,
         namespace,
     );
+    insert_tag_window(node, current, next)
+}
+
+/// Subscribes to pairwise rendezvous tags for one contact shared secret with
+/// optional next-epoch overlap.
+///
+/// Returns the number of new tags inserted into `node.subscriptions`.
+pub fn subscribe_pairwise_rv_tag_window(
+    node: &mut NodeState,
+    shared_secret: &[u8; 32],
+    namespace: Namespace,
+    now_seconds: u64,
+    epoch_seconds: u64,
+    overlap_seconds: u64,
+) -> usize {
+    let (current, next) = derive_pairwise_rv_tag_window(
+        shared_secret,
+        now_seconds,
+        epoch_seconds,
+        overlap_seconds,
+        namespace,
+    );
+    insert_tag_window(node, current, next)
+}
+
+/// Subscribes to pairwise rendezvous tags for every contact shared secret.
+///
+/// Returns the number of new tags inserted into `node.subscriptions`.
+pub fn subscribe_contact_rv_tag_windows<'a>(
+    node: &mut NodeState,
+    shared_secrets: impl IntoIterator<Item = &'a [u8; 32]>,
+    namespace: Namespace,
+    now_seconds: u64,
+    epoch_seconds: u64,
+    overlap_seconds: u64,
+) -> usize {
+    shared_secrets
+        .into_iter()
+        .map(|secret| {
+            subscribe_pairwise_rv_tag_window(
+                node,
+                secret,
+                namespace,
+                now_seconds,
+                epoch_seconds,
+                overlap_seconds,
+            )
+        })
+        .sum()
+}
+
+/// Key material a rotating rendezvous subscription is derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RvKey {
+    /// Public recipient pubkey (`derive_rv_tag`).
+    Recipient([u8; 32]),
+    /// Contact-pair shared secret (`derive_pairwise_rv_tag`).
+    Pairwise([u8; 32]),
+}
+
+/// Long-lived rendezvous identity the rotation manager keeps subscribed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentitySubscription {
+    pub key: RvKey,
+    pub namespace: Namespace,
+    /// Optional channel scope applied via `derive_channel_namespace`.
+    pub channel: Option<String>,
+}
+
+impl IdentitySubscription {
+    /// Identity subscription on a recipient pubkey's public rv tags.
+    pub fn recipient(pubkey: [u8; 32], namespace: Namespace) -> Self {
+        Self {
+            key: RvKey::Recipient(pubkey),
+            namespace,
+            channel: None,
+        }
+    }
+
+    /// Identity subscription on a contact pair's secret rv tags.
+    pub fn pairwise(shared_secret: [u8; 32], namespace: Namespace) -> Self {
+        Self {
+            key: RvKey::Pairwise(shared_secret),
+            namespace,
+            channel: None,
+        }
+    }
+
+    /// Scopes the subscription to `channel_id`.
+    pub fn with_channel(mut self, channel_id: impl Into<String>) -> Self {
+        self.channel = Some(channel_id.into());
+        self
+    }
+
+    /// Derives this identity's rendezvous tag for `epoch`.
+    pub fn tag_for_epoch(&self, epoch: Epoch) -> Tag {
+        let namespace = match self.channel.as_deref() {
+            Some(channel) => derive_channel_namespace(self.namespace, channel),
+            None => self.namespace,
+        };
+        match &self.key {
+            RvKey::Recipient(pubkey) => derive_rv_tag(pubkey, epoch, namespace),
+            RvKey::Pairwise(secret) => derive_pairwise_rv_tag(secret, epoch, namespace),
+        }
+    }
+}
+
+/// Tag changes applied by one rotation pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RvRotationOutcome {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Keeps rendezvous subscriptions aligned with wall-clock epochs.
+///
+/// Each `rotate` call installs the current epoch tag for every identity, adds
+/// the next epoch tag during the overlap tail, keeps the previous epoch tag
+/// for the same span after a boundary, and retires anything older. Only tags
+/// this manager inserted are ever removed from `NodeState::subscriptions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RvSubscriptionManager {
+    pub epoch_seconds: u64,
+    pub overlap_seconds: u64,
+    identities: Vec<IdentitySubscription>,
+    installed: HashMap<Tag, u32>,
+}
+
+impl Default for RvSubscriptionManager {
+    fn default() -> Self {
+        Self::new(86_400, 3_600)
+    }
+}
+
+impl RvSubscriptionManager {
+    pub fn new(epoch_seconds: u64, overlap_seconds: u64) -> Self {
+        Self {
+            epoch_seconds,
+            overlap_seconds,
+            identities: Vec::new(),
+            installed: HashMap::new(),
+        }
+    }
+
+    /// Adds an identity subscription. Returns false if already present.
+    pub fn add_identity(&mut self, identity: IdentitySubscription) -> bool {
+        if self.identities.contains(&identity) {
+            return false;
+        }
+        self.identities.push(identity);
+        true
+    }
+
+    /// Removes an identity subscription; its tags retire on the next rotation.
+    pub fn remove_identity(&mut self, identity: &IdentitySubscription) -> bool {
+        let before = self.identities.len();
+        self.identities.retain(|existing| existing != identity);
+        self.identities.len() != before
+    }
+
+    pub fn identities(&self) -> &[IdentitySubscription] {
+        &self.identities
+    }
+
+    /// Number of tags currently installed by this manager.
+    pub fn installed_len(&self) -> usize {
+        self.installed.len()
+    }
+
+    fn active_epochs(&self, now_seconds: u64) -> Vec<Epoch> {
+        let window = self.epoch_seconds.max(1);
+        let cur = current_epoch(now_seconds, window);
+        let mut epochs = vec![cur];
+        if in_next_epoch_overlap(now_seconds, window, self.overlap_seconds) {
+            epochs.push(Epoch(cur.0.saturating_add(1)));
+        }
+        let since_boundary = now_seconds % window;
+        if cur.0 > 0 && since_boundary < self.overlap_seconds.min(window) {
+            epochs.push(Epoch(cur.0 - 1));
+        }
+        epochs
+    }
+
+    /// Installs due rv tags and retires stale ones for wall-clock `now_seconds`.
+    pub fn rotate(&mut self, node: &mut NodeState, now_seconds: u64) -> RvRotationOutcome {
+        let mut desired = HashMap::new();
+        for epoch in self.active_epochs(now_seconds) {
+            for identity in &self.identities {
+                desired.insert(identity.tag_for_epoch(epoch), epoch.0);
+            }
+        }
+
+        let mut outcome = RvRotationOutcome::default();
+        self.installed.retain(|tag, _| {
+            if desired.contains_key(tag) {
+                return true;
+            }
+            if node.subscriptions.remove(tag) {
+                outcome.removed += 1;
+            }
+            false
+        });
+        for (tag, epoch) in desired {
+            if self.installed.contains_key(&tag) {
+                continue;
+            }
+            if node.subscriptions.insert(tag) {
+                self.installed.insert(tag, epoch);
+                outcome.added += 1;
+            }
+        }
+        outcome
+    }
+}
+
+fn insert_tag_window(node: &mut NodeState, current: Tag, next: Option<Tag>) -> usize {
     let mut added = 0_usize;
     if node.subscriptions.insert(current) {
         added += 1;
@@ -39,9 +262,13 @@ pub fn subscribe_rv_tag_window(
 
 #[cfg(test)]
 mod tests {
-    use super::{subscribe_rv_tag_window, subscribe_tag};
+    use super::{
+        subscribe_contact_rv_tag_windows, subscribe_pairwise_rv_tag_window,
+        subscribe_rv_tag_window, subscribe_tag, IdentitySubscription, RvRotationOutcome,
+        RvSubscriptionManager,
+    };
     use crate::state::NodeState;
-    use veil_core::tags::derive_rv_tag;
+    use veil_core::tags::{derive_pairwise_rv_tag, derive_rv_tag};
     use veil_core::{Epoch, Namespace};
 
     #[test]
@@ -78,4 +305,99 @@ mod tests {
             .subscriptions
             .contains(&derive_rv_tag(&key, Epoch(0), ns)));
     }
+
+    #[test]
+    fn subscribe_pairwise_window_does_not_use_public_rv_tags() {
+        let mut node = NodeState::default();
+        let secret = [0x44; 32];
+        let ns = Namespace(2);
+        let added = subscribe_pairwise_rv_tag_window(&mut node, &secret, ns, 95, 100, 10);
+        assert_eq!(added, 2);
+        assert!(node
+            .subscriptions
+            .contains(&derive_pairwise_rv_tag(&secret, Epoch(0), ns)));
+        assert!(node
+            .subscriptions
+            .contains(&derive_pairwise_rv_tag(&secret, Epoch(1), ns)));
+        assert!(!node
+            .subscriptions
+            .contains(&derive_rv_tag(&secret, Epoch(0), ns)));
+    }
+
+    #[test]
+    fn subscribe_contact_windows_covers_every_contact() {
+        let mut node = NodeState::default();
+        let secrets = [[0x01; 32], [0x02; 32], [0x03; 32]];
+        let ns = Namespace(2);
+        assert_eq!(
+            subscribe_contact_rv_tag_windows(&mut node, &secrets, ns, 10, 100, 10),
+            3
+        );
+        assert_eq!(
+            subscribe_contact_rv_tag_windows(&mut node, &secrets, ns, 95, 100, 10),
+            3
+        );
+        for secret in &secrets {
+            assert!(node
+                .subscriptions
+                .contains(&derive_pairwise_rv_tag(secret, Epoch(1), ns)));
+        }
+    }
+
+    #[test]
+    fn rotation_manager_installs_next_and_retires_stale_epochs() {
+        let mut node = NodeState::default();
+        let mut mgr = RvSubscriptionManager::new(100, 10);
+        let identity = IdentitySubscription::recipient([0x55; 32], Namespace(2));
+        assert!(mgr.add_identity(identity.clone()));
+        assert!(!mgr.add_identity(identity.clone()));
+
+        let tag = |epoch| identity.tag_for_epoch(Epoch(epoch));
+
+        assert_eq!(
+            mgr.rotate(&mut node, 150),
+            RvRotationOutcome {
+                added: 1,
+                removed: 0
+            }
+        );
+        assert!(node.subscriptions.contains(&tag(1)));
+
+        // Overlap tail installs the next epoch.
+        let out = mgr.rotate(&mut node, 195);
+        assert_eq!(out.added, 1);
+        assert!(node.subscriptions.contains(&tag(2)));
+
+        // Just past the boundary the previous epoch is still kept.
+        let out = mgr.rotate(&mut node, 205);
+        assert_eq!(out.removed, 0);
+        assert!(node.subscriptions.contains(&tag(1)));
+
+        // After the grace span the old epoch retires.
+        let out = mgr.rotate(&mut node, 250);
+        assert_eq!(out.removed, 1);
+        assert!(!node.subscriptions.contains(&tag(1)));
+        assert!(node.subscriptions.contains(&tag(2)));
+        assert_eq!(mgr.installed_len(), 1);
+    }
+
+    #[test]
+    fn rotation_manager_leaves_manual_subscriptions_alone() {
+        let mut node = NodeState::default();
+        let mut mgr = RvSubscriptionManager::new(100, 10);
+        let identity = IdentitySubscription::pairwise([0x66; 32], Namespace(2)).with_channel("dm");
+        let manual = identity.tag_for_epoch(Epoch(0));
+        subscribe_tag(&mut node, manual);
+        mgr.add_identity(identity.clone());
+
+        assert_eq!(mgr.rotate(&mut node, 50).added, 0);
+        mgr.rotate(&mut node, 150);
+        assert!(node.subscriptions.contains(&manual));
+
+        assert!(mgr.remove_identity(&identity));
+        let out = mgr.rotate(&mut node, 160);
+        assert_eq!(out.removed, 1);
+        assert_eq!(node.subscriptions.len(), 1);
+        assert!(node.subscriptions.contains(&manual));
+    }
 }
@@ -0,0 +1,704 @@
+//! Signed protocol-level deletions and object expiry.
+//!
+//! A [`Tombstone`] signed by an object's original sender asks every node to
+//! purge the object's cached shards and to refuse re-ingest for a retention
+//! window. Nodes forward tombstones whose authorship they confirmed, so
+//! deletions spread the same way shards do. Objects carrying an expiry
+//! timestamp are purged through the same state once that time passes.
+//!
+//! Authorship is checked against the sender recorded in the object itself,
+//! indexed when a cached object verifies so tombstones never trigger an
+//! object reconstruction.
+//! A device key may sign a tombstone for its root's objects when it attaches
+//! a certificate granting `CAP_TOMBSTONE`; the certificate's namespace scope
+//! is checked against the object once its header is readable.
+//! A node that cannot reconstruct the object yet keeps the tombstone pending,
+//! keyed by root and signer so a stranger's tombstone cannot shadow the
+//! author's, and applies it once the object's header becomes readable.
+//! Pending tombstones are bounded by [`MAX_PENDING_TOMBSTONES`] and are not
+//! forwarded. Expiries are recorded only for delivered, cached objects,
+//! bounded by [`MAX_TRACKED_EXPIRIES`], and dropped with the object's shards.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use veil_codec::delegation::{DelegationCertificate, CAP_TOMBSTONE};
+use veil_codec::object::Signature;
+use veil_core::hash::blake3_32;
+use veil_core::{Namespace, ObjectRoot};
+use veil_crypto::signing::{Signer, SigningError, Verifier};
+
+use crate::identity::verify_delegation_grant;
+use crate::state::NodeState;
+
+/// Current tombstone version.
+pub const TOMBSTONE_VERSION: u8 = 1;
+
+/// Most unconfirmed tombstones kept; the oldest is dropped beyond this.
+pub const MAX_PENDING_TOMBSTONES: usize = 1024;
+
+/// Most object expiries tracked; the furthest-out expiry is dropped beyond
+/// this.
+pub const MAX_TRACKED_EXPIRIES: usize = 16_384;
+
+const TOMBSTONE_MAGIC: &[u8] = b"VEIL_TOMBSTONE_V1";
+const TOMBSTONE_DOMAIN: &[u8] = b"veil/tombstone/v1";
+
+/// Errors raised while building or verifying tombstones.
+#[derive(Debug, Error)]
+pub enum TombstoneError {
+    #[error("unsupported tombstone version {0}")]
+    UnsupportedVersion(u8),
+    #[error("tombstone signature is invalid")]
+    SignatureInvalid,
+    #[error("encode error: {0}")]
+    Encode(String),
+    #[error("signing error: {0}")]
+    Signing(#[from] SigningError),
+}
+
+/// Sender-signed request to delete the object published under `object_root`
+/// (the wire root reported by publish).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub version: u8,
+    pub object_root: ObjectRoot,
+    pub sender_pubkey: [u8; 32],
+    /// UNIX seconds at signing time.
+    pub issued_at: u64,
+    /// Certificate letting a device key delete its root's objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegation: Option<DelegationCertificate>,
+    pub signature: Signature,
+}
+
+#[derive(Serialize)]
+struct TombstonePreimage<'a> {
+    version: u8,
+    object_root: ObjectRoot,
+    sender_pubkey: [u8; 32],
+    issued_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delegation: Option<&'a DelegationCertificate>,
+}
+
+impl Tombstone {
+    /// Builds and signs a tombstone for `object_root`.
+    pub fn sign(
+        signer: &dyn Signer,
+        object_root: ObjectRoot,
+        issued_at: u64,
+    ) -> Result<Self, TombstoneError> {
+        let mut tombstone = Self {
+            version: TOMBSTONE_VERSION,
+            object_root,
+            sender_pubkey: signer.public_key(),
+            issued_at,
+            delegation: None,
+            signature: Signature([0u8; 64]),
+        };
+        tombstone.signature = Signature(signer.sign(&tombstone.signing_digest()?)?);
+        Ok(tombstone)
+    }
+
+    /// Builds a tombstone signed by a device key under `delegation`, which
+    /// must grant `CAP_TOMBSTONE` for it to be honoured.
+    pub fn sign_delegated(
+        signer: &dyn Signer,
+        delegation: DelegationCertificate,
+        object_root: ObjectRoot,
+        issued_at: u64,
+    ) -> Result<Self, TombstoneError> {
+        let mut tombstone = Self {
+            delegation: Some(delegation),
+            ..Self::sign(signer, object_root, issued_at)?
+        };
+        tombstone.signature = Signature(signer.sign(&tombstone.signing_digest()?)?);
+        Ok(tombstone)
+    }
+
+    /// Domain-separated digest covered by the sender signature.
+    pub fn signing_digest(&self) -> Result<[u8; 32], TombstoneError> {
+        let mut bytes = TOMBSTONE_DOMAIN.to_vec();
+        ciborium::ser::into_writer(
+            &TombstonePreimage {
+                version: self.version,
+                object_root: self.object_root,
+                sender_pubkey: self.sender_pubkey,
+                issued_at: self.issued_at,
+                delegation: self.delegation.as_ref(),
+            },
+            &mut bytes,
+        )
+        .map_err(|e| TombstoneError::Encode(e.to_string()))?;
+        Ok(blake3_32(&bytes))
+    }
+
+    /// Checks version and signature.
+    pub fn verify(&self, verifier: &dyn Verifier) -> Result<(), TombstoneError> {
+        if self.version != TOMBSTONE_VERSION {
+            return Err(TombstoneError::UnsupportedVersion(self.version));
+        }
+        if verifier.verify(
+            self.sender_pubkey,
+            &self.signing_digest()?,
+            self.signature.0,
+        )? {
+            Ok(())
+        } else {
+            Err(TombstoneError::SignatureInvalid)
+        }
+    }
+}
+
+/// Encodes a tombstone as a control-plane packet.
+pub fn encode_tombstone_packet(tombstone: &Tombstone) -> Result<Vec<u8>, String> {
+    let mut out = TOMBSTONE_MAGIC.to_vec();
+    ciborium::ser::into_writer(tombstone, &mut out).map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// Decodes a tombstone packet, returning `None` for other payloads.
+pub fn decode_tombstone_packet(bytes: &[u8]) -> Option<Tombstone> {
+    let body = bytes.strip_prefix(TOMBSTONE_MAGIC)?;
+    ciborium::de::from_reader(body).ok()
+}
+
+/// Result of applying a tombstone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TombstoneOutcome {
+    /// Authorship confirmed; `purged` cached shards were dropped.
+    Applied { purged: usize },
+    /// Signature valid but the object's author is not known locally yet.
+    Pending,
+    /// Root already deleted, or this signer's tombstone is already pending.
+    Duplicate,
+    /// Signature or delegation invalid, or the signer is not the object's
+    /// author.
+    Rejected,
+}
+
+impl TombstoneOutcome {
+    /// Whether the tombstone should be forwarded to peers.
+    ///
+    /// Only tombstones checked against the object's author are relayed.
+    pub fn should_forward(self) -> bool {
+        matches!(self, Self::Applied { .. })
+    }
+}
+
+/// Tombstone held until its object's author and namespace are known.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingTombstone {
+    /// Step the tombstone is dropped at, and the deletion window once applied.
+    until: u64,
+    /// Namespaces a delegated signer may delete in; empty means any.
+    #[serde(default)]
+    namespaces: Vec<Namespace>,
+}
+
+impl PendingTombstone {
+    fn covers(&self, namespace: Namespace) -> bool {
+        self.namespaces.is_empty() || self.namespaces.contains(&namespace)
+    }
+}
+
+/// Known deletions and object expiries, keyed by wire root.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TombstoneState {
+    /// Deleted or expired roots, with the step re-ingest is refused until.
+    deletions: HashMap<ObjectRoot, u64>,
+    /// Unconfirmed tombstones by root and claimed author.
+    pending: HashMap<(ObjectRoot, [u8; 32]), PendingTombstone>,
+    /// UNIX seconds at which each cached object expires.
+    expiries: HashMap<ObjectRoot, u64>,
+    /// Verified author and namespace of each cached signed object.
+    #[serde(default)]
+    authors: HashMap<ObjectRoot, ([u8; 32], Namespace)>,
+}
+
+impl TombstoneState {
+    /// Whether shards for `root` must not be ingested or forwarded.
+    pub fn suppresses(&self, root: &ObjectRoot, now_step: u64) -> bool {
+        self.deletions
+            .get(root)
+            .is_some_and(|until| *until > now_step)
+    }
+
+    /// Whether any unconfirmed tombstone names `root`.
+    pub fn has_pending(&self, root: &ObjectRoot) -> bool {
+        self.pending
+            .keys()
+            .any(|(pending_root, _)| pending_root == root)
+    }
+
+    pub fn deletions_len(&self) -> usize {
+        self.deletions.len()
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn expiries_len(&self) -> usize {
+        self.expiries.len()
+    }
+
+    /// Records the verified author of cached object `root`; device-signed
+    /// objects are attributed to their root key.
+    pub fn record_author(&mut self, root: ObjectRoot, author: [u8; 32], namespace: Namespace) {
+        self.authors.insert(root, (author, namespace));
+    }
+
+    /// Drops per-object state once `root` is no longer cached.
+    pub fn forget(&mut self, root: &ObjectRoot) {
+        self.expiries.remove(root);
+        self.authors.remove(root);
+    }
+
+    /// Drops deletions and pending tombstones whose window has passed.
+    pub fn prune(&mut self, now_step: u64) {
+        self.deletions.retain(|_, until| *until > now_step);
+        self.pending.retain(|_, pending| pending.until > now_step);
+    }
+
+    fn insert_pending(&mut self, root: ObjectRoot, author: [u8; 32], pending: PendingTombstone) {
+        if self.pending.len() >= MAX_PENDING_TOMBSTONES {
+            if let Some(oldest) = self
+                .pending
+                .iter()
+                .min_by_key(|(_, pending)| pending.until)
+                .map(|(key, _)| *key)
+            {
+                self.pending.remove(&oldest);
+            }
+        }
+        self.pending.insert((root, author), pending);
+    }
+}
+
+/// Removes every cached and buffered shard of `root`.
+///
+/// Returns the number of cached shards dropped.
+pub fn purge_object(node: &mut NodeState, root: &ObjectRoot) -> usize {
+    node.inbox.remove(root);
+    node.held_shards.remove(root);
+    node.tombstones.forget(root);
+    let Some(shard_ids) = node.shard_index.remove(root) else {
+        return 0;
+    };
+    let mut purged = 0;
+    for sid in shard_ids {
+        node.shard_to_root.remove(&sid);
+        node.replica_estimate.remove(&sid);
+        node.shard_tier.remove(&sid);
+        node.shard_requested.remove(&sid);
+        if node.cache.remove(&sid).is_some() {
+            purged += 1;
+        }
+    }
+    node.content_index.retain(|_, wire| wire != root);
+    purged
+}
+
+/// Verifies `tombstone` and, when its signer (or the root delegating to it
+/// with `CAP_TOMBSTONE`) authored the object, purges the object and
+/// suppresses it for `retention_steps`.
+pub fn apply_tombstone(
+    node: &mut NodeState,
+    tombstone: &Tombstone,
+    verifier: &dyn Verifier,
+    now_step: u64,
+    retention_steps: u64,
+) -> TombstoneOutcome {
+    let root = tombstone.object_root;
+    if tombstone.verify(verifier).is_err() {
+        return TombstoneOutcome::Rejected;
+    }
+    let (author, namespaces) = match &tombstone.delegation {
+        Some(cert) => match verify_delegation_grant(
+            cert,
+            tombstone.sender_pubkey,
+            CAP_TOMBSTONE,
+            tombstone.issued_at.min(node.now_unix_secs()),
+            verifier,
+        ) {
+            Ok(root_key) => (root_key, cert.namespaces.clone()),
+            Err(_) => return TombstoneOutcome::Rejected,
+        },
+        None => (tombstone.sender_pubkey, Vec::new()),
+    };
+    if node.tombstones.deletions.contains_key(&root)
+        || node.tombstones.pending.contains_key(&(root, author))
+    {
+        return TombstoneOutcome::Duplicate;
+    }
+    let pending = PendingTombstone {
+        until: now_step.saturating_add(retention_steps),
+        namespaces,
+    };
+    match node.tombstones.authors.get(&root).copied() {
+        Some((object_author, namespace))
+            if object_author != author || !pending.covers(namespace) =>
+        {
+            TombstoneOutcome::Rejected
+        }
+        Some(_) => {
+            node.tombstones.deletions.insert(root, pending.until);
+            node.tombstones
+                .pending
+                .retain(|(pending_root, _), _| *pending_root != root);
+            TombstoneOutcome::Applied {
+                purged: purge_object(node, &root),
+            }
+        }
+        None => {
+            node.tombstones.insert_pending(root, author, pending);
+            TombstoneOutcome::Pending
+        }
+    }
+}
+
+/// Confirms a pending tombstone once the object's author and namespace are
+/// known, purging the object. Tombstones for `root` from anyone else, or from
+/// a device not delegated for `namespace`, are dropped. Returns whether the
+/// object is now deleted.
+pub fn confirm_pending_tombstone(
+    node: &mut NodeState,
+    root: &ObjectRoot,
+    author: [u8; 32],
+    namespace: Namespace,
+) -> bool {
+    let authored = node.tombstones.pending.remove(&(*root, author));
+    node.tombstones
+        .pending
+        .retain(|(pending_root, _), _| pending_root != root);
+    let Some(pending) = authored.filter(|pending| pending.covers(namespace)) else {
+        return false;
+    };
+    node.tombstones.deletions.insert(*root, pending.until);
+    purge_object(node, root);
+    true
+}
+
+/// Suppresses `root` for `retention_steps` and purges it if `unix_secs` has
+/// already passed; returns whether that is the case.
+pub fn reject_expired(
+    node: &mut NodeState,
+    root: ObjectRoot,
+    unix_secs: u64,
+    now_step: u64,
+    retention_steps: u64,
+) -> bool {
+    if unix_secs > node.now_unix_secs() {
+        return false;
+    }
+    node.tombstones
+        .deletions
+        .insert(root, now_step.saturating_add(retention_steps));
+    purge_object(node, &root);
+    true
+}
+
+/// Records that delivered object `root` expires at `unix_secs`, if any of
+/// its shards are cached. At [`MAX_TRACKED_EXPIRIES`] the furthest-out
+/// expiry is dropped, and the object then lapses with its cache TTL.
+pub fn record_expiry(node: &mut NodeState, root: ObjectRoot, unix_secs: u64) {
+    if !node.shard_index.contains_key(&root) {
+        return;
+    }
+    let expiries = &mut node.tombstones.expiries;
+    if !expiries.contains_key(&root) && expiries.len() >= MAX_TRACKED_EXPIRIES {
+        let Some((furthest, at)) = expiries.iter().max_by_key(|(_, at)| **at) else {
+            return;
+        };
+        if *at <= unix_secs {
+            return;
+        }
+        let furthest = *furthest;
+        expiries.remove(&furthest);
+    }
+    expiries.insert(root, unix_secs);
+}
+
+/// Purges cached objects whose expiry has passed, suppressing them for
+/// `retention_steps`, and prunes stale deletion state. Returns the number of
+/// cached shards dropped.
+pub fn purge_expired(node: &mut NodeState, now_step: u64, retention_steps: u64) -> usize {
+    let now_secs = node.now_unix_secs();
+    let due = node
+        .tombstones
+        .expiries
+        .iter()
+        .filter(|(_, at)| **at <= now_secs)
+        .map(|(root, _)| *root)
+        .collect::<Vec<_>>();
+    let mut purged = 0;
+    for root in due {
+        node.tombstones
+            .deletions
+            .insert(root, now_step.saturating_add(retention_steps));
+        purged += purge_object(node, &root);
+    }
+    node.tombstones.prune(now_step);
+    purged
+}
+
+#[cfg(test)]
+mod tests {
+    use veil_codec::delegation::{CAP_PUBLISH, CAP_TOMBSTONE};
+    use veil_codec::object::{
+        encode_object_cbor, object_signature_message_digest, ObjectV1, Signature,
+        OBJECT_FLAG_SIGNED, OBJECT_V1_VERSION,
+    };
+    use veil_codec::shard::encode_shard_cbor;
+    use veil_core::hash::blake3_32;
+    use veil_core::{Epoch, Namespace};
+    use veil_crypto::signing::{
+        Ed25519Signer, Ed25519Verifier, NostrSigner, NostrVerifier, Signer,
+    };
+    use veil_fec::sharder::{derive_object_root, object_to_shards};
+
+    use super::{
+        apply_tombstone, confirm_pending_tombstone, decode_tombstone_packet,
+        encode_tombstone_packet, purge_expired, record_expiry, reject_expired, Tombstone,
+        TombstoneOutcome, MAX_PENDING_TOMBSTONES, MAX_TRACKED_EXPIRIES,
+    };
+    use crate::cache::{cache_put, cache_put_with_policy};
+    use crate::identity::{delegate_device, DelegationScope};
+    use crate::policy::{LocalWotPolicy, TrustTier};
+    use crate::state::NodeState;
+
+    fn cache_signed_object(node: &mut NodeState, signer: &dyn Signer) -> [u8; 32] {
+        let mut object = ObjectV1 {
+            version: OBJECT_V1_VERSION,
+            namespace: Namespace(1),
+            epoch: Epoch(1),
+            flags: OBJECT_FLAG_SIGNED,
+            tag: [5u8; 32],
+            object_root: [6u8; 32],
+            sender_pubkey: Some(signer.public_key()),
+            signature: Some(Signature([0u8; 64])),
+            nonce: [7u8; 24],
+            ciphertext: vec![8u8; 64],
+            padding: Vec::new(),
+        };
+        let digest = object_signature_message_digest(&object).expect("digest");
+        object.signature = Some(Signature(signer.sign(&digest).expect("sign")));
+        let encoded = encode_object_cbor(&object).expect("encode");
+        let root = derive_object_root(&encoded);
+        for shard in
+            object_to_shards(&encoded, Namespace(1), Epoch(1), [5u8; 32], root).expect("shard")
+        {
+            let bytes = encode_shard_cbor(&shard).expect("encode shard");
+            cache_put(node, blake3_32(&bytes), bytes, 0, 1_000);
+        }
+        node.tombstones
+            .record_author(root, signer.public_key(), Namespace(1));
+        root
+    }
+
+    #[test]
+    fn device_tombstones_need_the_capability_and_namespace() {
+        let root_secret = [0x31; 32];
+        let root = NostrSigner::from_secret(root_secret).expect("root");
+        let scope = |capabilities, namespace| DelegationScope {
+            capabilities,
+            namespaces: vec![namespace],
+            not_before: 1_000,
+            not_after: 2_000,
+        };
+        let mut node = NodeState {
+            wall_clock_secs: Some(1_500),
+            ..NodeState::default()
+        };
+        let object_root = cache_signed_object(&mut node, &root);
+
+        for (index, scope) in [
+            (0, scope(CAP_PUBLISH, Namespace(1))),
+            (1, scope(CAP_TOMBSTONE, Namespace(2))),
+        ] {
+            let (device, cert) = delegate_device(&root_secret, index, scope).expect("delegate");
+            let tombstone =
+                Tombstone::sign_delegated(&device, cert, object_root, 1_200).expect("sign");
+            assert_eq!(
+                apply_tombstone(&mut node, &tombstone, &NostrVerifier, 1, 100),
+                TombstoneOutcome::Rejected
+            );
+        }
+
+        let (device, cert) =
+            delegate_device(&root_secret, 2, scope(CAP_TOMBSTONE, Namespace(1))).expect("delegate");
+        // The certificate is checked as of issue time, capped at the clock.
+        let late =
+            Tombstone::sign_delegated(&device, cert.clone(), object_root, 2_400).expect("sign");
+        let tombstone = Tombstone::sign_delegated(&device, cert, object_root, 1_200).expect("sign");
+        node.wall_clock_secs = Some(900);
+        assert_eq!(
+            apply_tombstone(&mut node, &tombstone, &NostrVerifier, 1, 100),
+            TombstoneOutcome::Rejected
+        );
+        node.wall_clock_secs = Some(2_500);
+        assert_eq!(
+            apply_tombstone(&mut node, &late, &NostrVerifier, 1, 100),
+            TombstoneOutcome::Rejected
+        );
+        assert!(matches!(
+            apply_tombstone(&mut node, &tombstone, &NostrVerifier, 1, 100),
+            TombstoneOutcome::Applied { purged } if purged > 0
+        ));
+        assert!(node.tombstones.suppresses(&object_root, 2));
+    }
+
+    #[test]
+    fn tombstone_from_sender_purges_and_suppresses() {
+        let author = Ed25519Signer::from_secret([1u8; 32]);
+        let mut node = NodeState::default();
+        let root = cache_signed_object(&mut node, &author);
+
+        let forged =
+            Tombstone::sign(&Ed25519Signer::from_secret([2u8; 32]), root, 10).expect("sign");
+        assert_eq!(
+            apply_tombstone(&mut node, &forged, &Ed25519Verifier, 5, 100),
+            TombstoneOutcome::Rejected
+        );
+        assert!(node.shard_index.contains_key(&root));
+
+        let tombstone = Tombstone::sign(&author, root, 10).expect("sign");
+        let packet = encode_tombstone_packet(&tombstone).expect("encode");
+        let decoded = decode_tombstone_packet(&packet).expect("decode");
+        let outcome = apply_tombstone(&mut node, &decoded, &Ed25519Verifier, 5, 100);
+        assert!(matches!(outcome, TombstoneOutcome::Applied { purged } if purged > 0));
+        assert!(node.cache.is_empty());
+        assert!(node.tombstones.suppresses(&root, 50));
+        assert!(!node.tombstones.suppresses(&root, 105));
+        assert_eq!(
+            apply_tombstone(&mut node, &decoded, &Ed25519Verifier, 6, 100),
+            TombstoneOutcome::Duplicate
+        );
+    }
+
+    #[test]
+    fn tombstone_without_cached_object_stays_pending() {
+        let author = Ed25519Signer::from_secret([3u8; 32]);
+        let mut node = NodeState::default();
+        let tombstone = Tombstone::sign(&author, [9u8; 32], 10).expect("sign");
+        let outcome = apply_tombstone(&mut node, &tombstone, &Ed25519Verifier, 1, 100);
+        assert_eq!(outcome, TombstoneOutcome::Pending);
+        assert!(!outcome.should_forward());
+        assert!(node.tombstones.has_pending(&[9u8; 32]));
+        assert!(!node.tombstones.suppresses(&[9u8; 32], 2));
+    }
+
+    #[test]
+    fn stranger_pending_tombstone_does_not_shadow_the_author() {
+        let author = Ed25519Signer::from_secret([5u8; 32]);
+        let stranger = Ed25519Signer::from_secret([6u8; 32]);
+        let mut node = NodeState::default();
+        let mut other = NodeState::default();
+        let root = cache_signed_object(&mut other, &author);
+
+        let forged = Tombstone::sign(&stranger, root, 10).expect("sign");
+        let genuine = Tombstone::sign(&author, root, 10).expect("sign");
+        for tombstone in [&forged, &genuine] {
+            assert_eq!(
+                apply_tombstone(&mut node, tombstone, &Ed25519Verifier, 1, 100),
+                TombstoneOutcome::Pending
+            );
+        }
+        assert_eq!(node.tombstones.pending_len(), 2);
+
+        assert!(!confirm_pending_tombstone(
+            &mut node,
+            &root,
+            [0x77; 32],
+            Namespace(1)
+        ));
+        assert!(!node.tombstones.has_pending(&root));
+
+        for tombstone in [&forged, &genuine] {
+            apply_tombstone(&mut node, tombstone, &Ed25519Verifier, 2, 100);
+        }
+        assert!(confirm_pending_tombstone(
+            &mut node,
+            &root,
+            author.public_key(),
+            Namespace(1)
+        ));
+        assert!(node.tombstones.suppresses(&root, 3));
+        assert_eq!(node.tombstones.pending_len(), 0);
+    }
+
+    #[test]
+    fn pending_tombstones_are_capped() {
+        let signer = Ed25519Signer::from_secret([7u8; 32]);
+        let mut node = NodeState::default();
+        for i in 0..=MAX_PENDING_TOMBSTONES {
+            let mut root = [0u8; 32];
+            root[..8].copy_from_slice(&(i as u64).to_be_bytes());
+            let tombstone = Tombstone::sign(&signer, root, 10).expect("sign");
+            apply_tombstone(&mut node, &tombstone, &Ed25519Verifier, i as u64, 100_000);
+        }
+        assert_eq!(node.tombstones.pending_len(), MAX_PENDING_TOMBSTONES);
+        assert!(!node.tombstones.has_pending(&[0u8; 32]));
+    }
+
+    #[test]
+    fn expired_objects_are_purged_by_wall_clock() {
+        let author = Ed25519Signer::from_secret([4u8; 32]);
+        let mut node = NodeState {
+            wall_clock_secs: Some(100),
+            ..NodeState::default()
+        };
+        let root = cache_signed_object(&mut node, &author);
+        // Steps run independently of the wall clock.
+        assert!(!reject_expired(&mut node, root, 200, 7, 50));
+        record_expiry(&mut node, root, 200);
+        node.wall_clock_secs = Some(199);
+        assert_eq!(purge_expired(&mut node, 8, 50), 0);
+        node.wall_clock_secs = Some(200);
+        assert!(purge_expired(&mut node, 9, 50) > 0);
+        assert!(node.tombstones.suppresses(&root, 58));
+        assert!(!node.tombstones.suppresses(&root, 59));
+        assert!(reject_expired(&mut node, [1u8; 32], 150, 9, 50));
+        assert!(node.tombstones.suppresses(&[1u8; 32], 58));
+    }
+
+    #[test]
+    fn expiries_track_only_cached_objects_and_are_capped() {
+        let author = Ed25519Signer::from_secret([4u8; 32]);
+        let mut node = NodeState::default();
+        record_expiry(&mut node, [1u8; 32], 500);
+        assert_eq!(node.tombstones.expiries_len(), 0);
+
+        let root = cache_signed_object(&mut node, &author);
+        record_expiry(&mut node, root, 500);
+        assert_eq!(node.tombstones.expiries_len(), 1);
+        // Evicting the object's shards drops its expiry.
+        cache_put_with_policy(
+            &mut node,
+            [9u8; 32],
+            vec![1],
+            1_000,
+            10,
+            TrustTier::Known,
+            1_000,
+            &LocalWotPolicy::default(),
+        );
+        assert!(!node.shard_index.contains_key(&root));
+        assert_eq!(node.tombstones.expiries_len(), 0);
+
+        let root = cache_signed_object(&mut node, &author);
+        for i in 0..MAX_TRACKED_EXPIRIES as u64 {
+            let mut other = [0xEEu8; 32];
+            other[..8].copy_from_slice(&i.to_be_bytes());
+            node.tombstones.expiries.insert(other, 1_000);
+        }
+        record_expiry(&mut node, root, 2_000);
+        assert!(!node.tombstones.expiries.contains_key(&root));
+        record_expiry(&mut node, root, 500);
+        assert!(node.tombstones.expiries.contains_key(&root));
+        assert_eq!(node.tombstones.expiries_len(), MAX_TRACKED_EXPIRIES);
+    }
+}
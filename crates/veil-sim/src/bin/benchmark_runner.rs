@@ -11,12 +11,17 @@ use veil_codec::object::{
     encode_object_cbor, object_signature_message_digest, ObjectV1, Signature, OBJECT_FLAG_SIGNED,
     OBJECT_V1_VERSION,
 };
-use veil_codec::shard::encode_shard_cbor;
+use veil_codec::shard::{decode_shard_cbor, encode_shard_cbor, ShardV1};
 use veil_core::hash::blake3_32;
 use veil_core::{Epoch, Namespace, ObjectRoot};
 use veil_crypto::aead::{build_veil_aad, AeadCipher, XChaCha20Poly1305Cipher};
 use veil_crypto::signing::{Ed25519Signer, Ed25519Verifier, Signer};
-use veil_fec::sharder::{derive_object_root, object_to_shards};
+use veil_fec::pooled::{object_to_wire_shards, ShardBufferPool, ShardingOptions};
+use veil_fec::profile::{ErasureCodingMode, Profile, PROFILE_LARGE_RESILIENT, PROFILE_SMALL};
+use veil_fec::progressive::{DecodeProgress, ProgressiveDecoder};
+use veil_fec::sharder::{
+    derive_object_root, object_to_shards, object_to_shards_with_profile, reconstruct_object_padded,
+};
 use veil_node::receive::ReceiveEvent;
 use veil_node::runtime::{pump_once, PumpParams, RuntimePolicyHooks, RuntimeStats};
 use veil_node::state::NodeState;
@@ -50,10 +55,22 @@ struct BenchResult {
     send_failures: usize,
 }
 
+#[derive(Debug, Clone, Serialize)]
+struct CodecBenchResult {
+    case: String,
+    path: String,
+    object_bytes: usize,
+    shards: usize,
+    iterations: usize,
+    elapsed_ms: u128,
+    throughput_mib_s: f64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct BenchReport {
     generated_at_unix_seconds: u64,
     results: Vec<BenchResult>,
+    codec_results: Vec<CodecBenchResult>,
 }
 
 #[derive(Debug, Clone)]
@@ -232,6 +249,137 @@ fn run_case(seed: u64, case: BenchCase) -> BenchResult {
     }
 }
 
+fn codec_row(
+    case: &str,
+    path: &str,
+    object_bytes: usize,
+    shards: usize,
+    iterations: usize,
+    started: Instant,
+) -> CodecBenchResult {
+    let elapsed = started.elapsed();
+    let secs = elapsed.as_secs_f64().max(1e-9);
+    CodecBenchResult {
+        case: case.to_string(),
+        path: path.to_string(),
+        object_bytes,
+        shards,
+        iterations,
+        elapsed_ms: elapsed.as_millis(),
+        throughput_mib_s: (object_bytes * iterations) as f64 / (1024.0 * 1024.0) / secs,
+    }
+}
+
+/// Times owned vs pooled (serial and parallel) sharding and batch vs
+/// progressive decoding of one object with the given profile.
+fn run_codec_case(
+    seed: u64,
+    name: &str,
+    object_len: usize,
+    profile: &Profile,
+    iterations: usize,
+) -> Vec<CodecBenchResult> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut object = vec![0_u8; object_len];
+    rng.fill_bytes(&mut object);
+    let root = derive_object_root(&object);
+    let namespace = Namespace(7);
+    let epoch = Epoch(1);
+    let tag = blake3_32(b"codec-bench");
+    let mode = ErasureCodingMode::HardenedNonSystematic;
+    let mut rows = Vec::new();
+
+    let started = Instant::now();
+    let mut wire = Vec::new();
+    for _ in 0..iterations {
+        let shards =
+            object_to_shards_with_profile(&object, namespace, epoch, tag, root, mode, 0, profile)
+                .expect("owned sharding should succeed");
+        wire = shards
+            .iter()
+            .map(|s| encode_shard_cbor(s).expect("shard encode should succeed"))
+            .collect::<Vec<_>>();
+    }
+    rows.push(codec_row(
+        name,
+        "encode_owned",
+        object_len,
+        wire.len(),
+        iterations,
+        started,
+    ));
+
+    for (path, options) in [
+        ("encode_pooled", ShardingOptions::default()),
+        ("encode_pooled_parallel", ShardingOptions::parallel()),
+    ] {
+        let mut pool = ShardBufferPool::default();
+        let started = Instant::now();
+        for _ in 0..iterations {
+            let set = object_to_wire_shards(
+                &object, namespace, epoch, tag, root, mode, 0, profile, &mut pool, &options,
+            )
+            .expect("pooled sharding should succeed");
+            assert_eq!(set.shards, wire, "pooled shards must match owned shards");
+            pool.recycle_all(set.shards);
+        }
+        rows.push(codec_row(
+            name,
+            path,
+            object_len,
+            wire.len(),
+            iterations,
+            started,
+        ));
+    }
+
+    // Decode from the last k shards so every source block needs RS recovery.
+    let shards = wire
+        .iter()
+        .map(|bytes| decode_shard_cbor(bytes).expect("shard decode should succeed"))
+        .collect::<Vec<ShardV1>>();
+    let k = profile.k as usize;
+    let subset = &shards[shards.len() - k..];
+
+    let started = Instant::now();
+    for _ in 0..iterations {
+        let bytes = reconstruct_object_padded(subset, root).expect("reconstruct should succeed");
+        assert_eq!(&bytes[..object_len], &object[..]);
+    }
+    rows.push(codec_row(
+        name,
+        "decode_batch",
+        object_len,
+        k,
+        iterations,
+        started,
+    ));
+
+    let started = Instant::now();
+    for _ in 0..iterations {
+        let mut decoder = ProgressiveDecoder::new(&subset[0].header).expect("decoder should build");
+        let mut done = false;
+        for shard in subset {
+            if let DecodeProgress::Complete(bytes) =
+                decoder.push(shard).expect("push should succeed")
+            {
+                assert_eq!(&bytes[..object_len], &object[..]);
+                done = true;
+            }
+        }
+        assert!(done, "progressive decoder should complete with k shards");
+    }
+    rows.push(codec_row(
+        name,
+        "decode_progressive",
+        object_len,
+        k,
+        iterations,
+        started,
+    ));
+    rows
+}
+
 fn parse_arg_u64(args: &[String], key: &str, default: u64) -> u64 {
     args.windows(2)
         .find(|w| w[0] == key)
@@ -283,6 +431,22 @@ fn write_outputs(output_dir: &Path, report: &BenchReport) -> std::io::Result<()>
         csv.push_str(&line);
     }
     fs::write(csv_path, csv)?;
+
+    let mut codec_csv =
+        String::from("case,path,object_bytes,shards,iterations,elapsed_ms,throughput_mib_s\n");
+    for row in &report.codec_results {
+        codec_csv.push_str(&format!(
+            "{},{},{},{},{},{},{:.6}\n",
+            row.case,
+            row.path,
+            row.object_bytes,
+            row.shards,
+            row.iterations,
+            row.elapsed_ms,
+            row.throughput_mib_s
+        ));
+    }
+    fs::write(output_dir.join("codec_bench.csv"), codec_csv)?;
     Ok(())
 }
 
@@ -291,7 +455,7 @@ fn main() {
     if has_flag(&args, "--help") {
         println!(
             "Usage: cargo run -p veil-sim --bin benchmark_runner -- [--seed N] [--out-dir DIR] [--quick]\n\
-             --quick runs smaller object counts for fast local checks."
+             --quick runs smaller object counts and codec iterations for fast local checks."
         );
         return;
     }
@@ -321,6 +485,19 @@ fn main() {
             .unwrap_or_default()
             .as_secs(),
         results: vec![run_case(seed, smoke), run_case(seed ^ 0xA11CE, load)],
+        codec_results: {
+            let iterations = if quick { 8 } else { 64 };
+            let mut rows =
+                run_codec_case(seed, "codec_small", 48 * 1024, &PROFILE_SMALL, iterations);
+            rows.extend(run_codec_case(
+                seed ^ 0xC0DEC,
+                "codec_large_resilient",
+                600 * 1024,
+                &PROFILE_LARGE_RESILIENT,
+                iterations,
+            ));
+            rows
+        },
     };
 
     write_outputs(&out_dir, &report).expect("writing benchmark outputs should succeed");
@@ -328,6 +505,7 @@ fn main() {
     println!("Wrote benchmark report:");
     println!("  {}", out_dir.join("bench_report.json").display());
     println!("  {}", out_dir.join("bench_report.csv").display());
+    println!("  {}", out_dir.join("codec_bench.csv").display());
     for row in &report.results {
         println!(
             "- {}: {:.2} MiB/s, p95={} steps, delivered={}/{}",
@@ -338,4 +516,10 @@ fn main() {
             row.objects
         );
     }
+    for row in &report.codec_results {
+        println!(
+            "- {}/{}: {:.2} MiB/s over {} iterations",
+            row.case, row.path, row.throughput_mib_s, row.iterations
+        );
+    }
 }
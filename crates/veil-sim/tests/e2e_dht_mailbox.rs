@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use veil_codec::object::OBJECT_FLAG_SIGNED;
+use veil_codec::shard::{decode_shard_cbor, encode_shard_cbor};
+use veil_core::hash::blake3_32;
+use veil_core::tags::derive_rv_tag;
+use veil_core::{Epoch, Namespace};
+use veil_crypto::aead::XChaCha20Poly1305Cipher;
+use veil_crypto::signing::{Ed25519Signer, Ed25519Verifier};
+use veil_fec::sharder::object_to_shards;
+use veil_node::dht::{xor_distance, DhtConfig, DhtContact, DhtLookupKind, DhtNode};
+use veil_node::publish::build_encoded_object;
+use veil_node::receive::{receive_shard, ReceiveEvent};
+use veil_node::state::NodeState;
+
+const STORAGE_NODES: usize = 16;
+const K: usize = 4;
+
+/// In-process network of DHT participants keyed by address.
+struct DhtSim {
+    nodes: BTreeMap<String, DhtNode>,
+    delivered: usize,
+}
+
+impl DhtSim {
+    fn new() -> Self {
+        Self {
+            nodes: BTreeMap::new(),
+            delivered: 0,
+        }
+    }
+
+    fn add(&mut self, addr: &str, node: DhtNode) {
+        self.nodes.insert(addr.to_string(), node);
+    }
+
+    fn node(&mut self, addr: &str) -> &mut DhtNode {
+        self.nodes.get_mut(addr).expect("node exists")
+    }
+
+    /// Delivers packets and polls nodes until no traffic remains.
+    fn run_until_idle(&mut self, now_step: u64) {
+        loop {
+            let mut queued = Vec::new();
+            for (addr, node) in &mut self.nodes {
+                node.poll(now_step);
+                for (to, packet) in node.take_outbound() {
+                    queued.push((addr.clone(), to, packet));
+                }
+            }
+            if queued.is_empty() {
+                return;
+            }
+            for (from, to, packet) in queued {
+                if let Some(dst) = self.nodes.get_mut(&to) {
+                    assert!(dst.handle_packet(&from, &packet, now_step));
+                    self.delivered += 1;
+                }
+            }
+        }
+    }
+}
+
+fn storage_contact(i: usize) -> DhtContact {
+    DhtContact {
+        key: blake3_32(format!("vps-key-{i}").as_bytes()),
+        addr: format!("vps-{i}"),
+    }
+}
+
+fn build_network(cfg: DhtConfig) -> DhtSim {
+    let mut sim = DhtSim::new();
+    for i in 0..STORAGE_NODES {
+        let mut node = DhtNode::storage(storage_contact(i), cfg);
+        // Discovery typically yields a handful of contacts per node.
+        node.seed(
+            [
+                storage_contact((i + 1) % STORAGE_NODES),
+                storage_contact((i + 5) % STORAGE_NODES),
+            ],
+            0,
+        );
+        sim.add(&format!("vps-{i}"), node);
+    }
+    // Each storage node joins by looking up its own key.
+    for i in 0..STORAGE_NODES {
+        let key = storage_contact(i).key;
+        sim.node(&format!("vps-{i}")).find_node(key, 0);
+        sim.run_until_idle(0);
+    }
+    for node in sim.nodes.values_mut() {
+        node.take_completed();
+    }
+    sim
+}
+
+fn k_closest_addrs(target: &[u8; 32], k: usize) -> Vec<String> {
+    let mut all = (0..STORAGE_NODES).map(storage_contact).collect::<Vec<_>>();
+    all.sort_by_key(|c| xor_distance(target, &c.key));
+    all.into_iter().take(k).map(|c| c.addr).collect()
+}
+
+#[test]
+fn e2e_dht_stores_rv_shards_at_k_closest_nodes_for_offline_recipient() {
+    let cfg = DhtConfig {
+        k: K,
+        ..DhtConfig::default()
+    };
+    let mut sim = build_network(cfg);
+
+    let recipient_pubkey = [0x5A_u8; 32];
+    let namespace = Namespace(2);
+    let epoch = Epoch(20_000);
+    let tag = derive_rv_tag(&recipient_pubkey, epoch, namespace);
+    let key = [0xC3_u8; 32];
+    let payload = b"message for an offline recipient";
+    let signer = Ed25519Signer::from_secret([0x42_u8; 32]);
+    let encoded = build_encoded_object(
+        payload,
+        namespace,
+        epoch,
+        tag,
+        &key,
+        1,
+        OBJECT_FLAG_SIGNED,
+        &XChaCha20Poly1305Cipher,
+        Some(&signer),
+    )
+    .expect("object should build");
+    let shards = object_to_shards(&encoded, namespace, epoch, tag, blake3_32(&encoded))
+        .expect("sharding should succeed");
+    let shard_bytes = shards
+        .iter()
+        .map(|s| encode_shard_cbor(s).expect("shard should encode"))
+        .collect::<Vec<_>>();
+
+    // Sender is a mobile client that only knows two storage nodes.
+    let mut sender = DhtNode::client([0x01; 32], cfg);
+    sender.seed([storage_contact(3), storage_contact(9)], 1);
+    sim.add("sender", sender);
+    sim.node("sender").store_shards(tag, shard_bytes.clone(), 1);
+    sim.run_until_idle(1);
+    let stored = sim.node("sender").take_completed();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].kind, DhtLookupKind::Store);
+    assert_eq!(stored[0].stored_at, K);
+
+    let mut holders = sim
+        .nodes
+        .iter()
+        .filter(|(_, n)| n.store.len() == shard_bytes.len())
+        .map(|(addr, _)| addr.clone())
+        .collect::<Vec<_>>();
+    let mut expected = k_closest_addrs(&tag, K);
+    holders.sort();
+    expected.sort();
+    assert_eq!(holders, expected);
+
+    // Recipient comes online later with different discovery seeds.
+    let mut recipient = DhtNode::client(recipient_pubkey, cfg);
+    recipient.seed([storage_contact(12)], 50);
+    sim.add("recipient", recipient);
+    sim.node("recipient").find_shards(tag, 50);
+    sim.run_until_idle(50);
+    let found = sim.node("recipient").take_completed();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].shards.len(), shard_bytes.len());
+
+    let mut state = NodeState::default();
+    state.subscriptions.insert(tag);
+    let mut delivered = None;
+    for bytes in &found[0].shards {
+        let shard = decode_shard_cbor(bytes).expect("stored shard decodes");
+        let event = receive_shard(
+            &mut state,
+            &shard,
+            50,
+            1_000,
+            &key,
+            &XChaCha20Poly1305Cipher,
+            &Ed25519Verifier,
+        )
+        .expect("receive should succeed");
+        if let ReceiveEvent::Delivered { payload, .. } = event {
+            delivered = Some(payload);
+        }
+    }
+    assert_eq!(delivered.as_deref(), Some(&payload[..]));
+    assert!(sim.delivered > 0, "the mailbox must be reached over DHT packets");
+}
+
+#[test]
+fn e2e_dht_lookup_routes_around_unresponsive_seed() {
+    let cfg = DhtConfig {
+        k: K,
+        request_timeout_steps: 5,
+        ..DhtConfig::default()
+    };
+    let mut sim = build_network(cfg);
+    let tag = [0x9E_u8; 32];
+
+    let mut recipient = DhtNode::client([0x02; 32], cfg);
+    recipient.seed(
+        [
+            DhtContact {
+                key: [0x9F; 32],
+                addr: "offline-vps".to_string(),
+            },
+            storage_contact(7),
+        ],
+        0,
+    );
+    sim.add("recipient", recipient);
+    sim.node("recipient").find_node(tag, 0);
+    sim.run_until_idle(0);
+    assert_eq!(sim.node("recipient").pending_lookups(), 1);
+
+    sim.run_until_idle(10);
+    let done = sim.node("recipient").take_completed();
+    assert_eq!(done.len(), 1);
+    let mut closest = done[0]
+        .closest
+        .iter()
+        .map(|c| c.addr.clone())
+        .collect::<Vec<_>>();
+    let mut expected = k_closest_addrs(&tag, K);
+    closest.sort();
+    expected.sort();
+    assert_eq!(closest, expected);
+    assert!(sim
+        .node("recipient")
+        .routing
+        .last_seen(&[0x9F; 32])
+        .is_none());
+}
@@ -136,6 +136,7 @@ async fn e2e_transport_runtime_smoke_starts_and_stops() {
             tick_interval: Duration::ZERO,
             error_backoff: Duration::ZERO,
             max_consecutive_errors: Some(4),
+            use_clock: false,
         },
         None,
     );
@@ -26,6 +26,13 @@ pub trait TransportAdapter {
     /// Returns the next inbound payload and its sending peer.
     fn recv(&mut self) -> Option<(Self::Peer, Vec<u8>)>;
 
+    /// Sends a payload relayed on behalf of another node.
+    ///
+    /// Defaults to [`Self::send`]; shaping adapters may delay relayed payloads.
+    fn relay(&mut self, peer: &Self::Peer, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.send(peer, bytes)
+    }
+
     /// Optional maximum payload hint used for lane/policy decisions.
     fn max_payload_hint(&self) -> Option<usize> {
         None
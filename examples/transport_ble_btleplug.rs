@@ -60,6 +60,7 @@ fn main() {
             tick_interval: Duration::from_millis(100),
             error_backoff: Duration::from_millis(250),
             max_consecutive_errors: Some(16),
+            use_clock: false,
         },
         None,
     );
@@ -37,6 +37,7 @@ fn main() {
             tick_interval: Duration::from_millis(50),
             error_backoff: Duration::from_millis(250),
             max_consecutive_errors: Some(8),
+            use_clock: false,
         },
         None,
     );
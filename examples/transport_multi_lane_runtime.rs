@@ -89,6 +89,7 @@ fn main() {
             tick_interval: Duration::from_millis(50),
             error_backoff: Duration::from_millis(250),
             max_consecutive_errors: Some(16),
+            use_clock: false,
         },
         None,
     );
@@ -1,11 +1,26 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use veil_codec::object::{decode_object_cbor, decode_object_cbor_prefix};
-use veil_codec::shard::decode_shard_cbor;
+use veil_codec::deterministic::check_deterministic_cbor;
+use veil_codec::object::{
+    decode_object_any_prefix_strict, decode_object_any_strict, decode_object_cbor,
+    decode_object_cbor_prefix, decode_object_cbor_strict, encode_object_cbor,
+};
+use veil_codec::shard::{decode_shard_cbor, decode_shard_cbor_strict, encode_shard_cbor};
 
 fuzz_target!(|data: &[u8]| {
     let _ = decode_object_cbor(data);
     let _ = decode_object_cbor_prefix(data);
     let _ = decode_shard_cbor(data);
+    let _ = check_deterministic_cbor(data);
+    let _ = decode_object_any_strict(data);
+    let _ = decode_object_any_prefix_strict(data);
+
+    // Strict acceptance means the input is the one canonical encoding.
+    if let Ok(object) = decode_object_cbor_strict(data) {
+        assert_eq!(encode_object_cbor(&object).expect("re-encode"), data);
+    }
+    if let Ok(shard) = decode_shard_cbor_strict(data) {
+        assert_eq!(encode_shard_cbor(&shard).expect("re-encode"), data);
+    }
 });